@@ -14,8 +14,10 @@
 // -------------------------------------------------------------------------------------------------
 
 use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
 
-use anyhow::Result;
+use anyhow::{anyhow, bail, Result};
+use nautilus_core::time::UnixNanos;
 use nautilus_model::enums::{AccountType, LiquiditySide, OrderSide};
 use nautilus_model::events::account::state::AccountState;
 use nautilus_model::events::order::filled::OrderFilled;
@@ -30,6 +32,8 @@ use nautilus_model::types::quantity::Quantity;
 use pyo3::prelude::*;
 use rust_decimal::prelude::ToPrimitive;
 
+use crate::models::fee::{MakerTakerFeeModel, SharedFeeModel};
+
 #[derive(Debug)]
 #[cfg_attr(
     feature = "python",
@@ -45,6 +49,7 @@ pub struct BaseAccount {
     pub commissions: HashMap<Currency, f64>,
     pub balances: HashMap<Currency, AccountBalance>,
     pub balances_starting: HashMap<Currency, Money>,
+    pub fee_model: SharedFeeModel,
 }
 
 impl BaseAccount {
@@ -64,9 +69,34 @@ impl BaseAccount {
             commissions: HashMap::new(),
             balances,
             balances_starting,
+            fee_model: Arc::new(Mutex::new(MakerTakerFeeModel)),
         })
     }
 
+    /// Sets the fee model used to calculate commissions.
+    ///
+    /// The model may be shared with the matching engines filling orders for the account.
+    pub fn set_fee_model(&mut self, fee_model: SharedFeeModel) {
+        self.fee_model = fee_model;
+    }
+
+    /// Records the fill with the fee model (for stateful models such as volume tiers).
+    ///
+    /// # Errors
+    ///
+    /// If the fee model lock is poisoned.
+    pub fn base_record_fill(
+        &mut self,
+        instrument: &dyn Instrument,
+        fill: &OrderFilled,
+    ) -> Result<()> {
+        self.fee_model
+            .lock()
+            .map_err(|e| anyhow!("Failed to lock fee model: {e}"))?
+            .record_fill(instrument, fill);
+        Ok(())
+    }
+
     #[must_use]
     pub fn base_balance_total(&self, currency: Option<Currency>) -> Option<Money> {
         let currency = currency
@@ -141,9 +171,9 @@ impl BaseAccount {
         self.events.push(event);
     }
 
-    pub fn base_calculate_balance_locked<T: Instrument>(
+    pub fn base_calculate_balance_locked(
         &mut self,
-        instrument: T,
+        instrument: &dyn Instrument,
         side: OrderSide,
         quantity: Quantity,
         price: Price,
@@ -176,9 +206,9 @@ impl BaseAccount {
         }
     }
 
-    pub fn base_calculate_pnls<T: Instrument>(
+    pub fn base_calculate_pnls(
         &self,
-        instrument: T,
+        instrument: &dyn Instrument,
         fill: OrderFilled,
         position: Option<Position>,
     ) -> Result<Vec<Money>> {
@@ -218,32 +248,34 @@ impl BaseAccount {
         Ok(pnls.into_values().collect())
     }
 
-    pub fn base_calculate_commission<T: Instrument>(
+    /// Calculates the commission for a fill at `ts_event` with the account's fee model.
+    ///
+    /// # Errors
+    ///
+    /// If `liquidity_side` is `NoLiquiditySide`, the fee model lock is poisoned, or the
+    /// fee model cannot calculate the commission.
+    pub fn base_calculate_commission(
         &self,
-        instrument: T,
+        instrument: &dyn Instrument,
         last_qty: Quantity,
         last_px: Price,
         liquidity_side: LiquiditySide,
         use_quote_for_inverse: Option<bool>,
+        ts_event: UnixNanos,
     ) -> Result<Money> {
-        assert!(
-            liquidity_side != LiquiditySide::NoLiquiditySide,
-            "Invalid liquidity side"
-        );
-        let notional = instrument
-            .calculate_notional_value(last_qty, last_px, use_quote_for_inverse)
-            .as_f64();
-        let commission = if liquidity_side == LiquiditySide::Maker {
-            notional * instrument.maker_fee().to_f64().unwrap()
-        } else if liquidity_side == LiquiditySide::Taker {
-            notional * instrument.taker_fee().to_f64().unwrap()
-        } else {
-            panic!("Invalid liquid side {liquidity_side}")
-        };
-        if instrument.is_inverse() && !use_quote_for_inverse.unwrap_or(false) {
-            Ok(Money::new(commission, instrument.base_currency().unwrap()).unwrap())
-        } else {
-            Ok(Money::new(commission, instrument.quote_currency()).unwrap())
+        if liquidity_side == LiquiditySide::NoLiquiditySide {
+            bail!("Invalid liquidity side {liquidity_side}")
         }
+        self.fee_model
+            .lock()
+            .map_err(|e| anyhow!("Failed to lock fee model: {e}"))?
+            .get_commission(
+                instrument,
+                last_qty,
+                last_px,
+                liquidity_side,
+                use_quote_for_inverse,
+                ts_event,
+            )
     }
 }
@@ -18,6 +18,7 @@ use std::fmt::Display;
 use std::ops::{Deref, DerefMut};
 
 use anyhow::Result;
+use nautilus_core::time::UnixNanos;
 use nautilus_model::enums::{AccountType, LiquiditySide, OrderSide};
 use nautilus_model::events::account::state::AccountState;
 use nautilus_model::events::order::filled::OrderFilled;
@@ -115,7 +116,13 @@ impl Account for CashAccount {
         price: Price,
         use_quote_for_inverse: Option<bool>,
     ) -> Result<Money> {
-        self.base_calculate_balance_locked(instrument, side, quantity, price, use_quote_for_inverse)
+        self.base_calculate_balance_locked(
+            &instrument,
+            side,
+            quantity,
+            price,
+            use_quote_for_inverse,
+        )
     }
     fn calculate_pnls<T: Instrument>(
         &self,
@@ -123,7 +130,7 @@ impl Account for CashAccount {
         fill: OrderFilled,
         position: Option<Position>,
     ) -> Result<Vec<Money>> {
-        self.base_calculate_pnls(instrument, fill, position)
+        self.base_calculate_pnls(&instrument, fill, position)
     }
     fn calculate_commission<T: Instrument>(
         &self,
@@ -132,13 +139,15 @@ impl Account for CashAccount {
         last_px: Price,
         liquidity_side: LiquiditySide,
         use_quote_for_inverse: Option<bool>,
+        ts_event: UnixNanos,
     ) -> Result<Money> {
         self.base_calculate_commission(
-            instrument,
+            &instrument,
             last_qty,
             last_px,
             liquidity_side,
             use_quote_for_inverse,
+            ts_event,
         )
     }
 }
@@ -433,7 +442,7 @@ mod tests {
             None,
             None,
         );
-        let position = Position::new(audusd_sim, fill).unwrap();
+        let position = Position::new(&audusd_sim, fill).unwrap();
         let pnls = cash_account_million_usd
             .calculate_pnls(audusd_sim, fill, Some(position))
             .unwrap();
@@ -468,7 +477,7 @@ mod tests {
             None,
             None,
         );
-        let position = Position::new(currency_pair_btcusdt, fill1).unwrap();
+        let position = Position::new(&currency_pair_btcusdt, fill1).unwrap();
         let result1 = cash_account_multi
             .calculate_pnls(currency_pair_btcusdt, fill1, Some(position.clone()))
             .unwrap();
@@ -528,11 +537,28 @@ mod tests {
                 Price::from("11450.50"),
                 LiquiditySide::Maker,
                 Some(use_quote_for_inverse),
+                0,
             )
             .unwrap();
         assert_eq!(result, expected);
     }
 
+    #[rstest]
+    fn test_calculate_commission_with_no_liquidity_side_errors(
+        cash_account_million_usd: CashAccount,
+        audusd_sim: CurrencyPair,
+    ) {
+        let result = cash_account_million_usd.calculate_commission(
+            audusd_sim,
+            Quantity::from("1500000"),
+            Price::from("0.8005"),
+            LiquiditySide::NoLiquiditySide,
+            None,
+            0,
+        );
+        assert!(result.is_err());
+    }
+
     #[rstest]
     fn test_calculate_commission_for_taker_fx(
         cash_account_million_usd: CashAccount,
@@ -545,6 +571,7 @@ mod tests {
                 Price::from("0.8005"),
                 LiquiditySide::Taker,
                 None,
+                0,
             )
             .unwrap();
         assert_eq!(result, Money::from("24.02 USD"));
@@ -562,6 +589,7 @@ mod tests {
                 Price::from("11450.50"),
                 LiquiditySide::Taker,
                 None,
+                0,
             )
             .unwrap();
         assert_eq!(result, Money::from("0.00654993 BTC"));
@@ -577,6 +605,7 @@ mod tests {
                 Price::from("120.310"),
                 LiquiditySide::Taker,
                 None,
+                0,
             )
             .unwrap();
         assert_eq!(result, Money::from("5294 JPY"));
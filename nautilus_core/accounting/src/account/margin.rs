@@ -22,8 +22,9 @@ use std::{
     hash::{Hash, Hasher},
 };
 
-use anyhow::Result;
-use nautilus_model::enums::{AccountType, LiquiditySide, OrderSide};
+use anyhow::{bail, Result};
+use nautilus_core::time::UnixNanos;
+use nautilus_model::enums::{AccountType, LiquiditySide, OrderSide, PositionSide};
 use nautilus_model::events::account::state::AccountState;
 use nautilus_model::events::order::filled::OrderFilled;
 use nautilus_model::identifiers::instrument_id::InstrumentId;
@@ -35,10 +36,10 @@ use nautilus_model::types::money::Money;
 use nautilus_model::types::price::Price;
 use nautilus_model::types::quantity::Quantity;
 use pyo3::prelude::*;
-use rust_decimal::prelude::ToPrimitive;
 
 use crate::account::base::BaseAccount;
 use crate::account::Account;
+use crate::models::margin::{LeverageMarginModel, MarginModel};
 
 #[derive(Debug)]
 #[cfg_attr(
@@ -50,6 +51,7 @@ pub struct MarginAccount {
     pub leverages: HashMap<InstrumentId, f64>,
     pub margins: HashMap<InstrumentId, MarginBalance>,
     pub default_leverage: f64,
+    pub margin_model: Box<dyn MarginModel>,
 }
 
 impl MarginAccount {
@@ -59,9 +61,15 @@ impl MarginAccount {
             leverages: HashMap::new(),
             margins: HashMap::new(),
             default_leverage: 1.0,
+            margin_model: Box::new(LeverageMarginModel),
         })
     }
 
+    /// Sets the margin model used to calculate margin requirements.
+    pub fn set_margin_model(&mut self, margin_model: Box<dyn MarginModel>) {
+        self.margin_model = margin_model;
+    }
+
     pub fn set_default_leverage(&mut self, leverage: f64) {
         self.default_leverage = leverage;
     }
@@ -176,56 +184,165 @@ impl MarginAccount {
         margin_balance.unwrap().maintenance
     }
 
+    /// Calculates the initial margin for the given `quantity` at `price` with the
+    /// account's margin model.
+    ///
+    /// # Errors
+    ///
+    /// If the margin model cannot calculate the margin for the instrument.
     pub fn calculate_initial_margin<T: Instrument>(
         &mut self,
         instrument: T,
         quantity: Quantity,
         price: Price,
         use_quote_for_inverse: Option<bool>,
-    ) -> Money {
-        let notional = instrument.calculate_notional_value(quantity, price, use_quote_for_inverse);
-        let leverage = self.get_leverage(&instrument.id());
-        if leverage == 0.0 {
-            self.leverages
-                .insert(instrument.id(), self.default_leverage);
-        }
-        let adjusted_notional = notional / leverage;
-        let initial_margin_f64 = instrument.margin_init().to_f64().unwrap();
-        let mut margin = adjusted_notional * initial_margin_f64;
-        // add taker fee
-        margin += adjusted_notional * instrument.taker_fee().to_f64().unwrap() * 2.0;
-        let use_quote_for_inverse = use_quote_for_inverse.unwrap_or(false);
-        if instrument.is_inverse() && !use_quote_for_inverse {
-            Money::new(margin, instrument.base_currency().unwrap()).unwrap()
-        } else {
-            Money::new(margin, instrument.quote_currency()).unwrap()
-        }
+    ) -> Result<Money> {
+        let leverage = self.resolve_leverage(&instrument.id());
+        self.margin_model.calculate_initial_margin(
+            &instrument,
+            quantity,
+            price,
+            leverage,
+            use_quote_for_inverse,
+        )
     }
 
+    /// Calculates the maintenance margin for the given `quantity` at `price` with the
+    /// account's margin model.
+    ///
+    /// # Errors
+    ///
+    /// If the margin model cannot calculate the margin for the instrument.
     pub fn calculate_maintenance_margin<T: Instrument>(
         &mut self,
         instrument: T,
         quantity: Quantity,
         price: Price,
         use_quote_for_inverse: Option<bool>,
-    ) -> Money {
-        let notional = instrument.calculate_notional_value(quantity, price, use_quote_for_inverse);
-        let leverage = self.get_leverage(&instrument.id());
-        if leverage == 0.0 {
-            self.leverages
-                .insert(instrument.id(), self.default_leverage);
+    ) -> Result<Money> {
+        let leverage = self.resolve_leverage(&instrument.id());
+        self.margin_model.calculate_maintenance_margin(
+            &instrument,
+            quantity,
+            price,
+            leverage,
+            use_quote_for_inverse,
+        )
+    }
+
+    /// Calculates and updates the initial and maintenance margins for the open
+    /// `positions` in the given instrument, allowing the margin model to offset
+    /// hedged long and short positions.
+    ///
+    /// # Errors
+    ///
+    /// If the margin cannot be calculated, or the account has insufficient balance
+    /// for the margin (the margins are then left unchanged).
+    pub fn update_position_margins(
+        &mut self,
+        instrument: &dyn Instrument,
+        positions: &[&Position],
+        price: Price,
+        use_quote_for_inverse: Option<bool>,
+    ) -> Result<()> {
+        let instrument_id = instrument.id();
+        let mut long_raw = 0;
+        let mut short_raw = 0;
+        for position in positions
+            .iter()
+            .filter(|position| position.instrument_id == instrument_id)
+        {
+            match position.side {
+                PositionSide::Long => long_raw += position.quantity.raw,
+                PositionSide::Short => short_raw += position.quantity.raw,
+                _ => {}
+            }
         }
-        let adjusted_notional = notional / leverage;
-        let margin_maint_f64 = instrument.margin_maint().to_f64().unwrap();
-        let mut margin = adjusted_notional * margin_maint_f64;
-        // Add taker fee
-        margin += adjusted_notional * instrument.taker_fee().to_f64().unwrap();
-        let use_quote_for_inverse = use_quote_for_inverse.unwrap_or(false);
-        if instrument.is_inverse() && !use_quote_for_inverse {
-            Money::new(margin, instrument.base_currency().unwrap()).unwrap()
-        } else {
-            Money::new(margin, instrument.quote_currency()).unwrap()
+        let size_precision = instrument.size_precision();
+        let long_qty = Quantity::from_raw(long_raw, size_precision)?;
+        let short_qty = Quantity::from_raw(short_raw, size_precision)?;
+
+        let (margin_init, margin_maint) = self.calculate_position_margins(
+            instrument,
+            long_qty,
+            short_qty,
+            price,
+            use_quote_for_inverse,
+        )?;
+        self.update_initial_margin(instrument_id, margin_init);
+        self.update_maintenance_margin(instrument_id, margin_maint);
+        Ok(())
+    }
+
+    /// Calculates the initial and maintenance margins for holding `long_qty` and
+    /// `short_qty` of the given instrument, checking they are covered by the balance
+    /// alongside the margins of the other instruments.
+    ///
+    /// # Errors
+    ///
+    /// If the margin cannot be calculated, or the account has insufficient balance
+    /// for the margin.
+    pub fn calculate_position_margins(
+        &mut self,
+        instrument: &dyn Instrument,
+        long_qty: Quantity,
+        short_qty: Quantity,
+        price: Price,
+        use_quote_for_inverse: Option<bool>,
+    ) -> Result<(Money, Money)> {
+        let instrument_id = instrument.id();
+        let leverage = self.resolve_leverage(&instrument_id);
+        let (margin_init, margin_maint) = self.margin_model.calculate_position_margins(
+            instrument,
+            long_qty,
+            short_qty,
+            price,
+            leverage,
+            use_quote_for_inverse,
+        )?;
+
+        let currency = margin_init.currency;
+        let Some(balance) = self.balances.get(&currency) else {
+            bail!("No balance in {currency} for margin of {instrument_id}")
+        };
+        let other_margins: i64 = self
+            .margins
+            .values()
+            .filter(|margin| margin.instrument_id != instrument_id && margin.currency == currency)
+            .map(|margin| margin.initial.raw + margin.maintenance.raw)
+            .sum();
+        if balance.total.raw - other_margins - margin_init.raw - margin_maint.raw < 0 {
+            bail!(
+                "Margin exceeded for {instrument_id}: balance {}",
+                balance.total
+            )
+        }
+        Ok((margin_init, margin_maint))
+    }
+
+    /// Returns whether the maintenance margin for the given `currency` exceeds the
+    /// account equity (total balance plus any `unrealized_pnl`).
+    #[must_use]
+    pub fn is_margin_call(&self, currency: Currency, unrealized_pnl: Option<Money>) -> bool {
+        let Some(balance) = self.balances.get(&currency) else {
+            return false;
+        };
+        let equity = balance.total.raw + unrealized_pnl.map_or(0, |pnl| pnl.raw);
+        let maintenance: i64 = self
+            .margins
+            .values()
+            .filter(|margin| margin.currency == currency)
+            .map(|margin| margin.maintenance.raw)
+            .sum();
+        maintenance > equity
+    }
+
+    fn resolve_leverage(&mut self, instrument_id: &InstrumentId) -> f64 {
+        let leverage = self.get_leverage(instrument_id);
+        if leverage == 0.0 {
+            self.leverages.insert(*instrument_id, self.default_leverage);
         }
+        leverage
     }
 
     pub fn recalculate_balance(&mut self, currency: Currency) {
@@ -322,7 +439,13 @@ impl Account for MarginAccount {
         price: Price,
         use_quote_for_inverse: Option<bool>,
     ) -> Result<Money> {
-        self.base_calculate_balance_locked(instrument, side, quantity, price, use_quote_for_inverse)
+        self.base_calculate_balance_locked(
+            &instrument,
+            side,
+            quantity,
+            price,
+            use_quote_for_inverse,
+        )
     }
     fn calculate_pnls<T: Instrument>(
         &self,
@@ -330,7 +453,7 @@ impl Account for MarginAccount {
         fill: OrderFilled,
         position: Option<Position>,
     ) -> Result<Vec<Money>> {
-        self.base_calculate_pnls(instrument, fill, position)
+        self.base_calculate_pnls(&instrument, fill, position)
     }
     fn calculate_commission<T: Instrument>(
         &self,
@@ -339,13 +462,15 @@ impl Account for MarginAccount {
         last_px: Price,
         liquidity_side: LiquiditySide,
         use_quote_for_inverse: Option<bool>,
+        ts_event: UnixNanos,
     ) -> Result<Money> {
         self.base_calculate_commission(
-            instrument,
+            &instrument,
             last_qty,
             last_px,
             liquidity_side,
             use_quote_for_inverse,
+            ts_event,
         )
     }
 }
@@ -387,6 +512,7 @@ mod tests {
     use crate::account::margin::MarginAccount;
     use crate::account::stubs::*;
     use crate::account::Account;
+    use crate::models::margin::FixedMarginModel;
     use nautilus_model::events::account::state::AccountState;
     use nautilus_model::events::account::stubs::*;
     use nautilus_model::identifiers::instrument_id::InstrumentId;
@@ -544,12 +670,14 @@ mod tests {
         audusd_sim: CurrencyPair,
     ) {
         margin_account.set_leverage(audusd_sim.id, 50.0);
-        let result = margin_account.calculate_initial_margin(
-            audusd_sim,
-            Quantity::from(100_000),
-            Price::from("0.8000"),
-            None,
-        );
+        let result = margin_account
+            .calculate_initial_margin(
+                audusd_sim,
+                Quantity::from(100_000),
+                Price::from("0.8000"),
+                None,
+            )
+            .unwrap();
         assert_eq!(result, Money::from("48.06 USD"));
     }
 
@@ -559,12 +687,14 @@ mod tests {
         audusd_sim: CurrencyPair,
     ) {
         margin_account.set_default_leverage(10.0);
-        let result = margin_account.calculate_initial_margin(
-            audusd_sim,
-            Quantity::from(100_000),
-            Price::from("0.8"),
-            None,
-        );
+        let result = margin_account
+            .calculate_initial_margin(
+                audusd_sim,
+                Quantity::from(100_000),
+                Price::from("0.8"),
+                None,
+            )
+            .unwrap();
         assert_eq!(result, Money::from("240.32 USD"));
     }
 
@@ -573,19 +703,23 @@ mod tests {
         mut margin_account: MarginAccount,
         xbtusd_bitmex: CryptoPerpetual,
     ) {
-        let result_use_quote_inverse_true = margin_account.calculate_initial_margin(
-            xbtusd_bitmex,
-            Quantity::from(100_000),
-            Price::from("11493.60"),
-            Some(false),
-        );
+        let result_use_quote_inverse_true = margin_account
+            .calculate_initial_margin(
+                xbtusd_bitmex,
+                Quantity::from(100_000),
+                Price::from("11493.60"),
+                Some(false),
+            )
+            .unwrap();
         assert_eq!(result_use_quote_inverse_true, Money::from("0.10005568 BTC"));
-        let result_use_quote_inverse_false = margin_account.calculate_initial_margin(
-            xbtusd_bitmex,
-            Quantity::from(100_000),
-            Price::from("11493.60"),
-            Some(true),
-        );
+        let result_use_quote_inverse_false = margin_account
+            .calculate_initial_margin(
+                xbtusd_bitmex,
+                Quantity::from(100_000),
+                Price::from("11493.60"),
+                Some(true),
+            )
+            .unwrap();
         assert_eq!(result_use_quote_inverse_false, Money::from("1150 USD"));
     }
 
@@ -594,12 +728,14 @@ mod tests {
         mut margin_account: MarginAccount,
         xbtusd_bitmex: CryptoPerpetual,
     ) {
-        let result = margin_account.calculate_maintenance_margin(
-            xbtusd_bitmex,
-            Quantity::from(100_000),
-            Price::from("11493.60"),
-            None,
-        );
+        let result = margin_account
+            .calculate_maintenance_margin(
+                xbtusd_bitmex,
+                Quantity::from(100_000),
+                Price::from("11493.60"),
+                None,
+            )
+            .unwrap();
         assert_eq!(result, Money::from("0.03697710 BTC"));
     }
 
@@ -609,12 +745,14 @@ mod tests {
         audusd_sim: CurrencyPair,
     ) {
         margin_account.set_default_leverage(50.0);
-        let result = margin_account.calculate_maintenance_margin(
-            audusd_sim,
-            Quantity::from(1_000_000),
-            Price::from("1"),
-            None,
-        );
+        let result = margin_account
+            .calculate_maintenance_margin(
+                audusd_sim,
+                Quantity::from(1_000_000),
+                Price::from("1"),
+                None,
+            )
+            .unwrap();
         assert_eq!(result, Money::from("600.40 USD"));
     }
 
@@ -624,12 +762,64 @@ mod tests {
         xbtusd_bitmex: CryptoPerpetual,
     ) {
         margin_account.set_default_leverage(10.0);
-        let result = margin_account.calculate_maintenance_margin(
-            xbtusd_bitmex,
-            Quantity::from(100_000),
-            Price::from("100000.00"),
+        let result = margin_account
+            .calculate_maintenance_margin(
+                xbtusd_bitmex,
+                Quantity::from(100_000),
+                Price::from("100000.00"),
+                None,
+            )
+            .unwrap();
+        assert_eq!(result, Money::from("0.00042500 BTC"));
+    }
+
+    #[rstest]
+    fn test_calculate_margin_with_fixed_margin_model(
+        mut margin_account: MarginAccount,
+        audusd_sim: CurrencyPair,
+    ) {
+        let mut margin_model = FixedMarginModel::new();
+        margin_model
+            .set_margin(
+                audusd_sim.id,
+                Money::from("2.00 USD"),
+                Money::from("1.50 USD"),
+            )
+            .unwrap();
+        margin_account.set_margin_model(Box::new(margin_model));
+        margin_account.set_default_leverage(50.0);
+        let result = margin_account
+            .calculate_initial_margin(
+                audusd_sim,
+                Quantity::from(100),
+                Price::from("0.80000"),
+                None,
+            )
+            .unwrap();
+        assert_eq!(result, Money::from("200.00 USD"));
+    }
+
+    #[rstest]
+    fn test_calculate_margin_with_fixed_margin_model_missing_instrument_errors(
+        mut margin_account: MarginAccount,
+        audusd_sim: CurrencyPair,
+    ) {
+        margin_account.set_margin_model(Box::new(FixedMarginModel::new()));
+        let result = margin_account.calculate_initial_margin(
+            audusd_sim,
+            Quantity::from(100),
+            Price::from("0.80000"),
             None,
         );
-        assert_eq!(result, Money::from("0.00042500 BTC"));
+        assert!(result.is_err());
+    }
+
+    #[rstest]
+    fn test_is_margin_call(mut margin_account: MarginAccount, audusd_sim: CurrencyPair) {
+        margin_account.update_maintenance_margin(audusd_sim.id, Money::from("1000.00 USD"));
+        let total = margin_account.balance_total(None).unwrap();
+        let unrealized_loss = Money::new(-total.as_f64(), total.currency).unwrap();
+        assert!(!margin_account.is_margin_call(Currency::USD(), None));
+        assert!(margin_account.is_margin_call(Currency::USD(), Some(unrealized_loss)));
     }
 }
@@ -14,6 +14,7 @@
 // -------------------------------------------------------------------------------------------------
 
 use anyhow::Result;
+use nautilus_core::time::UnixNanos;
 use nautilus_model::enums::{LiquiditySide, OrderSide};
 use nautilus_model::events::account::state::AccountState;
 use nautilus_model::events::order::filled::OrderFilled;
@@ -64,6 +65,7 @@ pub trait Account {
         last_px: Price,
         liquidity_side: LiquiditySide,
         use_quote_for_inverse: Option<bool>,
+        ts_event: UnixNanos,
     ) -> Result<Money>;
 }
 
@@ -60,6 +60,6 @@ pub fn calculate_commission<T: Instrument>(
     };
     let account = cash_account_million_usd(account_state);
     account
-        .calculate_commission(instrument, quantity, price, LiquiditySide::Taker, None)
+        .calculate_commission(instrument, quantity, price, LiquiditySide::Taker, None, 0)
         .unwrap()
 }
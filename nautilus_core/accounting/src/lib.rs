@@ -14,6 +14,7 @@
 // -------------------------------------------------------------------------------------------------
 
 pub mod account;
+pub mod models;
 #[cfg(test)]
 pub mod stubs;
 
@@ -0,0 +1,510 @@
+// -------------------------------------------------------------------------------------------------
+//  Copyright (C) 2015-2024 Nautech Systems Pty Ltd. All rights reserved.
+//  https://nautechsystems.io
+//
+//  Licensed under the GNU Lesser General Public License Version 3.0 (the "License");
+//  You may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at https://www.gnu.org/licenses/lgpl-3.0.en.html
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+// -------------------------------------------------------------------------------------------------
+
+//! Fee models used to calculate commissions for fills.
+//!
+//! A [`FeeModel`] is consulted by accounts and the backtest matching engine in place
+//! of reading the maker/taker fee rates directly from the instrument.
+
+use std::{
+    collections::VecDeque,
+    fmt::Debug,
+    sync::{Arc, Mutex},
+};
+
+use anyhow::{bail, Result};
+use nautilus_core::{datetime::NANOSECONDS_IN_SECOND, time::UnixNanos};
+use nautilus_model::{
+    enums::LiquiditySide,
+    events::order::filled::OrderFilled,
+    instruments::Instrument,
+    types::{money::Money, price::Price, quantity::Quantity},
+};
+use rust_decimal::{prelude::ToPrimitive, Decimal};
+
+/// The default rolling window for volume tiers (30 days).
+pub const THIRTY_DAYS_NS: u64 = 30 * 24 * 60 * 60 * NANOSECONDS_IN_SECOND;
+
+/// A fee model shared between a venue account and its matching engines.
+pub type SharedFeeModel = Arc<Mutex<dyn FeeModel>>;
+
+/// Provides commission calculations for fills.
+///
+/// Negative maker fees represent rebates and result in a negative commission.
+pub trait FeeModel: Debug + Send {
+    /// Returns the commission for a fill of `last_qty` at `last_px` occurring at `ts_event`.
+    ///
+    /// # Errors
+    ///
+    /// If `liquidity_side` is `NoLiquiditySide`, or the resulting commission is invalid.
+    fn get_commission(
+        &self,
+        instrument: &dyn Instrument,
+        last_qty: Quantity,
+        last_px: Price,
+        liquidity_side: LiquiditySide,
+        use_quote_for_inverse: Option<bool>,
+        ts_event: UnixNanos,
+    ) -> Result<Money>;
+
+    /// Records a fill so stateful models (such as volume tiers) can update.
+    fn record_fill(&mut self, _instrument: &dyn Instrument, _fill: &OrderFilled) {}
+
+    /// Resets any state accumulated from recorded fills.
+    fn reset(&mut self) {}
+}
+
+fn check_liquidity_side(liquidity_side: LiquiditySide) -> Result<()> {
+    if liquidity_side == LiquiditySide::NoLiquiditySide {
+        bail!("Invalid liquidity side {liquidity_side}")
+    }
+    Ok(())
+}
+
+fn notional_commission(
+    instrument: &dyn Instrument,
+    last_qty: Quantity,
+    last_px: Price,
+    rate: Decimal,
+    use_quote_for_inverse: Option<bool>,
+) -> Result<Money> {
+    // Notional is denominated in base currency for inverse instruments (unless
+    // `use_quote_for_inverse`), otherwise in quote currency
+    let notional = instrument.calculate_notional_value(last_qty, last_px, use_quote_for_inverse);
+    let rate = rate.to_f64().unwrap_or_default();
+    Money::new(notional.as_f64() * rate, notional.currency)
+}
+
+/// Charges the maker/taker fee rates defined on the instrument.
+///
+/// This is the default fee model and matches the historical commission behavior.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct MakerTakerFeeModel;
+
+impl FeeModel for MakerTakerFeeModel {
+    fn get_commission(
+        &self,
+        instrument: &dyn Instrument,
+        last_qty: Quantity,
+        last_px: Price,
+        liquidity_side: LiquiditySide,
+        use_quote_for_inverse: Option<bool>,
+        _ts_event: UnixNanos,
+    ) -> Result<Money> {
+        check_liquidity_side(liquidity_side)?;
+        let rate = match liquidity_side {
+            LiquiditySide::Maker => instrument.maker_fee(),
+            _ => instrument.taker_fee(),
+        };
+        notional_commission(instrument, last_qty, last_px, rate, use_quote_for_inverse)
+    }
+}
+
+/// Charges a fixed fee per contract filled, regardless of price.
+#[derive(Clone, Copy, Debug)]
+pub struct PerContractFeeModel {
+    pub maker_fee: Money,
+    pub taker_fee: Money,
+}
+
+impl PerContractFeeModel {
+    /// Initializes a new `PerContractFeeModel` instance.
+    ///
+    /// # Errors
+    ///
+    /// If the maker and taker fees are not in the same currency.
+    pub fn new(maker_fee: Money, taker_fee: Money) -> Result<Self> {
+        if maker_fee.currency != taker_fee.currency {
+            bail!(
+                "Maker fee currency {} does not match taker fee currency {}",
+                maker_fee.currency,
+                taker_fee.currency,
+            )
+        }
+        Ok(Self {
+            maker_fee,
+            taker_fee,
+        })
+    }
+}
+
+impl FeeModel for PerContractFeeModel {
+    fn get_commission(
+        &self,
+        _instrument: &dyn Instrument,
+        last_qty: Quantity,
+        _last_px: Price,
+        liquidity_side: LiquiditySide,
+        _use_quote_for_inverse: Option<bool>,
+        _ts_event: UnixNanos,
+    ) -> Result<Money> {
+        check_liquidity_side(liquidity_side)?;
+        let fee = match liquidity_side {
+            LiquiditySide::Maker => self.maker_fee,
+            _ => self.taker_fee,
+        };
+        Money::new(last_qty.as_f64() * fee.as_f64(), fee.currency)
+    }
+}
+
+/// Charges a percentage of the fill notional value, independent of the instrument's fees.
+#[derive(Clone, Copy, Debug)]
+pub struct PercentageFeeModel {
+    pub maker_fee: Decimal,
+    pub taker_fee: Decimal,
+}
+
+impl PercentageFeeModel {
+    /// Initializes a new `PercentageFeeModel` instance.
+    #[must_use]
+    pub fn new(maker_fee: Decimal, taker_fee: Decimal) -> Self {
+        Self {
+            maker_fee,
+            taker_fee,
+        }
+    }
+}
+
+impl FeeModel for PercentageFeeModel {
+    fn get_commission(
+        &self,
+        instrument: &dyn Instrument,
+        last_qty: Quantity,
+        last_px: Price,
+        liquidity_side: LiquiditySide,
+        use_quote_for_inverse: Option<bool>,
+        _ts_event: UnixNanos,
+    ) -> Result<Money> {
+        check_liquidity_side(liquidity_side)?;
+        let rate = match liquidity_side {
+            LiquiditySide::Maker => self.maker_fee,
+            _ => self.taker_fee,
+        };
+        notional_commission(instrument, last_qty, last_px, rate, use_quote_for_inverse)
+    }
+}
+
+/// Represents a single fee tier, active once the rolling volume reaches `min_volume`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FeeTier {
+    pub min_volume: f64,
+    pub maker_fee: Decimal,
+    pub taker_fee: Decimal,
+}
+
+impl FeeTier {
+    /// Initializes a new `FeeTier` instance.
+    #[must_use]
+    pub fn new(min_volume: f64, maker_fee: Decimal, taker_fee: Decimal) -> Self {
+        Self {
+            min_volume,
+            maker_fee,
+            taker_fee,
+        }
+    }
+}
+
+/// Charges percentage fees selected from tiers by rolling traded volume.
+///
+/// Volume is measured as the quote currency notional of recorded fills within the
+/// rolling window (30 days by default).
+#[derive(Clone, Debug)]
+pub struct TieredVolumeFeeModel {
+    tiers: Vec<FeeTier>,
+    window_ns: u64,
+    fills: VecDeque<(UnixNanos, f64)>,
+}
+
+impl TieredVolumeFeeModel {
+    /// Initializes a new `TieredVolumeFeeModel` instance.
+    ///
+    /// # Errors
+    ///
+    /// If `tiers` is empty, or any tier has a negative `min_volume`.
+    pub fn new(mut tiers: Vec<FeeTier>, window_ns: Option<u64>) -> Result<Self> {
+        if tiers.is_empty() {
+            bail!("`tiers` must not be empty")
+        }
+        if tiers.iter().any(|tier| tier.min_volume < 0.0) {
+            bail!("`min_volume` must not be negative")
+        }
+        tiers.sort_by(|a, b| a.min_volume.total_cmp(&b.min_volume));
+        Ok(Self {
+            tiers,
+            window_ns: window_ns.unwrap_or(THIRTY_DAYS_NS),
+            fills: VecDeque::new(),
+        })
+    }
+
+    /// Returns the traded volume within the rolling window ending at `ts_now`.
+    #[must_use]
+    pub fn rolling_volume(&self, ts_now: UnixNanos) -> f64 {
+        let cutoff = ts_now.saturating_sub(self.window_ns);
+        self.fills
+            .iter()
+            .filter(|(ts, _)| *ts >= cutoff && *ts <= ts_now)
+            .map(|(_, notional)| notional)
+            .sum()
+    }
+
+    /// Returns the tier applicable to the rolling volume at `ts_now`.
+    ///
+    /// Falls back to the lowest tier when the volume is below every threshold.
+    #[must_use]
+    pub fn current_tier(&self, ts_now: UnixNanos) -> &FeeTier {
+        let volume = self.rolling_volume(ts_now);
+        self.tiers
+            .iter()
+            .rev()
+            .find(|tier| volume >= tier.min_volume)
+            .unwrap_or(&self.tiers[0])
+    }
+
+    fn evict_expired(&mut self, ts_now: UnixNanos) {
+        let cutoff = ts_now.saturating_sub(self.window_ns);
+        while let Some((ts, _)) = self.fills.front() {
+            if *ts >= cutoff {
+                break;
+            }
+            self.fills.pop_front();
+        }
+    }
+}
+
+impl FeeModel for TieredVolumeFeeModel {
+    fn get_commission(
+        &self,
+        instrument: &dyn Instrument,
+        last_qty: Quantity,
+        last_px: Price,
+        liquidity_side: LiquiditySide,
+        use_quote_for_inverse: Option<bool>,
+        ts_event: UnixNanos,
+    ) -> Result<Money> {
+        check_liquidity_side(liquidity_side)?;
+        // Fills outside the window ending at this fill no longer count towards the tier
+        let tier = self.current_tier(ts_event);
+        let rate = match liquidity_side {
+            LiquiditySide::Maker => tier.maker_fee,
+            _ => tier.taker_fee,
+        };
+        notional_commission(instrument, last_qty, last_px, rate, use_quote_for_inverse)
+    }
+
+    fn record_fill(&mut self, instrument: &dyn Instrument, fill: &OrderFilled) {
+        let notional = instrument
+            .calculate_notional_value(fill.last_qty, fill.last_px, Some(true))
+            .as_f64();
+        self.fills.push_back((fill.ts_event, notional));
+        self.evict_expired(fill.ts_event);
+    }
+
+    fn reset(&mut self) {
+        self.fills.clear();
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Tests
+////////////////////////////////////////////////////////////////////////////////
+#[cfg(test)]
+mod tests {
+    use nautilus_model::{
+        events::order::stubs::*,
+        identifiers::stubs::*,
+        instruments::{currency_pair::CurrencyPair, stubs::*},
+    };
+    use rstest::rstest;
+    use rust_decimal_macros::dec;
+
+    use super::*;
+
+    fn tiered_model() -> TieredVolumeFeeModel {
+        TieredVolumeFeeModel::new(
+            vec![
+                FeeTier::new(1_000_000.0, dec!(0.0001), dec!(0.0003)),
+                FeeTier::new(0.0, dec!(0.0002), dec!(0.0004)),
+            ],
+            None,
+        )
+        .unwrap()
+    }
+
+    #[rstest]
+    fn test_maker_taker_uses_instrument_fees(audusd_sim: CurrencyPair) {
+        let commission = MakerTakerFeeModel
+            .get_commission(
+                &audusd_sim,
+                Quantity::from("1500000"),
+                Price::from("0.8005"),
+                LiquiditySide::Taker,
+                None,
+                0,
+            )
+            .unwrap();
+        assert_eq!(commission, Money::from("24.02 USD"));
+    }
+
+    #[rstest]
+    fn test_no_liquidity_side_is_error(audusd_sim: CurrencyPair) {
+        let result = MakerTakerFeeModel.get_commission(
+            &audusd_sim,
+            Quantity::from("100000"),
+            Price::from("0.80000"),
+            LiquiditySide::NoLiquiditySide,
+            None,
+            0,
+        );
+        assert!(result.is_err());
+    }
+
+    #[rstest]
+    #[case(LiquiditySide::Maker, "-2.00 USD")]
+    #[case(LiquiditySide::Taker, "5.00 USD")]
+    fn test_per_contract_fees(
+        audusd_sim: CurrencyPair,
+        #[case] liquidity_side: LiquiditySide,
+        #[case] expected: &str,
+    ) {
+        let model =
+            PerContractFeeModel::new(Money::from("-0.20 USD"), Money::from("0.50 USD")).unwrap();
+        let commission = model
+            .get_commission(
+                &audusd_sim,
+                Quantity::from("10"),
+                Price::from("0.80000"),
+                liquidity_side,
+                None,
+                0,
+            )
+            .unwrap();
+        assert_eq!(commission, Money::from(expected));
+    }
+
+    #[rstest]
+    fn test_per_contract_currency_mismatch() {
+        let result = PerContractFeeModel::new(Money::from("0.20 USD"), Money::from("0.50 AUD"));
+        assert!(result.is_err());
+    }
+
+    #[rstest]
+    #[case(LiquiditySide::Maker, "-10.00 USD")]
+    #[case(LiquiditySide::Taker, "20.00 USD")]
+    fn test_percentage_fees(
+        audusd_sim: CurrencyPair,
+        #[case] liquidity_side: LiquiditySide,
+        #[case] expected: &str,
+    ) {
+        let model = PercentageFeeModel::new(dec!(-0.0001), dec!(0.0002));
+        let commission = model
+            .get_commission(
+                &audusd_sim,
+                Quantity::from("100000"),
+                Price::from("1.00000"),
+                liquidity_side,
+                None,
+                0,
+            )
+            .unwrap();
+        assert_eq!(commission, Money::from(expected));
+    }
+
+    #[rstest]
+    fn test_tiered_requires_tiers() {
+        assert!(TieredVolumeFeeModel::new(vec![], None).is_err());
+    }
+
+    #[rstest]
+    fn test_tiered_selects_tier_by_rolling_volume(
+        audusd_sim: CurrencyPair,
+        mut order_filled: OrderFilled,
+    ) {
+        let mut model = tiered_model();
+        let qty = Quantity::from("100000");
+        let px = Price::from("1.00000");
+        let commission = model
+            .get_commission(&audusd_sim, qty, px, LiquiditySide::Taker, None, 0)
+            .unwrap();
+        assert_eq!(commission, Money::from("40.00 USD"));
+
+        order_filled.last_qty = Quantity::from("1000000");
+        order_filled.last_px = px;
+        order_filled.ts_event = 0;
+        model.record_fill(&audusd_sim, &order_filled);
+        assert_eq!(model.rolling_volume(0), 1_000_000.0);
+
+        let commission = model
+            .get_commission(&audusd_sim, qty, px, LiquiditySide::Taker, None, 0)
+            .unwrap();
+        assert_eq!(commission, Money::from("30.00 USD"));
+    }
+
+    #[rstest]
+    fn test_tiered_volume_expires_outside_window(
+        audusd_sim: CurrencyPair,
+        mut order_filled: OrderFilled,
+    ) {
+        let mut model = tiered_model();
+        order_filled.last_qty = Quantity::from("1000000");
+        order_filled.last_px = Price::from("1.00000");
+        order_filled.ts_event = 0;
+        model.record_fill(&audusd_sim, &order_filled);
+        assert_eq!(model.current_tier(0).min_volume, 1_000_000.0);
+
+        order_filled.last_qty = Quantity::from("1000");
+        order_filled.ts_event = THIRTY_DAYS_NS + 1;
+        model.record_fill(&audusd_sim, &order_filled);
+        assert_eq!(model.rolling_volume(THIRTY_DAYS_NS + 1), 1_000.0);
+        assert_eq!(model.current_tier(THIRTY_DAYS_NS + 1).min_volume, 0.0);
+    }
+
+    #[rstest]
+    fn test_tiered_commission_after_quiet_period_uses_fill_timestamp(
+        audusd_sim: CurrencyPair,
+        mut order_filled: OrderFilled,
+    ) {
+        let mut model = tiered_model();
+        order_filled.last_qty = Quantity::from("1000000");
+        order_filled.last_px = Price::from("1.00000");
+        order_filled.ts_event = 0;
+        model.record_fill(&audusd_sim, &order_filled);
+
+        // No fills are recorded during the window, so the volume tier has lapsed
+        let commission = model
+            .get_commission(
+                &audusd_sim,
+                Quantity::from("100000"),
+                Price::from("1.00000"),
+                LiquiditySide::Taker,
+                None,
+                THIRTY_DAYS_NS + 1,
+            )
+            .unwrap();
+        assert_eq!(commission, Money::from("40.00 USD"));
+    }
+
+    #[rstest]
+    fn test_tiered_reset_clears_volume(audusd_sim: CurrencyPair, mut order_filled: OrderFilled) {
+        let mut model = tiered_model();
+        order_filled.last_qty = Quantity::from("1000000");
+        order_filled.last_px = Price::from("1.00000");
+        order_filled.ts_event = 0;
+        model.record_fill(&audusd_sim, &order_filled);
+
+        model.reset();
+
+        assert_eq!(model.rolling_volume(0), 0.0);
+    }
+}
@@ -0,0 +1,487 @@
+// -------------------------------------------------------------------------------------------------
+//  Copyright (C) 2015-2024 Nautech Systems Pty Ltd. All rights reserved.
+//  https://nautechsystems.io
+//
+//  Licensed under the GNU Lesser General Public License Version 3.0 (the "License");
+//  You may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at https://www.gnu.org/licenses/lgpl-3.0.en.html
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+// -------------------------------------------------------------------------------------------------
+
+//! Margin models used to calculate initial and maintenance margin requirements.
+//!
+//! A [`MarginModel`] is consulted by a `MarginAccount` in place of applying a
+//! flat leverage to the instrument's margin rates.
+
+use std::{collections::HashMap, fmt::Debug};
+
+use anyhow::{bail, Result};
+use nautilus_model::{
+    identifiers::instrument_id::InstrumentId,
+    instruments::Instrument,
+    types::{money::Money, price::Price, quantity::Quantity},
+};
+use rust_decimal::prelude::ToPrimitive;
+
+/// Provides initial and maintenance margin calculations.
+pub trait MarginModel: Debug + Send {
+    /// Returns the initial margin required to open `quantity` at `price`.
+    ///
+    /// # Errors
+    ///
+    /// If the margin cannot be calculated for the instrument.
+    fn calculate_initial_margin(
+        &self,
+        instrument: &dyn Instrument,
+        quantity: Quantity,
+        price: Price,
+        leverage: f64,
+        use_quote_for_inverse: Option<bool>,
+    ) -> Result<Money>;
+
+    /// Returns the maintenance margin required to hold `quantity` at `price`.
+    ///
+    /// # Errors
+    ///
+    /// If the margin cannot be calculated for the instrument.
+    fn calculate_maintenance_margin(
+        &self,
+        instrument: &dyn Instrument,
+        quantity: Quantity,
+        price: Price,
+        leverage: f64,
+        use_quote_for_inverse: Option<bool>,
+    ) -> Result<Money>;
+
+    /// Returns the initial and maintenance margin for the combined long and short
+    /// positions held in a single instrument.
+    ///
+    /// By default positions are margined gross (long plus short quantity).
+    ///
+    /// # Errors
+    ///
+    /// If the margin cannot be calculated for the instrument.
+    fn calculate_position_margins(
+        &self,
+        instrument: &dyn Instrument,
+        long_qty: Quantity,
+        short_qty: Quantity,
+        price: Price,
+        leverage: f64,
+        use_quote_for_inverse: Option<bool>,
+    ) -> Result<(Money, Money)> {
+        let gross = Quantity::from_raw(long_qty.raw + short_qty.raw, instrument.size_precision())?;
+        Ok((
+            self.calculate_initial_margin(
+                instrument,
+                gross,
+                price,
+                leverage,
+                use_quote_for_inverse,
+            )?,
+            self.calculate_maintenance_margin(
+                instrument,
+                gross,
+                price,
+                leverage,
+                use_quote_for_inverse,
+            )?,
+        ))
+    }
+}
+
+/// Calculates margin from the leveraged notional value and the instrument's margin rates.
+///
+/// The taker fee is included in the requirement (twice for initial margin, to cover
+/// the round trip). This is the default margin model.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct LeverageMarginModel;
+
+impl LeverageMarginModel {
+    fn calculate_margin(
+        instrument: &dyn Instrument,
+        quantity: Quantity,
+        price: Price,
+        leverage: f64,
+        rate: f64,
+        fee_multiplier: f64,
+        use_quote_for_inverse: Option<bool>,
+    ) -> Result<Money> {
+        let notional = instrument.calculate_notional_value(quantity, price, use_quote_for_inverse);
+        let adjusted_notional = notional.as_f64() / leverage;
+        let mut margin = adjusted_notional * rate;
+        margin += adjusted_notional * instrument.taker_fee().to_f64().unwrap() * fee_multiplier;
+        Money::new(margin, notional.currency)
+    }
+}
+
+impl MarginModel for LeverageMarginModel {
+    fn calculate_initial_margin(
+        &self,
+        instrument: &dyn Instrument,
+        quantity: Quantity,
+        price: Price,
+        leverage: f64,
+        use_quote_for_inverse: Option<bool>,
+    ) -> Result<Money> {
+        let rate = instrument.margin_init().to_f64().unwrap();
+        Self::calculate_margin(
+            instrument,
+            quantity,
+            price,
+            leverage,
+            rate,
+            2.0,
+            use_quote_for_inverse,
+        )
+    }
+
+    fn calculate_maintenance_margin(
+        &self,
+        instrument: &dyn Instrument,
+        quantity: Quantity,
+        price: Price,
+        leverage: f64,
+        use_quote_for_inverse: Option<bool>,
+    ) -> Result<Money> {
+        let rate = instrument.margin_maint().to_f64().unwrap();
+        Self::calculate_margin(
+            instrument,
+            quantity,
+            price,
+            leverage,
+            rate,
+            1.0,
+            use_quote_for_inverse,
+        )
+    }
+}
+
+/// Represents exchange-set margin amounts per contract.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FixedMargin {
+    pub initial: Money,
+    pub maintenance: Money,
+}
+
+/// Calculates margin as a fixed amount per contract, as set by the exchange
+/// (similar to SPAN scanning-risk requirements for futures).
+///
+/// Leverage and price do not affect the requirement.
+#[derive(Clone, Debug, Default)]
+pub struct FixedMarginModel {
+    margins: HashMap<InstrumentId, FixedMargin>,
+}
+
+impl FixedMarginModel {
+    /// Initializes a new `FixedMarginModel` instance.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the per contract margin amounts for the given instrument.
+    ///
+    /// # Errors
+    ///
+    /// If the initial and maintenance margins are not in the same currency.
+    pub fn set_margin(
+        &mut self,
+        instrument_id: InstrumentId,
+        initial: Money,
+        maintenance: Money,
+    ) -> Result<()> {
+        if initial.currency != maintenance.currency {
+            bail!(
+                "Initial margin currency {} does not match maintenance margin currency {}",
+                initial.currency,
+                maintenance.currency,
+            )
+        }
+        self.margins.insert(
+            instrument_id,
+            FixedMargin {
+                initial,
+                maintenance,
+            },
+        );
+        Ok(())
+    }
+
+    /// Returns the per contract margin amounts for the given instrument (if set).
+    #[must_use]
+    pub fn get_margin(&self, instrument_id: &InstrumentId) -> Option<FixedMargin> {
+        self.margins.get(instrument_id).copied()
+    }
+
+    fn margin_for(&self, instrument: &dyn Instrument) -> Result<FixedMargin> {
+        match self.get_margin(&instrument.id()) {
+            Some(margin) => Ok(margin),
+            None => bail!("No fixed margin set for {}", instrument.id()),
+        }
+    }
+}
+
+impl MarginModel for FixedMarginModel {
+    fn calculate_initial_margin(
+        &self,
+        instrument: &dyn Instrument,
+        quantity: Quantity,
+        _price: Price,
+        _leverage: f64,
+        _use_quote_for_inverse: Option<bool>,
+    ) -> Result<Money> {
+        let margin = self.margin_for(instrument)?.initial;
+        Money::new(quantity.as_f64() * margin.as_f64(), margin.currency)
+    }
+
+    fn calculate_maintenance_margin(
+        &self,
+        instrument: &dyn Instrument,
+        quantity: Quantity,
+        _price: Price,
+        _leverage: f64,
+        _use_quote_for_inverse: Option<bool>,
+    ) -> Result<Money> {
+        let margin = self.margin_for(instrument)?.maintenance;
+        Money::new(quantity.as_f64() * margin.as_f64(), margin.currency)
+    }
+}
+
+/// Nets hedged long and short positions in the same instrument before applying an
+/// underlying margin model.
+///
+/// The offsetting (hedged) quantity is charged at `hedge_rate` of a single leg's
+/// requirement, where a rate of zero means hedged positions require no margin.
+#[derive(Debug)]
+pub struct PortfolioOffsetMarginModel {
+    pub hedge_rate: f64,
+    inner: Box<dyn MarginModel>,
+}
+
+impl PortfolioOffsetMarginModel {
+    /// Initializes a new `PortfolioOffsetMarginModel` instance.
+    ///
+    /// # Errors
+    ///
+    /// If `hedge_rate` is not in the range [0, 1].
+    pub fn new(inner: Box<dyn MarginModel>, hedge_rate: f64) -> Result<Self> {
+        nautilus_core::correctness::check_f64_in_range_inclusive(
+            hedge_rate,
+            0.0,
+            1.0,
+            "hedge_rate",
+        )?;
+        Ok(Self { hedge_rate, inner })
+    }
+}
+
+impl MarginModel for PortfolioOffsetMarginModel {
+    fn calculate_initial_margin(
+        &self,
+        instrument: &dyn Instrument,
+        quantity: Quantity,
+        price: Price,
+        leverage: f64,
+        use_quote_for_inverse: Option<bool>,
+    ) -> Result<Money> {
+        self.inner.calculate_initial_margin(
+            instrument,
+            quantity,
+            price,
+            leverage,
+            use_quote_for_inverse,
+        )
+    }
+
+    fn calculate_maintenance_margin(
+        &self,
+        instrument: &dyn Instrument,
+        quantity: Quantity,
+        price: Price,
+        leverage: f64,
+        use_quote_for_inverse: Option<bool>,
+    ) -> Result<Money> {
+        self.inner.calculate_maintenance_margin(
+            instrument,
+            quantity,
+            price,
+            leverage,
+            use_quote_for_inverse,
+        )
+    }
+
+    fn calculate_position_margins(
+        &self,
+        instrument: &dyn Instrument,
+        long_qty: Quantity,
+        short_qty: Quantity,
+        price: Price,
+        leverage: f64,
+        use_quote_for_inverse: Option<bool>,
+    ) -> Result<(Money, Money)> {
+        let precision = instrument.size_precision();
+        let net = Quantity::from_raw(long_qty.raw.abs_diff(short_qty.raw), precision)?;
+        let hedged = Quantity::from_raw(long_qty.raw.min(short_qty.raw), precision)?;
+
+        let mut initial =
+            self.calculate_initial_margin(instrument, net, price, leverage, use_quote_for_inverse)?;
+        let mut maintenance = self.calculate_maintenance_margin(
+            instrument,
+            net,
+            price,
+            leverage,
+            use_quote_for_inverse,
+        )?;
+
+        if self.hedge_rate > 0.0 && hedged.raw > 0 {
+            let hedged_initial = self.calculate_initial_margin(
+                instrument,
+                hedged,
+                price,
+                leverage,
+                use_quote_for_inverse,
+            )?;
+            let hedged_maintenance = self.calculate_maintenance_margin(
+                instrument,
+                hedged,
+                price,
+                leverage,
+                use_quote_for_inverse,
+            )?;
+            initial = Money::new(
+                initial.as_f64() + hedged_initial.as_f64() * self.hedge_rate,
+                initial.currency,
+            )?;
+            maintenance = Money::new(
+                maintenance.as_f64() + hedged_maintenance.as_f64() * self.hedge_rate,
+                maintenance.currency,
+            )?;
+        }
+
+        Ok((initial, maintenance))
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Tests
+////////////////////////////////////////////////////////////////////////////////
+#[cfg(test)]
+mod tests {
+    use nautilus_model::{
+        instruments::{currency_pair::CurrencyPair, stubs::*},
+        types::currency::Currency,
+    };
+    use rstest::rstest;
+
+    use super::*;
+
+    fn fixed_model(instrument: &CurrencyPair) -> FixedMarginModel {
+        let mut model = FixedMarginModel::new();
+        model
+            .set_margin(
+                instrument.id,
+                Money::from("2.00 USD"),
+                Money::from("1.50 USD"),
+            )
+            .unwrap();
+        model
+    }
+
+    #[rstest]
+    fn test_leverage_margin_model(audusd_sim: CurrencyPair) {
+        let margin = LeverageMarginModel
+            .calculate_initial_margin(
+                &audusd_sim,
+                Quantity::from("100000"),
+                Price::from("0.80000"),
+                10.0,
+                None,
+            )
+            .unwrap();
+        // Notional 80,000 / 10 leverage = 8,000 (with 0.00002 taker fee charged twice)
+        let expected = 8_000.0 * audusd_sim.margin_init().to_f64().unwrap() + 8_000.0 * 0.00004;
+        assert_eq!(margin, Money::new(expected, Currency::USD()).unwrap());
+    }
+
+    #[rstest]
+    fn test_fixed_margin_model(audusd_sim: CurrencyPair) {
+        let model = fixed_model(&audusd_sim);
+        let qty = Quantity::from("10");
+        let px = Price::from("0.80000");
+        let initial = model
+            .calculate_initial_margin(&audusd_sim, qty, px, 50.0, None)
+            .unwrap();
+        let maintenance = model
+            .calculate_maintenance_margin(&audusd_sim, qty, px, 50.0, None)
+            .unwrap();
+        assert_eq!(initial, Money::from("20.00 USD"));
+        assert_eq!(maintenance, Money::from("15.00 USD"));
+    }
+
+    #[rstest]
+    fn test_fixed_margin_model_missing_instrument(audusd_sim: CurrencyPair) {
+        let model = FixedMarginModel::new();
+        let result = model.calculate_initial_margin(
+            &audusd_sim,
+            Quantity::from("10"),
+            Price::from("0.80000"),
+            1.0,
+            None,
+        );
+        assert!(result.is_err());
+    }
+
+    #[rstest]
+    fn test_gross_position_margins(audusd_sim: CurrencyPair) {
+        let model = fixed_model(&audusd_sim);
+        let (initial, maintenance) = model
+            .calculate_position_margins(
+                &audusd_sim,
+                Quantity::from("10"),
+                Quantity::from("4"),
+                Price::from("0.80000"),
+                1.0,
+                None,
+            )
+            .unwrap();
+        assert_eq!(initial, Money::from("28.00 USD"));
+        assert_eq!(maintenance, Money::from("21.00 USD"));
+    }
+
+    #[rstest]
+    #[case(0.0, "12.00 USD", "9.00 USD")]
+    #[case(0.5, "16.00 USD", "12.00 USD")]
+    fn test_portfolio_offset_position_margins(
+        audusd_sim: CurrencyPair,
+        #[case] hedge_rate: f64,
+        #[case] expected_initial: &str,
+        #[case] expected_maintenance: &str,
+    ) {
+        let model = PortfolioOffsetMarginModel::new(Box::new(fixed_model(&audusd_sim)), hedge_rate)
+            .unwrap();
+        let (initial, maintenance) = model
+            .calculate_position_margins(
+                &audusd_sim,
+                Quantity::from("10"),
+                Quantity::from("4"),
+                Price::from("0.80000"),
+                1.0,
+                None,
+            )
+            .unwrap();
+        assert_eq!(initial, Money::from(expected_initial));
+        assert_eq!(maintenance, Money::from(expected_maintenance));
+    }
+
+    #[rstest]
+    fn test_portfolio_offset_invalid_hedge_rate(audusd_sim: CurrencyPair) {
+        let result = PortfolioOffsetMarginModel::new(Box::new(fixed_model(&audusd_sim)), 1.5);
+        assert!(result.is_err());
+    }
+}
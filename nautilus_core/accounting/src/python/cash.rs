@@ -16,6 +16,7 @@
 use std::collections::HashMap;
 
 use nautilus_core::python::to_pyvalue_err;
+use nautilus_core::time::UnixNanos;
 use nautilus_model::enums::{LiquiditySide, OrderSide};
 use nautilus_model::events::account::state::AccountState;
 use nautilus_model::events::order::filled::OrderFilled;
@@ -235,11 +236,16 @@ impl CashAccount {
         last_px: Price,
         liquidity_side: LiquiditySide,
         use_quote_for_inverse: Option<bool>,
+        ts_event: Option<UnixNanos>,
         py: Python,
     ) -> PyResult<Money> {
         if liquidity_side == LiquiditySide::NoLiquiditySide {
             return Err(to_pyvalue_err("Invalid liquidity side"));
         }
+        // Without a fill time, fees are evaluated as of the latest account state
+        let ts_event = ts_event
+            .or_else(|| self.last_event().map(|event| event.ts_event))
+            .unwrap_or_default();
         // extract instrument from PyObject
         let instrument_type = instrument
             .getattr(py, "instrument_type")?
@@ -253,6 +259,7 @@ impl CashAccount {
                     last_px,
                     liquidity_side,
                     use_quote_for_inverse,
+                    ts_event,
                 )
                 .unwrap())
         } else if instrument_type == "CurrencyPair" {
@@ -264,6 +271,7 @@ impl CashAccount {
                     last_px,
                     liquidity_side,
                     use_quote_for_inverse,
+                    ts_event,
                 )
                 .unwrap())
         } else if instrument_type == "CryptoPerpetual" {
@@ -275,6 +283,7 @@ impl CashAccount {
                     last_px,
                     liquidity_side,
                     use_quote_for_inverse,
+                    ts_event,
                 )
                 .unwrap())
         } else if instrument_type == "Equity" {
@@ -286,6 +295,7 @@ impl CashAccount {
                     last_px,
                     liquidity_side,
                     use_quote_for_inverse,
+                    ts_event,
                 )
                 .unwrap())
         } else if instrument_type == "FuturesContract" {
@@ -297,6 +307,7 @@ impl CashAccount {
                     last_px,
                     liquidity_side,
                     use_quote_for_inverse,
+                    ts_event,
                 )
                 .unwrap())
         } else if instrument_type == "OptionsContract" {
@@ -308,6 +319,7 @@ impl CashAccount {
                     last_px,
                     liquidity_side,
                     use_quote_for_inverse,
+                    ts_event,
                 )
                 .unwrap())
         } else {
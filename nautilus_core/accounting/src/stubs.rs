@@ -50,7 +50,7 @@ pub fn test_position_long(mut order_factory: OrderFactory, audusd_sim: CurrencyP
         None,
         None,
     );
-    Position::new(audusd_sim, order_filled).unwrap()
+    Position::new(&audusd_sim, order_filled).unwrap()
 }
 
 #[fixture]
@@ -77,5 +77,5 @@ pub fn test_position_short(mut order_factory: OrderFactory, audusd_sim: Currency
         None,
         None,
     );
-    Position::new(audusd_sim, order_filled).unwrap()
+    Position::new(&audusd_sim, order_filled).unwrap()
 }
@@ -0,0 +1,201 @@
+// -------------------------------------------------------------------------------------------------
+//  Copyright (C) 2015-2024 Nautech Systems Pty Ltd. All rights reserved.
+//  https://nautechsystems.io
+//
+//  Licensed under the GNU Lesser General Public License Version 3.0 (the "License");
+//  You may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at https://www.gnu.org/licenses/lgpl-3.0.en.html
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+// -------------------------------------------------------------------------------------------------
+
+use nautilus_model::{enums::OrderSide, types::price::Price};
+
+/// An order (or aggregated book liquidity) participating in an auction.
+///
+/// A `price` of `None` represents a market order, which is executable at any price.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AuctionOrder {
+    pub side: OrderSide,
+    pub price: Option<Price>,
+    pub quantity_raw: u64,
+}
+
+impl AuctionOrder {
+    #[must_use]
+    pub fn new(side: OrderSide, price: Option<Price>, quantity_raw: u64) -> Self {
+        Self {
+            side,
+            price,
+            quantity_raw,
+        }
+    }
+
+    /// Returns whether the order is executable at the given `price`.
+    #[must_use]
+    pub fn is_executable_at(&self, price: Price) -> bool {
+        match (self.side, self.price) {
+            (_, None) => true,
+            (OrderSide::Buy, Some(limit)) => limit >= price,
+            (OrderSide::Sell, Some(limit)) => limit <= price,
+            (OrderSide::NoOrderSide, _) => false,
+        }
+    }
+}
+
+/// The result of uncrossing an auction.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AuctionUncross {
+    /// The single clearing price all auction executions occur at.
+    pub price: Price,
+    /// The executed volume (raw quantity) at the clearing price.
+    pub volume_raw: u64,
+    /// The buy volume (raw quantity) executable at the clearing price.
+    pub buy_volume_raw: u64,
+    /// The sell volume (raw quantity) executable at the clearing price.
+    pub sell_volume_raw: u64,
+}
+
+impl AuctionUncross {
+    /// Returns the unexecuted volume at the clearing price (positive for excess buys).
+    #[must_use]
+    pub fn imbalance_raw(&self) -> i128 {
+        i128::from(self.buy_volume_raw) - i128::from(self.sell_volume_raw)
+    }
+}
+
+/// Returns the auction uncross for the given `orders`, or `None` if they do not cross.
+///
+/// The clearing price is chosen from the limit prices of the orders as the price which
+/// maximizes executed volume. Ties are broken by the smallest imbalance, then by the
+/// price closest to `reference_price`, then by the lowest price. If only market orders
+/// participate the auction clears at the `reference_price`.
+#[must_use]
+pub fn uncross(orders: &[AuctionOrder], reference_price: Option<Price>) -> Option<AuctionUncross> {
+    let mut candidates: Vec<Price> = orders.iter().filter_map(|order| order.price).collect();
+    if candidates.is_empty() {
+        candidates.extend(reference_price);
+    }
+    candidates.sort();
+    candidates.dedup();
+
+    let mut best: Option<AuctionUncross> = None;
+    for price in candidates {
+        let volume_at = |side: OrderSide| -> u64 {
+            orders
+                .iter()
+                .filter(|order| order.side == side && order.is_executable_at(price))
+                .map(|order| order.quantity_raw)
+                .sum()
+        };
+        let buy_volume_raw = volume_at(OrderSide::Buy);
+        let sell_volume_raw = volume_at(OrderSide::Sell);
+        let candidate = AuctionUncross {
+            price,
+            volume_raw: buy_volume_raw.min(sell_volume_raw),
+            buy_volume_raw,
+            sell_volume_raw,
+        };
+        if candidate.volume_raw == 0 {
+            continue;
+        }
+
+        let is_better = match best {
+            None => true,
+            Some(best) => {
+                let reference_distance = |uncross: &AuctionUncross| {
+                    reference_price.map_or(0, |reference| (uncross.price.raw - reference.raw).abs())
+                };
+                (
+                    candidate.volume_raw,
+                    std::cmp::Reverse(candidate.imbalance_raw().abs()),
+                    std::cmp::Reverse(reference_distance(&candidate)),
+                ) > (
+                    best.volume_raw,
+                    std::cmp::Reverse(best.imbalance_raw().abs()),
+                    std::cmp::Reverse(reference_distance(&best)),
+                )
+            }
+        };
+        if is_better {
+            best = Some(candidate);
+        }
+    }
+    best
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Tests
+////////////////////////////////////////////////////////////////////////////////
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    fn buy(price: Option<&str>, quantity_raw: u64) -> AuctionOrder {
+        AuctionOrder::new(OrderSide::Buy, price.map(Price::from), quantity_raw)
+    }
+
+    fn sell(price: Option<&str>, quantity_raw: u64) -> AuctionOrder {
+        AuctionOrder::new(OrderSide::Sell, price.map(Price::from), quantity_raw)
+    }
+
+    #[rstest]
+    fn test_uncross_when_not_crossed() {
+        let orders = [buy(Some("99.00"), 100), sell(Some("100.00"), 100)];
+        assert_eq!(uncross(&orders, None), None);
+    }
+
+    #[rstest]
+    fn test_uncross_maximizes_executed_volume() {
+        let orders = [
+            buy(Some("101.00"), 100),
+            buy(Some("100.00"), 200),
+            sell(Some("99.00"), 150),
+            sell(Some("100.00"), 100),
+        ];
+        let result = uncross(&orders, None).unwrap();
+        assert_eq!(result.price, Price::from("100.00"));
+        assert_eq!(result.volume_raw, 250);
+        assert_eq!(result.imbalance_raw(), 50);
+    }
+
+    #[rstest]
+    fn test_uncross_breaks_tie_by_smallest_imbalance() {
+        let orders = [
+            buy(Some("101.00"), 100),
+            buy(Some("100.00"), 50),
+            sell(Some("99.00"), 100),
+        ];
+        // 100 executes at both 100.00 and 101.00, with no imbalance at 101.00
+        let result = uncross(&orders, None).unwrap();
+        assert_eq!(result.price, Price::from("101.00"));
+        assert_eq!(result.volume_raw, 100);
+        assert_eq!(result.imbalance_raw(), 0);
+    }
+
+    #[rstest]
+    fn test_uncross_breaks_tie_by_reference_price() {
+        let orders = [buy(Some("101.00"), 100), sell(Some("99.00"), 100)];
+        let result = uncross(&orders, Some(Price::from("100.90"))).unwrap();
+        assert_eq!(result.price, Price::from("101.00"));
+
+        let result = uncross(&orders, Some(Price::from("99.10"))).unwrap();
+        assert_eq!(result.price, Price::from("99.00"));
+    }
+
+    #[rstest]
+    fn test_uncross_market_orders_only_uses_reference_price() {
+        let orders = [buy(None, 100), sell(None, 60)];
+        assert_eq!(uncross(&orders, None), None);
+
+        let result = uncross(&orders, Some(Price::from("100.00"))).unwrap();
+        assert_eq!(result.price, Price::from("100.00"));
+        assert_eq!(result.volume_raw, 60);
+    }
+}
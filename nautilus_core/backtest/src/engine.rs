@@ -13,12 +13,40 @@
 //  limitations under the License.
 // -------------------------------------------------------------------------------------------------
 
-use std::ops::{Deref, DerefMut};
+use std::{
+    cell::RefCell,
+    collections::VecDeque,
+    ops::{Deref, DerefMut},
+    rc::Rc,
+};
 
-use nautilus_common::{clock::TestClock, ffi::clock::TestClock_API, timer::TimeEventHandler};
+use anyhow::{anyhow, bail, Result};
+use indexmap::IndexMap;
+use nautilus_common::{
+    clock::TestClock,
+    ffi::clock::TestClock_API,
+    msgbus::{get_data_topic, MessageBus},
+    timer::TimeEventHandler,
+};
 use nautilus_core::{
     ffi::{cvec::CVec, parsing::u8_as_bool},
-    time::UnixNanos,
+    time::{AtomicTime, UnixNanos},
+    uuid::UUID4,
+};
+use nautilus_execution::messages::TradingCommand;
+use nautilus_model::{
+    data::{Data, HasTsInit},
+    events::order::event::OrderEvent,
+    identifiers::{client_order_id::ClientOrderId, trader_id::TraderId, venue::Venue},
+};
+use nautilus_persistence::backend::session::DataBackendSession;
+#[cfg(feature = "python")]
+use pyo3::{PyObject, Python};
+
+use crate::{
+    exchange::SimulatedExchange,
+    replay::{derive_seed, ReplayChecksum, StateChange},
+    result::{BacktestResult, BalanceSnapshot, OrderSummary},
 };
 
 /// Provides a means of accumulating and draining time event handlers.
@@ -58,6 +86,384 @@ impl Default for TimeEventAccumulator {
     }
 }
 
+/// A shared queue of trading commands to be executed by a [`BacktestEngine`].
+///
+/// Data subscribers hold a clone of the queue to send commands from within their
+/// callbacks, which the engine then routes to the simulated venues.
+pub type TradingCommandQueue = Rc<RefCell<VecDeque<TradingCommand>>>;
+
+/// Provides a backtest event loop which drives data, timers and order matching.
+///
+/// Data is consumed in `ts_init` order. Before each data point is processed the
+/// clock is advanced, stopping at every time event and every time a latent command
+/// or venue response is due. Each data point is then published on the message bus
+/// and processed by the simulated exchange for its venue, after which queued
+/// trading commands are executed.
+///
+/// Each engine keeps the atomic time it is created with in sync with its [`TestClock`].
+/// Simulated exchanges added to the engine should be created with the same time (see
+/// [`BacktestEngine::time`]), and separate engines should never share a time.
+pub struct BacktestEngine {
+    pub trader_id: TraderId,
+    pub clock: TestClock,
+    pub msgbus: MessageBus,
+    time: &'static AtomicTime,
+    accumulator: TimeEventAccumulator,
+    venues: IndexMap<Venue, SimulatedExchange>,
+    commands: TradingCommandQueue,
+    orders: IndexMap<ClientOrderId, OrderSummary>,
+    order_events: Vec<OrderEvent>,
+    iteration: usize,
+    backtest_start: Option<UnixNanos>,
+    backtest_end: Option<UnixNanos>,
+    random_seed: Option<u64>,
+    checksum: ReplayChecksum,
+}
+
+impl BacktestEngine {
+    /// Initializes a new `BacktestEngine` instance which keeps the given `time` in
+    /// sync with its [`TestClock`] (the simulated exchanges should share `time`).
+    #[must_use]
+    pub fn new(trader_id: TraderId, time: &'static AtomicTime) -> Self {
+        Self {
+            trader_id,
+            clock: TestClock::new(),
+            msgbus: MessageBus::new(trader_id, UUID4::new(), None, None),
+            time,
+            accumulator: TimeEventAccumulator::new(),
+            venues: IndexMap::new(),
+            commands: Rc::new(RefCell::new(VecDeque::new())),
+            orders: IndexMap::new(),
+            order_events: Vec::new(),
+            iteration: 0,
+            backtest_start: None,
+            backtest_end: None,
+            random_seed: None,
+            checksum: ReplayChecksum::new(),
+        }
+    }
+
+    /// Returns the atomic time kept in sync with the engine's clock.
+    #[must_use]
+    pub fn time(&self) -> &'static AtomicTime {
+        self.time
+    }
+
+    /// Sets the root `seed` which drives every random number generator in the backtest.
+    ///
+    /// Seeds derived from the root seed are applied to the fill and latency models
+    /// of each venue, and to the [`UUID4`] event IDs each venue generates, so that
+    /// runs with the same seed, data and commands emit identical events. Passing
+    /// `None` reverts event IDs to non-deterministic UUIDs.
+    pub fn set_random_seed(&mut self, seed: Option<u64>) {
+        self.random_seed = seed;
+        self.apply_random_seed();
+    }
+
+    /// Returns the rolling checksum over all order events, and account and position
+    /// state changes, emitted during the run.
+    #[must_use]
+    pub fn checksum(&self) -> u64 {
+        self.checksum.value()
+    }
+
+    /// Adds the simulated exchange for a venue.
+    ///
+    /// # Errors
+    ///
+    /// If a simulated exchange for the venue has already been added.
+    pub fn add_venue(&mut self, exchange: SimulatedExchange) -> Result<()> {
+        let venue = exchange.id;
+        if self.venues.contains_key(&venue) {
+            bail!("Venue {venue} already added")
+        }
+        self.venues.insert(venue, exchange);
+        self.apply_random_seed();
+        Ok(())
+    }
+
+    #[must_use]
+    pub fn get_venue(&self, venue: &Venue) -> Option<&SimulatedExchange> {
+        self.venues.get(venue)
+    }
+
+    /// Returns a handle to the queue of commands to be executed by the engine.
+    #[must_use]
+    pub fn command_queue(&self) -> TradingCommandQueue {
+        self.commands.clone()
+    }
+
+    /// Returns the number of data points processed.
+    #[must_use]
+    pub fn iteration(&self) -> usize {
+        self.iteration
+    }
+
+    /// Returns all order events generated by the simulated venues.
+    #[must_use]
+    pub fn order_events(&self) -> &[OrderEvent] {
+        &self.order_events
+    }
+
+    /// Resets the engine, simulated exchanges and pending commands (timers are retained).
+    ///
+    /// If a random seed is set, all random number generators are reseeded so their
+    /// sequences restart from the seed.
+    ///
+    /// # Errors
+    ///
+    /// If a simulated exchange cannot be reset.
+    pub fn reset(&mut self) -> Result<()> {
+        for exchange in self.venues.values_mut() {
+            exchange.reset()?;
+        }
+        self.commands.borrow_mut().clear();
+        self.orders.clear();
+        self.order_events.clear();
+        self.iteration = 0;
+        self.backtest_start = None;
+        self.backtest_end = None;
+        self.checksum.reset();
+        self.apply_random_seed();
+        Ok(())
+    }
+
+    /// Routes the given trading `command` to the simulated exchange for its venue.
+    ///
+    /// # Errors
+    ///
+    /// If no simulated exchange has been added for the commands venue, the exchange
+    /// does not trade the commands instrument, or the matching engine fails to process
+    /// the command.
+    pub fn execute(&mut self, command: TradingCommand) -> Result<()> {
+        let venue = command.instrument_id().venue;
+        let Some(exchange) = self.venues.get_mut(&venue) else {
+            bail!("No simulated exchange for {venue}")
+        };
+        if let TradingCommand::SubmitOrder(order) = &command {
+            self.orders
+                .insert(order.client_order_id(), OrderSummary::from(order.as_ref()));
+        }
+        let ts_now = self.time.get_time_ns();
+        exchange.send(command);
+        exchange.process(ts_now)?;
+        let events = exchange.drain_events(ts_now);
+        let state_changes = exchange.drain_state_changes();
+        self.handle_venue_events(events, state_changes)
+    }
+
+    /// Returns the results of the backtest run so far, including portfolio statistics.
+    #[must_use]
+    pub fn get_result(&self) -> BacktestResult {
+        let fills = self
+            .order_events
+            .iter()
+            .filter_map(|event| match event {
+                OrderEvent::OrderFilled(fill) | OrderEvent::OrderPartiallyFilled(fill) => {
+                    Some(*fill)
+                }
+                _ => None,
+            })
+            .collect();
+        // Closed positions are taken from their snapshots, as they may have been reopened
+        let positions = self
+            .venues
+            .values()
+            .flat_map(|exchange| {
+                exchange
+                    .position_snapshots()
+                    .iter()
+                    .chain(exchange.positions_open())
+                    .cloned()
+            })
+            .collect();
+        let balances = self
+            .venues
+            .values()
+            .flat_map(|exchange| exchange.account().base().events.iter())
+            .flat_map(|state| {
+                state.balances.iter().map(|balance| BalanceSnapshot {
+                    account_id: state.account_id,
+                    ts_event: state.ts_event,
+                    balance: *balance,
+                })
+            })
+            .collect();
+
+        BacktestResult::new(
+            self.trader_id,
+            self.backtest_start,
+            self.backtest_end,
+            self.iteration,
+            self.order_events.len(),
+            self.orders.values().cloned().collect(),
+            fills,
+            positions,
+            balances,
+            self.checksum.value(),
+        )
+    }
+
+    /// Runs the backtest over the query result of the given `session`.
+    ///
+    /// # Errors
+    ///
+    /// If a trading command cannot be routed to a simulated venue, a simulated venue
+    /// fails to process data or commands, or a time event handler fails.
+    pub fn run_session(
+        &mut self,
+        session: &mut DataBackendSession,
+        end: Option<UnixNanos>,
+    ) -> Result<()> {
+        self.run(session.get_query_result(), end)
+    }
+
+    /// Runs the backtest over the given `data`, which must be ordered by `ts_init`.
+    ///
+    /// If `end` is given, the clock is advanced to `end` once the data is exhausted.
+    ///
+    /// # Errors
+    ///
+    /// If a trading command cannot be routed to a simulated venue, a simulated venue
+    /// fails to process data or commands, or a time event handler fails.
+    pub fn run<I: IntoIterator<Item = Data>>(
+        &mut self,
+        data: I,
+        end: Option<UnixNanos>,
+    ) -> Result<()> {
+        for data in data {
+            self.backtest_start.get_or_insert(data.get_ts_init());
+            self.advance_time(data.get_ts_init())?;
+            self.msgbus.publish_data(&get_data_topic(&data), &data);
+            self.process_data(data)?;
+            self.process_commands()?;
+            self.iteration += 1;
+        }
+
+        if let Some(end) = end {
+            self.advance_time(end)?;
+        }
+        self.backtest_end = Some(self.clock.get_time_ns());
+        Ok(())
+    }
+
+    fn advance_time(&mut self, ts_now: UnixNanos) -> Result<()> {
+        while let Some(ts_due) = self.next_inflight_time_ns().filter(|ts| *ts < ts_now) {
+            self.advance_time_to(ts_due)?;
+        }
+        self.advance_time_to(ts_now)
+    }
+
+    fn advance_time_to(&mut self, ts_now: UnixNanos) -> Result<()> {
+        self.accumulator
+            .advance_clock(&mut self.clock, ts_now, false);
+        for handler in self.accumulator.drain() {
+            let ts_event = handler.event.ts_event;
+            self.set_time(ts_event);
+            self.process_venues(ts_event)?;
+            call_time_event_handler(handler)?;
+            self.process_commands()?;
+        }
+        self.set_time(ts_now);
+        self.process_venues(ts_now)
+    }
+
+    fn next_inflight_time_ns(&self) -> Option<UnixNanos> {
+        self.venues
+            .values()
+            .filter_map(SimulatedExchange::next_inflight_time_ns)
+            .min()
+    }
+
+    fn process_venues(&mut self, ts_now: UnixNanos) -> Result<()> {
+        let mut events = Vec::new();
+        let mut state_changes = Vec::new();
+        for exchange in self.venues.values_mut() {
+            exchange.process(ts_now)?;
+            events.extend(exchange.drain_events(ts_now));
+            state_changes.extend(exchange.drain_state_changes());
+        }
+        self.handle_venue_events(events, state_changes)
+    }
+
+    fn apply_random_seed(&mut self) {
+        for (i, exchange) in self.venues.values_mut().enumerate() {
+            exchange.reseed(self.random_seed.map(|seed| derive_seed(seed, i as u64 + 1)));
+        }
+    }
+
+    fn handle_venue_events(
+        &mut self,
+        events: Vec<OrderEvent>,
+        state_changes: Vec<StateChange>,
+    ) -> Result<()> {
+        for state_change in &state_changes {
+            self.checksum.update(state_change)?;
+        }
+        for event in &events {
+            self.checksum.update(event)?;
+            if let Some(order) = self.orders.get_mut(&event.client_order_id()) {
+                order.apply(event);
+            }
+        }
+        self.order_events.extend(events);
+        Ok(())
+    }
+
+    fn set_time(&mut self, ts_now: UnixNanos) {
+        self.clock.set_time(ts_now);
+        self.time.set_time(ts_now);
+    }
+
+    fn process_data(&mut self, data: Data) -> Result<()> {
+        let ts_now = data.get_ts_init();
+        let venue = match &data {
+            Data::Delta(delta) => delta.instrument_id.venue,
+            Data::Depth10(depth) => depth.instrument_id.venue,
+            Data::Quote(quote) => quote.instrument_id.venue,
+            Data::Trade(trade) => trade.instrument_id.venue,
+            Data::Bar(bar) => bar.bar_type.instrument_id.venue,
+        };
+        let Some(exchange) = self.venues.get_mut(&venue) else {
+            return Ok(());
+        };
+        exchange.process_data(data)?;
+        let events = exchange.drain_events(ts_now);
+        let state_changes = exchange.drain_state_changes();
+        self.handle_venue_events(events, state_changes)
+    }
+
+    fn process_commands(&mut self) -> Result<()> {
+        // Commands may be queued while executing, so pop one at a time
+        loop {
+            let command = self.commands.borrow_mut().pop_front();
+            match command {
+                Some(command) => self.execute(command)?,
+                None => return Ok(()),
+            }
+        }
+    }
+}
+
+#[cfg(feature = "python")]
+fn call_time_event_handler(handler: TimeEventHandler) -> Result<()> {
+    let name = handler.event.name;
+    Python::with_gil(|py| {
+        // SAFETY: The callback is borrowed from the handler registered with the clock
+        let callback = unsafe { PyObject::from_borrowed_ptr(py, handler.callback_ptr) };
+        callback
+            .call1(py, (handler.event,))
+            .map_err(|e| anyhow!("Error handling time event {name}: {e}"))?;
+        Ok(())
+    })
+}
+
+#[cfg(not(feature = "python"))]
+#[allow(clippy::unnecessary_wraps)]
+fn call_time_event_handler(_handler: TimeEventHandler) -> Result<()> {
+    Ok(())
+}
+
 ////////////////////////////////////////////////////////////////////////////////
 // C API
 ////////////////////////////////////////////////////////////////////////////////
@@ -109,13 +515,329 @@ pub extern "C" fn time_event_accumulator_drain(accumulator: &mut TimeEventAccumu
 ////////////////////////////////////////////////////////////////////////////////
 #[cfg(test)]
 mod tests {
-    use nautilus_common::timer::TimeEvent;
+    use std::sync::{Arc, Mutex};
+
+    use nautilus_accounting::models::fee::MakerTakerFeeModel;
+    use nautilus_common::{
+        handlers::{MessageHandler, SafeDataCallback},
+        timer::TimeEvent,
+    };
     use nautilus_core::uuid::UUID4;
+    use nautilus_model::{
+        data::quote::QuoteTick,
+        enums::{AccountType, BookType, OmsType, OrderSide, OrderStatus, OrderType},
+        events::order::{initialized::OrderInitializedBuilder, submitted::OrderSubmittedBuilder},
+        identifiers::instrument_id::InstrumentId,
+        instruments::{currency_pair::CurrencyPair, stubs::audusd_sim},
+        orders::{base::Order, market::MarketOrder},
+        types::{money::Money, price::Price, quantity::Quantity},
+    };
     use pyo3::{types::PyList, Py, Python};
     use rstest::*;
     use ustr::Ustr;
 
     use super::*;
+    use crate::{
+        matching_engine::OrderMatchingEngineConfig,
+        models::{
+            fill::{FillModel, ProbabilisticFillModel},
+            latency::ConstantLatencyModel,
+        },
+    };
+
+    fn sim_exchange(audusd_sim: CurrencyPair, time: &'static AtomicTime) -> SimulatedExchange {
+        sim_exchange_with(audusd_sim, Box::<ProbabilisticFillModel>::default(), time)
+    }
+
+    fn sim_exchange_with(
+        audusd_sim: CurrencyPair,
+        fill_model: Box<dyn FillModel>,
+        clock: &'static AtomicTime,
+    ) -> SimulatedExchange {
+        let mut exchange = SimulatedExchange::new(
+            Venue::from("SIM"),
+            OmsType::Netting,
+            AccountType::Cash,
+            vec![Money::from("1000000 USD")],
+            None,
+            1.0,
+            BookType::L1_MBP,
+            OrderMatchingEngineConfig::default(),
+            Arc::new(Mutex::new(MakerTakerFeeModel)),
+            None,
+            clock,
+        )
+        .unwrap();
+        exchange
+            .add_instrument(Box::new(audusd_sim), fill_model)
+            .unwrap();
+        exchange
+    }
+
+    fn seeded_engine(seed: u64, time: &'static AtomicTime) -> BacktestEngine {
+        let mut engine = BacktestEngine::new(TraderId::from("TRADER-001"), time);
+        let fill_model = ProbabilisticFillModel::new(1.0, 0.5, None).unwrap();
+        engine
+            .add_venue(sim_exchange_with(audusd_sim(), Box::new(fill_model), time))
+            .unwrap();
+        engine.set_random_seed(Some(seed));
+
+        let data = vec![quote("1.00000", "1.00001", 1_000)];
+        engine.run(data, Some(2_000)).unwrap();
+        engine
+    }
+
+    fn seeded_run_checksum(seed: u64, time: &'static AtomicTime) -> u64 {
+        let mut engine = seeded_engine(seed, time);
+        for i in 0..10 {
+            let order = market_order("AUD/USD.SIM", &format!("O-{i}"));
+            engine.execute(TradingCommand::SubmitOrder(order)).unwrap();
+        }
+        engine.set_random_seed(None);
+
+        let result = engine.get_result();
+        assert_eq!(result.checksum, engine.checksum());
+        result.checksum
+    }
+
+    // Each test declares its own `static` time so event timestamps are independent
+    fn engine(time: &'static AtomicTime) -> BacktestEngine {
+        let mut engine = BacktestEngine::new(TraderId::from("TRADER-001"), time);
+        engine.add_venue(sim_exchange(audusd_sim(), time)).unwrap();
+        engine
+    }
+
+    fn quote(bid: &str, ask: &str, ts_init: UnixNanos) -> Data {
+        Data::Quote(
+            QuoteTick::new(
+                InstrumentId::from("AUD/USD.SIM"),
+                Price::from(bid),
+                Price::from(ask),
+                Quantity::from("1000000"),
+                Quantity::from("1000000"),
+                ts_init,
+                ts_init,
+            )
+            .unwrap(),
+        )
+    }
+
+    fn market_order(instrument_id: &str, client_order_id: &str) -> Box<dyn Order> {
+        market_order_side(instrument_id, client_order_id, OrderSide::Buy)
+    }
+
+    fn market_order_side(
+        instrument_id: &str,
+        client_order_id: &str,
+        side: OrderSide,
+    ) -> Box<dyn Order> {
+        let init = OrderInitializedBuilder::default()
+            .instrument_id(InstrumentId::from(instrument_id))
+            .client_order_id(ClientOrderId::from(client_order_id))
+            .order_type(OrderType::Market)
+            .order_side(side)
+            .quantity(Quantity::from("100000"))
+            .build()
+            .unwrap();
+        let submitted = OrderSubmittedBuilder::default()
+            .client_order_id(init.client_order_id)
+            .strategy_id(init.strategy_id)
+            .build()
+            .unwrap();
+        let mut order: Box<dyn Order> = Box::new(MarketOrder::from(init));
+        order.apply(OrderEvent::OrderSubmitted(submitted)).unwrap();
+        order
+    }
+
+    #[rstest]
+    fn test_add_duplicate_venue(audusd_sim: CurrencyPair) {
+        static TIME: AtomicTime = AtomicTime::new(false, 0);
+        let mut engine = engine(&TIME);
+        assert!(engine.add_venue(sim_exchange(audusd_sim, &TIME)).is_err());
+    }
+
+    #[rstest]
+    fn test_execute_with_no_venue() {
+        static TIME: AtomicTime = AtomicTime::new(false, 0);
+        let mut engine = engine(&TIME);
+        let order = market_order("ETHUSDT-PERP.BINANCE", "O-1");
+        assert!(engine.execute(TradingCommand::SubmitOrder(order)).is_err());
+    }
+
+    #[rstest]
+    fn test_run_publishes_data_and_routes_commands() {
+        static TIME: AtomicTime = AtomicTime::new(false, 0);
+        let mut engine = engine(&TIME);
+        let received = Rc::new(RefCell::new(0));
+        let received_clone = received.clone();
+        let commands = engine.command_queue();
+        let callback = SafeDataCallback {
+            callback: Arc::new(move |_data: &Data| {
+                *received_clone.borrow_mut() += 1;
+                if *received_clone.borrow() == 1 {
+                    commands
+                        .borrow_mut()
+                        .push_back(TradingCommand::SubmitOrder(market_order(
+                            "AUD/USD.SIM",
+                            "O-1",
+                        )));
+                }
+            }),
+        };
+        let handler = MessageHandler::with_data_callback(Ustr::from("strategy"), callback);
+        engine.msgbus.subscribe("data.quotes.SIM.*", handler, None);
+
+        let data = vec![
+            quote("1.00000", "1.00001", 1_000),
+            quote("1.00001", "1.00002", 2_000),
+        ];
+        engine.run(data, Some(3_000)).unwrap();
+
+        assert_eq!(*received.borrow(), 2);
+        assert_eq!(engine.iteration(), 2);
+        assert_eq!(engine.clock.get_time_ns(), 3_000);
+
+        let events = engine.order_events();
+        assert_eq!(events.len(), 1);
+        let OrderEvent::OrderFilled(filled) = events[0] else {
+            panic!("Expected fill, was {:?}", events[0]);
+        };
+        assert_eq!(filled.last_px, Price::from("1.00001"));
+        let exchange = engine.get_venue(&Venue::from("SIM")).unwrap();
+        assert_eq!(exchange.positions_open().len(), 1);
+
+        let result = engine.get_result();
+        assert_eq!(result.backtest_start, Some(1_000));
+        assert_eq!(result.backtest_end, Some(3_000));
+        assert_eq!(result.total_orders, 1);
+        assert_eq!(result.orders[0].status, OrderStatus::Filled);
+        assert_eq!(result.fills.len(), 1);
+        assert_eq!(result.total_positions, 1);
+        assert!(!result.balances.is_empty());
+    }
+
+    #[rstest]
+    fn test_result_keeps_each_netting_round_trip() {
+        static TIME: AtomicTime = AtomicTime::new(false, 0);
+        let mut engine = engine(&TIME);
+        engine
+            .run(vec![quote("1.00000", "1.00001", 1_000)], None)
+            .unwrap();
+        for (i, side) in [
+            OrderSide::Buy,
+            OrderSide::Sell,
+            OrderSide::Buy,
+            OrderSide::Sell,
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            let order = market_order_side("AUD/USD.SIM", &format!("O-{i}"), side);
+            engine.execute(TradingCommand::SubmitOrder(order)).unwrap();
+        }
+
+        let result = engine.get_result();
+        assert_eq!(result.fills.len(), 4);
+        assert_eq!(result.total_positions, 2);
+        for position in &result.positions {
+            assert!(position.is_closed());
+            assert_eq!(position.events.len(), 2);
+            assert_eq!(position.realized_pnl, Some(Money::from("-5.00 USD")));
+        }
+        assert_eq!(result.stats_pnls["USD"].pnl_total, -10.0);
+    }
+
+    #[rstest]
+    fn test_latent_commands_execute_at_their_due_time(audusd_sim: CurrencyPair) {
+        static TIME: AtomicTime = AtomicTime::new(false, 0);
+        let mut engine = BacktestEngine::new(TraderId::from("TRADER-001"), &TIME);
+        let mut exchange = SimulatedExchange::new(
+            Venue::from("SIM"),
+            OmsType::Netting,
+            AccountType::Cash,
+            vec![Money::from("1000000 USD")],
+            None,
+            1.0,
+            BookType::L1_MBP,
+            OrderMatchingEngineConfig::default(),
+            Arc::new(Mutex::new(MakerTakerFeeModel)),
+            Some(Box::new(ConstantLatencyModel::new(500))),
+            engine.time(),
+        )
+        .unwrap();
+        exchange
+            .add_instrument(
+                Box::new(audusd_sim),
+                Box::<ProbabilisticFillModel>::default(),
+            )
+            .unwrap();
+        engine.add_venue(exchange).unwrap();
+
+        engine
+            .run(vec![quote("1.00000", "1.00001", 1_000)], None)
+            .unwrap();
+        let order = market_order("AUD/USD.SIM", "O-1");
+        engine.execute(TradingCommand::SubmitOrder(order)).unwrap();
+        assert!(engine.order_events().is_empty());
+
+        // The order fills when its latency elapses, not when the next quote arrives
+        engine
+            .run(vec![quote("1.00010", "1.00011", 10_000)], None)
+            .unwrap();
+        let events = engine.order_events();
+        assert_eq!(events.len(), 1);
+        let OrderEvent::OrderFilled(filled) = events[0] else {
+            panic!("Expected fill, was {:?}", events[0]);
+        };
+        assert_eq!(filled.ts_event, 1_500);
+        assert_eq!(filled.last_px, Price::from("1.00001"));
+    }
+
+    #[rstest]
+    fn test_engines_do_not_share_time() {
+        static TIME: AtomicTime = AtomicTime::new(false, 0);
+        static OTHER_TIME: AtomicTime = AtomicTime::new(false, 0);
+        let mut engine = engine(&TIME);
+        let other = BacktestEngine::new(TraderId::from("TRADER-002"), &OTHER_TIME);
+        engine
+            .run(vec![quote("1.00000", "1.00001", 1_000)], Some(2_000))
+            .unwrap();
+
+        assert_eq!(engine.time().get_time_ns(), 2_000);
+        assert_eq!(other.time().get_time_ns(), 0);
+    }
+
+    #[rstest]
+    fn test_seeded_runs_replay_identically() {
+        static TIME1: AtomicTime = AtomicTime::new(false, 0);
+        static TIME2: AtomicTime = AtomicTime::new(false, 0);
+        static TIME3: AtomicTime = AtomicTime::new(false, 0);
+        let checksum = seeded_run_checksum(42, &TIME1);
+        assert_eq!(seeded_run_checksum(42, &TIME2), checksum);
+        assert_ne!(seeded_run_checksum(7, &TIME3), checksum);
+    }
+
+    #[rstest]
+    fn test_seeded_engines_on_one_thread_are_independent() {
+        static TIME1: AtomicTime = AtomicTime::new(false, 0);
+        static TIME2: AtomicTime = AtomicTime::new(false, 0);
+        static TIME3: AtomicTime = AtomicTime::new(false, 0);
+        let mut engine1 = seeded_engine(42, &TIME1);
+        let mut engine2 = seeded_engine(42, &TIME2);
+
+        // Reseeding another engine on the same thread leaves both runs untouched
+        let mut engine3 = seeded_engine(7, &TIME3);
+        engine3.set_random_seed(None);
+
+        for i in 0..5 {
+            for engine in [&mut engine1, &mut engine2, &mut engine3] {
+                let order = market_order("AUD/USD.SIM", &format!("O-{i}"));
+                engine.execute(TradingCommand::SubmitOrder(order)).unwrap();
+            }
+        }
+        assert_eq!(engine1.checksum(), engine2.checksum());
+        assert_ne!(engine1.checksum(), engine3.checksum());
+    }
 
     #[rstest]
     fn test_accumulator_drain_sorted() {
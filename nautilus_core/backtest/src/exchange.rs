@@ -0,0 +1,1361 @@
+// -------------------------------------------------------------------------------------------------
+//  Copyright (C) 2015-2024 Nautech Systems Pty Ltd. All rights reserved.
+//  https://nautechsystems.io
+//
+//  Licensed under the GNU Lesser General Public License Version 3.0 (the "License");
+//  You may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at https://www.gnu.org/licenses/lgpl-3.0.en.html
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+// -------------------------------------------------------------------------------------------------
+
+use std::collections::HashMap;
+
+use anyhow::{bail, Result};
+use indexmap::IndexMap;
+use nautilus_accounting::{
+    account::{base::BaseAccount, cash::CashAccount, margin::MarginAccount, Account},
+    models::fee::SharedFeeModel,
+};
+use nautilus_core::{time::AtomicTime, time::UnixNanos, uuid::UUID4};
+use nautilus_execution::messages::TradingCommand;
+use nautilus_model::{
+    data::Data,
+    enums::{AccountType, BookType, OmsType, OrderSide, PositionSide, TimeInForce},
+    events::{
+        account::state::AccountState,
+        order::{event::OrderEvent, filled::OrderFilled, submitted::OrderSubmitted},
+    },
+    identifiers::{
+        account_id::AccountId, client_order_id::ClientOrderId, instrument_id::InstrumentId,
+        position_id::PositionId, venue::Venue,
+    },
+    instruments::Instrument,
+    orders::{base::Order, market::MarketOrder},
+    position::Position,
+    types::{
+        balance::{AccountBalance, MarginBalance},
+        currency::Currency,
+        money::Money,
+        price::Price,
+        quantity::Quantity,
+    },
+};
+use ustr::Ustr;
+
+use crate::{
+    matching_engine::{OrderMatchingEngine, OrderMatchingEngineConfig},
+    models::{
+        fill::FillModel,
+        latency::{LatencyCommandType, LatencyModel, LatencyQueue},
+    },
+    replay::{derive_seed, PositionChange, StateChange, UUID4Generator},
+};
+
+/// The account held at a [`SimulatedExchange`].
+#[derive(Debug)]
+pub enum ExchangeAccount {
+    Cash(CashAccount),
+    Margin(MarginAccount),
+}
+
+impl ExchangeAccount {
+    #[must_use]
+    pub fn base(&self) -> &BaseAccount {
+        match self {
+            Self::Cash(account) => &account.base,
+            Self::Margin(account) => &account.base,
+        }
+    }
+
+    fn base_mut(&mut self) -> &mut BaseAccount {
+        match self {
+            Self::Cash(account) => &mut account.base,
+            Self::Margin(account) => &mut account.base,
+        }
+    }
+
+    fn apply(&mut self, event: AccountState) {
+        match self {
+            Self::Cash(account) => account.apply(event),
+            Self::Margin(account) => account.apply(event),
+        }
+    }
+}
+
+/// Provides a simulated exchange venue for backtesting.
+///
+/// The exchange holds an independent account (cash or margin), positions, and one
+/// [`OrderMatchingEngine`] per instrument. The account and matching engines share a
+/// single fee model, so volume based fees reflect all fills at the venue. Commands
+/// sent to the exchange and the order events it generates are delayed by the
+/// optional latency model.
+pub struct SimulatedExchange {
+    pub id: Venue,
+    pub oms_type: OmsType,
+    pub account_type: AccountType,
+    pub account_id: AccountId,
+    pub base_currency: Option<Currency>,
+    pub starting_balances: Vec<Money>,
+    pub book_type: BookType,
+    pub config: OrderMatchingEngineConfig,
+    clock: &'static AtomicTime,
+    account: ExchangeAccount,
+    matching_engines: IndexMap<InstrumentId, OrderMatchingEngine>,
+    positions: IndexMap<PositionId, Position>,
+    position_snapshots: Vec<Position>,
+    order_position_ids: HashMap<ClientOrderId, PositionId>,
+    latency_model: Option<Box<dyn LatencyModel>>,
+    inflight_commands: LatencyQueue<TradingCommand>,
+    outbound_events: LatencyQueue<OrderEvent>,
+    state_changes: Vec<StateChange>,
+    uuid_generator: UUID4Generator,
+    position_count: usize,
+    liquidation_count: usize,
+}
+
+impl SimulatedExchange {
+    /// Initializes a new `SimulatedExchange` instance.
+    ///
+    /// # Errors
+    ///
+    /// If `account_type` is not `Cash` or `Margin`, or the starting balances are invalid.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        venue: Venue,
+        oms_type: OmsType,
+        account_type: AccountType,
+        starting_balances: Vec<Money>,
+        base_currency: Option<Currency>,
+        default_leverage: f64,
+        book_type: BookType,
+        config: OrderMatchingEngineConfig,
+        fee_model: SharedFeeModel,
+        latency_model: Option<Box<dyn LatencyModel>>,
+        clock: &'static AtomicTime,
+    ) -> Result<Self> {
+        if starting_balances.is_empty() {
+            bail!("`starting_balances` must not be empty")
+        }
+        let account_id = AccountId::new(&format!("{venue}-001"))?;
+        let balances = starting_balances
+            .iter()
+            .map(|balance| {
+                AccountBalance::new(*balance, Money::new(0.0, balance.currency)?, *balance)
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let ts_now = clock.get_time_ns();
+        let state = AccountState::new(
+            account_id,
+            account_type,
+            balances,
+            vec![],
+            true,
+            UUID4::new(),
+            ts_now,
+            ts_now,
+            base_currency,
+        )?;
+        let mut account = match account_type {
+            AccountType::Cash => ExchangeAccount::Cash(CashAccount::new(state, true)?),
+            AccountType::Margin => {
+                let mut account = MarginAccount::new(state, true)?;
+                account.set_default_leverage(default_leverage);
+                ExchangeAccount::Margin(account)
+            }
+            _ => bail!("Unsupported account type {account_type} for simulated exchange"),
+        };
+        account.base_mut().set_fee_model(fee_model);
+
+        Ok(Self {
+            id: venue,
+            oms_type,
+            account_type,
+            account_id,
+            base_currency,
+            starting_balances,
+            book_type,
+            config,
+            clock,
+            account,
+            matching_engines: IndexMap::new(),
+            positions: IndexMap::new(),
+            position_snapshots: Vec::new(),
+            order_position_ids: HashMap::new(),
+            latency_model,
+            inflight_commands: LatencyQueue::new(),
+            outbound_events: LatencyQueue::new(),
+            state_changes: Vec::new(),
+            uuid_generator: UUID4Generator::default(),
+            position_count: 0,
+            liquidation_count: 0,
+        })
+    }
+
+    /// Adds the given `instrument` to the exchange with its own matching engine.
+    ///
+    /// # Errors
+    ///
+    /// If the instrument is not for this venue, or has already been added.
+    pub fn add_instrument(
+        &mut self,
+        instrument: Box<dyn Instrument>,
+        fill_model: Box<dyn FillModel>,
+    ) -> Result<()> {
+        let instrument_id = instrument.id();
+        if instrument_id.venue != self.id {
+            bail!("Instrument {instrument_id} is not for venue {}", self.id)
+        }
+        if self.matching_engines.contains_key(&instrument_id) {
+            bail!("Instrument {instrument_id} already added")
+        }
+        let raw_id = u32::try_from(self.matching_engines.len() + 1)?;
+        let matching_engine = OrderMatchingEngine::new(
+            instrument,
+            raw_id,
+            self.account_id,
+            self.book_type,
+            fill_model,
+            self.account.base().fee_model.clone(),
+            self.config.clone(),
+            self.clock,
+        );
+        self.matching_engines.insert(instrument_id, matching_engine);
+        Ok(())
+    }
+
+    #[must_use]
+    pub fn instrument_ids(&self) -> Vec<InstrumentId> {
+        self.matching_engines.keys().copied().collect()
+    }
+
+    #[must_use]
+    pub fn get_matching_engine(
+        &self,
+        instrument_id: &InstrumentId,
+    ) -> Option<&OrderMatchingEngine> {
+        self.matching_engines.get(instrument_id)
+    }
+
+    #[must_use]
+    pub fn account(&self) -> &ExchangeAccount {
+        &self.account
+    }
+
+    #[must_use]
+    pub fn get_position(&self, position_id: &PositionId) -> Option<&Position> {
+        self.positions.get(position_id)
+    }
+
+    /// Returns all positions (open and closed) held at the exchange.
+    #[must_use]
+    pub fn positions(&self) -> Vec<&Position> {
+        self.positions.values().collect()
+    }
+
+    /// Returns a snapshot of every position taken when it closed.
+    ///
+    /// NETTING positions are reopened under the same ID, which resets their fills and
+    /// realized PnL, so the snapshots preserve each closed round trip.
+    #[must_use]
+    pub fn position_snapshots(&self) -> &[Position] {
+        &self.position_snapshots
+    }
+
+    /// Returns the open positions held at the exchange.
+    #[must_use]
+    pub fn positions_open(&self) -> Vec<&Position> {
+        self.positions
+            .values()
+            .filter(|position| position.is_open())
+            .collect()
+    }
+
+    /// Returns whether there are commands or events still in flight.
+    #[must_use]
+    pub fn has_inflight(&self) -> bool {
+        !self.inflight_commands.is_empty() || !self.outbound_events.is_empty()
+    }
+
+    /// Returns the earliest time a command or event in flight is due (if any).
+    #[must_use]
+    pub fn next_inflight_time_ns(&self) -> Option<UnixNanos> {
+        [
+            self.inflight_commands.next_time_ns(),
+            self.outbound_events.next_time_ns(),
+        ]
+        .into_iter()
+        .flatten()
+        .min()
+    }
+
+    /// Returns whether the maintenance margin of a margin account exceeds its equity,
+    /// including the unrealized PnL of open positions.
+    #[must_use]
+    pub fn is_margin_call(&self) -> bool {
+        let ExchangeAccount::Margin(account) = &self.account else {
+            return false;
+        };
+        let mut unrealized_pnls: HashMap<Currency, f64> = HashMap::new();
+        for position in self.positions.values().filter(|p| p.is_open()) {
+            let Some(matching_engine) = self.matching_engines.get(&position.instrument_id) else {
+                continue;
+            };
+            let price = match position.side {
+                PositionSide::Long => matching_engine.best_bid_price(),
+                _ => matching_engine.best_ask_price(),
+            };
+            if let Some(price) = price {
+                let pnl = position.unrealized_pnl(price);
+                *unrealized_pnls.entry(pnl.currency).or_default() += pnl.as_f64();
+            }
+        }
+        account.balances.keys().any(|currency| {
+            let pnl = unrealized_pnls.get(currency).copied().unwrap_or_default();
+            let pnl = Money::new(pnl, *currency).ok();
+            account.is_margin_call(*currency, pnl)
+        })
+    }
+
+    /// Sends the given trading `command` to the exchange.
+    ///
+    /// The command is processed on the next call to [`SimulatedExchange::process`] once
+    /// its latency (if any) has elapsed.
+    pub fn send(&mut self, command: TradingCommand) {
+        let latency_ns = match &mut self.latency_model {
+            Some(latency_model) => {
+                let command_type = match command {
+                    TradingCommand::SubmitOrder(_) => LatencyCommandType::Submit,
+                    TradingCommand::ModifyOrder(_) => LatencyCommandType::Modify,
+                    TradingCommand::CancelOrder(_) | TradingCommand::CancelAllOrders(_) => {
+                        LatencyCommandType::Cancel
+                    }
+                };
+                latency_model.command_latency_ns(command_type)
+            }
+            None => 0,
+        };
+        let ts_now = self.clock.get_time_ns();
+        self.inflight_commands.schedule(command, ts_now, latency_ns);
+    }
+
+    /// Processes the given market `data` with the matching engine for its instrument.
+    ///
+    /// # Errors
+    ///
+    /// If the matching engine fails to process the data, or a liquidation order for a
+    /// margin call cannot be constructed.
+    pub fn process_data(&mut self, data: Data) -> Result<()> {
+        let ts_now = self.clock.get_time_ns();
+        let instrument_id = match &data {
+            Data::Delta(delta) => delta.instrument_id,
+            Data::Depth10(depth) => depth.instrument_id,
+            Data::Quote(quote) => quote.instrument_id,
+            Data::Trade(trade) => trade.instrument_id,
+            Data::Bar(_) => return Ok(()),
+        };
+        let Some(matching_engine) = self.matching_engines.get_mut(&instrument_id) else {
+            return Ok(());
+        };
+
+        // The matching engine iterates its orders as part of processing the data
+        match data {
+            Data::Delta(delta) => matching_engine.process_order_book_delta(delta)?,
+            Data::Depth10(depth) => matching_engine.process_order_book_depth10(depth)?,
+            Data::Quote(quote) => matching_engine.process_quote_tick(&quote)?,
+            Data::Trade(trade) => matching_engine.process_trade_tick(&trade)?,
+            Data::Bar(_) => {}
+        }
+        let events = matching_engine.drain_events();
+        self.handle_events(events, ts_now);
+        self.check_margin_call(ts_now)
+    }
+
+    /// Processes all commands due by `ts_now`, then iterates the matching engines
+    /// which received commands.
+    ///
+    /// Matching engines without new commands are not iterated, so each data point is
+    /// only matched against once.
+    ///
+    /// # Errors
+    ///
+    /// If a command is for an instrument not traded on the exchange, or the matching
+    /// engine fails to process a command.
+    pub fn process(&mut self, ts_now: UnixNanos) -> Result<()> {
+        let mut instrument_ids: Vec<InstrumentId> = Vec::new();
+        for (_, command) in self.inflight_commands.advance(ts_now) {
+            let instrument_id = command.instrument_id();
+            self.execute(command, ts_now)?;
+            if !instrument_ids.contains(&instrument_id) {
+                instrument_ids.push(instrument_id);
+            }
+        }
+        let mut events = Vec::new();
+        for instrument_id in &instrument_ids {
+            if let Some(matching_engine) = self.matching_engines.get_mut(instrument_id) {
+                matching_engine.iterate(ts_now)?;
+                events.extend(matching_engine.drain_events());
+            }
+        }
+        self.handle_events(events, ts_now);
+        self.check_margin_call(ts_now)
+    }
+
+    /// Uncrosses the opening or closing auction for the given instrument at `ts_auction`.
+    ///
+    /// # Errors
+    ///
+    /// If the instrument is not traded on the exchange, or `time_in_force` is not an
+    /// auction time in force.
+    pub fn process_auction(
+        &mut self,
+        instrument_id: &InstrumentId,
+        time_in_force: TimeInForce,
+        ts_auction: UnixNanos,
+    ) -> Result<Option<Price>> {
+        let Some(matching_engine) = self.matching_engines.get_mut(instrument_id) else {
+            bail!("Instrument {instrument_id} not found at venue {}", self.id)
+        };
+        let price = matching_engine.process_auction(time_in_force, ts_auction)?;
+        let events = matching_engine.drain_events();
+        self.handle_events(events, ts_auction);
+        self.check_margin_call(ts_auction)?;
+        Ok(price)
+    }
+
+    /// Drains the order events which have reached the client by `ts_now`.
+    pub fn drain_events(&mut self, ts_now: UnixNanos) -> Vec<OrderEvent> {
+        self.outbound_events
+            .advance(ts_now)
+            .into_iter()
+            .map(|(_, event)| event)
+            .collect()
+    }
+
+    /// Resets the exchange to its starting state.
+    ///
+    /// # Errors
+    ///
+    /// If the starting account state cannot be recreated.
+    pub fn reset(&mut self) -> Result<()> {
+        let latency_model = self.latency_model.take();
+        let fee_model = self.account.base().fee_model.clone();
+        fee_model.lock().unwrap().reset();
+        let mut reset = Self::new(
+            self.id,
+            self.oms_type,
+            self.account_type,
+            self.starting_balances.clone(),
+            self.base_currency,
+            self.default_leverage(),
+            self.book_type,
+            self.config.clone(),
+            fee_model,
+            latency_model,
+            self.clock,
+        )?;
+        for matching_engine in self.matching_engines.values_mut() {
+            matching_engine.reset();
+        }
+        reset.matching_engines = std::mem::take(&mut self.matching_engines);
+        *self = reset;
+        Ok(())
+    }
+
+    /// Drains the account and position state changes made by fills since the last call.
+    pub fn drain_state_changes(&mut self) -> Vec<StateChange> {
+        std::mem::take(&mut self.state_changes)
+    }
+
+    /// Reseeds the random number generators of the latency and fill models, and of the
+    /// event IDs generated by the exchange, from `seed`.
+    ///
+    /// Each matching engine is seeded from its own stream derived from `seed`. Passing
+    /// `None` reverts event IDs to non-deterministic UUIDs, leaving the models as is.
+    pub fn reseed(&mut self, seed: Option<u64>) {
+        if let (Some(seed), Some(latency_model)) = (seed, self.latency_model.as_mut()) {
+            latency_model.reseed(derive_seed(seed, 0));
+        }
+        for matching_engine in self.matching_engines.values_mut() {
+            let raw_id = u64::from(matching_engine.raw_id);
+            matching_engine.reseed(seed.map(|seed| derive_seed(seed, raw_id)));
+        }
+        // Matching engine streams are numbered from 1 by their raw IDs
+        self.uuid_generator
+            .reseed(seed.map(|seed| derive_seed(seed, u64::MAX)));
+    }
+
+    fn default_leverage(&self) -> f64 {
+        match &self.account {
+            ExchangeAccount::Margin(account) => account.default_leverage,
+            ExchangeAccount::Cash(_) => 1.0,
+        }
+    }
+
+    fn execute(&mut self, command: TradingCommand, ts_now: UnixNanos) -> Result<()> {
+        let instrument_id = command.instrument_id();
+        let funding_error = match &command {
+            TradingCommand::SubmitOrder(order) => self.funding_error(order.as_ref())?,
+            _ => None,
+        };
+        let Some(matching_engine) = self.matching_engines.get_mut(&instrument_id) else {
+            bail!("Instrument {instrument_id} not found at venue {}", self.id)
+        };
+        match command {
+            TradingCommand::SubmitOrder(order) => {
+                if let Some(reason) = funding_error {
+                    matching_engine.process_rejection(order, &reason)?;
+                } else {
+                    if let (OmsType::Hedging, Some(position_id)) =
+                        (self.oms_type, order.position_id())
+                    {
+                        self.order_position_ids
+                            .insert(order.client_order_id(), position_id);
+                    }
+                    matching_engine.process_order(order)?;
+                }
+            }
+            TradingCommand::ModifyOrder(command) => matching_engine.process_modify(&command)?,
+            TradingCommand::CancelOrder(command) => matching_engine.process_cancel(&command)?,
+            TradingCommand::CancelAllOrders(command) => {
+                matching_engine.process_cancel_all(&command)?;
+            }
+        }
+        let events = matching_engine.drain_events();
+        self.handle_events(events, ts_now);
+        Ok(())
+    }
+
+    /// Returns the reason the account cannot fund the submitted `order` (if any).
+    ///
+    /// The order is estimated at its price or trigger price, otherwise at the opposite
+    /// side of the book, and orders which cannot be priced are left to the matching
+    /// engine. Cash accounts must hold the quote currency for buys and (when tracking
+    /// multiple currencies) the base currency for sells, and margin accounts must cover
+    /// the margin of any position the order opens or increases.
+    fn funding_error(&mut self, order: &dyn Order) -> Result<Option<String>> {
+        let instrument_id = order.instrument_id();
+        let Some(matching_engine) = self.matching_engines.get(&instrument_id) else {
+            return Ok(None);
+        };
+        let side = order.side();
+        let price = order
+            .price()
+            .or(order.trigger_price())
+            .or_else(|| match side {
+                OrderSide::Buy => matching_engine.best_ask_price(),
+                _ => matching_engine.best_bid_price(),
+            });
+        let Some(price) = price else {
+            return Ok(None);
+        };
+        let instrument = matching_engine.instrument.as_ref();
+        let quantity = order.quantity();
+
+        let required = match &mut self.account {
+            ExchangeAccount::Cash(account) => match (side, instrument.base_currency()) {
+                (OrderSide::Buy, _) => account.base_calculate_balance_locked(
+                    instrument,
+                    OrderSide::Buy,
+                    quantity,
+                    price,
+                    None,
+                )?,
+                (_, Some(base_currency)) if account.base_currency.is_none() => {
+                    Money::new(quantity.as_f64(), base_currency)?
+                }
+                _ => return Ok(None),
+            },
+            ExchangeAccount::Margin(account) => {
+                // The position the order reduces before opening the other side (if any)
+                let position_id = match self.oms_type {
+                    OmsType::Hedging => order.position_id(),
+                    _ => Some(PositionId::new(&format!(
+                        "{instrument_id}-{}",
+                        order.strategy_id()
+                    ))?),
+                };
+                let reduced_raw = position_id
+                    .and_then(|position_id| self.positions.get(&position_id))
+                    .filter(|position| position.is_open())
+                    .map_or(0, |position| match (position.side, side) {
+                        (PositionSide::Long, OrderSide::Sell)
+                        | (PositionSide::Short, OrderSide::Buy) => {
+                            position.quantity.raw.min(quantity.raw)
+                        }
+                        _ => 0,
+                    });
+                if reduced_raw == quantity.raw {
+                    return Ok(None);
+                }
+
+                let mut long_raw = 0;
+                let mut short_raw = 0;
+                for position in self
+                    .positions
+                    .values()
+                    .filter(|p| p.instrument_id == instrument_id && p.is_open())
+                {
+                    match position.side {
+                        PositionSide::Long => long_raw += position.quantity.raw,
+                        PositionSide::Short => short_raw += position.quantity.raw,
+                        _ => {}
+                    }
+                }
+                if side == OrderSide::Buy {
+                    short_raw -= reduced_raw;
+                    long_raw += quantity.raw - reduced_raw;
+                } else {
+                    long_raw -= reduced_raw;
+                    short_raw += quantity.raw - reduced_raw;
+                }
+                let size_precision = instrument.size_precision();
+                return Ok(account
+                    .calculate_position_margins(
+                        instrument,
+                        Quantity::from_raw(long_raw, size_precision)?,
+                        Quantity::from_raw(short_raw, size_precision)?,
+                        price,
+                        None,
+                    )
+                    .err()
+                    .map(|e| e.to_string()));
+            }
+        };
+        let free = self
+            .account
+            .base()
+            .base_balance_free(Some(required.currency))
+            .map_or(0, |free| free.raw);
+        if required.raw > free {
+            return Ok(Some(format!(
+                "Insufficient balance: {required} required, {} free",
+                Money::from_raw(free, required.currency)
+            )));
+        }
+        Ok(None)
+    }
+
+    /// Applies any fills in `events` to the account and positions, then schedules the
+    /// events for the client.
+    ///
+    /// The matching engine has already committed the fills, so a fill which cannot be
+    /// settled is logged and still published rather than aborting the backtest.
+    fn handle_events(&mut self, events: Vec<OrderEvent>, ts_now: UnixNanos) {
+        for mut event in events {
+            if let OrderEvent::OrderFilled(fill) | OrderEvent::OrderPartiallyFilled(fill) =
+                &mut event
+            {
+                if let Err(e) = self.apply_fill(fill) {
+                    log::error!("Error applying fill {} at {}: {e}", fill.trade_id, self.id);
+                }
+            }
+            let latency_ns = self
+                .latency_model
+                .as_mut()
+                .map_or(0, |latency_model| latency_model.response_latency_ns());
+            self.outbound_events.schedule(event, ts_now, latency_ns);
+        }
+    }
+
+    /// Liquidates every open position with a market order while the margin account
+    /// is in a margin call.
+    fn check_margin_call(&mut self, ts_now: UnixNanos) -> Result<()> {
+        if !self.is_margin_call() {
+            return Ok(());
+        }
+        let positions: Vec<Position> = self.positions_open().into_iter().cloned().collect();
+        for position in positions {
+            let order = self.liquidation_order(&position, ts_now)?;
+            if self.oms_type == OmsType::Hedging {
+                self.order_position_ids
+                    .insert(order.client_order_id(), position.id);
+            }
+            let Some(matching_engine) = self.matching_engines.get_mut(&position.instrument_id)
+            else {
+                continue;
+            };
+            matching_engine.process_order(order)?;
+            let events = matching_engine.drain_events();
+            self.handle_events(events, ts_now);
+        }
+        Ok(())
+    }
+
+    fn liquidation_order(
+        &mut self,
+        position: &Position,
+        ts_now: UnixNanos,
+    ) -> Result<Box<dyn Order>> {
+        self.liquidation_count += 1;
+        let client_order_id =
+            ClientOrderId::new(&format!("LIQ-{}-{:03}", self.id, self.liquidation_count))?;
+        let side = match position.side {
+            PositionSide::Long => OrderSide::Sell,
+            _ => OrderSide::Buy,
+        };
+        let mut order: Box<dyn Order> = Box::new(MarketOrder::new(
+            position.trader_id,
+            position.strategy_id,
+            position.instrument_id,
+            client_order_id,
+            side,
+            position.quantity,
+            TimeInForce::Ioc,
+            self.uuid_generator.generate(),
+            ts_now,
+            true,
+            false,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            Some(Ustr::from("LIQUIDATION")),
+        )?);
+        let submitted = OrderSubmitted::new(
+            position.trader_id,
+            position.strategy_id,
+            position.instrument_id,
+            client_order_id,
+            self.account_id,
+            self.uuid_generator.generate(),
+            ts_now,
+            ts_now,
+        )?;
+        order.apply(OrderEvent::OrderSubmitted(submitted))?;
+        Ok(order)
+    }
+
+    fn position_id_for(&mut self, fill: &OrderFilled) -> Result<PositionId> {
+        if let Some(position_id) = fill.position_id {
+            return Ok(position_id);
+        }
+        match self.oms_type {
+            OmsType::Hedging => {
+                if let Some(position_id) = self.order_position_ids.get(&fill.client_order_id) {
+                    return Ok(*position_id);
+                }
+                let raw_id = self.matching_engines[&fill.instrument_id].raw_id;
+                self.position_count += 1;
+                let position_id =
+                    PositionId::new(&format!("{}-{raw_id}-{:03}", self.id, self.position_count))?;
+                self.order_position_ids
+                    .insert(fill.client_order_id, position_id);
+                Ok(position_id)
+            }
+            _ => PositionId::new(&format!("{}-{}", fill.instrument_id, fill.strategy_id)),
+        }
+    }
+
+    fn apply_fill(&mut self, fill: &mut OrderFilled) -> Result<()> {
+        let position_id = self.position_id_for(fill)?;
+        fill.position_id = Some(position_id);
+
+        let instrument = self.matching_engines[&fill.instrument_id]
+            .instrument
+            .as_ref();
+        self.account.base_mut().base_record_fill(instrument, fill)?;
+
+        // Realized PnL is reset when a flat position is reopened
+        let realized_before = self
+            .positions
+            .get(&position_id)
+            .filter(|position| position.is_open())
+            .and_then(|position| position.realized_pnl)
+            .map_or(0.0, |pnl| pnl.as_f64());
+        if let Some(position) = self.positions.get_mut(&position_id) {
+            position.apply(fill);
+        } else {
+            self.positions
+                .insert(position_id, Position::new(instrument, *fill)?);
+        }
+        let position = &self.positions[&position_id];
+        if position.is_closed() {
+            self.position_snapshots.push(position.clone());
+        }
+        self.state_changes
+            .push(StateChange::Position(PositionChange::from(position)));
+        let realized_after = position.realized_pnl.map_or(0.0, |pnl| pnl.as_f64());
+        let settlement_currency = position.settlement_currency;
+
+        let mut deltas: IndexMap<Currency, f64> = IndexMap::new();
+        let commission = fill.commission;
+        match &self.account {
+            ExchangeAccount::Cash(account) => {
+                for pnl in account.base_calculate_pnls(instrument, *fill, None)? {
+                    *deltas.entry(pnl.currency).or_default() += pnl.as_f64();
+                }
+                if let Some(commission) = commission {
+                    *deltas.entry(commission.currency).or_default() -= commission.as_f64();
+                }
+            }
+            ExchangeAccount::Margin(_) => {
+                // Position realized PnL already includes commissions in the settlement currency
+                *deltas.entry(settlement_currency).or_default() += realized_after - realized_before;
+                if let Some(commission) = commission {
+                    if commission.currency != settlement_currency {
+                        *deltas.entry(commission.currency).or_default() -= commission.as_f64();
+                    }
+                }
+            }
+        }
+        self.adjust_balances(&deltas, fill.ts_event)?;
+
+        if let ExchangeAccount::Margin(account) = &mut self.account {
+            let instrument = self.matching_engines[&fill.instrument_id]
+                .instrument
+                .as_ref();
+            let positions: Vec<&Position> = self
+                .positions
+                .values()
+                .filter(|position| position.instrument_id == fill.instrument_id)
+                .collect();
+            account.update_position_margins(instrument, &positions, fill.last_px, None)?;
+        }
+        Ok(())
+    }
+
+    fn adjust_balances(
+        &mut self,
+        deltas: &IndexMap<Currency, f64>,
+        ts_event: UnixNanos,
+    ) -> Result<()> {
+        let base = self.account.base();
+        let mut balances = Vec::with_capacity(deltas.len());
+        for (currency, delta) in deltas {
+            if *delta == 0.0 {
+                continue;
+            }
+            let (total, locked) = match base.balances.get(currency) {
+                Some(balance) => (balance.total.as_f64(), balance.locked),
+                None => (0.0, Money::new(0.0, *currency)?),
+            };
+            let total = Money::new(total + delta, *currency)?;
+            if total.raw < 0 {
+                log::warn!("Account {} balance negative: {total}", self.account_id);
+            }
+            let free = Money::from_raw(total.raw - locked.raw, *currency);
+            balances.push(AccountBalance::new(total, locked, free)?);
+        }
+        if balances.is_empty() {
+            return Ok(());
+        }
+
+        let mut margins: Vec<MarginBalance> = match &self.account {
+            ExchangeAccount::Margin(account) => account.margins.values().copied().collect(),
+            ExchangeAccount::Cash(_) => vec![],
+        };
+        // Margins are held in a hash map, so are sorted for a reproducible account state
+        margins.sort_by_key(|margin| margin.instrument_id);
+        let state = AccountState::new(
+            self.account_id,
+            self.account_type,
+            balances,
+            margins,
+            true,
+            self.uuid_generator.generate(),
+            ts_event,
+            ts_event,
+            self.base_currency,
+        )?;
+        self.state_changes.push(StateChange::Account(state.clone()));
+        self.account.apply(state);
+        Ok(())
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Tests
+////////////////////////////////////////////////////////////////////////////////
+#[cfg(test)]
+mod tests {
+    use std::{
+        cell::Cell,
+        rc::Rc,
+        sync::{Arc, Mutex},
+    };
+
+    use nautilus_accounting::models::fee::{FeeTier, MakerTakerFeeModel, TieredVolumeFeeModel};
+    use nautilus_model::{
+        data::quote::QuoteTick,
+        enums::{OrderSide, OrderType},
+        events::order::{initialized::OrderInitializedBuilder, submitted::OrderSubmittedBuilder},
+        instruments::{currency_pair::CurrencyPair, stubs::audusd_sim},
+        orderbook::book::OrderBook,
+        orders::{base::Order, limit::LimitOrder, market::MarketOrder},
+        types::quantity::Quantity,
+    };
+    use rstest::rstest;
+    use rust_decimal_macros::dec;
+
+    use super::*;
+    use crate::models::{fill::ProbabilisticFillModel, latency::ConstantLatencyModel};
+
+    /// Never fills resting limit orders, counting how often it is asked to.
+    struct CountingFillModel {
+        limit_checks: Rc<Cell<usize>>,
+    }
+
+    impl FillModel for CountingFillModel {
+        fn is_limit_filled(&mut self, _book: &OrderBook, _order: &dyn Order) -> bool {
+            self.limit_checks.set(self.limit_checks.get() + 1);
+            false
+        }
+
+        fn is_slipped(&mut self, _book: &OrderBook, _order: &dyn Order) -> bool {
+            false
+        }
+    }
+
+    fn exchange(
+        time: &'static AtomicTime,
+        oms_type: OmsType,
+        account_type: AccountType,
+        latency_model: Option<Box<dyn LatencyModel>>,
+    ) -> SimulatedExchange {
+        exchange_with(
+            time,
+            oms_type,
+            account_type,
+            latency_model,
+            Money::from("1000000 USD"),
+            Box::<ProbabilisticFillModel>::default(),
+            Arc::new(Mutex::new(MakerTakerFeeModel)),
+        )
+    }
+
+    fn exchange_with(
+        time: &'static AtomicTime,
+        oms_type: OmsType,
+        account_type: AccountType,
+        latency_model: Option<Box<dyn LatencyModel>>,
+        starting_balance: Money,
+        fill_model: Box<dyn FillModel>,
+        fee_model: SharedFeeModel,
+    ) -> SimulatedExchange {
+        let mut exchange = SimulatedExchange::new(
+            Venue::from("SIM"),
+            oms_type,
+            account_type,
+            vec![starting_balance],
+            None,
+            10.0,
+            BookType::L1_MBP,
+            OrderMatchingEngineConfig::default(),
+            fee_model,
+            latency_model,
+            time,
+        )
+        .unwrap();
+        exchange
+            .add_instrument(Box::new(audusd_sim()), fill_model)
+            .unwrap();
+        exchange
+    }
+
+    fn quote(bid: &str, ask: &str) -> Data {
+        Data::Quote(
+            QuoteTick::new(
+                InstrumentId::from("AUD/USD.SIM"),
+                Price::from(bid),
+                Price::from(ask),
+                Quantity::from("1000000"),
+                Quantity::from("1000000"),
+                0,
+                0,
+            )
+            .unwrap(),
+        )
+    }
+
+    fn market_order(client_order_id: &str, side: OrderSide) -> TradingCommand {
+        let init = OrderInitializedBuilder::default()
+            .instrument_id(InstrumentId::from("AUD/USD.SIM"))
+            .client_order_id(ClientOrderId::from(client_order_id))
+            .order_type(OrderType::Market)
+            .order_side(side)
+            .quantity(Quantity::from("100000"))
+            .build()
+            .unwrap();
+        let submitted = OrderSubmittedBuilder::default()
+            .client_order_id(init.client_order_id)
+            .strategy_id(init.strategy_id)
+            .build()
+            .unwrap();
+        let mut order: Box<dyn Order> = Box::new(MarketOrder::from(init));
+        order.apply(OrderEvent::OrderSubmitted(submitted)).unwrap();
+        TradingCommand::SubmitOrder(order)
+    }
+
+    fn limit_order(client_order_id: &str, side: OrderSide, price: &str) -> TradingCommand {
+        let init = OrderInitializedBuilder::default()
+            .instrument_id(InstrumentId::from("AUD/USD.SIM"))
+            .client_order_id(ClientOrderId::from(client_order_id))
+            .order_type(OrderType::Limit)
+            .order_side(side)
+            .quantity(Quantity::from("100000"))
+            .price(Some(Price::from(price)))
+            .time_in_force(TimeInForce::Gtc)
+            .build()
+            .unwrap();
+        let submitted = OrderSubmittedBuilder::default()
+            .client_order_id(init.client_order_id)
+            .strategy_id(init.strategy_id)
+            .build()
+            .unwrap();
+        let mut order: Box<dyn Order> = Box::new(LimitOrder::from(init));
+        order.apply(OrderEvent::OrderSubmitted(submitted)).unwrap();
+        TradingCommand::SubmitOrder(order)
+    }
+
+    fn fills(events: &[OrderEvent]) -> Vec<OrderFilled> {
+        events
+            .iter()
+            .filter_map(|event| match event {
+                OrderEvent::OrderFilled(fill) | OrderEvent::OrderPartiallyFilled(fill) => {
+                    Some(*fill)
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[rstest]
+    fn test_new_with_betting_account_errors() {
+        static TIME: AtomicTime = AtomicTime::new(false, 0);
+        let result = SimulatedExchange::new(
+            Venue::from("SIM"),
+            OmsType::Netting,
+            AccountType::Betting,
+            vec![Money::from("1000000 USD")],
+            None,
+            1.0,
+            BookType::L1_MBP,
+            OrderMatchingEngineConfig::default(),
+            Arc::new(Mutex::new(MakerTakerFeeModel)),
+            None,
+            &TIME,
+        );
+        assert!(result.is_err());
+    }
+
+    #[rstest]
+    fn test_add_duplicate_instrument_errors(audusd_sim: CurrencyPair) {
+        static TIME: AtomicTime = AtomicTime::new(false, 0);
+        let mut exchange = exchange(&TIME, OmsType::Netting, AccountType::Cash, None);
+        let result = exchange.add_instrument(
+            Box::new(audusd_sim),
+            Box::<ProbabilisticFillModel>::default(),
+        );
+        assert!(result.is_err());
+        assert_eq!(exchange.instrument_ids().len(), 1);
+        assert_eq!(exchange.account_id, AccountId::from("SIM-001"));
+    }
+
+    #[rstest]
+    fn test_cash_account_fill_updates_balances() {
+        static TIME: AtomicTime = AtomicTime::new(false, 0);
+        let mut exchange = exchange(&TIME, OmsType::Netting, AccountType::Cash, None);
+        exchange.process_data(quote("1.00000", "1.00001")).unwrap();
+        exchange.send(market_order("O-1", OrderSide::Buy));
+        exchange.process(0).unwrap();
+
+        let fills = fills(&exchange.drain_events(0));
+        assert_eq!(fills.len(), 1);
+        assert_eq!(
+            fills[0].position_id,
+            Some(PositionId::from("AUD/USD.SIM-S-001"))
+        );
+
+        let base = exchange.account().base();
+        let usd = Currency::from("USD");
+        let aud = Currency::from("AUD");
+        assert_eq!(base.balances[&usd].total, Money::from("899997.00 USD"));
+        assert_eq!(base.balances[&aud].total, Money::from("100000.00 AUD"));
+        assert_eq!(exchange.positions_open().len(), 1);
+    }
+
+    #[rstest]
+    fn test_fills_recorded_with_fee_model_shared_by_account_and_engine() {
+        static TIME: AtomicTime = AtomicTime::new(false, 0);
+        let fee_model = Arc::new(Mutex::new(
+            TieredVolumeFeeModel::new(
+                vec![
+                    FeeTier::new(100_000.0, dec!(0.0001), dec!(0.0001)),
+                    FeeTier::new(0.0, dec!(0.0002), dec!(0.0002)),
+                ],
+                None,
+            )
+            .unwrap(),
+        ));
+        let mut exchange = exchange_with(
+            &TIME,
+            OmsType::Netting,
+            AccountType::Cash,
+            None,
+            Money::from("1000000 USD"),
+            Box::<ProbabilisticFillModel>::default(),
+            fee_model.clone(),
+        );
+        exchange.process_data(quote("1.00000", "1.00001")).unwrap();
+        exchange.send(market_order("O-1", OrderSide::Buy));
+        exchange.process(0).unwrap();
+        exchange.send(market_order("O-2", OrderSide::Buy));
+        exchange.process(0).unwrap();
+
+        let fills = fills(&exchange.drain_events(0));
+        assert_eq!(fills.len(), 2);
+        assert_eq!(fills[0].commission, Some(Money::from("20.00 USD")));
+        assert_eq!(fills[1].commission, Some(Money::from("10.00 USD")));
+        assert_eq!(fee_model.lock().unwrap().rolling_volume(0), 200_002.0);
+    }
+
+    #[rstest]
+    fn test_margin_account_round_trip_realizes_pnl() {
+        static TIME: AtomicTime = AtomicTime::new(false, 0);
+        let mut exchange = exchange(&TIME, OmsType::Netting, AccountType::Margin, None);
+        exchange.process_data(quote("1.00000", "1.00001")).unwrap();
+        exchange.send(market_order("O-1", OrderSide::Buy));
+        exchange.process(0).unwrap();
+
+        let ExchangeAccount::Margin(account) = exchange.account() else {
+            panic!("Expected margin account");
+        };
+        let instrument_id = InstrumentId::from("AUD/USD.SIM");
+        assert!(account.initial_margin(instrument_id).as_f64() > 0.0);
+        assert!(!exchange.is_margin_call());
+
+        exchange.process_data(quote("1.00010", "1.00011")).unwrap();
+        exchange.send(market_order("O-2", OrderSide::Sell));
+        exchange.process(0).unwrap();
+
+        assert_eq!(fills(&exchange.drain_events(0)).len(), 2);
+        assert!(exchange.positions_open().is_empty());
+        let base = exchange.account().base();
+        assert_eq!(
+            base.balances[&Currency::from("USD")].total,
+            Money::from("1000005.00 USD")
+        );
+    }
+
+    #[rstest]
+    fn test_each_data_point_matched_once() {
+        static TIME: AtomicTime = AtomicTime::new(false, 0);
+        let limit_checks = Rc::new(Cell::new(0));
+        let fill_model = CountingFillModel {
+            limit_checks: limit_checks.clone(),
+        };
+        let mut exchange = exchange_with(
+            &TIME,
+            OmsType::Netting,
+            AccountType::Cash,
+            None,
+            Money::from("1000000 USD"),
+            Box::new(fill_model),
+            Arc::new(Mutex::new(MakerTakerFeeModel)),
+        );
+        exchange.process_data(quote("1.00000", "1.00001")).unwrap();
+        exchange.send(limit_order("O-1", OrderSide::Buy, "0.99999"));
+        exchange.process(0).unwrap();
+        let limit_checks_resting = limit_checks.get();
+
+        // The ask reaches the limit price, which the fill model then declines once
+        exchange.process_data(quote("0.99998", "0.99999")).unwrap();
+        assert_eq!(limit_checks.get(), limit_checks_resting + 1);
+
+        // No new commands, so the matching engine is not iterated again
+        exchange.process(0).unwrap();
+        assert_eq!(limit_checks.get(), limit_checks_resting + 1);
+    }
+
+    #[rstest]
+    fn test_margin_call_liquidates_open_positions() {
+        static TIME: AtomicTime = AtomicTime::new(false, 0);
+        let mut exchange = exchange_with(
+            &TIME,
+            OmsType::Netting,
+            AccountType::Margin,
+            None,
+            Money::from("1000 USD"),
+            Box::<ProbabilisticFillModel>::default(),
+            Arc::new(Mutex::new(MakerTakerFeeModel)),
+        );
+        exchange.process_data(quote("1.00000", "1.00001")).unwrap();
+        exchange.send(market_order("O-1", OrderSide::Buy));
+        exchange.process(0).unwrap();
+        assert_eq!(exchange.positions_open().len(), 1);
+        assert!(!exchange.is_margin_call());
+
+        exchange.process_data(quote("0.99200", "0.99201")).unwrap();
+
+        let fills = fills(&exchange.drain_events(0));
+        assert_eq!(fills.len(), 2);
+        assert_eq!(fills[1].client_order_id, ClientOrderId::from("LIQ-SIM-001"));
+        assert_eq!(fills[1].order_side, OrderSide::Sell);
+        assert_eq!(fills[1].last_px, Price::from("0.99200"));
+        assert!(exchange.positions_open().is_empty());
+        assert!(!exchange.is_margin_call());
+    }
+
+    #[rstest]
+    fn test_cash_order_exceeding_balance_rejected() {
+        static TIME: AtomicTime = AtomicTime::new(false, 0);
+        let mut exchange = exchange_with(
+            &TIME,
+            OmsType::Netting,
+            AccountType::Cash,
+            None,
+            Money::from("1000 USD"),
+            Box::<ProbabilisticFillModel>::default(),
+            Arc::new(Mutex::new(MakerTakerFeeModel)),
+        );
+        exchange.process_data(quote("1.00000", "1.00001")).unwrap();
+        exchange.send(market_order("O-1", OrderSide::Buy));
+        exchange.process(0).unwrap();
+
+        let events = exchange.drain_events(0);
+        assert_eq!(events.len(), 1);
+        let OrderEvent::OrderRejected(rejected) = &events[0] else {
+            panic!("Expected order rejected, was {:?}", events[0]);
+        };
+        assert!(rejected.reason.as_str().starts_with("Insufficient balance"));
+        assert!(exchange.positions().is_empty());
+        assert_eq!(
+            exchange.account().base().balances[&Currency::from("USD")].total,
+            Money::from("1000 USD")
+        );
+    }
+
+    #[rstest]
+    fn test_margin_order_exceeding_margin_rejected() {
+        static TIME: AtomicTime = AtomicTime::new(false, 0);
+        let mut exchange = exchange_with(
+            &TIME,
+            OmsType::Netting,
+            AccountType::Margin,
+            None,
+            Money::from("100 USD"),
+            Box::<ProbabilisticFillModel>::default(),
+            Arc::new(Mutex::new(MakerTakerFeeModel)),
+        );
+        exchange.process_data(quote("1.00000", "1.00001")).unwrap();
+        exchange.send(market_order("O-1", OrderSide::Buy));
+        exchange.process(0).unwrap();
+
+        let events = exchange.drain_events(0);
+        assert_eq!(events.len(), 1);
+        let OrderEvent::OrderRejected(rejected) = &events[0] else {
+            panic!("Expected order rejected, was {:?}", events[0]);
+        };
+        assert!(rejected.reason.as_str().starts_with("Margin exceeded"));
+        assert!(exchange.positions().is_empty());
+    }
+
+    #[rstest]
+    fn test_fill_exceeding_margin_still_published() {
+        static TIME: AtomicTime = AtomicTime::new(false, 0);
+        let mut exchange = exchange_with(
+            &TIME,
+            OmsType::Netting,
+            AccountType::Margin,
+            None,
+            Money::from("1000 USD"),
+            Box::<ProbabilisticFillModel>::default(),
+            Arc::new(Mutex::new(MakerTakerFeeModel)),
+        );
+        exchange.process_data(quote("1.00000", "1.00002")).unwrap();
+
+        // Each resting order is funded on its own, but not both together
+        exchange.send(limit_order("O-1", OrderSide::Buy, "1.00001"));
+        exchange.send(limit_order("O-2", OrderSide::Buy, "1.00001"));
+        exchange.process(0).unwrap();
+        assert!(fills(&exchange.drain_events(0)).is_empty());
+
+        exchange.process_data(quote("1.00000", "1.00001")).unwrap();
+
+        assert_eq!(fills(&exchange.drain_events(0)).len(), 2);
+        let positions = exchange.positions_open();
+        assert_eq!(positions.len(), 1);
+        assert_eq!(positions[0].quantity, Quantity::from("200000"));
+    }
+
+    #[rstest]
+    fn test_hedging_oms_opens_separate_positions() {
+        static TIME: AtomicTime = AtomicTime::new(false, 0);
+        let mut exchange = exchange(&TIME, OmsType::Hedging, AccountType::Margin, None);
+        exchange.process_data(quote("1.00000", "1.00001")).unwrap();
+        exchange.send(market_order("O-1", OrderSide::Buy));
+        exchange.send(market_order("O-2", OrderSide::Buy));
+        exchange.process(0).unwrap();
+
+        let fills = fills(&exchange.drain_events(0));
+        assert_eq!(fills.len(), 2);
+        assert_eq!(fills[0].position_id, Some(PositionId::from("SIM-1-001")));
+        assert_eq!(fills[1].position_id, Some(PositionId::from("SIM-1-002")));
+        assert_eq!(exchange.positions_open().len(), 2);
+    }
+
+    #[rstest]
+    fn test_netting_oms_aggregates_position() {
+        static TIME: AtomicTime = AtomicTime::new(false, 0);
+        let mut exchange = exchange(&TIME, OmsType::Netting, AccountType::Margin, None);
+        exchange.process_data(quote("1.00000", "1.00001")).unwrap();
+        exchange.send(market_order("O-1", OrderSide::Buy));
+        exchange.send(market_order("O-2", OrderSide::Buy));
+        exchange.process(0).unwrap();
+
+        let positions = exchange.positions_open();
+        assert_eq!(positions.len(), 1);
+        assert_eq!(positions[0].quantity, Quantity::from("200000"));
+    }
+
+    #[rstest]
+    fn test_latency_delays_commands_and_events() {
+        static TIME: AtomicTime = AtomicTime::new(false, 0);
+        let latency_model = Box::new(ConstantLatencyModel::new(100));
+        let mut exchange = exchange(
+            &TIME,
+            OmsType::Netting,
+            AccountType::Cash,
+            Some(latency_model),
+        );
+        exchange.process_data(quote("1.00000", "1.00001")).unwrap();
+        exchange.send(market_order("O-1", OrderSide::Buy));
+
+        exchange.process(99).unwrap();
+        assert!(exchange.positions_open().is_empty());
+        assert!(exchange.has_inflight());
+
+        exchange.process(100).unwrap();
+        assert_eq!(exchange.positions_open().len(), 1);
+        assert!(exchange.drain_events(199).is_empty());
+        assert_eq!(fills(&exchange.drain_events(200)).len(), 1);
+        assert!(!exchange.has_inflight());
+    }
+
+    #[rstest]
+    fn test_reset_restores_starting_balances() {
+        static TIME: AtomicTime = AtomicTime::new(false, 0);
+        let mut exchange = exchange(&TIME, OmsType::Netting, AccountType::Cash, None);
+        exchange.process_data(quote("1.00000", "1.00001")).unwrap();
+        exchange.send(market_order("O-1", OrderSide::Buy));
+        exchange.process(0).unwrap();
+
+        exchange.reset().unwrap();
+
+        let base = exchange.account().base();
+        assert_eq!(
+            base.balances[&Currency::from("USD")].total,
+            Money::from("1000000 USD")
+        );
+        assert!(exchange.positions().is_empty());
+        assert_eq!(exchange.instrument_ids().len(), 1);
+    }
+}
@@ -13,4 +13,11 @@
 //  limitations under the License.
 // -------------------------------------------------------------------------------------------------
 
+pub mod auction;
 pub mod engine;
+pub mod exchange;
+pub mod matching_engine;
+pub mod models;
+pub mod replay;
+pub mod result;
+pub mod statistics;
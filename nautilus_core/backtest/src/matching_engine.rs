@@ -0,0 +1,2338 @@
+// -------------------------------------------------------------------------------------------------
+//  Copyright (C) 2015-2024 Nautech Systems Pty Ltd. All rights reserved.
+//  https://nautechsystems.io
+//
+//  Licensed under the GNU Lesser General Public License Version 3.0 (the "License");
+//  You may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at https://www.gnu.org/licenses/lgpl-3.0.en.html
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+// -------------------------------------------------------------------------------------------------
+
+use std::collections::HashSet;
+
+use anyhow::{anyhow, bail, Result};
+use indexmap::IndexMap;
+use nautilus_accounting::models::fee::SharedFeeModel;
+use nautilus_core::time::{AtomicTime, UnixNanos};
+use nautilus_execution::{
+    matching_core::OrderMatchingCore,
+    messages::{cancel::CancelOrder, cancel_all::CancelAllOrders, modify::ModifyOrder},
+    trailing::{trailing_stop_calculate_with_price_tiers, PriceTier},
+};
+use nautilus_model::{
+    data::{
+        delta::OrderBookDelta, depth::OrderBookDepth10, order::BookOrder, quote::QuoteTick,
+        trade::TradeTick,
+    },
+    enums::{BookType, LiquiditySide, OrderSide, OrderStatus, OrderType, TimeInForce},
+    events::order::{
+        accepted::OrderAccepted, cancel_rejected::OrderCancelRejected, canceled::OrderCanceled,
+        event::OrderEvent, expired::OrderExpired, filled::OrderFilled,
+        modify_rejected::OrderModifyRejected, rejected::OrderRejected, triggered::OrderTriggered,
+        updated::OrderUpdated,
+    },
+    identifiers::{
+        account_id::AccountId, client_order_id::ClientOrderId, instrument_id::InstrumentId,
+        strategy_id::StrategyId, trade_id::TradeId, trader_id::TraderId, venue::Venue,
+        venue_order_id::VenueOrderId,
+    },
+    instruments::Instrument,
+    orderbook::book::OrderBook,
+    orders::base::Order,
+    types::{price::Price, quantity::Quantity},
+};
+use ustr::Ustr;
+
+use crate::{
+    auction::{uncross, AuctionOrder},
+    models::fill::FillModel,
+    replay::{derive_seed, UUID4Generator},
+};
+
+/// Configuration for an [`OrderMatchingEngine`].
+#[derive(Clone, Debug)]
+pub struct OrderMatchingEngineConfig {
+    /// If stop orders are rejected on submission if their trigger price is in the market.
+    pub reject_stop_orders: bool,
+    /// If orders with GTD time in force are supported by the venue.
+    pub support_gtd_orders: bool,
+    /// The price tiers of the venue, used for `PRICE_TIER` trailing offsets.
+    pub price_tiers: Vec<PriceTier>,
+}
+
+impl Default for OrderMatchingEngineConfig {
+    fn default() -> Self {
+        Self {
+            reject_stop_orders: true,
+            support_gtd_orders: true,
+            price_tiers: Vec::new(),
+        }
+    }
+}
+
+/// Provides an order matching engine for a single market.
+///
+/// The engine owns an [`OrderBook`] for the instrument, processes order commands
+/// against it, and accumulates the resulting order events which can then be
+/// drained with [`OrderMatchingEngine::drain_events`].
+pub struct OrderMatchingEngine {
+    pub venue: Venue,
+    pub instrument: Box<dyn Instrument>,
+    pub raw_id: u32,
+    pub book_type: BookType,
+    pub account_id: AccountId,
+    pub config: OrderMatchingEngineConfig,
+    clock: &'static AtomicTime,
+    fill_model: Box<dyn FillModel>,
+    fee_model: SharedFeeModel,
+    uuid_generator: UUID4Generator,
+    book: OrderBook,
+    core: OrderMatchingCore,
+    orders: IndexMap<ClientOrderId, Box<dyn Order>>,
+    triggered: HashSet<ClientOrderId>,
+    events: Vec<OrderEvent>,
+    venue_order_id_count: usize,
+    execution_count: usize,
+}
+
+impl OrderMatchingEngine {
+    /// Initializes a new `OrderMatchingEngine` instance.
+    #[must_use]
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        instrument: Box<dyn Instrument>,
+        raw_id: u32,
+        account_id: AccountId,
+        book_type: BookType,
+        fill_model: Box<dyn FillModel>,
+        fee_model: SharedFeeModel,
+        config: OrderMatchingEngineConfig,
+        clock: &'static AtomicTime,
+    ) -> Self {
+        let book = OrderBook::new(instrument.id(), book_type);
+        let mut core = OrderMatchingCore::new(instrument.id(), instrument.price_increment());
+        core.set_price_tiers(config.price_tiers.clone());
+        Self {
+            venue: instrument.venue(),
+            instrument,
+            raw_id,
+            book_type,
+            account_id,
+            config,
+            clock,
+            fill_model,
+            fee_model,
+            uuid_generator: UUID4Generator::default(),
+            book,
+            core,
+            orders: IndexMap::new(),
+            triggered: HashSet::new(),
+            events: Vec::new(),
+            venue_order_id_count: 0,
+            execution_count: 0,
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.book.reset();
+        self.core.reset();
+        self.orders.clear();
+        self.triggered.clear();
+        self.events.clear();
+        self.venue_order_id_count = 0;
+        self.execution_count = 0;
+    }
+
+    /// Reseeds the random number generators of the engine's fill model and event IDs
+    /// from `seed`.
+    ///
+    /// Passing `None` reverts event IDs to non-deterministic UUIDs, leaving the fill
+    /// model as is.
+    pub fn reseed(&mut self, seed: Option<u64>) {
+        if let Some(seed) = seed {
+            self.fill_model.reseed(seed);
+        }
+        self.uuid_generator
+            .reseed(seed.map(|seed| derive_seed(seed, 0)));
+    }
+
+    #[must_use]
+    pub fn instrument_id(&self) -> InstrumentId {
+        self.instrument.id()
+    }
+
+    #[must_use]
+    pub fn book(&self) -> &OrderBook {
+        &self.book
+    }
+
+    #[must_use]
+    pub fn best_bid_price(&self) -> Option<Price> {
+        self.book.best_bid_price()
+    }
+
+    #[must_use]
+    pub fn best_ask_price(&self) -> Option<Price> {
+        self.book.best_ask_price()
+    }
+
+    #[must_use]
+    pub fn order_exists(&self, client_order_id: &ClientOrderId) -> bool {
+        self.orders.contains_key(client_order_id)
+    }
+
+    #[must_use]
+    pub fn get_order(&self, client_order_id: &ClientOrderId) -> Option<&dyn Order> {
+        self.orders.get(client_order_id).map(|order| order.as_ref())
+    }
+
+    /// Returns the orders currently working in the matching engine.
+    #[must_use]
+    pub fn get_open_orders(&self) -> Vec<&dyn Order> {
+        self.orders.values().map(|order| order.as_ref()).collect()
+    }
+
+    /// Drain the order events generated since the last call, in the order they occurred.
+    pub fn drain_events(&mut self) -> Vec<OrderEvent> {
+        std::mem::take(&mut self.events)
+    }
+
+    // -- DATA PROCESSING -------------------------------------------------------------------------
+
+    pub fn process_order_book_delta(&mut self, delta: OrderBookDelta) -> Result<()> {
+        // A sequence gap leaves the book buffering deltas until the next snapshot,
+        // so matching continues against the last consistent state of the book
+        if let Err(e) = self.book.apply_delta(delta) {
+            log::warn!("Error applying delta for {}: {e}", self.instrument.id());
+        }
+        self.iterate(delta.ts_init)
+    }
+
+    pub fn process_order_book_depth10(&mut self, depth: OrderBookDepth10) -> Result<()> {
+        let ts_init = depth.ts_init;
+        self.book.apply_depth(depth);
+        self.iterate(ts_init)
+    }
+
+    pub fn process_quote_tick(&mut self, tick: &QuoteTick) -> Result<()> {
+        if self.book_type == BookType::L1_MBP {
+            self.book.update_quote_tick(tick);
+        }
+        self.iterate(tick.ts_init)
+    }
+
+    pub fn process_trade_tick(&mut self, tick: &TradeTick) -> Result<()> {
+        if self.book_type == BookType::L1_MBP {
+            self.book.update_trade_tick(tick);
+        }
+        self.core.set_last(tick.price);
+        self.iterate(tick.ts_init)
+    }
+
+    /// Iterate the matching engine, expiring GTD orders and matching or triggering
+    /// any working orders against the current market.
+    ///
+    /// # Errors
+    ///
+    /// If a generated order event cannot be constructed or applied to its order.
+    pub fn iterate(&mut self, ts_now: UnixNanos) -> Result<()> {
+        self.update_core_prices();
+
+        let client_order_ids: Vec<ClientOrderId> = self.orders.keys().copied().collect();
+        for client_order_id in client_order_ids {
+            let Some(order) = self.orders.get(&client_order_id) else {
+                continue;
+            };
+            if order.is_closed() || is_auction_order(order.as_ref()) {
+                continue;
+            }
+
+            if order.time_in_force() == TimeInForce::Gtd {
+                if let Some(expire_time) = order.expire_time() {
+                    if ts_now >= expire_time {
+                        self.expire_order(client_order_id, ts_now)?;
+                        continue;
+                    }
+                }
+            }
+
+            self.match_order(client_order_id)?;
+
+            let order = &self.orders[&client_order_id];
+            if order.is_open()
+                && is_trailing_stop_order(order.as_ref())
+                && !self.triggered.contains(&client_order_id)
+            {
+                self.update_trailing_stop_order(client_order_id)?;
+            }
+        }
+
+        self.purge_closed_orders();
+        Ok(())
+    }
+
+    // -- COMMAND PROCESSING ----------------------------------------------------------------------
+
+    /// Rejects the given submitted `order` for `reason` without matching it, e.g.
+    /// when the account cannot fund the order.
+    ///
+    /// # Errors
+    ///
+    /// If the rejected event cannot be constructed or applied to the order.
+    pub fn process_rejection(&mut self, mut order: Box<dyn Order>, reason: &str) -> Result<()> {
+        let event = self.order_rejected_event(order.as_ref(), reason)?;
+        order
+            .apply(event.clone())
+            .map_err(|e| anyhow!("Error applying {event:?}: {e}"))?;
+        self.events.push(event);
+        Ok(())
+    }
+
+    /// Process the given submitted `order`, accepting, filling or rejecting it.
+    ///
+    /// Orders which fail validation, including orders which were never submitted,
+    /// are rejected.
+    ///
+    /// # Errors
+    ///
+    /// If a generated order event cannot be constructed or applied to its order.
+    pub fn process_order(&mut self, order: Box<dyn Order>) -> Result<()> {
+        self.update_core_prices();
+
+        if let Some(reason) = self.validate_order(order.as_ref()) {
+            return self.process_rejection(order, &reason);
+        }
+
+        let client_order_id = order.client_order_id();
+        let order_type = order.order_type();
+        let is_auction = is_auction_order(order.as_ref());
+        self.orders.insert(client_order_id, order);
+
+        if is_auction {
+            // Auction orders accumulate until the auction is uncrossed
+            return self.accept_order(client_order_id);
+        }
+
+        match order_type {
+            OrderType::Market => self.process_market_order(client_order_id)?,
+            OrderType::MarketToLimit => self.process_market_to_limit_order(client_order_id)?,
+            OrderType::Limit => self.process_limit_order(client_order_id)?,
+            OrderType::StopMarket | OrderType::TrailingStopMarket => {
+                self.process_stop_market_order(client_order_id)?;
+            }
+            OrderType::StopLimit | OrderType::TrailingStopLimit => {
+                self.process_stop_limit_order(client_order_id)?;
+            }
+            OrderType::MarketIfTouched => self.process_market_if_touched_order(client_order_id)?,
+            OrderType::LimitIfTouched => self.process_limit_if_touched_order(client_order_id)?,
+        }
+
+        self.purge_closed_orders();
+        Ok(())
+    }
+
+    /// Process the `command` to modify a working order, rejecting the modification
+    /// if the order is not found or the new prices are invalid.
+    ///
+    /// # Errors
+    ///
+    /// If a generated order event cannot be constructed or applied to its order.
+    pub fn process_modify(&mut self, command: &ModifyOrder) -> Result<()> {
+        self.update_core_prices();
+
+        let Some(order) = self.orders.get(&command.client_order_id) else {
+            return self.generate_order_modify_rejected(
+                command.trader_id,
+                command.strategy_id,
+                command.instrument_id,
+                command.client_order_id,
+                command.venue_order_id,
+                &format!("{} not found", command.client_order_id),
+            );
+        };
+
+        let client_order_id = command.client_order_id;
+        let order_type = order.order_type();
+        let side = order.side();
+        let is_post_only = order.is_post_only();
+        let is_triggered = self.triggered.contains(&client_order_id);
+        let quantity = command.quantity.unwrap_or(order.quantity());
+        let price = order.price().map(|price| command.price.unwrap_or(price));
+        let trigger_price = order
+            .trigger_price()
+            .map(|trigger_price| command.trigger_price.unwrap_or(trigger_price));
+
+        if quantity < order.filled_qty() {
+            let reason = format!(
+                "Cannot modify order quantity {} below filled quantity {}",
+                quantity,
+                order.filled_qty(),
+            );
+            return self.reject_modify(client_order_id, &reason);
+        }
+
+        let is_limit_resting = match order_type {
+            OrderType::Limit | OrderType::MarketToLimit => true,
+            OrderType::StopLimit | OrderType::TrailingStopLimit | OrderType::LimitIfTouched => {
+                is_triggered
+            }
+            _ => false,
+        };
+
+        if is_limit_resting {
+            let Some(price) = price else {
+                let reason = format!("{order_type} order has no price");
+                return self.reject_modify(client_order_id, &reason);
+            };
+            if is_post_only && self.core.is_limit_matched(side, price) {
+                let reason = format!(
+                    "POST_ONLY {order_type} {side} order with new limit px of {price} would have been a TAKER: bid={}, ask={}",
+                    format_price(self.core.bid),
+                    format_price(self.core.ask),
+                );
+                return self.reject_modify(client_order_id, &reason);
+            }
+        } else if let Some(trigger_price) = trigger_price {
+            let is_in_market = match order_type {
+                OrderType::MarketIfTouched | OrderType::LimitIfTouched => {
+                    self.core.is_touch_triggered(side, trigger_price)
+                }
+                _ => self.core.is_stop_triggered(side, trigger_price),
+            };
+            if is_in_market {
+                let reason = format!(
+                    "{order_type} {side} order new trigger px of {trigger_price} was in the market: bid={}, ask={}",
+                    format_price(self.core.bid),
+                    format_price(self.core.ask),
+                );
+                return self.reject_modify(client_order_id, &reason);
+            }
+        }
+
+        self.update_order(client_order_id, quantity, price, trigger_price)?;
+
+        if let Some(price) = price {
+            if is_limit_resting
+                && !is_auction_order(self.orders[&client_order_id].as_ref())
+                && self.core.is_limit_matched(side, price)
+            {
+                self.fill_limit_order(client_order_id, LiquiditySide::Taker)?;
+            }
+        }
+
+        self.purge_closed_orders();
+        Ok(())
+    }
+
+    /// Process the `command` to cancel a working order, rejecting the cancel if
+    /// the order is not found.
+    ///
+    /// # Errors
+    ///
+    /// If a generated order event cannot be constructed or applied to its order.
+    pub fn process_cancel(&mut self, command: &CancelOrder) -> Result<()> {
+        if self.orders.contains_key(&command.client_order_id) {
+            self.cancel_order(command.client_order_id)?;
+            self.purge_closed_orders();
+            Ok(())
+        } else {
+            self.generate_order_cancel_rejected(
+                command.trader_id,
+                command.strategy_id,
+                command.instrument_id,
+                command.client_order_id,
+                command.venue_order_id,
+                &format!("{} not found", command.client_order_id),
+            )
+        }
+    }
+
+    /// Process the `command` to cancel all working orders for the command's side.
+    ///
+    /// # Errors
+    ///
+    /// If a generated order event cannot be constructed or applied to its order.
+    pub fn process_cancel_all(&mut self, command: &CancelAllOrders) -> Result<()> {
+        let client_order_ids: Vec<ClientOrderId> = self
+            .orders
+            .values()
+            .filter(|order| {
+                command.order_side == OrderSide::NoOrderSide || command.order_side == order.side()
+            })
+            .map(|order| order.client_order_id())
+            .collect();
+
+        for client_order_id in client_order_ids {
+            self.cancel_order(client_order_id)?;
+        }
+
+        self.purge_closed_orders();
+        Ok(())
+    }
+
+    // -- AUCTIONS --------------------------------------------------------------------------------
+
+    /// Uncross the opening (`AtTheOpen`) or closing (`AtTheClose`) auction at `ts_auction`.
+    ///
+    /// The auction orders with the given `time_in_force` are crossed together with
+    /// the liquidity in the book at a single clearing price which maximizes the
+    /// executed volume (see [`uncross`]). Auction orders are then filled at the
+    /// clearing price in priority order (market orders first, then by limit price
+    /// and time), and any unfilled quantity is expired.
+    ///
+    /// Returns the clearing price, or `None` if the auction did not cross.
+    ///
+    /// # Errors
+    ///
+    /// If `time_in_force` is not `AtTheOpen` or `AtTheClose`, or if a generated order
+    /// event cannot be constructed or applied to its order.
+    pub fn process_auction(
+        &mut self,
+        time_in_force: TimeInForce,
+        ts_auction: UnixNanos,
+    ) -> Result<Option<Price>> {
+        if !matches!(
+            time_in_force,
+            TimeInForce::AtTheOpen | TimeInForce::AtTheClose
+        ) {
+            bail!("Invalid auction time in force {time_in_force}")
+        }
+
+        let client_order_ids: Vec<ClientOrderId> = self
+            .orders
+            .values()
+            .filter(|order| order.is_open() && order.time_in_force() == time_in_force)
+            .map(|order| order.client_order_id())
+            .collect();
+
+        let mut participants: Vec<AuctionOrder> = client_order_ids
+            .iter()
+            .map(|client_order_id| {
+                let order = &self.orders[client_order_id];
+                AuctionOrder::new(order.side(), order.price(), order.leaves_qty().raw)
+            })
+            .collect();
+        for (side, levels) in [
+            (OrderSide::Buy, self.book.bids()),
+            (OrderSide::Sell, self.book.asks()),
+        ] {
+            participants.extend(
+                levels.iter().map(|level| {
+                    AuctionOrder::new(side, Some(level.price.value), level.size_raw())
+                }),
+            );
+        }
+
+        let reference_price = self.core.last.or_else(|| self.mid_price());
+        let result = uncross(&participants, reference_price);
+
+        if let Some(result) = result {
+            let price = Price::from_raw(result.price.raw, self.instrument.price_precision())?;
+            for side in [OrderSide::Buy, OrderSide::Sell] {
+                let mut remaining_raw = result.volume_raw;
+                for client_order_id in self.auction_priority(&client_order_ids, side) {
+                    let order = &self.orders[&client_order_id];
+                    let participant =
+                        AuctionOrder::new(side, order.price(), order.leaves_qty().raw);
+                    if remaining_raw == 0 || !participant.is_executable_at(price) {
+                        continue;
+                    }
+                    let fill_raw = order.leaves_qty().raw.min(remaining_raw);
+                    let last_qty = Quantity::from_raw(fill_raw, self.instrument.size_precision())?;
+                    remaining_raw -= fill_raw;
+                    self.fill_order_at(
+                        client_order_id,
+                        price,
+                        last_qty,
+                        LiquiditySide::Taker,
+                        ts_auction,
+                    )?;
+                }
+            }
+            self.core.set_last(price);
+        }
+
+        for client_order_id in client_order_ids {
+            if self.orders[&client_order_id].is_open() {
+                self.expire_order(client_order_id, ts_auction)?;
+            }
+        }
+        self.purge_closed_orders();
+
+        Ok(result.map(|result| result.price))
+    }
+
+    /// Returns the auction orders for `side` in execution priority order.
+    fn auction_priority(
+        &self,
+        client_order_ids: &[ClientOrderId],
+        side: OrderSide,
+    ) -> Vec<ClientOrderId> {
+        let mut client_order_ids: Vec<ClientOrderId> = client_order_ids
+            .iter()
+            .filter(|client_order_id| self.orders[*client_order_id].side() == side)
+            .copied()
+            .collect();
+        // Stable sort retains time priority for orders at the same price
+        client_order_ids.sort_by_key(|client_order_id| {
+            match (side, self.orders[client_order_id].price()) {
+                (_, None) => (0, 0),
+                (OrderSide::Buy, Some(price)) => (1, -price.raw),
+                (_, Some(price)) => (1, price.raw),
+            }
+        });
+        client_order_ids
+    }
+
+    // -- ORDER PROCESSING ------------------------------------------------------------------------
+
+    fn validate_order(&self, order: &dyn Order) -> Option<String> {
+        if order.instrument_id() != self.instrument.id() {
+            return Some(format!(
+                "Instrument ID {} did not match engine {}",
+                order.instrument_id(),
+                self.instrument.id(),
+            ));
+        }
+
+        if order.status() != OrderStatus::Submitted {
+            return Some(format!(
+                "Invalid order status for order {}, was {} when SUBMITTED was expected",
+                order.client_order_id(),
+                order.status(),
+            ));
+        }
+
+        if order.side() == OrderSide::NoOrderSide {
+            return Some(format!(
+                "Invalid order side for order {}, was {}",
+                order.client_order_id(),
+                order.side(),
+            ));
+        }
+
+        if self.orders.contains_key(&order.client_order_id()) {
+            return Some(format!("Duplicate {}", order.client_order_id()));
+        }
+
+        let size_precision = self.instrument.size_precision();
+        if order.quantity().precision != size_precision {
+            return Some(format!(
+                "Invalid order quantity precision for order {}, was {} when {} size precision is {}",
+                order.client_order_id(),
+                order.quantity().precision,
+                self.instrument.id(),
+                size_precision,
+            ));
+        }
+
+        let price_precision = self.instrument.price_precision();
+        if let Some(price) = order.price() {
+            if price.precision != price_precision {
+                return Some(format!(
+                    "Invalid order price precision for order {}, was {} when {} price precision is {}",
+                    order.client_order_id(),
+                    price.precision,
+                    self.instrument.id(),
+                    price_precision,
+                ));
+            }
+        }
+
+        if let Some(trigger_price) = order.trigger_price() {
+            if trigger_price.precision != price_precision {
+                return Some(format!(
+                    "Invalid order trigger price precision for order {}, was {} when {} price precision is {}",
+                    order.client_order_id(),
+                    trigger_price.precision,
+                    self.instrument.id(),
+                    price_precision,
+                ));
+            }
+        }
+
+        if is_auction_order(order)
+            && !matches!(order.order_type(), OrderType::Market | OrderType::Limit)
+        {
+            return Some(format!(
+                "{} time in force only supported for MARKET and LIMIT orders, was {}",
+                order.time_in_force(),
+                order.order_type(),
+            ));
+        }
+
+        if order.time_in_force() == TimeInForce::Gtd && !self.config.support_gtd_orders {
+            return Some(format!(
+                "GTD time in force not supported for {}",
+                self.venue,
+            ));
+        }
+
+        None
+    }
+
+    fn process_market_order(&mut self, client_order_id: ClientOrderId) -> Result<()> {
+        let side = self.orders[&client_order_id].side();
+        if !self.has_market(side) {
+            let reason = format!("No market for {}", self.instrument.id());
+            return self.reject_order(client_order_id, &reason);
+        }
+
+        self.fill_market_order(client_order_id)
+    }
+
+    fn process_market_to_limit_order(&mut self, client_order_id: ClientOrderId) -> Result<()> {
+        let side = self.orders[&client_order_id].side();
+        if !self.has_market(side) {
+            let reason = format!("No market for {}", self.instrument.id());
+            return self.reject_order(client_order_id, &reason);
+        }
+
+        self.accept_order(client_order_id)?;
+        self.fill_market_order(client_order_id)
+    }
+
+    fn process_limit_order(&mut self, client_order_id: ClientOrderId) -> Result<()> {
+        let Some(price) = self.price_or_reject(client_order_id)? else {
+            return Ok(());
+        };
+        let order = &self.orders[&client_order_id];
+        let side = order.side();
+        let time_in_force = order.time_in_force();
+
+        if order.is_post_only() && self.core.is_limit_matched(side, price) {
+            let reason = format!(
+                "POST_ONLY LIMIT {side} order limit px of {price} would have been a TAKER: bid={}, ask={}",
+                format_price(self.core.bid),
+                format_price(self.core.ask),
+            );
+            return self.reject_order(client_order_id, &reason);
+        }
+
+        self.accept_order(client_order_id)?;
+
+        if self.core.is_limit_matched(side, price) {
+            self.fill_limit_order(client_order_id, LiquiditySide::Taker)
+        } else if matches!(time_in_force, TimeInForce::Fok | TimeInForce::Ioc) {
+            self.cancel_order(client_order_id)
+        } else {
+            Ok(())
+        }
+    }
+
+    fn process_stop_market_order(&mut self, client_order_id: ClientOrderId) -> Result<()> {
+        let Some(trigger_price) = self.trigger_price_or_reject(client_order_id)? else {
+            return Ok(());
+        };
+        let order = &self.orders[&client_order_id];
+        let side = order.side();
+        let order_type = order.order_type();
+
+        if self.core.is_stop_triggered(side, trigger_price) {
+            if self.config.reject_stop_orders {
+                let reason = format!(
+                    "{order_type} {side} order stop px of {trigger_price} was in the market: bid={}, ask={}",
+                    format_price(self.core.bid),
+                    format_price(self.core.ask),
+                );
+                return self.reject_order(client_order_id, &reason);
+            }
+            return self.fill_market_order(client_order_id);
+        }
+
+        self.accept_order(client_order_id)
+    }
+
+    fn process_stop_limit_order(&mut self, client_order_id: ClientOrderId) -> Result<()> {
+        let Some(price) = self.price_or_reject(client_order_id)? else {
+            return Ok(());
+        };
+        let Some(trigger_price) = self.trigger_price_or_reject(client_order_id)? else {
+            return Ok(());
+        };
+        let order = &self.orders[&client_order_id];
+        let side = order.side();
+        let order_type = order.order_type();
+
+        let is_triggered = self.core.is_stop_triggered(side, trigger_price);
+        if is_triggered && self.config.reject_stop_orders {
+            let reason = format!(
+                "{order_type} {side} order stop px of {trigger_price} was in the market: bid={}, ask={}",
+                format_price(self.core.bid),
+                format_price(self.core.ask),
+            );
+            return self.reject_order(client_order_id, &reason);
+        }
+
+        self.accept_order(client_order_id)?;
+
+        if is_triggered {
+            self.trigger_order(client_order_id)?;
+            if self.core.is_limit_matched(side, price) {
+                self.fill_limit_order(client_order_id, LiquiditySide::Taker)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn process_market_if_touched_order(&mut self, client_order_id: ClientOrderId) -> Result<()> {
+        let Some(trigger_price) = self.trigger_price_or_reject(client_order_id)? else {
+            return Ok(());
+        };
+        let side = self.orders[&client_order_id].side();
+
+        if self.core.is_touch_triggered(side, trigger_price) {
+            if self.config.reject_stop_orders {
+                let reason = format!(
+                    "MARKET_IF_TOUCHED {side} order trigger px of {trigger_price} was in the market: bid={}, ask={}",
+                    format_price(self.core.bid),
+                    format_price(self.core.ask),
+                );
+                return self.reject_order(client_order_id, &reason);
+            }
+            return self.fill_market_order(client_order_id);
+        }
+
+        self.accept_order(client_order_id)
+    }
+
+    fn process_limit_if_touched_order(&mut self, client_order_id: ClientOrderId) -> Result<()> {
+        let Some(price) = self.price_or_reject(client_order_id)? else {
+            return Ok(());
+        };
+        let Some(trigger_price) = self.trigger_price_or_reject(client_order_id)? else {
+            return Ok(());
+        };
+        let side = self.orders[&client_order_id].side();
+
+        let is_triggered = self.core.is_touch_triggered(side, trigger_price);
+        if is_triggered && self.config.reject_stop_orders {
+            let reason = format!(
+                "LIMIT_IF_TOUCHED {side} order trigger px of {trigger_price} was in the market: bid={}, ask={}",
+                format_price(self.core.bid),
+                format_price(self.core.ask),
+            );
+            return self.reject_order(client_order_id, &reason);
+        }
+
+        self.accept_order(client_order_id)?;
+
+        if is_triggered {
+            self.trigger_order(client_order_id)?;
+            if self.core.is_limit_matched(side, price) {
+                self.fill_limit_order(client_order_id, LiquiditySide::Taker)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the limit price of the order, rejecting the order if it has none.
+    fn price_or_reject(&mut self, client_order_id: ClientOrderId) -> Result<Option<Price>> {
+        let order = &self.orders[&client_order_id];
+        if let Some(price) = order.price() {
+            return Ok(Some(price));
+        }
+        let reason = format!("{} order has no price", order.order_type());
+        self.reject_order(client_order_id, &reason)?;
+        Ok(None)
+    }
+
+    /// Returns the trigger price of the order, rejecting the order if it has none.
+    fn trigger_price_or_reject(&mut self, client_order_id: ClientOrderId) -> Result<Option<Price>> {
+        let order = &self.orders[&client_order_id];
+        if let Some(trigger_price) = order.trigger_price() {
+            return Ok(Some(trigger_price));
+        }
+        let reason = format!("{} order has no trigger price", order.order_type());
+        self.reject_order(client_order_id, &reason)?;
+        Ok(None)
+    }
+
+    // -- ORDER MATCHING --------------------------------------------------------------------------
+
+    fn match_order(&mut self, client_order_id: ClientOrderId) -> Result<()> {
+        let order = &self.orders[&client_order_id];
+        let order_type = order.order_type();
+        let side = order.side();
+        let price = order.price();
+        let trigger_price = order.trigger_price();
+        let missing = |field: &str| anyhow!("{order_type} order {client_order_id} has no {field}");
+
+        match order_type {
+            OrderType::Market => {}
+            OrderType::Limit | OrderType::MarketToLimit => {
+                if let Some(price) = price {
+                    if self.core.is_limit_matched(side, price) {
+                        self.fill_limit_order(client_order_id, LiquiditySide::Maker)?;
+                    }
+                }
+            }
+            OrderType::StopMarket | OrderType::TrailingStopMarket => {
+                let trigger_price = trigger_price.ok_or_else(|| missing("trigger price"))?;
+                if self.core.is_stop_triggered(side, trigger_price) {
+                    self.fill_market_order(client_order_id)?;
+                }
+            }
+            OrderType::MarketIfTouched => {
+                let trigger_price = trigger_price.ok_or_else(|| missing("trigger price"))?;
+                if self.core.is_touch_triggered(side, trigger_price) {
+                    self.fill_market_order(client_order_id)?;
+                }
+            }
+            OrderType::StopLimit | OrderType::TrailingStopLimit | OrderType::LimitIfTouched => {
+                let price = price.ok_or_else(|| missing("price"))?;
+                if self.triggered.contains(&client_order_id) {
+                    if self.core.is_limit_matched(side, price) {
+                        self.fill_limit_order(client_order_id, LiquiditySide::Maker)?;
+                    }
+                    return Ok(());
+                }
+
+                let trigger_price = trigger_price.ok_or_else(|| missing("trigger price"))?;
+                let is_triggered = if order_type == OrderType::LimitIfTouched {
+                    self.core.is_touch_triggered(side, trigger_price)
+                } else {
+                    self.core.is_stop_triggered(side, trigger_price)
+                };
+                if is_triggered {
+                    self.trigger_order(client_order_id)?;
+                    if self.core.is_limit_matched(side, price) {
+                        self.fill_limit_order(client_order_id, LiquiditySide::Taker)?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn update_trailing_stop_order(&mut self, client_order_id: ClientOrderId) -> Result<()> {
+        let order = self.orders[&client_order_id].as_ref();
+
+        // Market data may not yet be available to trail from
+        let Ok((trigger_price, price)) = trailing_stop_calculate_with_price_tiers(
+            self.instrument.price_increment(),
+            &self.core.price_tiers,
+            order,
+            self.core.bid,
+            self.core.ask,
+            self.core.last,
+        ) else {
+            return Ok(());
+        };
+        if trigger_price.is_none() && price.is_none() {
+            return Ok(());
+        }
+
+        let quantity = order.quantity();
+        self.update_order(client_order_id, quantity, price, trigger_price)
+    }
+
+    fn fill_market_order(&mut self, client_order_id: ClientOrderId) -> Result<()> {
+        let order = &self.orders[&client_order_id];
+        let side = order.side();
+        let price = match side {
+            OrderSide::Buy => Price::max(self.instrument.price_precision()),
+            OrderSide::Sell => Price::min(self.instrument.price_precision()),
+            OrderSide::NoOrderSide => bail!("Invalid order side {side} for {client_order_id}"),
+        };
+        let book_order = BookOrder::new(side, price, order.leaves_qty(), 0);
+        let fills = self.book.simulate_fills(&book_order);
+
+        self.apply_fills(client_order_id, fills, LiquiditySide::Taker)
+    }
+
+    fn fill_limit_order(
+        &mut self,
+        client_order_id: ClientOrderId,
+        liquidity_side: LiquiditySide,
+    ) -> Result<()> {
+        let order = &self.orders[&client_order_id];
+        let order_type = order.order_type();
+        let price = order
+            .price()
+            .ok_or_else(|| anyhow!("{order_type} order {client_order_id} has no price"))?;
+
+        if liquidity_side == LiquiditySide::Maker
+            && !self.fill_model.is_limit_filled(&self.book, order.as_ref())
+        {
+            return Ok(());
+        }
+
+        let fills = if self.book_type == BookType::L1_MBP && liquidity_side == LiquiditySide::Maker
+        {
+            // Passive fills on a top-of-book only market are assumed to
+            // execute in full at the orders limit price
+            vec![(price, order.leaves_qty())]
+        } else {
+            let book_order = BookOrder::new(order.side(), price, order.leaves_qty(), 0);
+            self.book.simulate_fills(&book_order)
+        };
+
+        self.apply_fills(client_order_id, fills, liquidity_side)
+    }
+
+    fn apply_fills(
+        &mut self,
+        client_order_id: ClientOrderId,
+        fills: Vec<(Price, Quantity)>,
+        liquidity_side: LiquiditySide,
+    ) -> Result<()> {
+        let order = &self.orders[&client_order_id];
+        let order_type = order.order_type();
+        let side = order.side();
+        let time_in_force = order.time_in_force();
+
+        if time_in_force == TimeInForce::Fok {
+            let fill_qty_raw: u64 = fills.iter().map(|(_, qty)| qty.raw).sum();
+            if fill_qty_raw < order.leaves_qty().raw {
+                return self.cancel_order(client_order_id);
+            }
+        }
+
+        let price_precision = self.instrument.price_precision();
+        let size_precision = self.instrument.size_precision();
+        let price_increment = self.instrument.price_increment();
+        let mut last_fill_px: Option<Price> = None;
+
+        // Only fills without a limit price can slip through the top of an L1 book
+        let can_slip = liquidity_side == LiquiditySide::Taker
+            && self.book_type == BookType::L1_MBP
+            && order.price().is_none();
+
+        for (fill_px, fill_qty) in fills {
+            let order = &self.orders[&client_order_id];
+            if !order.is_open() {
+                break;
+            }
+
+            let last_qty =
+                Quantity::from_raw(fill_qty.raw.min(order.leaves_qty().raw), size_precision)?;
+            if last_qty.is_zero() {
+                continue;
+            }
+            let mut last_px = Price::from_raw(fill_px.raw, price_precision)?;
+            if can_slip && self.fill_model.is_slipped(&self.book, order.as_ref()) {
+                last_px = match side {
+                    OrderSide::Buy => last_px + price_increment,
+                    _ => last_px - price_increment,
+                };
+            }
+
+            let is_initial_market_to_limit_fill =
+                order_type == OrderType::MarketToLimit && order.price().is_none();
+
+            self.fill_order(client_order_id, last_px, last_qty, liquidity_side)?;
+            last_fill_px = Some(last_px);
+
+            if is_initial_market_to_limit_fill && self.orders[&client_order_id].is_open() {
+                // Remaining quantity now rests as a limit order at the first fill price
+                let quantity = self.orders[&client_order_id].quantity();
+                self.update_order(client_order_id, quantity, Some(last_px), None)?;
+                break;
+            }
+        }
+
+        let order = &self.orders[&client_order_id];
+        if !order.is_open() {
+            return Ok(());
+        }
+
+        let is_market_style = matches!(
+            order_type,
+            OrderType::Market
+                | OrderType::MarketIfTouched
+                | OrderType::StopMarket
+                | OrderType::TrailingStopMarket
+        );
+
+        if is_market_style && self.book_type == BookType::L1_MBP {
+            if let Some(last_fill_px) = last_fill_px {
+                // Exhausted the simulated top-of-book volume, so continue
+                // aggressively filling one tick through the last fill price
+                let fill_px = match side {
+                    OrderSide::Buy => last_fill_px + price_increment,
+                    _ => last_fill_px - price_increment,
+                };
+                let leaves_qty = order.leaves_qty();
+                return self.fill_order(client_order_id, fill_px, leaves_qty, liquidity_side);
+            }
+        }
+
+        if time_in_force == TimeInForce::Ioc || self.orders[&client_order_id].price().is_none() {
+            // Unfilled remainder of immediate or market orders cannot rest
+            return self.cancel_order(client_order_id);
+        }
+        Ok(())
+    }
+
+    fn fill_order(
+        &mut self,
+        client_order_id: ClientOrderId,
+        last_px: Price,
+        last_qty: Quantity,
+        liquidity_side: LiquiditySide,
+    ) -> Result<()> {
+        let ts_now = self.clock.get_time_ns();
+        self.fill_order_at(client_order_id, last_px, last_qty, liquidity_side, ts_now)
+    }
+
+    fn fill_order_at(
+        &mut self,
+        client_order_id: ClientOrderId,
+        last_px: Price,
+        last_qty: Quantity,
+        liquidity_side: LiquiditySide,
+        ts_event: UnixNanos,
+    ) -> Result<()> {
+        let fee_model = self
+            .fee_model
+            .lock()
+            .map_err(|e| anyhow!("Failed to lock fee model: {e}"))?;
+        let commission = fee_model.get_commission(
+            self.instrument.as_ref(),
+            last_qty,
+            last_px,
+            liquidity_side,
+            None,
+            ts_event,
+        );
+        drop(fee_model);
+        let commission = match commission {
+            Ok(commission) => commission,
+            Err(e) => {
+                // The fill cannot be priced, so the order is not filled
+                if self.orders[&client_order_id].status() == OrderStatus::Submitted {
+                    let reason = format!("Failed to calculate commission: {e}");
+                    return self.reject_order(client_order_id, &reason);
+                }
+                return self.cancel_order(client_order_id);
+            }
+        };
+
+        let venue_order_id = self.orders[&client_order_id].venue_order_id();
+        let venue_order_id = venue_order_id.unwrap_or_else(|| self.generate_venue_order_id());
+        let trade_id = self.generate_trade_id();
+
+        let order = &self.orders[&client_order_id];
+        let filled = OrderFilled::new(
+            order.trader_id(),
+            order.strategy_id(),
+            order.instrument_id(),
+            client_order_id,
+            venue_order_id,
+            self.account_id,
+            trade_id,
+            order.side(),
+            order.order_type(),
+            last_qty,
+            last_px,
+            self.instrument.quote_currency(),
+            liquidity_side,
+            self.uuid_generator.generate(),
+            ts_event,
+            ts_event,
+            false,
+            None,
+            Some(commission),
+        )?;
+
+        let event = if last_qty < order.leaves_qty() {
+            OrderEvent::OrderPartiallyFilled(filled)
+        } else {
+            OrderEvent::OrderFilled(filled)
+        };
+        self.apply_event(event)
+    }
+
+    // -- EVENT GENERATION ------------------------------------------------------------------------
+
+    fn accept_order(&mut self, client_order_id: ClientOrderId) -> Result<()> {
+        let venue_order_id = self.generate_venue_order_id();
+        let ts_now = self.clock.get_time_ns();
+        let order = &self.orders[&client_order_id];
+        let accepted = OrderAccepted::new(
+            order.trader_id(),
+            order.strategy_id(),
+            order.instrument_id(),
+            client_order_id,
+            venue_order_id,
+            self.account_id,
+            self.uuid_generator.generate(),
+            ts_now,
+            ts_now,
+            false,
+        )?;
+        self.apply_event(OrderEvent::OrderAccepted(accepted))?;
+
+        if self.orders[&client_order_id].order_type() == OrderType::Limit {
+            self.notify_order_resting(client_order_id);
+        }
+        Ok(())
+    }
+
+    fn reject_order(&mut self, client_order_id: ClientOrderId, reason: &str) -> Result<()> {
+        let event = self.order_rejected_event(self.orders[&client_order_id].as_ref(), reason)?;
+        self.apply_event(event)
+    }
+
+    fn order_rejected_event(&self, order: &dyn Order, reason: &str) -> Result<OrderEvent> {
+        let ts_now = self.clock.get_time_ns();
+        let rejected = OrderRejected::new(
+            order.trader_id(),
+            order.strategy_id(),
+            order.instrument_id(),
+            order.client_order_id(),
+            self.account_id,
+            Ustr::from(reason),
+            self.uuid_generator.generate(),
+            ts_now,
+            ts_now,
+            false,
+        )?;
+        Ok(OrderEvent::OrderRejected(rejected))
+    }
+
+    fn update_order(
+        &mut self,
+        client_order_id: ClientOrderId,
+        quantity: Quantity,
+        price: Option<Price>,
+        trigger_price: Option<Price>,
+    ) -> Result<()> {
+        let ts_now = self.clock.get_time_ns();
+        let order = &self.orders[&client_order_id];
+
+        // A new limit price or a larger size loses the orders existing queue priority,
+        // which only applies while the order is resting at its limit price
+        let is_resting = match order.order_type() {
+            OrderType::Limit | OrderType::MarketToLimit => true,
+            OrderType::StopLimit | OrderType::TrailingStopLimit | OrderType::LimitIfTouched => {
+                self.triggered.contains(&client_order_id)
+            }
+            _ => false,
+        };
+        let is_requeued = is_resting
+            && (price.map_or(false, |price| Some(price) != order.price())
+                || quantity > order.quantity());
+
+        let updated = OrderUpdated::new(
+            order.trader_id(),
+            order.strategy_id(),
+            order.instrument_id(),
+            client_order_id,
+            quantity,
+            self.uuid_generator.generate(),
+            ts_now,
+            ts_now,
+            false,
+            order.venue_order_id(),
+            Some(self.account_id),
+            price,
+            trigger_price,
+        )?;
+        self.apply_event(OrderEvent::OrderUpdated(updated))?;
+
+        if is_requeued {
+            self.notify_order_resting(client_order_id);
+        }
+        Ok(())
+    }
+
+    fn trigger_order(&mut self, client_order_id: ClientOrderId) -> Result<()> {
+        let ts_now = self.clock.get_time_ns();
+        let order = &self.orders[&client_order_id];
+        let triggered = OrderTriggered::new(
+            order.trader_id(),
+            order.strategy_id(),
+            order.instrument_id(),
+            client_order_id,
+            self.uuid_generator.generate(),
+            ts_now,
+            ts_now,
+            false,
+            order.venue_order_id(),
+            Some(self.account_id),
+        )?;
+        self.triggered.insert(client_order_id);
+        self.apply_event(OrderEvent::OrderTriggered(triggered))?;
+        self.notify_order_resting(client_order_id);
+        Ok(())
+    }
+
+    fn cancel_order(&mut self, client_order_id: ClientOrderId) -> Result<()> {
+        let ts_now = self.clock.get_time_ns();
+        let order = &self.orders[&client_order_id];
+        let canceled = OrderCanceled::new(
+            order.trader_id(),
+            order.strategy_id(),
+            order.instrument_id(),
+            client_order_id,
+            self.uuid_generator.generate(),
+            ts_now,
+            ts_now,
+            false,
+            order.venue_order_id(),
+            Some(self.account_id),
+        )?;
+        self.apply_event(OrderEvent::OrderCanceled(canceled))
+    }
+
+    fn expire_order(&mut self, client_order_id: ClientOrderId, ts_now: UnixNanos) -> Result<()> {
+        let order = &self.orders[&client_order_id];
+        let expired = OrderExpired::new(
+            order.trader_id(),
+            order.strategy_id(),
+            order.instrument_id(),
+            client_order_id,
+            self.uuid_generator.generate(),
+            ts_now,
+            ts_now,
+            false,
+            order.venue_order_id(),
+            Some(self.account_id),
+        )?;
+        self.apply_event(OrderEvent::OrderExpired(expired))
+    }
+
+    fn reject_modify(&mut self, client_order_id: ClientOrderId, reason: &str) -> Result<()> {
+        let order = &self.orders[&client_order_id];
+        self.generate_order_modify_rejected(
+            order.trader_id(),
+            order.strategy_id(),
+            order.instrument_id(),
+            client_order_id,
+            order.venue_order_id(),
+            reason,
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn generate_order_modify_rejected(
+        &mut self,
+        trader_id: TraderId,
+        strategy_id: StrategyId,
+        instrument_id: InstrumentId,
+        client_order_id: ClientOrderId,
+        venue_order_id: Option<VenueOrderId>,
+        reason: &str,
+    ) -> Result<()> {
+        let ts_now = self.clock.get_time_ns();
+        let modify_rejected = OrderModifyRejected::new(
+            trader_id,
+            strategy_id,
+            instrument_id,
+            client_order_id,
+            Ustr::from(reason),
+            self.uuid_generator.generate(),
+            ts_now,
+            ts_now,
+            false,
+            venue_order_id,
+            Some(self.account_id),
+        )?;
+        self.apply_event(OrderEvent::OrderModifyRejected(modify_rejected))
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn generate_order_cancel_rejected(
+        &mut self,
+        trader_id: TraderId,
+        strategy_id: StrategyId,
+        instrument_id: InstrumentId,
+        client_order_id: ClientOrderId,
+        venue_order_id: Option<VenueOrderId>,
+        reason: &str,
+    ) -> Result<()> {
+        let ts_now = self.clock.get_time_ns();
+        let cancel_rejected = OrderCancelRejected::new(
+            trader_id,
+            strategy_id,
+            instrument_id,
+            client_order_id,
+            Ustr::from(reason),
+            self.uuid_generator.generate(),
+            ts_now,
+            ts_now,
+            false,
+            venue_order_id,
+            Some(self.account_id),
+        )?;
+        self.apply_event(OrderEvent::OrderCancelRejected(cancel_rejected))
+    }
+
+    /// Apply the `event` to the matching engine's order (if held) and record it.
+    fn apply_event(&mut self, event: OrderEvent) -> Result<()> {
+        if let Some(order) = self.orders.get_mut(&event.client_order_id()) {
+            match &event {
+                // Updates are applied directly as they do not transition the order status
+                OrderEvent::OrderUpdated(updated) => order.update(updated),
+                OrderEvent::OrderModifyRejected(_) | OrderEvent::OrderCancelRejected(_) => {}
+                _ => order
+                    .apply(event.clone())
+                    .map_err(|e| anyhow!("Error applying {event:?}: {e}"))?,
+            }
+        }
+        self.events.push(event);
+        Ok(())
+    }
+
+    // -- HELPERS ---------------------------------------------------------------------------------
+
+    fn update_core_prices(&mut self) {
+        if let Some(bid) = self.book.best_bid_price() {
+            self.core.set_bid(bid);
+        }
+        if let Some(ask) = self.book.best_ask_price() {
+            self.core.set_ask(ask);
+        }
+    }
+
+    fn mid_price(&self) -> Option<Price> {
+        let bid = self.book.best_bid_price()?;
+        let ask = self.book.best_ask_price()?;
+        let precision = self.instrument.price_precision();
+        Price::from_raw((bid.raw + ask.raw) / 2, precision).ok()
+    }
+
+    fn has_market(&self, side: OrderSide) -> bool {
+        match side {
+            OrderSide::Buy => self.book.has_ask(),
+            OrderSide::Sell => self.book.has_bid(),
+            OrderSide::NoOrderSide => false,
+        }
+    }
+
+    fn notify_order_resting(&mut self, client_order_id: ClientOrderId) {
+        let order = self.orders[&client_order_id].as_ref();
+        self.fill_model.on_order_resting(&self.book, order);
+    }
+
+    fn purge_closed_orders(&mut self) {
+        let closed: Vec<ClientOrderId> = self
+            .orders
+            .values()
+            .filter(|order| order.is_closed())
+            .map(|order| order.client_order_id())
+            .collect();
+
+        for client_order_id in closed {
+            self.orders.shift_remove(&client_order_id);
+            self.triggered.remove(&client_order_id);
+            self.fill_model.on_order_closed(&client_order_id);
+        }
+    }
+
+    fn generate_venue_order_id(&mut self) -> VenueOrderId {
+        self.venue_order_id_count += 1;
+        VenueOrderId::from(
+            format!(
+                "{}-{}-{:03}",
+                self.venue, self.raw_id, self.venue_order_id_count
+            )
+            .as_str(),
+        )
+    }
+
+    fn generate_trade_id(&mut self) -> TradeId {
+        self.execution_count += 1;
+        TradeId::from(
+            format!("{}-{}-{:03}", self.venue, self.raw_id, self.execution_count).as_str(),
+        )
+    }
+}
+
+fn is_auction_order(order: &dyn Order) -> bool {
+    matches!(
+        order.time_in_force(),
+        TimeInForce::AtTheOpen | TimeInForce::AtTheClose
+    )
+}
+
+fn is_trailing_stop_order(order: &dyn Order) -> bool {
+    matches!(
+        order.order_type(),
+        OrderType::TrailingStopMarket | OrderType::TrailingStopLimit
+    )
+}
+
+fn format_price(price: Option<Price>) -> String {
+    price.map_or_else(|| "None".to_string(), |price| price.to_string())
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Tests
+////////////////////////////////////////////////////////////////////////////////
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use nautilus_accounting::models::fee::{FeeModel, MakerTakerFeeModel};
+    use nautilus_core::{time::get_atomic_clock_static, uuid::UUID4};
+    use nautilus_model::{
+        enums::{BookAction, TrailingOffsetType, TriggerType},
+        events::order::{
+            initialized::{OrderInitialized, OrderInitializedBuilder},
+            submitted::OrderSubmittedBuilder,
+        },
+        identifiers::client_id::ClientId,
+        instruments::{currency_pair::CurrencyPair, stubs::audusd_sim},
+        orders::{
+            limit::LimitOrder, limit_if_touched::LimitIfTouchedOrder, market::MarketOrder,
+            market_if_touched::MarketIfTouchedOrder, market_to_limit::MarketToLimitOrder,
+            stop_limit::StopLimitOrder, stop_market::StopMarketOrder,
+            trailing_stop_limit::TrailingStopLimitOrder,
+            trailing_stop_market::TrailingStopMarketOrder,
+        },
+        types::money::Money,
+    };
+    use rstest::{fixture, rstest};
+
+    use super::*;
+    use crate::models::fill::{ProbabilisticFillModel, QueuePositionFillModel};
+
+    #[fixture]
+    fn engine(audusd_sim: CurrencyPair) -> OrderMatchingEngine {
+        OrderMatchingEngine::new(
+            Box::new(audusd_sim),
+            1,
+            AccountId::from("SIM-001"),
+            BookType::L1_MBP,
+            Box::<ProbabilisticFillModel>::default(),
+            Arc::new(Mutex::new(MakerTakerFeeModel)),
+            OrderMatchingEngineConfig::default(),
+            get_atomic_clock_static(),
+        )
+    }
+
+    fn process_book_order(
+        engine: &mut OrderMatchingEngine,
+        action: BookAction,
+        side: OrderSide,
+        price: &str,
+        size: &str,
+        order_id: u64,
+    ) {
+        let order = BookOrder::new(side, Price::from(price), Quantity::from(size), order_id);
+        let delta =
+            OrderBookDelta::new(InstrumentId::from("AUD/USD.SIM"), action, order, 0, 0, 0, 0);
+        engine.process_order_book_delta(delta).unwrap();
+    }
+
+    fn process_quote(engine: &mut OrderMatchingEngine, bid: &str, ask: &str, size: &str) {
+        let tick = QuoteTick::new(
+            InstrumentId::from("AUD/USD.SIM"),
+            Price::from(bid),
+            Price::from(ask),
+            Quantity::from(size),
+            Quantity::from(size),
+            0,
+            0,
+        )
+        .unwrap();
+        engine.process_quote_tick(&tick).unwrap();
+    }
+
+    fn order_initialized(
+        client_order_id: &str,
+        order_type: OrderType,
+        side: OrderSide,
+        price: Option<&str>,
+        trigger_price: Option<&str>,
+    ) -> OrderInitialized {
+        OrderInitializedBuilder::default()
+            .instrument_id(InstrumentId::from("AUD/USD.SIM"))
+            .client_order_id(ClientOrderId::from(client_order_id))
+            .order_type(order_type)
+            .order_side(side)
+            .quantity(Quantity::from("100000"))
+            .price(price.map(Price::from))
+            .trigger_price(trigger_price.map(Price::from))
+            .trigger_type(trigger_price.map(|_| TriggerType::Default))
+            .time_in_force(TimeInForce::Gtc)
+            .build()
+            .unwrap()
+    }
+
+    fn submitted(init: OrderInitialized) -> Box<dyn Order> {
+        let submitted = OrderSubmittedBuilder::default()
+            .client_order_id(init.client_order_id)
+            .strategy_id(init.strategy_id)
+            .build()
+            .unwrap();
+        let mut order: Box<dyn Order> = match init.order_type {
+            OrderType::Market => Box::new(MarketOrder::from(init)),
+            OrderType::Limit => Box::new(LimitOrder::from(init)),
+            OrderType::StopMarket => Box::new(StopMarketOrder::from(init)),
+            OrderType::StopLimit => Box::new(StopLimitOrder::from(init)),
+            OrderType::MarketToLimit => Box::new(MarketToLimitOrder::from(init)),
+            OrderType::MarketIfTouched => Box::new(MarketIfTouchedOrder::from(init)),
+            OrderType::LimitIfTouched => Box::new(LimitIfTouchedOrder::from(init)),
+            OrderType::TrailingStopMarket => Box::new(TrailingStopMarketOrder::from(init)),
+            OrderType::TrailingStopLimit => Box::new(TrailingStopLimitOrder::from(init)),
+        };
+        order.apply(OrderEvent::OrderSubmitted(submitted)).unwrap();
+        order
+    }
+
+    #[rstest]
+    fn test_market_order_with_no_market_rejected(mut engine: OrderMatchingEngine) {
+        let init = order_initialized("O-1", OrderType::Market, OrderSide::Buy, None, None);
+        engine.process_order(submitted(init)).unwrap();
+
+        let events = engine.drain_events();
+        assert_eq!(events.len(), 1);
+        assert!(matches!(events[0], OrderEvent::OrderRejected(_)));
+        assert!(!engine.order_exists(&ClientOrderId::from("O-1")));
+    }
+
+    #[rstest]
+    fn test_order_not_submitted_rejected(mut engine: OrderMatchingEngine) {
+        process_quote(&mut engine, "1.00000", "1.00001", "1000000");
+
+        let init = order_initialized("O-1", OrderType::Market, OrderSide::Buy, None, None);
+        engine
+            .process_order(Box::new(MarketOrder::from(init)))
+            .unwrap();
+
+        let events = engine.drain_events();
+        assert_eq!(events.len(), 1);
+        let OrderEvent::OrderRejected(rejected) = events[0] else {
+            panic!("Expected rejection, was {:?}", events[0]);
+        };
+        assert!(rejected.reason.contains("INITIALIZED"));
+        assert!(!engine.order_exists(&ClientOrderId::from("O-1")));
+    }
+
+    #[rstest]
+    fn test_market_order_filled_at_ask(mut engine: OrderMatchingEngine) {
+        process_quote(&mut engine, "1.00000", "1.00001", "1000000");
+
+        let init = order_initialized("O-1", OrderType::Market, OrderSide::Buy, None, None);
+        engine.process_order(submitted(init)).unwrap();
+
+        let events = engine.drain_events();
+        assert_eq!(events.len(), 1);
+        let OrderEvent::OrderFilled(filled) = events[0] else {
+            panic!("Expected fill, was {:?}", events[0]);
+        };
+        assert_eq!(filled.last_px, Price::from("1.00001"));
+        assert_eq!(filled.last_qty, Quantity::from("100000"));
+        assert_eq!(filled.liquidity_side, LiquiditySide::Taker);
+        assert_eq!(filled.trade_id, TradeId::from("SIM-1-001"));
+        assert_eq!(filled.commission, Some(Money::from("2.00 USD")));
+    }
+
+    #[derive(Debug)]
+    struct FailingFeeModel;
+
+    impl FeeModel for FailingFeeModel {
+        fn get_commission(
+            &self,
+            _instrument: &dyn Instrument,
+            _last_qty: Quantity,
+            _last_px: Price,
+            _liquidity_side: LiquiditySide,
+            _use_quote_for_inverse: Option<bool>,
+            _ts_event: UnixNanos,
+        ) -> Result<Money> {
+            bail!("Commission unavailable")
+        }
+    }
+
+    #[rstest]
+    fn test_market_order_rejected_when_commission_fails(audusd_sim: CurrencyPair) {
+        let mut engine = OrderMatchingEngine::new(
+            Box::new(audusd_sim),
+            1,
+            AccountId::from("SIM-001"),
+            BookType::L1_MBP,
+            Box::<ProbabilisticFillModel>::default(),
+            Arc::new(Mutex::new(FailingFeeModel)),
+            OrderMatchingEngineConfig::default(),
+            get_atomic_clock_static(),
+        );
+        process_quote(&mut engine, "1.00000", "1.00001", "1000000");
+
+        let init = order_initialized("O-1", OrderType::Market, OrderSide::Buy, None, None);
+        engine.process_order(submitted(init)).unwrap();
+
+        let events = engine.drain_events();
+        assert_eq!(events.len(), 1);
+        let OrderEvent::OrderRejected(rejected) = events[0] else {
+            panic!("Expected rejection, was {:?}", events[0]);
+        };
+        assert!(rejected.reason.contains("Commission unavailable"));
+    }
+
+    #[rstest]
+    fn test_market_order_exhausting_top_of_book_slips_one_tick(mut engine: OrderMatchingEngine) {
+        process_quote(&mut engine, "1.00000", "1.00001", "50000");
+
+        let init = order_initialized("O-1", OrderType::Market, OrderSide::Buy, None, None);
+        engine.process_order(submitted(init)).unwrap();
+
+        let events = engine.drain_events();
+        assert_eq!(events.len(), 2);
+        let OrderEvent::OrderPartiallyFilled(first) = events[0] else {
+            panic!("Expected partial fill, was {:?}", events[0]);
+        };
+        let OrderEvent::OrderFilled(second) = events[1] else {
+            panic!("Expected fill, was {:?}", events[1]);
+        };
+        assert_eq!(first.last_qty, Quantity::from("50000"));
+        assert_eq!(second.last_qty, Quantity::from("50000"));
+        assert_eq!(
+            second.last_px,
+            Price::from("1.00001") + engine.instrument.price_increment()
+        );
+    }
+
+    #[rstest]
+    fn test_limit_order_rests_then_fills_as_maker(mut engine: OrderMatchingEngine) {
+        process_quote(&mut engine, "1.00000", "1.00001", "1000000");
+
+        let init = order_initialized(
+            "O-1",
+            OrderType::Limit,
+            OrderSide::Buy,
+            Some("0.99990"),
+            None,
+        );
+        engine.process_order(submitted(init)).unwrap();
+
+        let events = engine.drain_events();
+        assert_eq!(events.len(), 1);
+        assert!(matches!(events[0], OrderEvent::OrderAccepted(_)));
+        assert_eq!(engine.get_open_orders().len(), 1);
+
+        process_quote(&mut engine, "0.99980", "0.99985", "1000000");
+
+        let events = engine.drain_events();
+        assert_eq!(events.len(), 1);
+        let OrderEvent::OrderFilled(filled) = events[0] else {
+            panic!("Expected fill, was {:?}", events[0]);
+        };
+        assert_eq!(filled.last_px, Price::from("0.99990"));
+        assert_eq!(filled.liquidity_side, LiquiditySide::Maker);
+        assert_eq!(filled.venue_order_id, VenueOrderId::from("SIM-1-001"));
+        assert!(engine.get_open_orders().is_empty());
+    }
+
+    #[rstest]
+    fn test_marketable_limit_order_fills_as_taker(mut engine: OrderMatchingEngine) {
+        process_quote(&mut engine, "1.00000", "1.00001", "1000000");
+
+        let init = order_initialized(
+            "O-1",
+            OrderType::Limit,
+            OrderSide::Sell,
+            Some("0.99990"),
+            None,
+        );
+        engine.process_order(submitted(init)).unwrap();
+
+        let events = engine.drain_events();
+        assert_eq!(events.len(), 2);
+        assert!(matches!(events[0], OrderEvent::OrderAccepted(_)));
+        let OrderEvent::OrderFilled(filled) = events[1] else {
+            panic!("Expected fill, was {:?}", events[1]);
+        };
+        assert_eq!(filled.last_px, Price::from("1.00000"));
+        assert_eq!(filled.liquidity_side, LiquiditySide::Taker);
+    }
+
+    #[rstest]
+    fn test_post_only_limit_order_that_would_take_rejected(mut engine: OrderMatchingEngine) {
+        process_quote(&mut engine, "1.00000", "1.00001", "1000000");
+
+        let mut init = order_initialized(
+            "O-1",
+            OrderType::Limit,
+            OrderSide::Buy,
+            Some("1.00001"),
+            None,
+        );
+        init.post_only = true;
+        engine.process_order(submitted(init)).unwrap();
+
+        let events = engine.drain_events();
+        assert_eq!(events.len(), 1);
+        assert!(matches!(events[0], OrderEvent::OrderRejected(_)));
+    }
+
+    #[rstest]
+    fn test_invalid_price_precision_rejected(mut engine: OrderMatchingEngine) {
+        let init = order_initialized("O-1", OrderType::Limit, OrderSide::Buy, Some("1.0"), None);
+        engine.process_order(submitted(init)).unwrap();
+
+        let events = engine.drain_events();
+        assert_eq!(events.len(), 1);
+        assert!(matches!(events[0], OrderEvent::OrderRejected(_)));
+    }
+
+    #[rstest]
+    fn test_ioc_limit_order_not_marketable_canceled(mut engine: OrderMatchingEngine) {
+        process_quote(&mut engine, "1.00000", "1.00001", "1000000");
+
+        let mut init = order_initialized(
+            "O-1",
+            OrderType::Limit,
+            OrderSide::Buy,
+            Some("0.99990"),
+            None,
+        );
+        init.time_in_force = TimeInForce::Ioc;
+        engine.process_order(submitted(init)).unwrap();
+
+        let events = engine.drain_events();
+        assert_eq!(events.len(), 2);
+        assert!(matches!(events[0], OrderEvent::OrderAccepted(_)));
+        assert!(matches!(events[1], OrderEvent::OrderCanceled(_)));
+        assert!(engine.get_open_orders().is_empty());
+    }
+
+    #[rstest]
+    fn test_gtd_limit_order_expires(mut engine: OrderMatchingEngine) {
+        process_quote(&mut engine, "1.00000", "1.00001", "1000000");
+
+        let mut init = order_initialized(
+            "O-1",
+            OrderType::Limit,
+            OrderSide::Buy,
+            Some("0.99990"),
+            None,
+        );
+        init.time_in_force = TimeInForce::Gtd;
+        init.expire_time = Some(1_000);
+        engine.process_order(submitted(init)).unwrap();
+        engine.iterate(999).unwrap();
+        assert_eq!(engine.drain_events().len(), 1);
+
+        engine.iterate(1_000).unwrap();
+
+        let events = engine.drain_events();
+        assert_eq!(events.len(), 1);
+        assert!(matches!(events[0], OrderEvent::OrderExpired(_)));
+        assert!(engine.get_open_orders().is_empty());
+    }
+
+    #[rstest]
+    fn test_cancel_order(mut engine: OrderMatchingEngine) {
+        process_quote(&mut engine, "1.00000", "1.00001", "1000000");
+
+        let init = order_initialized(
+            "O-1",
+            OrderType::Limit,
+            OrderSide::Buy,
+            Some("0.99990"),
+            None,
+        );
+        let command = CancelOrder::new(
+            init.trader_id,
+            ClientId::from("SIM"),
+            init.strategy_id,
+            init.instrument_id,
+            init.client_order_id,
+            None,
+            UUID4::new(),
+            0,
+        )
+        .unwrap();
+        engine.process_order(submitted(init)).unwrap();
+        engine.process_cancel(&command).unwrap();
+
+        let events = engine.drain_events();
+        assert_eq!(events.len(), 2);
+        assert!(matches!(events[1], OrderEvent::OrderCanceled(_)));
+        assert!(engine.get_open_orders().is_empty());
+
+        engine.process_cancel(&command).unwrap();
+
+        let events = engine.drain_events();
+        assert_eq!(events.len(), 1);
+        assert!(matches!(events[0], OrderEvent::OrderCancelRejected(_)));
+    }
+
+    #[rstest]
+    fn test_modify_limit_order_price(mut engine: OrderMatchingEngine) {
+        process_quote(&mut engine, "1.00000", "1.00001", "1000000");
+
+        let init = order_initialized(
+            "O-1",
+            OrderType::Limit,
+            OrderSide::Buy,
+            Some("0.99990"),
+            None,
+        );
+        let command = ModifyOrder::new(
+            init.trader_id,
+            ClientId::from("SIM"),
+            init.strategy_id,
+            init.instrument_id,
+            init.client_order_id,
+            None,
+            None,
+            Some(Price::from("0.99995")),
+            None,
+            UUID4::new(),
+            0,
+        )
+        .unwrap();
+        engine.process_order(submitted(init)).unwrap();
+        engine.process_modify(&command).unwrap();
+
+        let events = engine.drain_events();
+        assert_eq!(events.len(), 2);
+        assert!(matches!(events[1], OrderEvent::OrderUpdated(_)));
+        let order = engine.get_order(&ClientOrderId::from("O-1")).unwrap();
+        assert_eq!(order.price(), Some(Price::from("0.99995")));
+    }
+
+    #[rstest]
+    fn test_modify_reducing_size_keeps_queue_position(audusd_sim: CurrencyPair) {
+        let mut engine = OrderMatchingEngine::new(
+            Box::new(audusd_sim),
+            1,
+            AccountId::from("SIM-001"),
+            BookType::L3_MBO,
+            Box::new(QueuePositionFillModel::new()),
+            Arc::new(Mutex::new(MakerTakerFeeModel)),
+            OrderMatchingEngineConfig::default(),
+            get_atomic_clock_static(),
+        );
+        process_book_order(
+            &mut engine,
+            BookAction::Add,
+            OrderSide::Buy,
+            "1.00000",
+            "100000",
+            1,
+        );
+        process_book_order(
+            &mut engine,
+            BookAction::Add,
+            OrderSide::Sell,
+            "1.00002",
+            "100000",
+            2,
+        );
+
+        let init = order_initialized(
+            "O-1",
+            OrderType::Limit,
+            OrderSide::Buy,
+            Some("1.00000"),
+            None,
+        );
+        let command = ModifyOrder::new(
+            init.trader_id,
+            ClientId::from("SIM"),
+            init.strategy_id,
+            init.instrument_id,
+            init.client_order_id,
+            None,
+            Some(Quantity::from("50000")),
+            None,
+            None,
+            UUID4::new(),
+            0,
+        )
+        .unwrap();
+        engine.process_order(submitted(init)).unwrap();
+
+        // Volume joining the level behind the order must stay behind it after the modify
+        process_book_order(
+            &mut engine,
+            BookAction::Add,
+            OrderSide::Buy,
+            "1.00000",
+            "500000",
+            3,
+        );
+        engine.process_modify(&command).unwrap();
+        process_book_order(
+            &mut engine,
+            BookAction::Delete,
+            OrderSide::Buy,
+            "1.00000",
+            "100000",
+            1,
+        );
+        process_book_order(
+            &mut engine,
+            BookAction::Add,
+            OrderSide::Sell,
+            "1.00000",
+            "100000",
+            4,
+        );
+
+        let events = engine.drain_events();
+        assert_eq!(events.len(), 3);
+        assert!(matches!(events[0], OrderEvent::OrderAccepted(_)));
+        assert!(matches!(events[1], OrderEvent::OrderUpdated(_)));
+        let OrderEvent::OrderFilled(filled) = events[2] else {
+            panic!("Expected fill, was {:?}", events[2]);
+        };
+        assert_eq!(filled.last_px, Price::from("1.00000"));
+        assert_eq!(filled.last_qty, Quantity::from("50000"));
+    }
+
+    #[rstest]
+    fn test_stop_market_order_in_market_rejected(mut engine: OrderMatchingEngine) {
+        process_quote(&mut engine, "1.00000", "1.00001", "1000000");
+
+        let init = order_initialized(
+            "O-1",
+            OrderType::StopMarket,
+            OrderSide::Buy,
+            None,
+            Some("1.00000"),
+        );
+        engine.process_order(submitted(init)).unwrap();
+
+        let events = engine.drain_events();
+        assert_eq!(events.len(), 1);
+        assert!(matches!(events[0], OrderEvent::OrderRejected(_)));
+    }
+
+    #[rstest]
+    fn test_stop_market_order_triggers_and_fills(mut engine: OrderMatchingEngine) {
+        process_quote(&mut engine, "1.00000", "1.00001", "1000000");
+
+        let init = order_initialized(
+            "O-1",
+            OrderType::StopMarket,
+            OrderSide::Buy,
+            None,
+            Some("1.00010"),
+        );
+        engine.process_order(submitted(init)).unwrap();
+        process_quote(&mut engine, "1.00010", "1.00011", "1000000");
+
+        let events = engine.drain_events();
+        assert_eq!(events.len(), 2);
+        assert!(matches!(events[0], OrderEvent::OrderAccepted(_)));
+        let OrderEvent::OrderFilled(filled) = events[1] else {
+            panic!("Expected fill, was {:?}", events[1]);
+        };
+        assert_eq!(filled.last_px, Price::from("1.00011"));
+    }
+
+    #[rstest]
+    fn test_stop_limit_order_triggers_then_fills(mut engine: OrderMatchingEngine) {
+        process_quote(&mut engine, "1.00000", "1.00001", "1000000");
+
+        let init = order_initialized(
+            "O-1",
+            OrderType::StopLimit,
+            OrderSide::Buy,
+            Some("1.00005"),
+            Some("1.00010"),
+        );
+        engine.process_order(submitted(init)).unwrap();
+        process_quote(&mut engine, "1.00009", "1.00010", "1000000");
+
+        let events = engine.drain_events();
+        assert_eq!(events.len(), 2);
+        assert!(matches!(events[0], OrderEvent::OrderAccepted(_)));
+        assert!(matches!(events[1], OrderEvent::OrderTriggered(_)));
+
+        process_quote(&mut engine, "1.00003", "1.00004", "1000000");
+
+        let events = engine.drain_events();
+        assert_eq!(events.len(), 1);
+        let OrderEvent::OrderFilled(filled) = events[0] else {
+            panic!("Expected fill, was {:?}", events[0]);
+        };
+        assert_eq!(filled.last_px, Price::from("1.00005"));
+        assert_eq!(filled.liquidity_side, LiquiditySide::Maker);
+    }
+
+    #[rstest]
+    fn test_market_to_limit_order_remainder_rests_at_first_fill_price(
+        mut engine: OrderMatchingEngine,
+    ) {
+        process_quote(&mut engine, "1.00000", "1.00001", "50000");
+
+        let init = order_initialized("O-1", OrderType::MarketToLimit, OrderSide::Buy, None, None);
+        engine.process_order(submitted(init)).unwrap();
+
+        let events = engine.drain_events();
+        assert_eq!(events.len(), 3);
+        assert!(matches!(events[0], OrderEvent::OrderAccepted(_)));
+        let OrderEvent::OrderPartiallyFilled(filled) = events[1] else {
+            panic!("Expected partial fill, was {:?}", events[1]);
+        };
+        assert_eq!(filled.last_px, Price::from("1.00001"));
+        assert_eq!(filled.last_qty, Quantity::from("50000"));
+        assert!(matches!(events[2], OrderEvent::OrderUpdated(_)));
+        let order = engine.get_order(&ClientOrderId::from("O-1")).unwrap();
+        assert_eq!(order.price(), Some(Price::from("1.00001")));
+
+        process_quote(&mut engine, "1.00000", "1.00001", "1000000");
+
+        let events = engine.drain_events();
+        assert_eq!(events.len(), 1);
+        let OrderEvent::OrderFilled(filled) = events[0] else {
+            panic!("Expected fill, was {:?}", events[0]);
+        };
+        assert_eq!(filled.last_px, Price::from("1.00001"));
+        assert_eq!(filled.last_qty, Quantity::from("50000"));
+        assert_eq!(filled.liquidity_side, LiquiditySide::Maker);
+        assert!(engine.get_open_orders().is_empty());
+    }
+
+    #[rstest]
+    fn test_market_if_touched_order_touched_and_fills(mut engine: OrderMatchingEngine) {
+        process_quote(&mut engine, "1.00000", "1.00001", "1000000");
+
+        let init = order_initialized(
+            "O-1",
+            OrderType::MarketIfTouched,
+            OrderSide::Sell,
+            None,
+            Some("1.00010"),
+        );
+        engine.process_order(submitted(init)).unwrap();
+        process_quote(&mut engine, "1.00010", "1.00011", "1000000");
+
+        let events = engine.drain_events();
+        assert_eq!(events.len(), 2);
+        assert!(matches!(events[0], OrderEvent::OrderAccepted(_)));
+        let OrderEvent::OrderFilled(filled) = events[1] else {
+            panic!("Expected fill, was {:?}", events[1]);
+        };
+        assert_eq!(filled.last_px, Price::from("1.00010"));
+        assert_eq!(filled.liquidity_side, LiquiditySide::Taker);
+    }
+
+    #[rstest]
+    fn test_limit_if_touched_order_triggers_and_fills(mut engine: OrderMatchingEngine) {
+        process_quote(&mut engine, "1.00000", "1.00001", "1000000");
+
+        let init = order_initialized(
+            "O-1",
+            OrderType::LimitIfTouched,
+            OrderSide::Buy,
+            Some("0.99995"),
+            Some("0.99990"),
+        );
+        engine.process_order(submitted(init)).unwrap();
+        process_quote(&mut engine, "0.99989", "0.99990", "1000000");
+
+        let events = engine.drain_events();
+        assert_eq!(events.len(), 3);
+        assert!(matches!(events[0], OrderEvent::OrderAccepted(_)));
+        assert!(matches!(events[1], OrderEvent::OrderTriggered(_)));
+        let OrderEvent::OrderFilled(filled) = events[2] else {
+            panic!("Expected fill, was {:?}", events[2]);
+        };
+        assert_eq!(filled.last_px, Price::from("0.99990"));
+        assert_eq!(filled.liquidity_side, LiquiditySide::Taker);
+    }
+
+    #[rstest]
+    fn test_trailing_stop_market_order_trails_then_fills(mut engine: OrderMatchingEngine) {
+        process_quote(&mut engine, "1.00000", "1.00001", "1000000");
+
+        let mut init = order_initialized(
+            "O-1",
+            OrderType::TrailingStopMarket,
+            OrderSide::Sell,
+            None,
+            Some("0.99900"),
+        );
+        init.trigger_type = Some(TriggerType::BidAsk);
+        init.trailing_offset = Some(Price::from("0.00010"));
+        init.trailing_offset_type = Some(TrailingOffsetType::Price);
+        engine.process_order(submitted(init)).unwrap();
+
+        let events = engine.drain_events();
+        assert_eq!(events.len(), 1);
+        assert!(matches!(events[0], OrderEvent::OrderAccepted(_)));
+
+        process_quote(&mut engine, "1.00020", "1.00021", "1000000");
+
+        let events = engine.drain_events();
+        assert_eq!(events.len(), 1);
+        assert!(matches!(events[0], OrderEvent::OrderUpdated(_)));
+        let order = engine.get_order(&ClientOrderId::from("O-1")).unwrap();
+        assert_eq!(order.trigger_price(), Some(Price::from("1.00010")));
+
+        // The trigger price does not trail back as the market falls
+        process_quote(&mut engine, "1.00010", "1.00011", "1000000");
+
+        let events = engine.drain_events();
+        assert_eq!(events.len(), 1);
+        let OrderEvent::OrderFilled(filled) = events[0] else {
+            panic!("Expected fill, was {:?}", events[0]);
+        };
+        assert_eq!(filled.last_px, Price::from("1.00010"));
+    }
+
+    #[rstest]
+    fn test_trailing_stop_limit_order_trails_triggers_then_fills(mut engine: OrderMatchingEngine) {
+        process_quote(&mut engine, "1.00000", "1.00001", "1000000");
+
+        let mut init = order_initialized(
+            "O-1",
+            OrderType::TrailingStopLimit,
+            OrderSide::Buy,
+            Some("1.00100"),
+            Some("1.00050"),
+        );
+        init.trigger_type = Some(TriggerType::BidAsk);
+        init.limit_offset = Some(Price::from("0.00005"));
+        init.trailing_offset = Some(Price::from("0.00010"));
+        init.trailing_offset_type = Some(TrailingOffsetType::Price);
+        engine.process_order(submitted(init)).unwrap();
+        engine.drain_events();
+
+        process_quote(&mut engine, "0.99980", "0.99981", "1000000");
+
+        let events = engine.drain_events();
+        assert_eq!(events.len(), 1);
+        assert!(matches!(events[0], OrderEvent::OrderUpdated(_)));
+        let order = engine.get_order(&ClientOrderId::from("O-1")).unwrap();
+        assert_eq!(order.price(), Some(Price::from("0.99986")));
+        assert_eq!(order.trigger_price(), Some(Price::from("0.99991")));
+
+        process_quote(&mut engine, "0.99991", "0.99992", "1000000");
+
+        let events = engine.drain_events();
+        assert_eq!(events.len(), 1);
+        assert!(matches!(events[0], OrderEvent::OrderTriggered(_)));
+
+        process_quote(&mut engine, "0.99984", "0.99985", "1000000");
+
+        let events = engine.drain_events();
+        assert_eq!(events.len(), 1);
+        let OrderEvent::OrderFilled(filled) = events[0] else {
+            panic!("Expected fill, was {:?}", events[0]);
+        };
+        assert_eq!(filled.last_px, Price::from("0.99986"));
+        assert_eq!(filled.liquidity_side, LiquiditySide::Maker);
+    }
+
+    fn auction_order(
+        client_order_id: &str,
+        order_type: OrderType,
+        side: OrderSide,
+        price: Option<&str>,
+        time_in_force: TimeInForce,
+    ) -> Box<dyn Order> {
+        let mut init = order_initialized(client_order_id, order_type, side, price, None);
+        init.time_in_force = time_in_force;
+        submitted(init)
+    }
+
+    #[rstest]
+    fn test_auction_orders_accumulate_until_uncrossed(mut engine: OrderMatchingEngine) {
+        process_quote(&mut engine, "1.00000", "1.00001", "1000000");
+
+        let order = auction_order(
+            "O-1",
+            OrderType::Market,
+            OrderSide::Buy,
+            None,
+            TimeInForce::AtTheOpen,
+        );
+        engine.process_order(order).unwrap();
+        process_quote(&mut engine, "1.00000", "1.00001", "1000000");
+
+        let events = engine.drain_events();
+        assert_eq!(events.len(), 1);
+        assert!(matches!(events[0], OrderEvent::OrderAccepted(_)));
+
+        let price = engine
+            .process_auction(TimeInForce::AtTheOpen, 1_000)
+            .unwrap();
+        assert_eq!(price, Some(Price::from("1.00001")));
+
+        let events = engine.drain_events();
+        assert_eq!(events.len(), 1);
+        let OrderEvent::OrderFilled(filled) = events[0] else {
+            panic!("Expected fill, was {:?}", events[0]);
+        };
+        assert_eq!(filled.last_px, Price::from("1.00001"));
+        assert_eq!(filled.last_qty, Quantity::from("100000"));
+        assert_eq!(filled.ts_event, 1_000);
+        assert!(!engine.order_exists(&ClientOrderId::from("O-1")));
+    }
+
+    #[rstest]
+    fn test_auction_orders_cross_at_single_price(mut engine: OrderMatchingEngine) {
+        let orders = [
+            ("O-1", OrderSide::Buy, "1.00010"),
+            ("O-2", OrderSide::Buy, "1.00005"),
+            ("O-3", OrderSide::Sell, "1.00000"),
+            ("O-4", OrderSide::Sell, "1.00020"),
+        ];
+        for (client_order_id, side, price) in orders {
+            let order = auction_order(
+                client_order_id,
+                OrderType::Limit,
+                side,
+                Some(price),
+                TimeInForce::AtTheClose,
+            );
+            engine.process_order(order).unwrap();
+        }
+        engine.drain_events();
+
+        // Opening auction orders are not eligible for the close
+        let price = engine
+            .process_auction(TimeInForce::AtTheOpen, 1_000)
+            .unwrap();
+        assert_eq!(price, None);
+
+        let price = engine
+            .process_auction(TimeInForce::AtTheClose, 2_000)
+            .unwrap();
+        assert_eq!(price, Some(Price::from("1.00010")));
+
+        let events = engine.drain_events();
+        assert_eq!(events.len(), 4);
+        let OrderEvent::OrderFilled(filled) = events[0] else {
+            panic!("Expected fill, was {:?}", events[0]);
+        };
+        assert_eq!(filled.client_order_id, ClientOrderId::from("O-1"));
+        assert_eq!(filled.last_px, Price::from("1.00010"));
+        let OrderEvent::OrderFilled(filled) = events[1] else {
+            panic!("Expected fill, was {:?}", events[1]);
+        };
+        assert_eq!(filled.client_order_id, ClientOrderId::from("O-3"));
+        assert_eq!(filled.last_px, Price::from("1.00010"));
+        assert!(matches!(events[2], OrderEvent::OrderExpired(_)));
+        assert!(matches!(events[3], OrderEvent::OrderExpired(_)));
+        assert!(engine.get_open_orders().is_empty());
+    }
+
+    #[rstest]
+    fn test_auction_stop_order_rejected(mut engine: OrderMatchingEngine) {
+        let mut init = order_initialized(
+            "O-1",
+            OrderType::StopMarket,
+            OrderSide::Buy,
+            None,
+            Some("1.00010"),
+        );
+        init.time_in_force = TimeInForce::AtTheOpen;
+        engine.process_order(submitted(init)).unwrap();
+
+        let events = engine.drain_events();
+        assert_eq!(events.len(), 1);
+        assert!(matches!(events[0], OrderEvent::OrderRejected(_)));
+    }
+
+    #[rstest]
+    fn test_process_auction_with_invalid_time_in_force(mut engine: OrderMatchingEngine) {
+        assert!(engine.process_auction(TimeInForce::Gtc, 1_000).is_err());
+    }
+}
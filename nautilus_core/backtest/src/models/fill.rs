@@ -0,0 +1,347 @@
+// -------------------------------------------------------------------------------------------------
+//  Copyright (C) 2015-2024 Nautech Systems Pty Ltd. All rights reserved.
+//  https://nautechsystems.io
+//
+//  Licensed under the GNU Lesser General Public License Version 3.0 (the "License");
+//  You may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at https://www.gnu.org/licenses/lgpl-3.0.en.html
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+// -------------------------------------------------------------------------------------------------
+
+use std::collections::HashMap;
+
+use anyhow::Result;
+use nautilus_core::correctness::check_f64_in_range_inclusive;
+use nautilus_model::{
+    data::order::OrderId, enums::OrderSide, identifiers::client_order_id::ClientOrderId,
+    orderbook::book::OrderBook, orders::base::Order, types::price::Price,
+};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+/// Provides a model for deciding how simulated orders are filled by an
+/// [`OrderMatchingEngine`](crate::matching_engine::OrderMatchingEngine).
+pub trait FillModel {
+    /// Returns whether a resting limit `order`, whose price has been reached, fills now.
+    fn is_limit_filled(&mut self, book: &OrderBook, order: &dyn Order) -> bool;
+
+    /// Returns whether an aggressive fill for `order` slips by one price increment.
+    fn is_slipped(&mut self, book: &OrderBook, order: &dyn Order) -> bool;
+
+    /// Called when `order` starts resting in the book at its limit price.
+    fn on_order_resting(&mut self, _book: &OrderBook, _order: &dyn Order) {}
+
+    /// Called when the order is closed so any state held for it can be released.
+    fn on_order_closed(&mut self, _client_order_id: &ClientOrderId) {}
+
+    /// Reseeds any random number generator used by the model.
+    fn reseed(&mut self, _seed: u64) {}
+}
+
+/// Provides a fill model which fills limit orders, and slips aggressive
+/// orders, with fixed probabilities.
+///
+/// The model draws from its own random number generator, so runs using the
+/// same `random_seed` produce identical fills.
+pub struct ProbabilisticFillModel {
+    pub prob_fill_on_limit: f64,
+    pub prob_slippage: f64,
+    rng: StdRng,
+}
+
+impl ProbabilisticFillModel {
+    pub fn new(
+        prob_fill_on_limit: f64,
+        prob_slippage: f64,
+        random_seed: Option<u64>,
+    ) -> Result<Self> {
+        check_f64_in_range_inclusive(prob_fill_on_limit, 0.0, 1.0, "prob_fill_on_limit")?;
+        check_f64_in_range_inclusive(prob_slippage, 0.0, 1.0, "prob_slippage")?;
+
+        let rng = match random_seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        };
+
+        Ok(Self {
+            prob_fill_on_limit,
+            prob_slippage,
+            rng,
+        })
+    }
+
+    fn event_success(&mut self, probability: f64) -> bool {
+        if probability <= 0.0 {
+            false
+        } else if probability >= 1.0 {
+            true
+        } else {
+            self.rng.gen_bool(probability)
+        }
+    }
+}
+
+impl Default for ProbabilisticFillModel {
+    /// Creates a model which always fills limit orders and never slips.
+    fn default() -> Self {
+        Self::new(1.0, 0.0, None).unwrap()
+    }
+}
+
+impl FillModel for ProbabilisticFillModel {
+    fn is_limit_filled(&mut self, _book: &OrderBook, _order: &dyn Order) -> bool {
+        self.event_success(self.prob_fill_on_limit)
+    }
+
+    fn is_slipped(&mut self, _book: &OrderBook, _order: &dyn Order) -> bool {
+        self.event_success(self.prob_slippage)
+    }
+
+    fn reseed(&mut self, seed: u64) {
+        self.rng = StdRng::seed_from_u64(seed);
+    }
+}
+
+struct QueuePosition {
+    price: Price,
+    orders_ahead: Vec<OrderId>,
+    size_ahead_raw: u64,
+}
+
+/// Provides a fill model which tracks each resting orders position in the
+/// FIFO queue of its price level.
+///
+/// When an order starts resting, the orders already at its price level are
+/// recorded as being ahead of it. The order only fills once that volume has
+/// been removed from the level, or the market has traded through its price.
+/// For L2 books the single order per level stands in for the whole queue, so
+/// the volume ahead only ever shrinks with the level size.
+#[derive(Default)]
+pub struct QueuePositionFillModel {
+    positions: HashMap<ClientOrderId, QueuePosition>,
+}
+
+impl QueuePositionFillModel {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the volume currently queued ahead of the order (if tracked).
+    #[must_use]
+    pub fn size_ahead_raw(&self, client_order_id: &ClientOrderId) -> Option<u64> {
+        self.positions
+            .get(client_order_id)
+            .map(|position| position.size_ahead_raw)
+    }
+}
+
+impl FillModel for QueuePositionFillModel {
+    fn is_limit_filled(&mut self, book: &OrderBook, order: &dyn Order) -> bool {
+        let side = order.side();
+        let Some(position) = self.positions.get_mut(&order.client_order_id()) else {
+            return true;
+        };
+
+        let is_traded_through = match side {
+            OrderSide::Buy => book
+                .best_ask_price()
+                .map_or(false, |ask| ask < position.price),
+            OrderSide::Sell => book
+                .best_bid_price()
+                .map_or(false, |bid| bid > position.price),
+            OrderSide::NoOrderSide => panic!("Invalid order side {side}"),
+        };
+        if is_traded_through {
+            return true;
+        }
+
+        let size_remaining_raw = book.get_level(side, position.price).map_or(0, |level| {
+            position
+                .orders_ahead
+                .iter()
+                .filter_map(|order_id| level.orders.get(order_id))
+                .map(|book_order| book_order.size.raw)
+                .sum()
+        });
+        position.size_ahead_raw = position.size_ahead_raw.min(size_remaining_raw);
+        position.size_ahead_raw == 0
+    }
+
+    fn is_slipped(&mut self, _book: &OrderBook, _order: &dyn Order) -> bool {
+        false
+    }
+
+    fn on_order_resting(&mut self, book: &OrderBook, order: &dyn Order) {
+        let Some(price) = order.price() else {
+            return;
+        };
+
+        let orders_ahead = book
+            .get_level(order.side(), price)
+            .map(|level| level.get_orders())
+            .unwrap_or_default();
+        let position = QueuePosition {
+            price,
+            orders_ahead: orders_ahead.iter().map(|o| o.order_id).collect(),
+            size_ahead_raw: orders_ahead.iter().map(|o| o.size.raw).sum(),
+        };
+        self.positions.insert(order.client_order_id(), position);
+    }
+
+    fn on_order_closed(&mut self, client_order_id: &ClientOrderId) {
+        self.positions.remove(client_order_id);
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Tests
+////////////////////////////////////////////////////////////////////////////////
+#[cfg(test)]
+mod tests {
+    use nautilus_model::{
+        data::order::BookOrder,
+        enums::{BookType, OrderType},
+        events::order::initialized::OrderInitializedBuilder,
+        identifiers::instrument_id::InstrumentId,
+        orders::limit::LimitOrder,
+        types::quantity::Quantity,
+    };
+    use rstest::rstest;
+
+    use super::*;
+
+    fn limit_order(side: OrderSide, price: &str) -> LimitOrder {
+        let init = OrderInitializedBuilder::default()
+            .instrument_id(InstrumentId::from("AUD/USD.SIM"))
+            .order_type(OrderType::Limit)
+            .order_side(side)
+            .quantity(Quantity::from("100000"))
+            .price(Some(Price::from(price)))
+            .build()
+            .unwrap();
+        LimitOrder::from(init)
+    }
+
+    #[rstest]
+    fn test_probabilistic_fill_model_invalid_probability() {
+        assert!(ProbabilisticFillModel::new(1.1, 0.0, None).is_err());
+        assert!(ProbabilisticFillModel::new(0.5, -0.1, None).is_err());
+    }
+
+    #[rstest]
+    fn test_probabilistic_fill_model_certain_outcomes() {
+        let book = OrderBook::new(InstrumentId::from("AUD/USD.SIM"), BookType::L1_MBP);
+        let order = limit_order(OrderSide::Buy, "1.00000");
+        let mut optimistic = ProbabilisticFillModel::new(1.0, 0.0, Some(42)).unwrap();
+        let mut pessimistic = ProbabilisticFillModel::new(0.0, 1.0, Some(42)).unwrap();
+
+        assert!(optimistic.is_limit_filled(&book, &order));
+        assert!(!optimistic.is_slipped(&book, &order));
+        assert!(!pessimistic.is_limit_filled(&book, &order));
+        assert!(pessimistic.is_slipped(&book, &order));
+    }
+
+    #[rstest]
+    fn test_probabilistic_fill_model_same_seed_is_reproducible() {
+        let book = OrderBook::new(InstrumentId::from("AUD/USD.SIM"), BookType::L1_MBP);
+        let order = limit_order(OrderSide::Buy, "1.00000");
+        let mut model1 = ProbabilisticFillModel::new(0.5, 0.5, Some(42)).unwrap();
+        let mut model2 = ProbabilisticFillModel::new(0.5, 0.5, Some(42)).unwrap();
+
+        let outcomes1: Vec<bool> = (0..100)
+            .map(|_| model1.is_limit_filled(&book, &order))
+            .collect();
+        let outcomes2: Vec<bool> = (0..100)
+            .map(|_| model2.is_limit_filled(&book, &order))
+            .collect();
+
+        assert_eq!(outcomes1, outcomes2);
+        assert!(outcomes1.contains(&true));
+        assert!(outcomes1.contains(&false));
+    }
+
+    #[rstest]
+    fn test_probabilistic_fill_model_reseed_is_reproducible() {
+        let book = OrderBook::new(InstrumentId::from("AUD/USD.SIM"), BookType::L1_MBP);
+        let order = limit_order(OrderSide::Buy, "1.00000");
+        let mut model1 = ProbabilisticFillModel::new(0.5, 0.5, Some(42)).unwrap();
+        let mut model2 = ProbabilisticFillModel::new(0.5, 0.5, None).unwrap();
+        model2.reseed(42);
+
+        let outcomes1: Vec<bool> = (0..100).map(|_| model1.is_slipped(&book, &order)).collect();
+        let outcomes2: Vec<bool> = (0..100).map(|_| model2.is_slipped(&book, &order)).collect();
+
+        assert_eq!(outcomes1, outcomes2);
+    }
+
+    #[rstest]
+    fn test_queue_position_fill_model_waits_for_orders_ahead() {
+        let mut book = OrderBook::new(InstrumentId::from("AUD/USD.SIM"), BookType::L3_MBO);
+        let price = Price::from("1.00000");
+        let ahead1 = BookOrder::new(OrderSide::Buy, price, Quantity::from("100000"), 1);
+        let ahead2 = BookOrder::new(OrderSide::Buy, price, Quantity::from("200000"), 2);
+        book.add(ahead1, 0, 1);
+        book.add(ahead2, 0, 2);
+
+        let order = limit_order(OrderSide::Buy, "1.00000");
+        let mut model = QueuePositionFillModel::new();
+        model.on_order_resting(&book, &order);
+
+        // Orders joining the level later are queued behind
+        let behind = BookOrder::new(OrderSide::Buy, price, Quantity::from("500000"), 3);
+        book.add(behind, 0, 3);
+
+        let client_order_id = order.client_order_id();
+        assert!(!model.is_limit_filled(&book, &order));
+        assert_eq!(
+            model.size_ahead_raw(&client_order_id),
+            Some(300_000 * 1_000_000_000)
+        );
+
+        book.delete(ahead1, 0, 4);
+        assert!(!model.is_limit_filled(&book, &order));
+        assert_eq!(
+            model.size_ahead_raw(&client_order_id),
+            Some(200_000 * 1_000_000_000)
+        );
+
+        book.delete(ahead2, 0, 5);
+        assert!(model.is_limit_filled(&book, &order));
+
+        model.on_order_closed(&client_order_id);
+        assert_eq!(model.size_ahead_raw(&client_order_id), None);
+    }
+
+    #[rstest]
+    fn test_queue_position_fill_model_traded_through_fills() {
+        let mut book = OrderBook::new(InstrumentId::from("AUD/USD.SIM"), BookType::L3_MBO);
+        let price = Price::from("1.00000");
+        book.add(
+            BookOrder::new(OrderSide::Buy, price, Quantity::from("100000"), 1),
+            0,
+            1,
+        );
+
+        let order = limit_order(OrderSide::Buy, "1.00000");
+        let mut model = QueuePositionFillModel::new();
+        model.on_order_resting(&book, &order);
+        assert!(!model.is_limit_filled(&book, &order));
+
+        book.add(
+            BookOrder::new(
+                OrderSide::Sell,
+                Price::from("0.99999"),
+                Quantity::from("100000"),
+                2,
+            ),
+            0,
+            2,
+        );
+        assert!(model.is_limit_filled(&book, &order));
+    }
+}
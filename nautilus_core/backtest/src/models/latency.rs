@@ -0,0 +1,400 @@
+// -------------------------------------------------------------------------------------------------
+//  Copyright (C) 2015-2024 Nautech Systems Pty Ltd. All rights reserved.
+//  https://nautechsystems.io
+//
+//  Licensed under the GNU Lesser General Public License Version 3.0 (the "License");
+//  You may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at https://www.gnu.org/licenses/lgpl-3.0.en.html
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+// -------------------------------------------------------------------------------------------------
+
+use std::{
+    cmp::{Ordering, Reverse},
+    collections::BinaryHeap,
+};
+
+use anyhow::{bail, Result};
+use nautilus_core::time::UnixNanos;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+/// The type of order command sent to a simulated venue.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum LatencyCommandType {
+    Submit,
+    Modify,
+    Cancel,
+}
+
+/// Provides a model for the latency between a client and a simulated venue.
+pub trait LatencyModel {
+    /// Returns the delay (nanoseconds) before a command of `command_type` reaches the venue.
+    fn command_latency_ns(&mut self, command_type: LatencyCommandType) -> u64;
+
+    /// Returns the delay (nanoseconds) before a venue response reaches the client.
+    fn response_latency_ns(&mut self) -> u64;
+
+    /// Reseeds any random number generator used by the model.
+    fn reseed(&mut self, _seed: u64) {}
+}
+
+/// Applies the same constant latency to all commands and responses.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ConstantLatencyModel {
+    pub latency_ns: u64,
+}
+
+impl ConstantLatencyModel {
+    /// Initializes a new `ConstantLatencyModel` instance.
+    #[must_use]
+    pub fn new(latency_ns: u64) -> Self {
+        Self { latency_ns }
+    }
+}
+
+impl LatencyModel for ConstantLatencyModel {
+    fn command_latency_ns(&mut self, _command_type: LatencyCommandType) -> u64 {
+        self.latency_ns
+    }
+
+    fn response_latency_ns(&mut self) -> u64 {
+        self.latency_ns
+    }
+}
+
+/// Applies a base latency plus an additional latency per command type.
+///
+/// Venue responses are delayed by the base latency only.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct PerCommandLatencyModel {
+    pub base_latency_ns: u64,
+    pub submit_latency_ns: u64,
+    pub modify_latency_ns: u64,
+    pub cancel_latency_ns: u64,
+}
+
+impl PerCommandLatencyModel {
+    /// Initializes a new `PerCommandLatencyModel` instance.
+    #[must_use]
+    pub fn new(
+        base_latency_ns: u64,
+        submit_latency_ns: u64,
+        modify_latency_ns: u64,
+        cancel_latency_ns: u64,
+    ) -> Self {
+        Self {
+            base_latency_ns,
+            submit_latency_ns,
+            modify_latency_ns,
+            cancel_latency_ns,
+        }
+    }
+}
+
+impl LatencyModel for PerCommandLatencyModel {
+    fn command_latency_ns(&mut self, command_type: LatencyCommandType) -> u64 {
+        let command_latency_ns = match command_type {
+            LatencyCommandType::Submit => self.submit_latency_ns,
+            LatencyCommandType::Modify => self.modify_latency_ns,
+            LatencyCommandType::Cancel => self.cancel_latency_ns,
+        };
+        self.base_latency_ns + command_latency_ns
+    }
+
+    fn response_latency_ns(&mut self) -> u64 {
+        self.base_latency_ns
+    }
+}
+
+/// The distribution latencies are sampled from by a [`SampledLatencyModel`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LatencyDistribution {
+    /// Uniformly distributed in the inclusive range [`min_ns`, `max_ns`].
+    Uniform { min_ns: u64, max_ns: u64 },
+    /// Normally distributed, truncated at zero.
+    Normal { mean_ns: f64, std_dev_ns: f64 },
+}
+
+/// Samples latencies for all commands and responses from a distribution.
+#[derive(Debug)]
+pub struct SampledLatencyModel {
+    pub distribution: LatencyDistribution,
+    rng: StdRng,
+}
+
+impl SampledLatencyModel {
+    /// Initializes a new `SampledLatencyModel` instance.
+    ///
+    /// # Errors
+    ///
+    /// If the distribution parameters are invalid.
+    pub fn new(distribution: LatencyDistribution, random_seed: Option<u64>) -> Result<Self> {
+        match distribution {
+            LatencyDistribution::Uniform { min_ns, max_ns } if min_ns > max_ns => {
+                bail!("`min_ns` {min_ns} was greater than `max_ns` {max_ns}")
+            }
+            LatencyDistribution::Normal {
+                mean_ns,
+                std_dev_ns,
+            } if !mean_ns.is_finite() || !std_dev_ns.is_finite() || std_dev_ns < 0.0 => {
+                bail!("Invalid normal distribution mean {mean_ns} and std dev {std_dev_ns}")
+            }
+            _ => {}
+        }
+        let rng = match random_seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        };
+        Ok(Self { distribution, rng })
+    }
+
+    fn sample(&mut self) -> u64 {
+        match self.distribution {
+            LatencyDistribution::Uniform { min_ns, max_ns } => self.rng.gen_range(min_ns..=max_ns),
+            LatencyDistribution::Normal {
+                mean_ns,
+                std_dev_ns,
+            } => {
+                // Box-Muller transform
+                let u1: f64 = 1.0 - self.rng.gen::<f64>();
+                let u2: f64 = self.rng.gen();
+                let z = (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos();
+                (mean_ns + z * std_dev_ns).max(0.0).round() as u64
+            }
+        }
+    }
+}
+
+impl LatencyModel for SampledLatencyModel {
+    fn command_latency_ns(&mut self, _command_type: LatencyCommandType) -> u64 {
+        self.sample()
+    }
+
+    fn response_latency_ns(&mut self) -> u64 {
+        self.sample()
+    }
+
+    fn reseed(&mut self, seed: u64) {
+        self.rng = StdRng::seed_from_u64(seed);
+    }
+}
+
+/// Holds items (commands or responses) until their latency has elapsed.
+///
+/// Delayed items are kept in a min-heap keyed by their due time and a sequence
+/// number, and released once the queue is advanced past their due time. Items due
+/// at the same time are released in the order they were scheduled.
+///
+/// Items are not registered as `TestClock` time alerts, as the clock dispatches
+/// alerts to Python handlers. Instead the backtest engine advances its clock to
+/// each due time in turn (interleaved with any timers), so latency is not
+/// quantized to the arrival of market data.
+pub struct LatencyQueue<T> {
+    pending: BinaryHeap<Reverse<ScheduledItem<T>>>,
+    ts_last: UnixNanos,
+    count: u64,
+}
+
+struct ScheduledItem<T> {
+    ts_due: UnixNanos,
+    sequence: u64,
+    item: T,
+}
+
+impl<T> ScheduledItem<T> {
+    fn key(&self) -> (UnixNanos, u64) {
+        (self.ts_due, self.sequence)
+    }
+}
+
+impl<T> PartialEq for ScheduledItem<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.key() == other.key()
+    }
+}
+
+impl<T> Eq for ScheduledItem<T> {}
+
+impl<T> PartialOrd for ScheduledItem<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T> Ord for ScheduledItem<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.key().cmp(&other.key())
+    }
+}
+
+impl<T> LatencyQueue<T> {
+    /// Initializes a new `LatencyQueue` instance.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            pending: BinaryHeap::new(),
+            ts_last: 0,
+            count: 0,
+        }
+    }
+
+    /// Returns the number of items not yet released.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Returns whether there are no items waiting to be released.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    /// Returns the earliest time an item is due to be released (if any).
+    #[must_use]
+    pub fn next_time_ns(&self) -> Option<UnixNanos> {
+        self.pending
+            .peek()
+            .map(|Reverse(scheduled)| scheduled.ts_due)
+    }
+
+    /// Schedules `item` to be released `latency_ns` after `ts_now`.
+    pub fn schedule(&mut self, item: T, ts_now: UnixNanos, latency_ns: u64) {
+        // Items cannot be scheduled before the last time the queue was advanced to
+        let ts_due = ts_now.max(self.ts_last) + latency_ns;
+        self.count += 1;
+        self.pending.push(Reverse(ScheduledItem {
+            ts_due,
+            sequence: self.count,
+            item,
+        }));
+    }
+
+    /// Advances the queue to `ts_now`, returning all items now due with the time
+    /// they were released.
+    pub fn advance(&mut self, ts_now: UnixNanos) -> Vec<(UnixNanos, T)> {
+        self.ts_last = self.ts_last.max(ts_now);
+        let mut released = Vec::new();
+        while let Some(Reverse(scheduled)) = self.pending.peek() {
+            if scheduled.ts_due > ts_now {
+                break;
+            }
+            if let Some(Reverse(scheduled)) = self.pending.pop() {
+                released.push((scheduled.ts_due, scheduled.item));
+            }
+        }
+        released
+    }
+
+    /// Removes all pending items.
+    pub fn clear(&mut self) {
+        self.pending.clear();
+    }
+}
+
+impl<T> Default for LatencyQueue<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Tests
+////////////////////////////////////////////////////////////////////////////////
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    fn test_constant_latency_model() {
+        let mut model = ConstantLatencyModel::new(1_000);
+        assert_eq!(model.command_latency_ns(LatencyCommandType::Submit), 1_000);
+        assert_eq!(model.command_latency_ns(LatencyCommandType::Cancel), 1_000);
+        assert_eq!(model.response_latency_ns(), 1_000);
+    }
+
+    #[rstest]
+    #[case(LatencyCommandType::Submit, 1_100)]
+    #[case(LatencyCommandType::Modify, 1_200)]
+    #[case(LatencyCommandType::Cancel, 1_300)]
+    fn test_per_command_latency_model(
+        #[case] command_type: LatencyCommandType,
+        #[case] expected: u64,
+    ) {
+        let mut model = PerCommandLatencyModel::new(1_000, 100, 200, 300);
+        assert_eq!(model.command_latency_ns(command_type), expected);
+        assert_eq!(model.response_latency_ns(), 1_000);
+    }
+
+    #[rstest]
+    fn test_sampled_uniform_latency_in_range() {
+        let distribution = LatencyDistribution::Uniform {
+            min_ns: 100,
+            max_ns: 200,
+        };
+        let mut model = SampledLatencyModel::new(distribution, Some(42)).unwrap();
+        for _ in 0..100 {
+            let latency = model.command_latency_ns(LatencyCommandType::Submit);
+            assert!((100..=200).contains(&latency));
+        }
+    }
+
+    #[rstest]
+    fn test_sampled_latency_is_reproducible_with_seed() {
+        let distribution = LatencyDistribution::Normal {
+            mean_ns: 1_000.0,
+            std_dev_ns: 250.0,
+        };
+        let mut model1 = SampledLatencyModel::new(distribution, Some(1)).unwrap();
+        let mut model2 = SampledLatencyModel::new(distribution, Some(1)).unwrap();
+        let samples1: Vec<u64> = (0..10).map(|_| model1.response_latency_ns()).collect();
+        let samples2: Vec<u64> = (0..10).map(|_| model2.response_latency_ns()).collect();
+        assert_eq!(samples1, samples2);
+    }
+
+    #[rstest]
+    fn test_sampled_invalid_distribution() {
+        let distribution = LatencyDistribution::Uniform {
+            min_ns: 200,
+            max_ns: 100,
+        };
+        assert!(SampledLatencyModel::new(distribution, None).is_err());
+    }
+
+    #[rstest]
+    fn test_latency_queue_releases_when_due() {
+        let mut queue: LatencyQueue<&str> = LatencyQueue::new();
+        queue.schedule("b", 0, 200);
+        queue.schedule("a", 0, 100);
+        queue.schedule("c", 0, 200);
+        assert_eq!(queue.len(), 3);
+        assert_eq!(queue.next_time_ns(), Some(100));
+
+        assert!(queue.advance(99).is_empty());
+        assert_eq!(queue.advance(100), vec![(100, "a")]);
+        assert_eq!(queue.advance(500), vec![(200, "b"), (200, "c")]);
+        assert!(queue.is_empty());
+    }
+
+    #[rstest]
+    fn test_latency_queue_never_schedules_before_last_advance() {
+        let mut queue: LatencyQueue<&str> = LatencyQueue::new();
+        assert!(queue.advance(1_000).is_empty());
+        queue.schedule("a", 500, 100);
+        assert!(queue.advance(1_099).is_empty());
+        assert_eq!(queue.advance(1_100), vec![(1_100, "a")]);
+    }
+
+    #[rstest]
+    fn test_latency_queue_zero_latency_is_immediate() {
+        let mut queue: LatencyQueue<u32> = LatencyQueue::new();
+        queue.schedule(1, 50, 0);
+        assert_eq!(queue.advance(50), vec![(50, 1)]);
+    }
+}
@@ -0,0 +1,233 @@
+// -------------------------------------------------------------------------------------------------
+//  Copyright (C) 2015-2024 Nautech Systems Pty Ltd. All rights reserved.
+//  https://nautechsystems.io
+//
+//  Licensed under the GNU Lesser General Public License Version 3.0 (the "License");
+//  You may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at https://www.gnu.org/licenses/lgpl-3.0.en.html
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+// -------------------------------------------------------------------------------------------------
+
+use std::{cell::RefCell, hash::Hasher};
+
+use anyhow::Result;
+use nautilus_core::{time::UnixNanos, uuid::UUID4};
+use nautilus_model::{
+    enums::PositionSide,
+    events::account::state::AccountState,
+    identifiers::position_id::PositionId,
+    position::Position,
+    types::{money::Money, quantity::Quantity},
+};
+use rand::{rngs::StdRng, SeedableRng};
+use serde::Serialize;
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0100_0000_01b3;
+
+/// Returns a seed for the given `stream` derived from the root `seed`.
+///
+/// Uses the SplitMix64 mixing function, so that independent random number
+/// generators driven by one root seed produce uncorrelated sequences.
+#[must_use]
+pub fn derive_seed(seed: u64, stream: u64) -> u64 {
+    let mut z = seed.wrapping_add(stream.wrapping_add(1).wrapping_mul(0x9e37_79b9_7f4a_7c15));
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+/// Generates the [`UUID4`] event IDs of a backtest component.
+///
+/// Once seeded, the generator draws from its own random number generator, so that
+/// components driven by separate seeds never affect each other's sequence of IDs.
+#[derive(Debug, Default)]
+pub struct UUID4Generator {
+    rng: RefCell<Option<StdRng>>,
+}
+
+impl UUID4Generator {
+    /// Reseeds the generator from `seed`, or reverts to non-deterministic IDs if `None`.
+    pub fn reseed(&mut self, seed: Option<u64>) {
+        *self.rng.get_mut() = seed.map(StdRng::seed_from_u64);
+    }
+
+    /// Returns the next [`UUID4`].
+    #[must_use]
+    pub fn generate(&self) -> UUID4 {
+        match self.rng.borrow_mut().as_mut() {
+            Some(rng) => UUID4::from_rng(rng),
+            None => UUID4::new(),
+        }
+    }
+}
+
+/// A change of account or position state at a simulated venue, which is folded into
+/// the [`ReplayChecksum`] along with the order events.
+#[derive(Clone, Debug, Serialize)]
+pub enum StateChange {
+    Account(AccountState),
+    Position(PositionChange),
+}
+
+/// The state of a position after a fill was applied to it.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+pub struct PositionChange {
+    pub position_id: PositionId,
+    pub side: PositionSide,
+    pub signed_qty: f64,
+    pub quantity: Quantity,
+    pub avg_px_open: f64,
+    pub avg_px_close: Option<f64>,
+    pub realized_pnl: Option<Money>,
+    pub ts_event: UnixNanos,
+}
+
+impl From<&Position> for PositionChange {
+    fn from(position: &Position) -> Self {
+        Self {
+            position_id: position.id,
+            side: position.side,
+            signed_qty: position.signed_qty,
+            quantity: position.quantity,
+            avg_px_open: position.avg_px_open,
+            avg_px_close: position.avg_px_close,
+            realized_pnl: position.realized_pnl,
+            ts_event: position.ts_last,
+        }
+    }
+}
+
+/// Provides a rolling checksum over the events emitted during a backtest run.
+///
+/// Each event is serialized to JSON and folded into a 64-bit FNV-1a hash in the
+/// order it was emitted. Two runs emitting identical events in the same order
+/// produce the same checksum, which allows runs to be compared across builds.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ReplayChecksum {
+    hash: u64,
+    count: usize,
+}
+
+impl ReplayChecksum {
+    /// Initializes a new `ReplayChecksum` instance.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            hash: FNV_OFFSET_BASIS,
+            count: 0,
+        }
+    }
+
+    /// Returns the current checksum value.
+    #[must_use]
+    pub fn value(&self) -> u64 {
+        self.hash
+    }
+
+    /// Returns the number of events included in the checksum.
+    #[must_use]
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Folds the given `event` into the checksum.
+    ///
+    /// # Errors
+    ///
+    /// If the event cannot be serialized.
+    pub fn update<T: Serialize>(&mut self, event: &T) -> Result<()> {
+        let bytes = serde_json::to_vec(event)?;
+        self.write(&bytes);
+        // Delimit events so that the same bytes split differently hash differently
+        self.write(&[0xff]);
+        self.count += 1;
+        Ok(())
+    }
+
+    /// Resets the checksum to its initial state.
+    pub fn reset(&mut self) {
+        *self = Self::new();
+    }
+}
+
+impl Default for ReplayChecksum {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Hasher for ReplayChecksum {
+    fn finish(&self) -> u64 {
+        self.hash
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.hash ^= u64::from(*byte);
+            self.hash = self.hash.wrapping_mul(FNV_PRIME);
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Tests
+////////////////////////////////////////////////////////////////////////////////
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    fn test_derive_seed_is_deterministic_per_stream() {
+        assert_eq!(derive_seed(42, 1), derive_seed(42, 1));
+        assert_ne!(derive_seed(42, 1), derive_seed(42, 2));
+        assert_ne!(derive_seed(42, 1), derive_seed(43, 1));
+    }
+
+    #[rstest]
+    fn test_seeded_uuid4_generators_are_independent() {
+        let mut generator1 = UUID4Generator::default();
+        let mut generator2 = UUID4Generator::default();
+        generator1.reseed(Some(42));
+        generator2.reseed(Some(42));
+        let first = generator1.generate();
+
+        // Drawing from one generator does not advance the other
+        assert_ne!(generator1.generate(), first);
+        assert_eq!(generator2.generate(), first);
+
+        generator1.reseed(None);
+        assert_ne!(generator1.generate(), generator2.generate());
+    }
+
+    #[rstest]
+    fn test_fnv1a_known_value() {
+        let mut checksum = ReplayChecksum::new();
+        checksum.write(b"a");
+        assert_eq!(checksum.finish(), 0xaf63_dc4c_8601_ec8c);
+    }
+
+    #[rstest]
+    fn test_checksum_depends_on_order() {
+        let mut checksum1 = ReplayChecksum::new();
+        checksum1.update(&"event-1").unwrap();
+        checksum1.update(&"event-2").unwrap();
+
+        let mut checksum2 = ReplayChecksum::new();
+        checksum2.update(&"event-2").unwrap();
+        checksum2.update(&"event-1").unwrap();
+
+        assert_eq!(checksum1.count(), 2);
+        assert_ne!(checksum1.value(), checksum2.value());
+
+        checksum2.reset();
+        assert_eq!(checksum2, ReplayChecksum::new());
+    }
+}
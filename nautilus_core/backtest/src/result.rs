@@ -0,0 +1,543 @@
+// -------------------------------------------------------------------------------------------------
+//  Copyright (C) 2015-2024 Nautech Systems Pty Ltd. All rights reserved.
+//  https://nautechsystems.io
+//
+//  Licensed under the GNU Lesser General Public License Version 3.0 (the "License");
+//  You may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at https://www.gnu.org/licenses/lgpl-3.0.en.html
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+// -------------------------------------------------------------------------------------------------
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs::File,
+    path::Path,
+    sync::Arc,
+};
+
+use anyhow::Result;
+use datafusion::{
+    arrow::{
+        array::{ArrayRef, Float64Array, StringArray, UInt64Array},
+        datatypes::{Field, Schema},
+        record_batch::RecordBatch,
+    },
+    parquet::arrow::ArrowWriter,
+};
+use nautilus_core::time::UnixNanos;
+use nautilus_model::{
+    enums::{OrderSide, OrderStatus, OrderType, TimeInForce},
+    events::order::{event::OrderEvent, filled::OrderFilled},
+    identifiers::{
+        account_id::AccountId, client_order_id::ClientOrderId, instrument_id::InstrumentId,
+        strategy_id::StrategyId, trader_id::TraderId, venue_order_id::VenueOrderId,
+    },
+    orders::base::Order,
+    position::Position,
+    types::{balance::AccountBalance, price::Price, quantity::Quantity},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::statistics::{PnlStatistics, ReturnsStatistics, DEFAULT_TRADING_PERIOD};
+
+/// A summary of an order submitted during a backtest, kept up to date from its events.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct OrderSummary {
+    pub client_order_id: ClientOrderId,
+    pub venue_order_id: Option<VenueOrderId>,
+    pub instrument_id: InstrumentId,
+    pub strategy_id: StrategyId,
+    pub side: OrderSide,
+    pub order_type: OrderType,
+    pub time_in_force: TimeInForce,
+    pub quantity: Quantity,
+    pub price: Option<Price>,
+    pub trigger_price: Option<Price>,
+    pub status: OrderStatus,
+    pub filled_qty: Quantity,
+    pub avg_px: Option<f64>,
+    pub ts_init: UnixNanos,
+    pub ts_last: UnixNanos,
+}
+
+impl OrderSummary {
+    /// Updates the summary from the given order `event`.
+    pub fn apply(&mut self, event: &OrderEvent) {
+        match event {
+            OrderEvent::OrderAccepted(accepted) => {
+                self.status = OrderStatus::Accepted;
+                self.venue_order_id = Some(accepted.venue_order_id);
+            }
+            OrderEvent::OrderRejected(_) => self.status = OrderStatus::Rejected,
+            OrderEvent::OrderCanceled(_) => self.status = OrderStatus::Canceled,
+            OrderEvent::OrderExpired(_) => self.status = OrderStatus::Expired,
+            OrderEvent::OrderTriggered(_) => self.status = OrderStatus::Triggered,
+            OrderEvent::OrderUpdated(updated) => {
+                self.quantity = updated.quantity;
+                self.price = updated.price.or(self.price);
+                self.trigger_price = updated.trigger_price.or(self.trigger_price);
+            }
+            OrderEvent::OrderPartiallyFilled(fill) | OrderEvent::OrderFilled(fill) => {
+                let filled_qty = self.filled_qty.as_f64();
+                let last_qty = fill.last_qty.as_f64();
+                let avg_px = self.avg_px.unwrap_or_default();
+                self.avg_px = Some(
+                    (avg_px * filled_qty + fill.last_px.as_f64() * last_qty)
+                        / (filled_qty + last_qty),
+                );
+                self.filled_qty = self.filled_qty + fill.last_qty;
+                self.venue_order_id = Some(fill.venue_order_id);
+                self.status = match event {
+                    OrderEvent::OrderFilled(_) => OrderStatus::Filled,
+                    _ => OrderStatus::PartiallyFilled,
+                };
+            }
+            _ => return,
+        }
+        self.ts_last = event.ts_event();
+    }
+}
+
+impl From<&dyn Order> for OrderSummary {
+    fn from(order: &dyn Order) -> Self {
+        Self {
+            client_order_id: order.client_order_id(),
+            venue_order_id: order.venue_order_id(),
+            instrument_id: order.instrument_id(),
+            strategy_id: order.strategy_id(),
+            side: order.side(),
+            order_type: order.order_type(),
+            time_in_force: order.time_in_force(),
+            quantity: order.quantity(),
+            price: order.price(),
+            trigger_price: order.trigger_price(),
+            status: order.status(),
+            filled_qty: order.filled_qty(),
+            avg_px: order.avg_px(),
+            ts_init: order.ts_init(),
+            ts_last: order.ts_last(),
+        }
+    }
+}
+
+/// A snapshot of an account balance in a single currency.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BalanceSnapshot {
+    pub account_id: AccountId,
+    pub ts_event: UnixNanos,
+    pub balance: AccountBalance,
+}
+
+/// The results of a backtest run, including computed portfolio statistics.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BacktestResult {
+    pub trader_id: TraderId,
+    pub backtest_start: Option<UnixNanos>,
+    pub backtest_end: Option<UnixNanos>,
+    pub iterations: usize,
+    pub total_events: usize,
+    pub total_orders: usize,
+    pub total_positions: usize,
+    pub orders: Vec<OrderSummary>,
+    pub fills: Vec<OrderFilled>,
+    pub positions: Vec<Position>,
+    pub balances: Vec<BalanceSnapshot>,
+    /// The realized PnL statistics keyed by currency code.
+    pub stats_pnls: BTreeMap<String, PnlStatistics>,
+    pub stats_returns: ReturnsStatistics,
+    /// The rolling checksum over all events emitted during the run.
+    pub checksum: u64,
+}
+
+impl BacktestResult {
+    /// Initializes a new `BacktestResult` instance, calculating the portfolio statistics.
+    ///
+    /// The `positions` should contain a snapshot of each closed position, plus any
+    /// positions still open, so every round trip contributes to the statistics.
+    #[must_use]
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        trader_id: TraderId,
+        backtest_start: Option<UnixNanos>,
+        backtest_end: Option<UnixNanos>,
+        iterations: usize,
+        total_events: usize,
+        orders: Vec<OrderSummary>,
+        mut fills: Vec<OrderFilled>,
+        positions: Vec<Position>,
+        balances: Vec<BalanceSnapshot>,
+        checksum: u64,
+    ) -> Self {
+        fills.sort_by_key(|fill| fill.ts_event);
+
+        let mut realized_pnls: HashMap<String, Vec<f64>> = HashMap::new();
+        for pnl in positions
+            .iter()
+            .filter_map(|position| position.realized_pnl)
+        {
+            realized_pnls
+                .entry(pnl.currency.code.to_string())
+                .or_default()
+                .push(pnl.as_f64());
+        }
+        let stats_pnls = realized_pnls
+            .into_iter()
+            .map(|(currency, pnls)| (currency, PnlStatistics::new(&pnls)))
+            .collect();
+
+        // Returns are realized when positions close, summed for positions closed together
+        let mut returns: BTreeMap<UnixNanos, f64> = BTreeMap::new();
+        for position in &positions {
+            if let Some(ts_closed) = position.ts_closed {
+                *returns.entry(ts_closed).or_default() += position.realized_return;
+            }
+        }
+        let returns: Vec<(UnixNanos, f64)> = returns.into_iter().collect();
+        let position_refs: Vec<&Position> = positions.iter().collect();
+        let stats_returns =
+            ReturnsStatistics::new(&returns, &position_refs, DEFAULT_TRADING_PERIOD);
+
+        Self {
+            trader_id,
+            backtest_start,
+            backtest_end,
+            iterations,
+            total_events,
+            total_orders: orders.len(),
+            total_positions: positions.len(),
+            orders,
+            fills,
+            positions,
+            balances,
+            stats_pnls,
+            stats_returns,
+            checksum,
+        }
+    }
+
+    /// Serializes the result to a JSON string.
+    ///
+    /// # Errors
+    ///
+    /// If serialization fails.
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// Writes the orders, fills, positions and balances of the result as Parquet
+    /// files (`orders.parquet`, `fills.parquet`, `positions.parquet` and
+    /// `balances.parquet`) into the given `directory`.
+    ///
+    /// # Errors
+    ///
+    /// If a file cannot be created or written.
+    pub fn write_parquet(&self, directory: &Path) -> Result<()> {
+        std::fs::create_dir_all(directory)?;
+        write_parquet_file(&directory.join("orders.parquet"), self.orders_columns())?;
+        write_parquet_file(&directory.join("fills.parquet"), self.fills_columns())?;
+        write_parquet_file(
+            &directory.join("positions.parquet"),
+            self.positions_columns(),
+        )?;
+        write_parquet_file(&directory.join("balances.parquet"), self.balances_columns())?;
+        Ok(())
+    }
+
+    fn orders_columns(&self) -> Vec<(&'static str, ArrayRef)> {
+        let orders = &self.orders;
+        vec![
+            (
+                "client_order_id",
+                strings(orders, |o| o.client_order_id.to_string()),
+            ),
+            (
+                "venue_order_id",
+                optional_strings(orders, |o| o.venue_order_id.map(|id| id.to_string())),
+            ),
+            (
+                "instrument_id",
+                strings(orders, |o| o.instrument_id.to_string()),
+            ),
+            (
+                "strategy_id",
+                strings(orders, |o| o.strategy_id.to_string()),
+            ),
+            ("side", strings(orders, |o| o.side.to_string())),
+            ("order_type", strings(orders, |o| o.order_type.to_string())),
+            (
+                "time_in_force",
+                strings(orders, |o| o.time_in_force.to_string()),
+            ),
+            ("quantity", floats(orders, |o| Some(o.quantity.as_f64()))),
+            ("price", floats(orders, |o| o.price.map(|px| px.as_f64()))),
+            (
+                "trigger_price",
+                floats(orders, |o| o.trigger_price.map(|px| px.as_f64())),
+            ),
+            ("status", strings(orders, |o| o.status.to_string())),
+            (
+                "filled_qty",
+                floats(orders, |o| Some(o.filled_qty.as_f64())),
+            ),
+            ("avg_px", floats(orders, |o| o.avg_px)),
+            ("ts_init", timestamps(orders, |o| Some(o.ts_init))),
+            ("ts_last", timestamps(orders, |o| Some(o.ts_last))),
+        ]
+    }
+
+    fn fills_columns(&self) -> Vec<(&'static str, ArrayRef)> {
+        let fills = &self.fills;
+        vec![
+            ("trade_id", strings(fills, |f| f.trade_id.to_string())),
+            (
+                "client_order_id",
+                strings(fills, |f| f.client_order_id.to_string()),
+            ),
+            (
+                "venue_order_id",
+                strings(fills, |f| f.venue_order_id.to_string()),
+            ),
+            (
+                "position_id",
+                optional_strings(fills, |f| f.position_id.map(|id| id.to_string())),
+            ),
+            (
+                "instrument_id",
+                strings(fills, |f| f.instrument_id.to_string()),
+            ),
+            ("strategy_id", strings(fills, |f| f.strategy_id.to_string())),
+            ("order_side", strings(fills, |f| f.order_side.to_string())),
+            ("last_qty", floats(fills, |f| Some(f.last_qty.as_f64()))),
+            ("last_px", floats(fills, |f| Some(f.last_px.as_f64()))),
+            ("currency", strings(fills, |f| f.currency.code.to_string())),
+            (
+                "commission",
+                floats(fills, |f| f.commission.map(|c| c.as_f64())),
+            ),
+            (
+                "liquidity_side",
+                strings(fills, |f| f.liquidity_side.to_string()),
+            ),
+            ("ts_event", timestamps(fills, |f| Some(f.ts_event))),
+        ]
+    }
+
+    fn positions_columns(&self) -> Vec<(&'static str, ArrayRef)> {
+        let positions = &self.positions;
+        vec![
+            ("position_id", strings(positions, |p| p.id.to_string())),
+            (
+                "instrument_id",
+                strings(positions, |p| p.instrument_id.to_string()),
+            ),
+            (
+                "strategy_id",
+                strings(positions, |p| p.strategy_id.to_string()),
+            ),
+            ("entry", strings(positions, |p| p.entry.to_string())),
+            ("side", strings(positions, |p| p.side.to_string())),
+            ("quantity", floats(positions, |p| Some(p.quantity.as_f64()))),
+            ("peak_qty", floats(positions, |p| Some(p.peak_qty.as_f64()))),
+            ("avg_px_open", floats(positions, |p| Some(p.avg_px_open))),
+            ("avg_px_close", floats(positions, |p| p.avg_px_close)),
+            (
+                "realized_pnl",
+                floats(positions, |p| p.realized_pnl.map(|pnl| pnl.as_f64())),
+            ),
+            (
+                "realized_return",
+                floats(positions, |p| Some(p.realized_return)),
+            ),
+            ("ts_opened", timestamps(positions, |p| Some(p.ts_opened))),
+            ("ts_closed", timestamps(positions, |p| p.ts_closed)),
+            (
+                "duration_ns",
+                timestamps(positions, |p| Some(p.duration_ns)),
+            ),
+        ]
+    }
+
+    fn balances_columns(&self) -> Vec<(&'static str, ArrayRef)> {
+        let balances = &self.balances;
+        vec![
+            (
+                "account_id",
+                strings(balances, |b| b.account_id.to_string()),
+            ),
+            ("ts_event", timestamps(balances, |b| Some(b.ts_event))),
+            (
+                "currency",
+                strings(balances, |b| b.balance.currency.code.to_string()),
+            ),
+            (
+                "total",
+                floats(balances, |b| Some(b.balance.total.as_f64())),
+            ),
+            (
+                "locked",
+                floats(balances, |b| Some(b.balance.locked.as_f64())),
+            ),
+            ("free", floats(balances, |b| Some(b.balance.free.as_f64()))),
+        ]
+    }
+}
+
+fn strings<T>(rows: &[T], f: impl Fn(&T) -> String) -> ArrayRef {
+    Arc::new(StringArray::from(rows.iter().map(f).collect::<Vec<_>>()))
+}
+
+fn optional_strings<T>(rows: &[T], f: impl Fn(&T) -> Option<String>) -> ArrayRef {
+    Arc::new(StringArray::from(rows.iter().map(f).collect::<Vec<_>>()))
+}
+
+fn floats<T>(rows: &[T], f: impl Fn(&T) -> Option<f64>) -> ArrayRef {
+    Arc::new(Float64Array::from(rows.iter().map(f).collect::<Vec<_>>()))
+}
+
+fn timestamps<T>(rows: &[T], f: impl Fn(&T) -> Option<UnixNanos>) -> ArrayRef {
+    Arc::new(UInt64Array::from(rows.iter().map(f).collect::<Vec<_>>()))
+}
+
+fn write_parquet_file(path: &Path, columns: Vec<(&'static str, ArrayRef)>) -> Result<()> {
+    let fields: Vec<Field> = columns
+        .iter()
+        .map(|(name, array)| Field::new(*name, array.data_type().clone(), true))
+        .collect();
+    let schema = Arc::new(Schema::new(fields));
+    let arrays = columns.into_iter().map(|(_, array)| array).collect();
+    let batch = RecordBatch::try_new(schema.clone(), arrays)?;
+
+    let mut writer = ArrowWriter::try_new(File::create(path)?, schema, None)?;
+    writer.write(&batch)?;
+    writer.close()?;
+    Ok(())
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Tests
+////////////////////////////////////////////////////////////////////////////////
+#[cfg(test)]
+mod tests {
+    use datafusion::parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+    use nautilus_model::{
+        instruments::{currency_pair::CurrencyPair, stubs::audusd_sim},
+        orders::{
+            market::MarketOrder,
+            stubs::{TestOrderEventStubs, TestOrderStubs},
+        },
+        stubs::{test_position_long, test_position_short},
+        types::money::Money,
+    };
+    use rstest::rstest;
+
+    use super::*;
+
+    fn result(positions: Vec<Position>) -> BacktestResult {
+        let fills = positions
+            .iter()
+            .flat_map(|position| position.events.iter().copied())
+            .collect();
+        let balance = AccountBalance::new(
+            Money::from("1000000 USD"),
+            Money::from("0 USD"),
+            Money::from("1000000 USD"),
+        )
+        .unwrap();
+        let balances = vec![BalanceSnapshot {
+            account_id: AccountId::from("SIM-001"),
+            ts_event: 0,
+            balance,
+        }];
+        BacktestResult::new(
+            TraderId::from("TRADER-001"),
+            Some(0),
+            Some(1_000),
+            2,
+            4,
+            vec![],
+            fills,
+            positions,
+            balances,
+            0,
+        )
+    }
+
+    #[rstest]
+    fn test_order_summary_applies_fill(audusd_sim: CurrencyPair) {
+        let order = TestOrderStubs::market_order(
+            audusd_sim.id,
+            OrderSide::Buy,
+            Quantity::from(100),
+            None,
+            None,
+        );
+        let mut summary = OrderSummary::from(&order as &dyn Order);
+        assert_eq!(summary.status, OrderStatus::Initialized);
+
+        let fill = TestOrderEventStubs::order_filled::<MarketOrder, CurrencyPair>(
+            &order,
+            &audusd_sim,
+            None,
+            None,
+            None,
+            Some(Price::from("1.00010")),
+            None,
+            None,
+            None,
+        );
+        summary.apply(&OrderEvent::OrderFilled(fill));
+
+        assert_eq!(summary.status, OrderStatus::Filled);
+        assert_eq!(summary.filled_qty, Quantity::from(100));
+        assert!((summary.avg_px.unwrap() - 1.0001).abs() < 1e-9);
+        assert_eq!(summary.venue_order_id, Some(fill.venue_order_id));
+    }
+
+    #[rstest]
+    fn test_result_statistics(test_position_long: Position, test_position_short: Position) {
+        let result = result(vec![test_position_long, test_position_short]);
+
+        assert_eq!(result.total_positions, 2);
+        assert_eq!(result.fills.len(), 2);
+        assert_eq!(result.stats_returns.long_ratio, Some(0.5));
+        let stats = &result.stats_pnls["USD"];
+        // Positions are open so only commissions are realized
+        assert!(stats.pnl_total < 0.0);
+        assert_eq!(stats.win_rate, 0.0);
+    }
+
+    #[rstest]
+    fn test_result_to_json(test_position_long: Position) {
+        let json = result(vec![test_position_long]).to_json().unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(value["iterations"], 2);
+        assert_eq!(value["positions"].as_array().unwrap().len(), 1);
+        assert!(value["stats_pnls"]["USD"]["win_rate"].is_number());
+        assert!(value["stats_returns"]["sharpe_ratio"].is_null());
+    }
+
+    #[rstest]
+    fn test_result_write_parquet(test_position_long: Position, test_position_short: Position) {
+        let directory = tempfile::tempdir().unwrap();
+        result(vec![test_position_long, test_position_short])
+            .write_parquet(directory.path())
+            .unwrap();
+
+        for name in ["orders", "fills", "positions", "balances"] {
+            assert!(directory.path().join(format!("{name}.parquet")).exists());
+        }
+        let file = File::open(directory.path().join("positions.parquet")).unwrap();
+        let reader = ParquetRecordBatchReaderBuilder::try_new(file)
+            .unwrap()
+            .build()
+            .unwrap();
+        let num_rows: usize = reader.map(|batch| batch.unwrap().num_rows()).sum();
+        assert_eq!(num_rows, 2);
+    }
+}
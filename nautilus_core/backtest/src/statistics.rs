@@ -0,0 +1,379 @@
+// -------------------------------------------------------------------------------------------------
+//  Copyright (C) 2015-2024 Nautech Systems Pty Ltd. All rights reserved.
+//  https://nautechsystems.io
+//
+//  Licensed under the GNU Lesser General Public License Version 3.0 (the "License");
+//  You may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at https://www.gnu.org/licenses/lgpl-3.0.en.html
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+// -------------------------------------------------------------------------------------------------
+
+use std::collections::BTreeMap;
+
+use nautilus_core::time::UnixNanos;
+use nautilus_model::{enums::OrderSide, position::Position};
+use serde::{Deserialize, Serialize};
+
+const NANOSECONDS_IN_DAY: u64 = 86_400_000_000_000;
+
+/// The default number of trading days in a year used to annualize statistics.
+pub const DEFAULT_TRADING_PERIOD: u32 = 252;
+
+/// Returns the average of `values`, or `None` if empty.
+fn mean(values: &[f64]) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+    Some(values.iter().sum::<f64>() / values.len() as f64)
+}
+
+/// Returns the sample standard deviation (with one degree of freedom) of `values`.
+fn std_dev(values: &[f64]) -> Option<f64> {
+    if values.len() < 2 {
+        return None;
+    }
+    let mean = mean(values)?;
+    let variance =
+        values.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (values.len() - 1) as f64;
+    Some(variance.sqrt())
+}
+
+fn finite(value: f64) -> Option<f64> {
+    value.is_finite().then_some(value)
+}
+
+/// Sums the timestamped `returns` into daily bins, including days with no returns.
+#[must_use]
+pub fn downsample_to_daily_bins(returns: &[(UnixNanos, f64)]) -> Vec<f64> {
+    let mut bins: BTreeMap<u64, f64> = BTreeMap::new();
+    for (ts, value) in returns.iter().filter(|(_, value)| !value.is_nan()) {
+        *bins.entry(ts / NANOSECONDS_IN_DAY).or_default() += value;
+    }
+    let (Some(first), Some(last)) = (bins.keys().next(), bins.keys().next_back()) else {
+        return Vec::new();
+    };
+    (*first..=*last)
+        .map(|day| bins.get(&day).copied().unwrap_or_default())
+        .collect()
+}
+
+// -- REALIZED PNL STATISTICS ---------------------------------------------------------------------
+
+/// Returns the ratio of winning (positive) realized PnLs.
+#[must_use]
+pub fn win_rate(realized_pnls: &[f64]) -> f64 {
+    let winners = realized_pnls.iter().filter(|pnl| **pnl > 0.0).count();
+    winners as f64 / realized_pnls.len().max(1) as f64
+}
+
+/// Returns the average winning realized PnL.
+#[must_use]
+pub fn avg_winner(realized_pnls: &[f64]) -> f64 {
+    let winners: Vec<f64> = realized_pnls
+        .iter()
+        .copied()
+        .filter(|pnl| *pnl > 0.0)
+        .collect();
+    mean(&winners).unwrap_or_default()
+}
+
+/// Returns the average losing (or flat) realized PnL.
+#[must_use]
+pub fn avg_loser(realized_pnls: &[f64]) -> f64 {
+    let losers: Vec<f64> = realized_pnls
+        .iter()
+        .copied()
+        .filter(|pnl| *pnl <= 0.0)
+        .collect();
+    mean(&losers).unwrap_or_default()
+}
+
+/// Returns the largest realized PnL.
+#[must_use]
+pub fn max_winner(realized_pnls: &[f64]) -> f64 {
+    realized_pnls
+        .iter()
+        .copied()
+        .reduce(f64::max)
+        .unwrap_or_default()
+}
+
+/// Returns the smallest winning realized PnL.
+#[must_use]
+pub fn min_winner(realized_pnls: &[f64]) -> f64 {
+    realized_pnls
+        .iter()
+        .copied()
+        .filter(|pnl| *pnl > 0.0)
+        .reduce(f64::min)
+        .unwrap_or_default()
+}
+
+/// Returns the largest losing realized PnL (the most negative).
+#[must_use]
+pub fn max_loser(realized_pnls: &[f64]) -> f64 {
+    realized_pnls
+        .iter()
+        .copied()
+        .filter(|pnl| *pnl < 0.0)
+        .reduce(f64::min)
+        .unwrap_or_default()
+}
+
+/// Returns the smallest losing (or flat) realized PnL (the least negative).
+#[must_use]
+pub fn min_loser(realized_pnls: &[f64]) -> f64 {
+    realized_pnls
+        .iter()
+        .copied()
+        .filter(|pnl| *pnl <= 0.0)
+        .reduce(f64::max)
+        .unwrap_or_default()
+}
+
+/// Returns the expected realized PnL per trade from the win rate and average winner and loser.
+#[must_use]
+pub fn expectancy(realized_pnls: &[f64]) -> f64 {
+    if realized_pnls.is_empty() {
+        return 0.0;
+    }
+    let win_rate = win_rate(realized_pnls);
+    avg_winner(realized_pnls) * win_rate + avg_loser(realized_pnls) * (1.0 - win_rate)
+}
+
+// -- RETURNS STATISTICS --------------------------------------------------------------------------
+
+/// Returns the average of the non-zero `returns`.
+#[must_use]
+pub fn returns_avg(returns: &[f64]) -> Option<f64> {
+    let returns: Vec<f64> = returns.iter().copied().filter(|r| *r != 0.0).collect();
+    mean(&returns)
+}
+
+/// Returns the average of the positive `returns`.
+#[must_use]
+pub fn returns_avg_win(returns: &[f64]) -> Option<f64> {
+    let returns: Vec<f64> = returns.iter().copied().filter(|r| *r > 0.0).collect();
+    mean(&returns)
+}
+
+/// Returns the average of the negative `returns`.
+#[must_use]
+pub fn returns_avg_loss(returns: &[f64]) -> Option<f64> {
+    let returns: Vec<f64> = returns.iter().copied().filter(|r| *r < 0.0).collect();
+    mean(&returns)
+}
+
+/// Returns the ratio of the sum of positive `returns` to the sum of negative `returns`.
+#[must_use]
+pub fn profit_factor(returns: &[f64]) -> Option<f64> {
+    let positive: f64 = returns.iter().filter(|r| **r >= 0.0).sum();
+    let negative: f64 = returns.iter().filter(|r| **r < 0.0).sum();
+    if returns.is_empty() || negative == 0.0 {
+        return None;
+    }
+    Some((positive / negative).abs())
+}
+
+/// Returns the mean of `returns` divided by their standard deviation.
+#[must_use]
+pub fn risk_return_ratio(returns: &[f64]) -> Option<f64> {
+    finite(mean(returns)? / std_dev(returns)?)
+}
+
+/// Returns the annualized volatility of the daily binned `returns`.
+#[must_use]
+pub fn returns_volatility(returns: &[(UnixNanos, f64)], period: u32) -> Option<f64> {
+    let daily = downsample_to_daily_bins(returns);
+    finite(std_dev(&daily)? * f64::from(period).sqrt())
+}
+
+/// Returns the annualized Sharpe ratio of the daily binned `returns`.
+#[must_use]
+pub fn sharpe_ratio(returns: &[(UnixNanos, f64)], period: u32) -> Option<f64> {
+    let daily = downsample_to_daily_bins(returns);
+    finite(mean(&daily)? / std_dev(&daily)? * f64::from(period).sqrt())
+}
+
+/// Returns the annualized Sortino ratio of the daily binned `returns`.
+#[must_use]
+pub fn sortino_ratio(returns: &[(UnixNanos, f64)], period: u32) -> Option<f64> {
+    let daily = downsample_to_daily_bins(returns);
+    let downside_sq: f64 = daily.iter().filter(|r| **r < 0.0).map(|r| r.powi(2)).sum();
+    let downside = (downside_sq / daily.len() as f64).sqrt();
+    if downside == 0.0 {
+        return None;
+    }
+    finite(mean(&daily)? / downside * f64::from(period).sqrt())
+}
+
+// -- POSITION STATISTICS -------------------------------------------------------------------------
+
+/// Returns the ratio of positions which were entered long.
+#[must_use]
+pub fn long_ratio(positions: &[&Position]) -> Option<f64> {
+    if positions.is_empty() {
+        return None;
+    }
+    let longs = positions
+        .iter()
+        .filter(|position| position.entry == OrderSide::Buy)
+        .count();
+    Some(longs as f64 / positions.len() as f64)
+}
+
+/// Statistics calculated from the realized PnLs of positions in a single currency.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PnlStatistics {
+    pub pnl_total: f64,
+    pub max_winner: f64,
+    pub avg_winner: f64,
+    pub min_winner: f64,
+    pub min_loser: f64,
+    pub avg_loser: f64,
+    pub max_loser: f64,
+    pub expectancy: f64,
+    pub win_rate: f64,
+}
+
+impl PnlStatistics {
+    #[must_use]
+    pub fn new(realized_pnls: &[f64]) -> Self {
+        Self {
+            pnl_total: realized_pnls.iter().sum(),
+            max_winner: max_winner(realized_pnls),
+            avg_winner: avg_winner(realized_pnls),
+            min_winner: min_winner(realized_pnls),
+            min_loser: min_loser(realized_pnls),
+            avg_loser: avg_loser(realized_pnls),
+            max_loser: max_loser(realized_pnls),
+            expectancy: expectancy(realized_pnls),
+            win_rate: win_rate(realized_pnls),
+        }
+    }
+}
+
+/// Statistics calculated from timestamped position returns.
+///
+/// Statistics which are undefined for the returns (such as a Sharpe ratio with
+/// no variance) are `None`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ReturnsStatistics {
+    pub returns_volatility: Option<f64>,
+    pub avg_return: Option<f64>,
+    pub avg_win_return: Option<f64>,
+    pub avg_loss_return: Option<f64>,
+    pub sharpe_ratio: Option<f64>,
+    pub sortino_ratio: Option<f64>,
+    pub profit_factor: Option<f64>,
+    pub risk_return_ratio: Option<f64>,
+    pub long_ratio: Option<f64>,
+}
+
+impl ReturnsStatistics {
+    #[must_use]
+    pub fn new(returns: &[(UnixNanos, f64)], positions: &[&Position], period: u32) -> Self {
+        let values: Vec<f64> = returns.iter().map(|(_, value)| *value).collect();
+        Self {
+            returns_volatility: returns_volatility(returns, period),
+            avg_return: returns_avg(&values),
+            avg_win_return: returns_avg_win(&values),
+            avg_loss_return: returns_avg_loss(&values),
+            sharpe_ratio: sharpe_ratio(returns, period),
+            sortino_ratio: sortino_ratio(returns, period),
+            profit_factor: profit_factor(&values),
+            risk_return_ratio: risk_return_ratio(&values),
+            long_ratio: long_ratio(positions),
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Tests
+////////////////////////////////////////////////////////////////////////////////
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    const PNLS: [f64; 5] = [100.0, -50.0, 25.0, 0.0, -25.0];
+
+    #[rstest]
+    fn test_pnl_statistics() {
+        let stats = PnlStatistics::new(&PNLS);
+        assert_eq!(stats.pnl_total, 50.0);
+        assert_eq!(stats.max_winner, 100.0);
+        assert_eq!(stats.avg_winner, 62.5);
+        assert_eq!(stats.min_winner, 25.0);
+        assert_eq!(stats.min_loser, 0.0);
+        assert_eq!(stats.avg_loser, -25.0);
+        assert_eq!(stats.max_loser, -50.0);
+        assert_eq!(stats.win_rate, 0.4);
+        assert!((stats.expectancy - 10.0).abs() < 1e-9);
+    }
+
+    #[rstest]
+    fn test_pnl_statistics_when_empty() {
+        let stats = PnlStatistics::new(&[]);
+        assert_eq!(stats.win_rate, 0.0);
+        assert_eq!(stats.expectancy, 0.0);
+        assert_eq!(stats.max_winner, 0.0);
+    }
+
+    #[rstest]
+    fn test_downsample_to_daily_bins_fills_missing_days() {
+        let returns = [
+            (0, 0.25),
+            (NANOSECONDS_IN_DAY / 2, 0.5),
+            (NANOSECONDS_IN_DAY * 2, -0.25),
+        ];
+        assert_eq!(downsample_to_daily_bins(&returns), vec![0.75, 0.0, -0.25]);
+    }
+
+    #[rstest]
+    fn test_profit_factor() {
+        assert_eq!(profit_factor(&[0.5, 0.25, -0.25]), Some(3.0));
+        assert_eq!(profit_factor(&[0.5, 0.25]), None);
+        assert_eq!(profit_factor(&[]), None);
+    }
+
+    #[rstest]
+    fn test_returns_averages() {
+        let returns = [0.5, 0.0, -0.25, 1.0, -0.75];
+        assert_eq!(returns_avg(&returns), Some(0.125));
+        assert_eq!(returns_avg_win(&returns), Some(0.75));
+        assert_eq!(returns_avg_loss(&returns), Some(-0.5));
+        assert_eq!(returns_avg(&[0.0]), None);
+    }
+
+    #[rstest]
+    fn test_sharpe_and_sortino_ratios() {
+        let returns: Vec<(UnixNanos, f64)> = [0.01, -0.01, 0.02, 0.0]
+            .iter()
+            .enumerate()
+            .map(|(i, r)| (i as u64 * NANOSECONDS_IN_DAY, *r))
+            .collect();
+
+        // Daily mean 0.005, sample std dev 0.0129099
+        let sharpe = sharpe_ratio(&returns, DEFAULT_TRADING_PERIOD).unwrap();
+        assert!((sharpe - 0.005 / 0.012_909_944 * 252f64.sqrt()).abs() < 1e-6);
+
+        // Downside deviation sqrt(0.0001 / 4) = 0.005
+        let sortino = sortino_ratio(&returns, DEFAULT_TRADING_PERIOD).unwrap();
+        assert!((sortino - 252f64.sqrt()).abs() < 1e-9);
+    }
+
+    #[rstest]
+    fn test_ratios_undefined_without_variance() {
+        let returns = [(0, 0.01), (NANOSECONDS_IN_DAY, 0.01)];
+        assert_eq!(sharpe_ratio(&returns, DEFAULT_TRADING_PERIOD), None);
+        assert_eq!(sortino_ratio(&returns, DEFAULT_TRADING_PERIOD), None);
+        assert_eq!(sharpe_ratio(&[], DEFAULT_TRADING_PERIOD), None);
+    }
+}
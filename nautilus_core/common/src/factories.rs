@@ -15,16 +15,25 @@
 
 use std::collections::HashMap;
 
-use nautilus_core::{time::AtomicTime, uuid::UUID4};
+use nautilus_core::{
+    time::{AtomicTime, UnixNanos},
+    uuid::UUID4,
+};
 use nautilus_model::{
-    enums::{ContingencyType, OrderSide, TimeInForce},
+    enums::{ContingencyType, OrderSide, TimeInForce, TrailingOffsetType, TriggerType},
     identifiers::{
         client_order_id::ClientOrderId, exec_algorithm_id::ExecAlgorithmId,
         instrument_id::InstrumentId, order_list_id::OrderListId, strategy_id::StrategyId,
         trader_id::TraderId,
     },
-    orders::market::MarketOrder,
-    types::quantity::Quantity,
+    orders::{
+        any::OrderAny, base::OrderCore, limit::LimitOrder, limit_if_touched::LimitIfTouchedOrder,
+        market::MarketOrder, market_if_touched::MarketIfTouchedOrder,
+        market_to_limit::MarketToLimitOrder, stop_limit::StopLimitOrder,
+        stop_market::StopMarketOrder, trailing_stop_limit::TrailingStopLimitOrder,
+        trailing_stop_market::TrailingStopMarketOrder,
+    },
+    types::{price::Price, quantity::Quantity},
 };
 use ustr::Ustr;
 
@@ -105,11 +114,7 @@ impl OrderFactory {
         tags: Option<Ustr>,
     ) -> MarketOrder {
         let client_order_id = self.generate_client_order_id();
-        let exec_spawn_id: Option<ClientOrderId> = if exec_algorithm_id.is_none() {
-            None
-        } else {
-            Some(client_order_id)
-        };
+        let exec_spawn_id = exec_spawn_id(client_order_id, exec_algorithm_id);
         MarketOrder::new(
             self.trader_id,
             self.strategy_id,
@@ -133,6 +138,572 @@ impl OrderFactory {
         )
         .unwrap()
     }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn limit(
+        &mut self,
+        instrument_id: InstrumentId,
+        order_side: OrderSide,
+        quantity: Quantity,
+        price: Price,
+        time_in_force: Option<TimeInForce>,
+        expire_time: Option<UnixNanos>,
+        post_only: Option<bool>,
+        reduce_only: Option<bool>,
+        quote_quantity: Option<bool>,
+        display_qty: Option<Quantity>,
+        emulation_trigger: Option<TriggerType>,
+        trigger_instrument_id: Option<InstrumentId>,
+        exec_algorithm_id: Option<ExecAlgorithmId>,
+        exec_algorithm_params: Option<HashMap<Ustr, Ustr>>,
+        tags: Option<Ustr>,
+    ) -> LimitOrder {
+        let client_order_id = self.generate_client_order_id();
+        let exec_spawn_id = exec_spawn_id(client_order_id, exec_algorithm_id);
+        LimitOrder::new(
+            self.trader_id,
+            self.strategy_id,
+            instrument_id,
+            client_order_id,
+            order_side,
+            quantity,
+            price,
+            time_in_force.unwrap_or(TimeInForce::Gtc),
+            expire_time,
+            post_only.unwrap_or(false),
+            reduce_only.unwrap_or(false),
+            quote_quantity.unwrap_or(false),
+            display_qty,
+            emulation_trigger,
+            trigger_instrument_id,
+            Some(ContingencyType::NoContingency),
+            None,
+            None,
+            None,
+            exec_algorithm_id,
+            exec_algorithm_params,
+            exec_spawn_id,
+            tags,
+            UUID4::new(),
+            self.clock.get_time_ns(),
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn stop_market(
+        &mut self,
+        instrument_id: InstrumentId,
+        order_side: OrderSide,
+        quantity: Quantity,
+        trigger_price: Price,
+        trigger_type: Option<TriggerType>,
+        time_in_force: Option<TimeInForce>,
+        expire_time: Option<UnixNanos>,
+        reduce_only: Option<bool>,
+        quote_quantity: Option<bool>,
+        display_qty: Option<Quantity>,
+        emulation_trigger: Option<TriggerType>,
+        trigger_instrument_id: Option<InstrumentId>,
+        exec_algorithm_id: Option<ExecAlgorithmId>,
+        exec_algorithm_params: Option<HashMap<Ustr, Ustr>>,
+        tags: Option<Ustr>,
+    ) -> StopMarketOrder {
+        let client_order_id = self.generate_client_order_id();
+        let exec_spawn_id = exec_spawn_id(client_order_id, exec_algorithm_id);
+        StopMarketOrder::new(
+            self.trader_id,
+            self.strategy_id,
+            instrument_id,
+            client_order_id,
+            order_side,
+            quantity,
+            trigger_price,
+            trigger_type.unwrap_or(TriggerType::Default),
+            time_in_force.unwrap_or(TimeInForce::Gtc),
+            expire_time,
+            reduce_only.unwrap_or(false),
+            quote_quantity.unwrap_or(false),
+            display_qty,
+            emulation_trigger,
+            trigger_instrument_id,
+            Some(ContingencyType::NoContingency),
+            None,
+            None,
+            None,
+            exec_algorithm_id,
+            exec_algorithm_params,
+            exec_spawn_id,
+            tags,
+            UUID4::new(),
+            self.clock.get_time_ns(),
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn stop_limit(
+        &mut self,
+        instrument_id: InstrumentId,
+        order_side: OrderSide,
+        quantity: Quantity,
+        price: Price,
+        trigger_price: Price,
+        trigger_type: Option<TriggerType>,
+        time_in_force: Option<TimeInForce>,
+        expire_time: Option<UnixNanos>,
+        post_only: Option<bool>,
+        reduce_only: Option<bool>,
+        quote_quantity: Option<bool>,
+        display_qty: Option<Quantity>,
+        emulation_trigger: Option<TriggerType>,
+        trigger_instrument_id: Option<InstrumentId>,
+        exec_algorithm_id: Option<ExecAlgorithmId>,
+        exec_algorithm_params: Option<HashMap<Ustr, Ustr>>,
+        tags: Option<Ustr>,
+    ) -> StopLimitOrder {
+        let client_order_id = self.generate_client_order_id();
+        let exec_spawn_id = exec_spawn_id(client_order_id, exec_algorithm_id);
+        StopLimitOrder::new(
+            self.trader_id,
+            self.strategy_id,
+            instrument_id,
+            client_order_id,
+            order_side,
+            quantity,
+            price,
+            trigger_price,
+            trigger_type.unwrap_or(TriggerType::Default),
+            time_in_force.unwrap_or(TimeInForce::Gtc),
+            expire_time,
+            post_only.unwrap_or(false),
+            reduce_only.unwrap_or(false),
+            quote_quantity.unwrap_or(false),
+            display_qty,
+            emulation_trigger,
+            trigger_instrument_id,
+            Some(ContingencyType::NoContingency),
+            None,
+            None,
+            None,
+            exec_algorithm_id,
+            exec_algorithm_params,
+            exec_spawn_id,
+            tags,
+            UUID4::new(),
+            self.clock.get_time_ns(),
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn market_to_limit(
+        &mut self,
+        instrument_id: InstrumentId,
+        order_side: OrderSide,
+        quantity: Quantity,
+        time_in_force: Option<TimeInForce>,
+        expire_time: Option<UnixNanos>,
+        reduce_only: Option<bool>,
+        quote_quantity: Option<bool>,
+        display_qty: Option<Quantity>,
+        exec_algorithm_id: Option<ExecAlgorithmId>,
+        exec_algorithm_params: Option<HashMap<Ustr, Ustr>>,
+        tags: Option<Ustr>,
+    ) -> MarketToLimitOrder {
+        let client_order_id = self.generate_client_order_id();
+        let exec_spawn_id = exec_spawn_id(client_order_id, exec_algorithm_id);
+        MarketToLimitOrder::new(
+            self.trader_id,
+            self.strategy_id,
+            instrument_id,
+            client_order_id,
+            order_side,
+            quantity,
+            time_in_force.unwrap_or(TimeInForce::Gtc),
+            expire_time,
+            false,
+            reduce_only.unwrap_or(false),
+            quote_quantity.unwrap_or(false),
+            display_qty,
+            Some(ContingencyType::NoContingency),
+            None,
+            None,
+            None,
+            exec_algorithm_id,
+            exec_algorithm_params,
+            exec_spawn_id,
+            tags,
+            UUID4::new(),
+            self.clock.get_time_ns(),
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn market_if_touched(
+        &mut self,
+        instrument_id: InstrumentId,
+        order_side: OrderSide,
+        quantity: Quantity,
+        trigger_price: Price,
+        trigger_type: Option<TriggerType>,
+        time_in_force: Option<TimeInForce>,
+        expire_time: Option<UnixNanos>,
+        reduce_only: Option<bool>,
+        quote_quantity: Option<bool>,
+        emulation_trigger: Option<TriggerType>,
+        trigger_instrument_id: Option<InstrumentId>,
+        exec_algorithm_id: Option<ExecAlgorithmId>,
+        exec_algorithm_params: Option<HashMap<Ustr, Ustr>>,
+        tags: Option<Ustr>,
+    ) -> MarketIfTouchedOrder {
+        let client_order_id = self.generate_client_order_id();
+        let exec_spawn_id = exec_spawn_id(client_order_id, exec_algorithm_id);
+        MarketIfTouchedOrder::new(
+            self.trader_id,
+            self.strategy_id,
+            instrument_id,
+            client_order_id,
+            order_side,
+            quantity,
+            trigger_price,
+            trigger_type.unwrap_or(TriggerType::Default),
+            time_in_force.unwrap_or(TimeInForce::Gtc),
+            expire_time,
+            reduce_only.unwrap_or(false),
+            quote_quantity.unwrap_or(false),
+            None,
+            emulation_trigger,
+            trigger_instrument_id,
+            Some(ContingencyType::NoContingency),
+            None,
+            None,
+            None,
+            exec_algorithm_id,
+            exec_algorithm_params,
+            exec_spawn_id,
+            tags,
+            UUID4::new(),
+            self.clock.get_time_ns(),
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn limit_if_touched(
+        &mut self,
+        instrument_id: InstrumentId,
+        order_side: OrderSide,
+        quantity: Quantity,
+        price: Price,
+        trigger_price: Price,
+        trigger_type: Option<TriggerType>,
+        time_in_force: Option<TimeInForce>,
+        expire_time: Option<UnixNanos>,
+        post_only: Option<bool>,
+        reduce_only: Option<bool>,
+        quote_quantity: Option<bool>,
+        display_qty: Option<Quantity>,
+        emulation_trigger: Option<TriggerType>,
+        trigger_instrument_id: Option<InstrumentId>,
+        exec_algorithm_id: Option<ExecAlgorithmId>,
+        exec_algorithm_params: Option<HashMap<Ustr, Ustr>>,
+        tags: Option<Ustr>,
+    ) -> LimitIfTouchedOrder {
+        let client_order_id = self.generate_client_order_id();
+        let exec_spawn_id = exec_spawn_id(client_order_id, exec_algorithm_id);
+        LimitIfTouchedOrder::new(
+            self.trader_id,
+            self.strategy_id,
+            instrument_id,
+            client_order_id,
+            order_side,
+            quantity,
+            price,
+            trigger_price,
+            trigger_type.unwrap_or(TriggerType::Default),
+            time_in_force.unwrap_or(TimeInForce::Gtc),
+            expire_time,
+            post_only.unwrap_or(false),
+            reduce_only.unwrap_or(false),
+            quote_quantity.unwrap_or(false),
+            display_qty,
+            emulation_trigger,
+            trigger_instrument_id,
+            Some(ContingencyType::NoContingency),
+            None,
+            None,
+            None,
+            exec_algorithm_id,
+            exec_algorithm_params,
+            exec_spawn_id,
+            tags,
+            UUID4::new(),
+            self.clock.get_time_ns(),
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn trailing_stop_market(
+        &mut self,
+        instrument_id: InstrumentId,
+        order_side: OrderSide,
+        quantity: Quantity,
+        trigger_price: Price,
+        trailing_offset: Price,
+        trailing_offset_type: Option<TrailingOffsetType>,
+        trigger_type: Option<TriggerType>,
+        time_in_force: Option<TimeInForce>,
+        expire_time: Option<UnixNanos>,
+        reduce_only: Option<bool>,
+        quote_quantity: Option<bool>,
+        display_qty: Option<Quantity>,
+        emulation_trigger: Option<TriggerType>,
+        trigger_instrument_id: Option<InstrumentId>,
+        exec_algorithm_id: Option<ExecAlgorithmId>,
+        exec_algorithm_params: Option<HashMap<Ustr, Ustr>>,
+        tags: Option<Ustr>,
+    ) -> TrailingStopMarketOrder {
+        let client_order_id = self.generate_client_order_id();
+        let exec_spawn_id = exec_spawn_id(client_order_id, exec_algorithm_id);
+        TrailingStopMarketOrder::new(
+            self.trader_id,
+            self.strategy_id,
+            instrument_id,
+            client_order_id,
+            order_side,
+            quantity,
+            trigger_price,
+            trigger_type.unwrap_or(TriggerType::Default),
+            trailing_offset,
+            trailing_offset_type.unwrap_or(TrailingOffsetType::Price),
+            time_in_force.unwrap_or(TimeInForce::Gtc),
+            expire_time,
+            reduce_only.unwrap_or(false),
+            quote_quantity.unwrap_or(false),
+            display_qty,
+            emulation_trigger,
+            trigger_instrument_id,
+            Some(ContingencyType::NoContingency),
+            None,
+            None,
+            None,
+            exec_algorithm_id,
+            exec_algorithm_params,
+            exec_spawn_id,
+            tags,
+            UUID4::new(),
+            self.clock.get_time_ns(),
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn trailing_stop_limit(
+        &mut self,
+        instrument_id: InstrumentId,
+        order_side: OrderSide,
+        quantity: Quantity,
+        price: Price,
+        trigger_price: Price,
+        limit_offset: Price,
+        trailing_offset: Price,
+        trailing_offset_type: Option<TrailingOffsetType>,
+        trigger_type: Option<TriggerType>,
+        time_in_force: Option<TimeInForce>,
+        expire_time: Option<UnixNanos>,
+        post_only: Option<bool>,
+        reduce_only: Option<bool>,
+        quote_quantity: Option<bool>,
+        display_qty: Option<Quantity>,
+        emulation_trigger: Option<TriggerType>,
+        trigger_instrument_id: Option<InstrumentId>,
+        exec_algorithm_id: Option<ExecAlgorithmId>,
+        exec_algorithm_params: Option<HashMap<Ustr, Ustr>>,
+        tags: Option<Ustr>,
+    ) -> TrailingStopLimitOrder {
+        let client_order_id = self.generate_client_order_id();
+        let exec_spawn_id = exec_spawn_id(client_order_id, exec_algorithm_id);
+        TrailingStopLimitOrder::new(
+            self.trader_id,
+            self.strategy_id,
+            instrument_id,
+            client_order_id,
+            order_side,
+            quantity,
+            price,
+            trigger_price,
+            trigger_type.unwrap_or(TriggerType::Default),
+            limit_offset,
+            trailing_offset,
+            trailing_offset_type.unwrap_or(TrailingOffsetType::Price),
+            time_in_force.unwrap_or(TimeInForce::Gtc),
+            expire_time,
+            post_only.unwrap_or(false),
+            reduce_only.unwrap_or(false),
+            quote_quantity.unwrap_or(false),
+            display_qty,
+            emulation_trigger,
+            trigger_instrument_id,
+            Some(ContingencyType::NoContingency),
+            None,
+            None,
+            None,
+            exec_algorithm_id,
+            exec_algorithm_params,
+            exec_spawn_id,
+            tags,
+            UUID4::new(),
+            self.clock.get_time_ns(),
+        )
+    }
+
+    /// Creates a bracket order list of an entry order with a stop-loss and take-profit.
+    ///
+    /// The entry is a `MARKET` order, or a `LIMIT` order if an `entry_price` is given. The
+    /// entry triggers the contingent orders (OTO), which are a `STOP_MARKET` stop-loss and a
+    /// `LIMIT` take-profit on the opposite side, each canceling the other (OCO). All three
+    /// orders share the same order list ID, and are returned in the order entry, stop-loss,
+    /// take-profit.
+    #[allow(clippy::too_many_arguments)]
+    pub fn bracket(
+        &mut self,
+        instrument_id: InstrumentId,
+        order_side: OrderSide,
+        quantity: Quantity,
+        entry_price: Option<Price>,
+        sl_trigger_price: Price,
+        tp_price: Price,
+        sl_trigger_type: Option<TriggerType>,
+        time_in_force: Option<TimeInForce>,
+        tp_post_only: Option<bool>,
+        emulation_trigger: Option<TriggerType>,
+        tags: Option<Ustr>,
+    ) -> Vec<OrderAny> {
+        let order_list_id = self.generate_order_list_id();
+        let entry_client_order_id = self.generate_client_order_id();
+        let sl_client_order_id = self.generate_client_order_id();
+        let tp_client_order_id = self.generate_client_order_id();
+        let ts_init = self.clock.get_time_ns();
+        let contingent_side = OrderCore::opposite_side(order_side);
+
+        let entry_linked_ids = Some(vec![sl_client_order_id, tp_client_order_id]);
+        let entry: OrderAny = match entry_price {
+            Some(price) => LimitOrder::new(
+                self.trader_id,
+                self.strategy_id,
+                instrument_id,
+                entry_client_order_id,
+                order_side,
+                quantity,
+                price,
+                time_in_force.unwrap_or(TimeInForce::Gtc),
+                None,
+                false,
+                false,
+                false,
+                None,
+                emulation_trigger,
+                None,
+                Some(ContingencyType::Oto),
+                Some(order_list_id),
+                entry_linked_ids,
+                None,
+                None,
+                None,
+                None,
+                tags,
+                UUID4::new(),
+                ts_init,
+            )
+            .into(),
+            None => MarketOrder::new(
+                self.trader_id,
+                self.strategy_id,
+                instrument_id,
+                entry_client_order_id,
+                order_side,
+                quantity,
+                time_in_force.unwrap_or(TimeInForce::Gtc),
+                UUID4::new(),
+                ts_init,
+                false,
+                false,
+                Some(ContingencyType::Oto),
+                Some(order_list_id),
+                entry_linked_ids,
+                None,
+                None,
+                None,
+                None,
+                tags,
+            )
+            .unwrap()
+            .into(),
+        };
+
+        let stop_loss = StopMarketOrder::new(
+            self.trader_id,
+            self.strategy_id,
+            instrument_id,
+            sl_client_order_id,
+            contingent_side,
+            quantity,
+            sl_trigger_price,
+            sl_trigger_type.unwrap_or(TriggerType::Default),
+            TimeInForce::Gtc,
+            None,
+            true,
+            false,
+            None,
+            emulation_trigger,
+            None,
+            Some(ContingencyType::Oco),
+            Some(order_list_id),
+            Some(vec![tp_client_order_id]),
+            Some(entry_client_order_id),
+            None,
+            None,
+            None,
+            tags,
+            UUID4::new(),
+            ts_init,
+        );
+
+        let take_profit = LimitOrder::new(
+            self.trader_id,
+            self.strategy_id,
+            instrument_id,
+            tp_client_order_id,
+            contingent_side,
+            quantity,
+            tp_price,
+            TimeInForce::Gtc,
+            None,
+            tp_post_only.unwrap_or(true),
+            true,
+            false,
+            None,
+            emulation_trigger,
+            None,
+            Some(ContingencyType::Oco),
+            Some(order_list_id),
+            Some(vec![sl_client_order_id]),
+            Some(entry_client_order_id),
+            None,
+            None,
+            None,
+            tags,
+            UUID4::new(),
+            ts_init,
+        );
+
+        vec![entry, stop_loss.into(), take_profit.into()]
+    }
+}
+
+/// Returns the spawn ID for an order, which is its own client order ID when the order is
+/// to be executed by an execution algorithm.
+fn exec_spawn_id(
+    client_order_id: ClientOrderId,
+    exec_algorithm_id: Option<ExecAlgorithmId>,
+) -> Option<ClientOrderId> {
+    exec_algorithm_id.map(|_| client_order_id)
 }
 
 ////////////////////////////////////////////////////////////////////////////////
@@ -141,10 +712,15 @@ impl OrderFactory {
 #[cfg(test)]
 pub mod tests {
     use nautilus_model::{
-        enums::{OrderSide, TimeInForce},
+        enums::{
+            ContingencyType, OrderSide, OrderType, TimeInForce, TrailingOffsetType, TriggerType,
+        },
         identifiers::{
-            client_order_id::ClientOrderId, instrument_id::InstrumentId, order_list_id::OrderListId,
+            client_order_id::ClientOrderId, exec_algorithm_id::ExecAlgorithmId,
+            instrument_id::InstrumentId, order_list_id::OrderListId,
         },
+        orders::any::OrderAny,
+        types::price::Price,
     };
     use rstest::rstest;
 
@@ -234,4 +810,179 @@ pub mod tests {
         );
         assert_eq!(market_order.order_list_id, None);
     }
+
+    #[rstest]
+    fn test_limit_order(mut order_factory: OrderFactory) {
+        let limit_order = order_factory.limit(
+            InstrumentId::from("BTCUSDT.BINANCE"),
+            OrderSide::Sell,
+            100.into(),
+            Price::from("50000.00"),
+            Some(TimeInForce::Day),
+            None,
+            Some(true),
+            None,
+            None,
+            Some(10.into()),
+            Some(TriggerType::BidAsk),
+            None,
+            Some(ExecAlgorithmId::from("TWAP")),
+            None,
+            None,
+        );
+        assert_eq!(limit_order.side, OrderSide::Sell);
+        assert_eq!(limit_order.order_type, OrderType::Limit);
+        assert_eq!(limit_order.price, Price::from("50000.00"));
+        assert_eq!(limit_order.time_in_force, TimeInForce::Day);
+        assert!(limit_order.is_post_only);
+        assert_eq!(limit_order.display_qty, Some(10.into()));
+        assert_eq!(limit_order.emulation_trigger, Some(TriggerType::BidAsk));
+        assert_eq!(
+            limit_order.exec_spawn_id,
+            Some(ClientOrderId::new("O-19700101-0000-001-001-1").unwrap())
+        );
+        assert_eq!(
+            limit_order.contingency_type,
+            Some(ContingencyType::NoContingency)
+        );
+    }
+
+    #[rstest]
+    fn test_stop_limit_order(mut order_factory: OrderFactory) {
+        let stop_limit_order = order_factory.stop_limit(
+            InstrumentId::from("BTCUSDT.BINANCE"),
+            OrderSide::Buy,
+            100.into(),
+            Price::from("50100.00"),
+            Price::from("50000.00"),
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+        );
+        assert_eq!(stop_limit_order.order_type, OrderType::StopLimit);
+        assert_eq!(stop_limit_order.price, Price::from("50100.00"));
+        assert_eq!(stop_limit_order.trigger_price, Price::from("50000.00"));
+        assert_eq!(stop_limit_order.trigger_type, TriggerType::Default);
+        assert_eq!(stop_limit_order.time_in_force, TimeInForce::Gtc);
+        assert_eq!(stop_limit_order.exec_spawn_id, None);
+    }
+
+    #[rstest]
+    fn test_trailing_stop_market_order(mut order_factory: OrderFactory) {
+        let trailing_order = order_factory.trailing_stop_market(
+            InstrumentId::from("BTCUSDT.BINANCE"),
+            OrderSide::Sell,
+            100.into(),
+            Price::from("49000.00"),
+            Price::from("100"),
+            Some(TrailingOffsetType::BasisPoints),
+            Some(TriggerType::LastTrade),
+            None,
+            None,
+            Some(true),
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+        );
+        assert_eq!(trailing_order.order_type, OrderType::TrailingStopMarket);
+        assert_eq!(trailing_order.trigger_price, Price::from("49000.00"));
+        assert_eq!(trailing_order.trailing_offset, Price::from("100"));
+        assert_eq!(
+            trailing_order.trailing_offset_type,
+            TrailingOffsetType::BasisPoints
+        );
+        assert_eq!(trailing_order.trigger_type, TriggerType::LastTrade);
+        assert!(trailing_order.is_reduce_only);
+    }
+
+    #[rstest]
+    fn test_bracket_order_with_market_entry(mut order_factory: OrderFactory) {
+        let orders = order_factory.bracket(
+            InstrumentId::from("BTCUSDT.BINANCE"),
+            OrderSide::Buy,
+            100.into(),
+            None,
+            Price::from("49000.00"),
+            Price::from("51000.00"),
+            None,
+            None,
+            None,
+            None,
+            None,
+        );
+        let order_list_id = OrderListId::new("OL-19700101-0000-001-001-1").unwrap();
+        let entry_id = ClientOrderId::new("O-19700101-0000-001-001-1").unwrap();
+        let sl_id = ClientOrderId::new("O-19700101-0000-001-001-2").unwrap();
+        let tp_id = ClientOrderId::new("O-19700101-0000-001-001-3").unwrap();
+        let (entry, stop_loss, take_profit) = (&orders[0], &orders[1], &orders[2]);
+
+        assert_eq!(orders.len(), 3);
+        assert!(matches!(entry, OrderAny::Market(_)));
+        assert!(matches!(stop_loss, OrderAny::StopMarket(_)));
+        assert!(matches!(take_profit, OrderAny::Limit(_)));
+        assert_eq!(entry.client_order_id(), entry_id);
+        assert_eq!(entry.core().contingency_type, Some(ContingencyType::Oto));
+        assert_eq!(entry.core().linked_order_ids, Some(vec![sl_id, tp_id]));
+        assert_eq!(stop_loss.client_order_id(), sl_id);
+        assert_eq!(stop_loss.side(), OrderSide::Sell);
+        assert_eq!(stop_loss.trigger_price(), Some(Price::from("49000.00")));
+        assert_eq!(
+            stop_loss.core().contingency_type,
+            Some(ContingencyType::Oco)
+        );
+        assert_eq!(stop_loss.core().linked_order_ids, Some(vec![tp_id]));
+        assert_eq!(stop_loss.core().parent_order_id, Some(entry_id));
+        assert_eq!(take_profit.client_order_id(), tp_id);
+        assert_eq!(take_profit.side(), OrderSide::Sell);
+        assert_eq!(take_profit.price(), Some(Price::from("51000.00")));
+        assert_eq!(take_profit.core().linked_order_ids, Some(vec![sl_id]));
+        assert_eq!(take_profit.core().parent_order_id, Some(entry_id));
+        assert!(orders
+            .iter()
+            .all(|order| order.core().order_list_id == Some(order_list_id)));
+    }
+
+    #[rstest]
+    fn test_bracket_order_with_limit_entry(mut order_factory: OrderFactory) {
+        let orders = order_factory.bracket(
+            InstrumentId::from("BTCUSDT.BINANCE"),
+            OrderSide::Sell,
+            100.into(),
+            Some(Price::from("50000.00")),
+            Price::from("51000.00"),
+            Price::from("49000.00"),
+            Some(TriggerType::BidAsk),
+            Some(TimeInForce::Day),
+            Some(false),
+            None,
+            None,
+        );
+        let entry = &orders[0];
+        let stop_loss = &orders[1];
+        let take_profit = &orders[2];
+
+        assert!(matches!(entry, OrderAny::Limit(_)));
+        assert_eq!(entry.price(), Some(Price::from("50000.00")));
+        assert_eq!(entry.core().time_in_force, TimeInForce::Day);
+        assert_eq!(stop_loss.side(), OrderSide::Buy);
+        assert_eq!(
+            stop_loss.as_order().trigger_type(),
+            Some(TriggerType::BidAsk)
+        );
+        assert!(!take_profit.as_order().is_post_only());
+        assert!(take_profit.as_order().is_reduce_only());
+    }
 }
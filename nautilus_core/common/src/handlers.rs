@@ -16,6 +16,7 @@
 use std::{fmt, sync::Arc};
 
 use nautilus_core::message::Message;
+use nautilus_model::data::Data;
 use pyo3::{ffi, prelude::*};
 use ustr::Ustr;
 
@@ -51,6 +52,15 @@ pub struct SafeTimeEventCallback {
 unsafe impl Send for SafeTimeEventCallback {}
 unsafe impl Sync for SafeTimeEventCallback {}
 
+// Note: Intended to be used on a single thread (such as a backtest event loop)
+#[derive(Clone)]
+pub struct SafeDataCallback {
+    pub callback: Arc<dyn Fn(&Data)>,
+}
+
+unsafe impl Send for SafeDataCallback {}
+unsafe impl Sync for SafeDataCallback {}
+
 // TODO: Make this more generic
 #[derive(Clone)]
 #[cfg_attr(
@@ -60,6 +70,7 @@ unsafe impl Sync for SafeTimeEventCallback {}
 pub struct MessageHandler {
     pub handler_id: Ustr,
     _callback: Option<SafeMessageCallback>,
+    data_callback: Option<SafeDataCallback>,
 }
 
 impl MessageHandler {
@@ -69,6 +80,24 @@ impl MessageHandler {
         Self {
             handler_id,
             _callback: callback,
+            data_callback: None,
+        }
+    }
+
+    /// Initializes a new `MessageHandler` which handles published data.
+    #[must_use]
+    pub fn with_data_callback(handler_id: Ustr, callback: SafeDataCallback) -> Self {
+        Self {
+            handler_id,
+            _callback: None,
+            data_callback: Some(callback),
+        }
+    }
+
+    /// Handles the given `data` (if the handler has a data callback).
+    pub fn handle_data(&self, data: &Data) {
+        if let Some(data_callback) = &self.data_callback {
+            (data_callback.callback)(data);
         }
     }
 }
@@ -23,7 +23,7 @@ use std::{
 
 use indexmap::IndexMap;
 use nautilus_core::uuid::UUID4;
-use nautilus_model::identifiers::trader_id::TraderId;
+use nautilus_model::{data::Data, identifiers::trader_id::TraderId};
 use serde::{Deserialize, Serialize};
 use serde_json;
 use ustr::Ustr;
@@ -391,6 +391,22 @@ impl MessageBus {
         })
     }
 
+    /// Publishes the given `data` to all handlers subscribed to a pattern matching `topic`,
+    /// in priority order.
+    pub fn publish_data(&mut self, topic: &Ustr, data: &Data) {
+        let mut subs: Vec<&Subscription> = self
+            .subscriptions
+            .keys()
+            .filter(|sub| is_matching(topic, &sub.topic))
+            .collect();
+        subs.sort();
+
+        for sub in subs {
+            sub.handler.handle_data(data);
+        }
+        self.pub_count += 1;
+    }
+
     pub fn publish_external(&self, topic: String, payload: Vec<u8>) {
         if let Some(tx) = &self.tx {
             let msg = BusMessage { topic, payload };
@@ -424,6 +440,27 @@ impl MessageBus {
     }
 }
 
+/// Returns the topic `data` is published on.
+///
+/// Topics are of the form `data.quotes.{venue}.{symbol}`, and `data.bars.{bar_type}` for bars.
+#[must_use]
+pub fn get_data_topic(data: &Data) -> Ustr {
+    let (category, instrument_id) = match data {
+        Data::Delta(delta) => ("book.deltas", delta.instrument_id),
+        Data::Depth10(depth) => ("book.depth", depth.instrument_id),
+        Data::Quote(quote) => ("quotes", quote.instrument_id),
+        Data::Trade(trade) => ("trades", trade.instrument_id),
+        Data::Bar(bar) => return Ustr::from(format!("data.bars.{}", bar.bar_type).as_str()),
+    };
+    Ustr::from(
+        format!(
+            "data.{category}.{}.{}",
+            instrument_id.venue, instrument_id.symbol
+        )
+        .as_str(),
+    )
+}
+
 /// Match a topic and a string pattern
 /// pattern can contains -
 /// '*' - match 0 or more characters after this
@@ -461,13 +498,14 @@ pub fn is_matching(topic: &Ustr, pattern: &Ustr) -> bool {
 ////////////////////////////////////////////////////////////////////////////////
 #[cfg(test)]
 mod tests {
-    use std::sync::Arc;
+    use std::{cell::RefCell, rc::Rc, sync::Arc};
 
     use nautilus_core::{message::Message, uuid::UUID4};
+    use nautilus_model::data::quote::{stubs::quote_tick_ethusdt_binance, QuoteTick};
     use rstest::*;
 
     use super::*;
-    use crate::handlers::{MessageHandler, SafeMessageCallback};
+    use crate::handlers::{MessageHandler, SafeDataCallback, SafeMessageCallback};
 
     fn stub_msgbus() -> MessageBus {
         MessageBus::new(TraderId::from("trader-001"), UUID4::new(), None, None)
@@ -575,6 +613,34 @@ mod tests {
         assert_eq!(msgbus.topics(), vec![topic]);
     }
 
+    #[rstest]
+    fn test_get_data_topic(quote_tick_ethusdt_binance: QuoteTick) {
+        let topic = get_data_topic(&Data::Quote(quote_tick_ethusdt_binance));
+        assert_eq!(topic, Ustr::from("data.quotes.BINANCE.ETHUSDT-PERP"));
+    }
+
+    #[rstest]
+    fn test_publish_data_to_matching_subscribers(quote_tick_ethusdt_binance: QuoteTick) {
+        let mut msgbus = stub_msgbus();
+        let received = Rc::new(RefCell::new(Vec::new()));
+
+        let received_clone = received.clone();
+        let callback = SafeDataCallback {
+            callback: Arc::new(move |data: &Data| {
+                received_clone.borrow_mut().push(data.clone());
+            }),
+        };
+        let handler = MessageHandler::with_data_callback(Ustr::from("1"), callback);
+        msgbus.subscribe("data.quotes.BINANCE.*", handler, None);
+
+        let data = Data::Quote(quote_tick_ethusdt_binance);
+        msgbus.publish_data(&get_data_topic(&data), &data);
+        msgbus.publish_data(&Ustr::from("data.trades.BINANCE.ETHUSDT-PERP"), &data);
+
+        assert_eq!(received.borrow().len(), 1);
+        assert_eq!(msgbus.pub_count, 2);
+    }
+
     #[rstest]
     fn test_unsubscribe() {
         let mut msgbus = stub_msgbus();
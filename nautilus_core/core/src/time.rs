@@ -96,7 +96,7 @@ impl Default for AtomicTime {
 impl AtomicTime {
     /// New atomic clock set with the given UNIX time (nanoseconds).
     #[must_use]
-    pub fn new(realtime: bool, time: UnixNanos) -> Self {
+    pub const fn new(realtime: bool, time: UnixNanos) -> Self {
         Self {
             realtime: AtomicBool::new(realtime),
             timestamp_ns: AtomicU64::new(time),
@@ -20,8 +20,9 @@ use std::{
     str::FromStr,
 };
 
+use rand::RngCore;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
-use uuid::Uuid;
+use uuid::{Builder, Uuid};
 
 /// Represents a pseudo-random UUID (universally unique identifier)
 /// version 4 based on a 128-bit label as specified in RFC 4122.
@@ -38,7 +39,19 @@ pub struct UUID4 {
 impl UUID4 {
     #[must_use]
     pub fn new() -> Self {
-        let uuid = Uuid::new_v4();
+        Self::from_uuid(Uuid::new_v4())
+    }
+
+    /// Creates a new [`UUID4`] from the random bytes of the given `rng`, so that a
+    /// seeded generator produces a reproducible sequence of UUIDs.
+    #[must_use]
+    pub fn from_rng<R: RngCore + ?Sized>(rng: &mut R) -> Self {
+        let mut bytes = [0; 16];
+        rng.fill_bytes(&mut bytes);
+        Self::from_uuid(Builder::from_random_bytes(bytes).into_uuid())
+    }
+
+    fn from_uuid(uuid: Uuid) -> Self {
         let c_string = CString::new(uuid.to_string()).expect("`CString` conversion failed");
         let bytes = c_string.as_bytes_with_nul();
         let mut value = [0; 37];
@@ -111,6 +124,7 @@ impl<'de> Deserialize<'de> for UUID4 {
 ////////////////////////////////////////////////////////////////////////////////
 #[cfg(test)]
 mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
     use rstest::*;
     use uuid;
 
@@ -158,4 +172,17 @@ mod tests {
         let result_string = format!("{uuid}");
         assert_eq!(result_string, uuid_string);
     }
+
+    #[rstest]
+    fn test_uuid4_from_seeded_rng_is_reproducible() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let first = (UUID4::from_rng(&mut rng), UUID4::from_rng(&mut rng));
+        let mut rng = StdRng::seed_from_u64(42);
+        let second = (UUID4::from_rng(&mut rng), UUID4::from_rng(&mut rng));
+
+        assert_eq!(first, second);
+        assert_ne!(first.0, first.1);
+        let uuid_parsed = Uuid::parse_str(&first.0.to_string()).unwrap();
+        assert_eq!(uuid_parsed.get_version().unwrap(), uuid::Version::Random);
+    }
 }
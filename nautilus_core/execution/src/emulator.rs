@@ -0,0 +1,1180 @@
+// -------------------------------------------------------------------------------------------------
+//  Copyright (C) 2015-2024 Nautech Systems Pty Ltd. All rights reserved.
+//  https://nautechsystems.io
+//
+//  Licensed under the GNU Lesser General Public License Version 3.0 (the "License");
+//  You may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at https://www.gnu.org/licenses/lgpl-3.0.en.html
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+// -------------------------------------------------------------------------------------------------
+
+use std::collections::HashSet;
+
+use anyhow::{anyhow, bail, Result};
+use indexmap::IndexMap;
+use nautilus_core::{
+    time::{AtomicTime, UnixNanos},
+    uuid::UUID4,
+};
+use nautilus_model::{
+    data::{quote::QuoteTick, trade::TradeTick},
+    enums::{OrderSide, OrderType, TimeInForce, TrailingOffsetType, TriggerType},
+    events::order::{
+        canceled::OrderCanceled, emulated::OrderEmulated, event::OrderEvent,
+        released::OrderReleased, updated::OrderUpdated,
+    },
+    identifiers::{client_order_id::ClientOrderId, instrument_id::InstrumentId},
+    orders::{any::OrderAny, base::Order, limit::LimitOrder, market::MarketOrder},
+    types::price::Price,
+};
+
+use crate::{
+    matching_core::OrderMatchingCore,
+    messages::{
+        cancel::CancelOrder, cancel_all::CancelAllOrders, modify::ModifyOrder, TradingCommand,
+    },
+    trailing::trailing_stop_calculate_with_price_tiers,
+};
+
+const EMULATED_ORDER_TYPES: &[OrderType] = &[
+    OrderType::Limit,
+    OrderType::StopMarket,
+    OrderType::StopLimit,
+    OrderType::MarketIfTouched,
+    OrderType::LimitIfTouched,
+    OrderType::TrailingStopMarket,
+    OrderType::TrailingStopLimit,
+];
+
+/// Provides order emulation for order types which are not natively supported by a venue.
+///
+/// Emulated orders are held locally with an `EMULATED` status, and their trigger conditions
+/// are evaluated against the market data of their trigger instrument: the last trade price
+/// for a `LAST_TRADE` emulation trigger, otherwise the bid and ask. Once triggered (or
+/// matched, for limit orders) an order is transformed and released, either as a market order
+/// or as a limit order at its limit price. The resulting `SubmitOrder` commands and order
+/// events are drained with [`OrderEmulator::drain_commands`] and
+/// [`OrderEmulator::drain_events`].
+pub struct OrderEmulator {
+    clock: &'static AtomicTime,
+    matching_cores: IndexMap<InstrumentId, OrderMatchingCore>,
+    orders: IndexMap<ClientOrderId, OrderAny>,
+    subscribed_quotes: HashSet<InstrumentId>,
+    subscribed_trades: HashSet<InstrumentId>,
+    commands: Vec<TradingCommand>,
+    events: Vec<OrderEvent>,
+}
+
+impl OrderEmulator {
+    /// Initializes a new `OrderEmulator` instance.
+    #[must_use]
+    pub fn new(clock: &'static AtomicTime) -> Self {
+        Self {
+            clock,
+            matching_cores: IndexMap::new(),
+            orders: IndexMap::new(),
+            subscribed_quotes: HashSet::new(),
+            subscribed_trades: HashSet::new(),
+            commands: Vec::new(),
+            events: Vec::new(),
+        }
+    }
+
+    /// Creates a matching core for the given trigger instrument, if one does not already exist.
+    pub fn create_matching_core(
+        &mut self,
+        instrument_id: InstrumentId,
+        price_increment: Price,
+    ) -> &mut OrderMatchingCore {
+        self.matching_cores
+            .entry(instrument_id)
+            .or_insert_with(|| OrderMatchingCore::new(instrument_id, price_increment))
+    }
+
+    #[must_use]
+    pub fn get_matching_core(&self, instrument_id: &InstrumentId) -> Option<&OrderMatchingCore> {
+        self.matching_cores.get(instrument_id)
+    }
+
+    /// Returns the instruments for which quotes are required to evaluate emulated orders.
+    #[must_use]
+    pub fn subscribed_quotes(&self) -> Vec<InstrumentId> {
+        self.subscribed_quotes.iter().copied().collect()
+    }
+
+    /// Returns the instruments for which trades are required to evaluate emulated orders.
+    #[must_use]
+    pub fn subscribed_trades(&self) -> Vec<InstrumentId> {
+        self.subscribed_trades.iter().copied().collect()
+    }
+
+    #[must_use]
+    pub fn get_order(&self, client_order_id: &ClientOrderId) -> Option<&OrderAny> {
+        self.orders.get(client_order_id)
+    }
+
+    /// Returns the orders currently held by the emulator.
+    #[must_use]
+    pub fn get_emulated_orders(&self) -> Vec<&OrderAny> {
+        self.orders.values().collect()
+    }
+
+    /// Drain the commands for released orders generated since the last call.
+    pub fn drain_commands(&mut self) -> Vec<TradingCommand> {
+        std::mem::take(&mut self.commands)
+    }
+
+    /// Drain the order events generated since the last call, in the order they occurred.
+    pub fn drain_events(&mut self) -> Vec<OrderEvent> {
+        std::mem::take(&mut self.events)
+    }
+
+    // -- COMMANDS --------------------------------------------------------------------------------
+
+    /// Starts emulating the given `order`, which is held until its trigger condition is met.
+    ///
+    /// # Errors
+    ///
+    /// If the order has no emulation trigger, its order type, emulation trigger or trailing
+    /// offset type cannot be emulated, or there is no matching core for its trigger instrument.
+    /// `PRICE_TIER` trailing offsets can only be emulated once the price tiers of the trigger
+    /// instrument have been set on its matching core.
+    pub fn handle_submit_order(&mut self, mut order: OrderAny) -> Result<()> {
+        let client_order_id = order.client_order_id();
+        let order_type = order.order_type();
+        if !EMULATED_ORDER_TYPES.contains(&order_type) {
+            bail!("Cannot emulate order {client_order_id}, `OrderType` {order_type} not supported");
+        }
+
+        let emulation_trigger = match order.as_order().emulation_trigger() {
+            Some(TriggerType::NoTrigger) | None => {
+                bail!("Cannot emulate order {client_order_id}, no `emulation_trigger`")
+            }
+            Some(emulation_trigger) => emulation_trigger,
+        };
+        if !matches!(
+            emulation_trigger,
+            TriggerType::Default | TriggerType::BidAsk | TriggerType::LastTrade
+        ) {
+            bail!(
+                "Cannot emulate order {client_order_id}, `TriggerType` {emulation_trigger} not supported"
+            );
+        }
+
+        let trigger_instrument_id = trigger_instrument_id(&order);
+        let Some(core) = self.matching_cores.get(&trigger_instrument_id) else {
+            bail!(
+                "Cannot emulate order {client_order_id}, no matching core for {trigger_instrument_id}"
+            );
+        };
+
+        if let Some(offset_type) = order.as_order().trailing_offset_type() {
+            let is_supported = match offset_type {
+                TrailingOffsetType::Price
+                | TrailingOffsetType::BasisPoints
+                | TrailingOffsetType::Ticks => true,
+                TrailingOffsetType::PriceTier => !core.price_tiers.is_empty(),
+                TrailingOffsetType::NoTrailingOffset => false,
+            };
+            if !is_supported {
+                bail!(
+                    "Cannot emulate order {client_order_id}, `TrailingOffsetType` {offset_type} not supported"
+                );
+            }
+        }
+
+        match emulation_trigger {
+            TriggerType::LastTrade => self.subscribed_trades.insert(trigger_instrument_id),
+            _ => self.subscribed_quotes.insert(trigger_instrument_id),
+        };
+
+        let ts_now = self.clock.get_time_ns();
+        let emulated = OrderEmulated::new(
+            order.as_order().trader_id(),
+            order.strategy_id(),
+            order.instrument_id(),
+            client_order_id,
+            UUID4::new(),
+            ts_now,
+            ts_now,
+        )?;
+        let event = OrderEvent::OrderEmulated(emulated);
+        order.apply(event.clone())?;
+        self.events.push(event);
+        self.orders.insert(client_order_id, order);
+
+        if is_trailing_stop(order_type) {
+            self.update_trailing_stop(client_order_id)?;
+        }
+
+        Ok(())
+    }
+
+    /// Modifies an emulated order, which is then released if its trigger condition is met
+    /// at the new prices.
+    ///
+    /// # Errors
+    ///
+    /// If the order is not held by the emulator, the modified prices do not apply to the
+    /// order type, or the resulting order events cannot be applied.
+    pub fn handle_modify_order(&mut self, command: &ModifyOrder) -> Result<()> {
+        let client_order_id = command.client_order_id;
+        let Some(order) = self.orders.get(&client_order_id) else {
+            bail!("Cannot modify order {client_order_id}, not found in emulator");
+        };
+        if command.price.is_some() && order.price().is_none() {
+            bail!("Cannot modify order {client_order_id}, order has no `price`");
+        }
+        if command.trigger_price.is_some() && order.trigger_price().is_none() {
+            bail!("Cannot modify order {client_order_id}, order has no `trigger_price`");
+        }
+
+        let ts_now = self.clock.get_time_ns();
+        let updated = OrderUpdated::new(
+            order.as_order().trader_id(),
+            order.strategy_id(),
+            order.instrument_id(),
+            client_order_id,
+            command.quantity.unwrap_or_else(|| order.quantity()),
+            UUID4::new(),
+            ts_now,
+            ts_now,
+            false,
+            None,
+            None,
+            command.price,
+            command.trigger_price,
+        )?;
+        self.apply_event(OrderEvent::OrderUpdated(updated))?;
+        self.check_order(client_order_id)?;
+
+        Ok(())
+    }
+
+    /// Cancels an emulated order.
+    ///
+    /// # Errors
+    ///
+    /// If the order is not held by the emulator, or it cannot be canceled.
+    pub fn handle_cancel_order(&mut self, command: &CancelOrder) -> Result<()> {
+        let client_order_id = command.client_order_id;
+        if !self.orders.contains_key(&client_order_id) {
+            bail!("Cannot cancel order {client_order_id}, not found in emulator");
+        }
+        self.cancel_order(client_order_id)
+    }
+
+    /// Cancels all emulated orders of the strategy for the instrument, filtered by side
+    /// unless the command side is `NO_ORDER_SIDE`.
+    ///
+    /// # Errors
+    ///
+    /// If any of the orders cannot be canceled, in which case the orders after it are left
+    /// emulated.
+    pub fn handle_cancel_all_orders(&mut self, command: &CancelAllOrders) -> Result<()> {
+        let client_order_ids: Vec<ClientOrderId> = self
+            .orders
+            .values()
+            .filter(|order| {
+                order.instrument_id() == command.instrument_id
+                    && order.strategy_id() == command.strategy_id
+                    && (command.order_side == OrderSide::NoOrderSide
+                        || order.side() == command.order_side)
+            })
+            .map(OrderAny::client_order_id)
+            .collect();
+
+        for client_order_id in client_order_ids {
+            self.cancel_order(client_order_id)?;
+        }
+
+        Ok(())
+    }
+
+    // -- DATA PROCESSING -------------------------------------------------------------------------
+
+    /// Updates the bid and ask of the trigger instrument, then evaluates its emulated orders.
+    ///
+    /// # Errors
+    ///
+    /// If the order events generated for a triggered or trailed order cannot be applied.
+    pub fn handle_quote_tick(&mut self, quote: &QuoteTick) -> Result<()> {
+        let Some(core) = self.matching_cores.get_mut(&quote.instrument_id) else {
+            return Ok(());
+        };
+        core.handle_quote_tick(quote);
+        self.iterate(quote.instrument_id)
+    }
+
+    /// Updates the last price of the trigger instrument, then evaluates its emulated orders.
+    ///
+    /// # Errors
+    ///
+    /// If the order events generated for a triggered or trailed order cannot be applied.
+    pub fn handle_trade_tick(&mut self, trade: &TradeTick) -> Result<()> {
+        let Some(core) = self.matching_cores.get_mut(&trade.instrument_id) else {
+            return Ok(());
+        };
+        core.handle_trade_tick(trade);
+        self.iterate(trade.instrument_id)
+    }
+
+    fn iterate(&mut self, instrument_id: InstrumentId) -> Result<()> {
+        let client_order_ids: Vec<ClientOrderId> = self
+            .orders
+            .values()
+            .filter(|order| trigger_instrument_id(order) == instrument_id)
+            .map(OrderAny::client_order_id)
+            .collect();
+
+        for client_order_id in client_order_ids {
+            if self.check_order(client_order_id)? {
+                continue;
+            }
+            if is_trailing_stop(self.orders[&client_order_id].order_type()) {
+                self.update_trailing_stop(client_order_id)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Releases the order if its trigger condition is met, returning whether it was released.
+    fn check_order(&mut self, client_order_id: ClientOrderId) -> Result<bool> {
+        let order = &self.orders[&client_order_id];
+        let core = &self.matching_cores[&trigger_instrument_id(order)];
+
+        // Match against the prices of the order's own trigger source
+        let (bid, ask) = trigger_prices(order, core);
+        let mut core = OrderMatchingCore::new(core.instrument_id, core.price_increment);
+        core.bid = bid;
+        core.ask = ask;
+        let side = order.side();
+
+        let is_triggered = match order.order_type() {
+            OrderType::Limit => order
+                .price()
+                .map_or(false, |price| core.is_limit_matched(side, price)),
+            OrderType::StopMarket
+            | OrderType::StopLimit
+            | OrderType::TrailingStopMarket
+            | OrderType::TrailingStopLimit => {
+                order.trigger_price().map_or(false, |trigger_price| {
+                    core.is_stop_triggered(side, trigger_price)
+                })
+            }
+            OrderType::MarketIfTouched | OrderType::LimitIfTouched => {
+                order.trigger_price().map_or(false, |trigger_price| {
+                    core.is_touch_triggered(side, trigger_price)
+                })
+            }
+            _ => false,
+        };
+
+        if is_triggered {
+            self.release_order(client_order_id)?;
+        }
+        Ok(is_triggered)
+    }
+
+    fn update_trailing_stop(&mut self, client_order_id: ClientOrderId) -> Result<()> {
+        let order = &self.orders[&client_order_id];
+        let core = &self.matching_cores[&trigger_instrument_id(order)];
+        let (bid, ask) = trigger_prices(order, core);
+
+        // Market data may not yet be available to trail from
+        let Ok((trigger_price, price)) = trailing_stop_calculate_with_price_tiers(
+            core.price_increment,
+            &core.price_tiers,
+            order.as_order(),
+            bid,
+            ask,
+            core.last,
+        ) else {
+            return Ok(());
+        };
+        if trigger_price.is_none() && price.is_none() {
+            return Ok(());
+        }
+
+        let ts_now = self.clock.get_time_ns();
+        let updated = OrderUpdated::new(
+            order.as_order().trader_id(),
+            order.strategy_id(),
+            order.instrument_id(),
+            client_order_id,
+            order.quantity(),
+            UUID4::new(),
+            ts_now,
+            ts_now,
+            false,
+            None,
+            None,
+            price,
+            trigger_price,
+        )?;
+        self.apply_event(OrderEvent::OrderUpdated(updated))
+    }
+
+    // -- EVENT GENERATION ------------------------------------------------------------------------
+
+    fn release_order(&mut self, client_order_id: ClientOrderId) -> Result<()> {
+        let order = self
+            .orders
+            .get(&client_order_id)
+            .ok_or_else(|| anyhow!("Emulated order {client_order_id} not found"))?;
+        let (bid, ask) = trigger_prices(order, &self.matching_cores[&trigger_instrument_id(order)]);
+        let ts_now = self.clock.get_time_ns();
+
+        let (mut transformed, released_price): (Box<dyn Order>, Price) = match order.order_type() {
+            OrderType::StopLimit | OrderType::LimitIfTouched | OrderType::TrailingStopLimit => {
+                let price = order
+                    .price()
+                    .ok_or_else(|| anyhow!("No price for order {client_order_id}"))?;
+                (Box::new(transform_to_limit(order, price, ts_now)), price)
+            }
+            _ => {
+                let market_price = match order.side() {
+                    OrderSide::Buy => ask,
+                    _ => bid,
+                };
+                let released_price = market_price
+                    .ok_or_else(|| anyhow!("No market price to release order {client_order_id}"))?;
+                (Box::new(transform_to_market(order, ts_now)), released_price)
+            }
+        };
+
+        let released = OrderReleased::new(
+            transformed.trader_id(),
+            transformed.strategy_id(),
+            transformed.instrument_id(),
+            client_order_id,
+            released_price,
+            UUID4::new(),
+            ts_now,
+            ts_now,
+        )?;
+        let event = OrderEvent::OrderReleased(released);
+        transformed.apply(event.clone())?;
+        self.orders.shift_remove(&client_order_id);
+        self.events.push(event);
+        self.commands.push(TradingCommand::SubmitOrder(transformed));
+
+        Ok(())
+    }
+
+    fn cancel_order(&mut self, client_order_id: ClientOrderId) -> Result<()> {
+        let order = self
+            .orders
+            .get_mut(&client_order_id)
+            .ok_or_else(|| anyhow!("Emulated order {client_order_id} not found"))?;
+        let ts_now = self.clock.get_time_ns();
+        let canceled = OrderCanceled::new(
+            order.as_order().trader_id(),
+            order.strategy_id(),
+            order.instrument_id(),
+            client_order_id,
+            UUID4::new(),
+            ts_now,
+            ts_now,
+            false,
+            None,
+            None,
+        )?;
+        let event = OrderEvent::OrderCanceled(canceled);
+        order.apply(event.clone())?;
+        self.orders.shift_remove(&client_order_id);
+        self.events.push(event);
+
+        Ok(())
+    }
+
+    /// Applies the `event` to its emulated order, only recording the event once applied.
+    fn apply_event(&mut self, event: OrderEvent) -> Result<()> {
+        let client_order_id = event.client_order_id();
+        let order = self
+            .orders
+            .get_mut(&client_order_id)
+            .ok_or_else(|| anyhow!("Emulated order {client_order_id} not found"))?;
+        order.apply(event.clone())?;
+        self.events.push(event);
+
+        Ok(())
+    }
+}
+
+fn trigger_instrument_id(order: &OrderAny) -> InstrumentId {
+    order
+        .as_order()
+        .trigger_instrument_id()
+        .unwrap_or_else(|| order.instrument_id())
+}
+
+/// Returns the bid and ask prices the `order` is evaluated against, which for orders with a
+/// `LAST_TRADE` emulation trigger are both the last trade price.
+fn trigger_prices(order: &OrderAny, core: &OrderMatchingCore) -> (Option<Price>, Option<Price>) {
+    match order.as_order().emulation_trigger() {
+        Some(TriggerType::LastTrade) => (core.last, core.last),
+        _ => (core.bid, core.ask),
+    }
+}
+
+fn is_trailing_stop(order_type: OrderType) -> bool {
+    matches!(
+        order_type,
+        OrderType::TrailingStopMarket | OrderType::TrailingStopLimit
+    )
+}
+
+/// Transforms the emulated `order` into a market order, carrying over its event history.
+fn transform_to_market(order: &OrderAny, ts_init: UnixNanos) -> MarketOrder {
+    let order = order.as_order();
+    // Market orders cannot have an expiry
+    let time_in_force = match order.time_in_force() {
+        TimeInForce::Gtd => TimeInForce::Gtc,
+        time_in_force => time_in_force,
+    };
+    let mut transformed = MarketOrder::new(
+        order.trader_id(),
+        order.strategy_id(),
+        order.instrument_id(),
+        order.client_order_id(),
+        order.side(),
+        order.quantity(),
+        time_in_force,
+        order.init_id(),
+        ts_init,
+        order.is_reduce_only(),
+        order.is_quote_quantity(),
+        order.contingency_type(),
+        order.order_list_id(),
+        order.linked_order_ids(),
+        order.parent_order_id(),
+        order.exec_algorithm_id(),
+        order.exec_algorithm_params(),
+        order.exec_spawn_id(),
+        order.tags(),
+    )
+    .unwrap();
+    transformed.events = order.events().into_iter().cloned().collect();
+    transformed
+}
+
+/// Transforms the emulated `order` into a limit order at `price`, carrying over its event
+/// history. The transformed order has no emulation trigger, as it is no longer emulated.
+fn transform_to_limit(order: &OrderAny, price: Price, ts_init: UnixNanos) -> LimitOrder {
+    let order = order.as_order();
+    let mut transformed = LimitOrder::new(
+        order.trader_id(),
+        order.strategy_id(),
+        order.instrument_id(),
+        order.client_order_id(),
+        order.side(),
+        order.quantity(),
+        price,
+        order.time_in_force(),
+        order.expire_time(),
+        order.is_post_only(),
+        order.is_reduce_only(),
+        order.is_quote_quantity(),
+        order.display_qty(),
+        None,
+        order.trigger_instrument_id(),
+        order.contingency_type(),
+        order.order_list_id(),
+        order.linked_order_ids(),
+        order.parent_order_id(),
+        order.exec_algorithm_id(),
+        order.exec_algorithm_params(),
+        order.exec_spawn_id(),
+        order.tags(),
+        order.init_id(),
+        ts_init,
+    );
+    transformed.events = order.events().into_iter().cloned().collect();
+    transformed
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Tests
+////////////////////////////////////////////////////////////////////////////////
+#[cfg(test)]
+mod tests {
+    use nautilus_model::{
+        enums::{AggressorSide, OrderStatus},
+        events::order::initialized::OrderInitializedBuilder,
+        identifiers::{
+            client_id::ClientId, strategy_id::StrategyId, trade_id::TradeId, trader_id::TraderId,
+        },
+        types::quantity::Quantity,
+    };
+    use rstest::{fixture, rstest};
+
+    use super::*;
+    use crate::trailing::PriceTier;
+
+    #[fixture]
+    fn emulator() -> OrderEmulator {
+        static TIME: AtomicTime = AtomicTime::new(false, 0);
+        let mut emulator = OrderEmulator::new(&TIME);
+        emulator.create_matching_core(InstrumentId::from("AUD/USD.SIM"), Price::from("0.00001"));
+        emulator
+    }
+
+    fn process_quote(emulator: &mut OrderEmulator, bid: &str, ask: &str) {
+        let quote = QuoteTick::new(
+            InstrumentId::from("AUD/USD.SIM"),
+            Price::from(bid),
+            Price::from(ask),
+            Quantity::from("100000"),
+            Quantity::from("100000"),
+            0,
+            0,
+        )
+        .unwrap();
+        emulator.handle_quote_tick(&quote).unwrap();
+    }
+
+    fn process_trade(emulator: &mut OrderEmulator, price: &str) {
+        let trade = TradeTick::new(
+            InstrumentId::from("AUD/USD.SIM"),
+            Price::from(price),
+            Quantity::from("100000"),
+            AggressorSide::Buyer,
+            TradeId::new("1").unwrap(),
+            0,
+            0,
+        );
+        emulator.handle_trade_tick(&trade).unwrap();
+    }
+
+    fn emulated_order(
+        client_order_id: &str,
+        order_type: OrderType,
+        side: OrderSide,
+        price: Option<&str>,
+        trigger_price: Option<&str>,
+        emulation_trigger: Option<TriggerType>,
+    ) -> OrderAny {
+        OrderInitializedBuilder::default()
+            .instrument_id(InstrumentId::from("AUD/USD.SIM"))
+            .client_order_id(ClientOrderId::from(client_order_id))
+            .order_type(order_type)
+            .order_side(side)
+            .price(price.map(Price::from))
+            .trigger_price(trigger_price.map(Price::from))
+            .trigger_type(trigger_price.and(emulation_trigger))
+            .trailing_offset(Some(Price::from("0.00010")))
+            .trailing_offset_type(Some(TrailingOffsetType::Price))
+            .limit_offset(Some(Price::from("0.00005")))
+            .emulation_trigger(emulation_trigger)
+            .build()
+            .unwrap()
+            .into()
+    }
+
+    fn released_order(emulator: &mut OrderEmulator) -> Box<dyn Order> {
+        let mut commands = emulator.drain_commands();
+        assert_eq!(commands.len(), 1);
+        match commands.remove(0) {
+            TradingCommand::SubmitOrder(order) => order,
+            _ => panic!("Expected `SubmitOrder` command"),
+        }
+    }
+
+    #[rstest]
+    fn test_submit_order_holds_order_emulated(mut emulator: OrderEmulator) {
+        let order = emulated_order(
+            "O-1",
+            OrderType::StopMarket,
+            OrderSide::Buy,
+            None,
+            Some("1.00010"),
+            Some(TriggerType::Default),
+        );
+
+        emulator.handle_submit_order(order).unwrap();
+
+        let order = emulator.get_order(&ClientOrderId::from("O-1")).unwrap();
+        assert_eq!(order.status(), OrderStatus::Emulated);
+        assert_eq!(emulator.get_emulated_orders().len(), 1);
+        assert_eq!(
+            emulator.subscribed_quotes(),
+            vec![InstrumentId::from("AUD/USD.SIM")]
+        );
+        assert!(emulator.subscribed_trades().is_empty());
+        let events = emulator.drain_events();
+        assert!(matches!(events.as_slice(), [OrderEvent::OrderEmulated(_)]));
+    }
+
+    #[rstest]
+    fn test_submit_order_with_last_trade_trigger_subscribes_trades(mut emulator: OrderEmulator) {
+        let order = emulated_order(
+            "O-1",
+            OrderType::StopMarket,
+            OrderSide::Buy,
+            None,
+            Some("1.00010"),
+            Some(TriggerType::LastTrade),
+        );
+
+        emulator.handle_submit_order(order).unwrap();
+
+        assert!(emulator.subscribed_quotes().is_empty());
+        assert_eq!(
+            emulator.subscribed_trades(),
+            vec![InstrumentId::from("AUD/USD.SIM")]
+        );
+    }
+
+    #[rstest]
+    #[case(OrderType::StopMarket, None, Some(TriggerType::NoTrigger))]
+    #[case(OrderType::StopMarket, None, None)]
+    #[case(OrderType::StopMarket, None, Some(TriggerType::MarkPrice))]
+    #[case(OrderType::Market, None, Some(TriggerType::Default))]
+    #[case(OrderType::StopMarket, Some("EUR/USD.SIM"), Some(TriggerType::Default))]
+    fn test_submit_order_invalid(
+        mut emulator: OrderEmulator,
+        #[case] order_type: OrderType,
+        #[case] trigger_instrument_id: Option<&str>,
+        #[case] emulation_trigger: Option<TriggerType>,
+    ) {
+        let order: OrderAny = OrderInitializedBuilder::default()
+            .instrument_id(InstrumentId::from("AUD/USD.SIM"))
+            .order_type(order_type)
+            .trigger_price(Some(Price::from("1.00010")))
+            .trigger_type(Some(TriggerType::Default))
+            .trigger_instrument_id(trigger_instrument_id.map(InstrumentId::from))
+            .emulation_trigger(emulation_trigger)
+            .build()
+            .unwrap()
+            .into();
+
+        assert!(emulator.handle_submit_order(order).is_err());
+        assert!(emulator.get_emulated_orders().is_empty());
+        assert!(emulator.subscribed_quotes().is_empty());
+        assert!(emulator.drain_events().is_empty());
+    }
+
+    #[rstest]
+    fn test_order_not_triggered_remains_emulated(mut emulator: OrderEmulator) {
+        let order = emulated_order(
+            "O-1",
+            OrderType::StopMarket,
+            OrderSide::Buy,
+            None,
+            Some("1.00010"),
+            Some(TriggerType::Default),
+        );
+        emulator.handle_submit_order(order).unwrap();
+
+        process_quote(&mut emulator, "1.00000", "1.00005");
+
+        assert!(emulator.drain_commands().is_empty());
+        assert_eq!(emulator.get_emulated_orders().len(), 1);
+    }
+
+    #[rstest]
+    #[case(
+        OrderType::StopMarket,
+        OrderSide::Buy,
+        None,
+        Some("1.00005"),
+        "1.00006"
+    )]
+    #[case(
+        OrderType::StopMarket,
+        OrderSide::Sell,
+        None,
+        Some("1.00000"),
+        "1.00000"
+    )]
+    #[case(
+        OrderType::MarketIfTouched,
+        OrderSide::Buy,
+        None,
+        Some("1.00010"),
+        "1.00006"
+    )]
+    #[case(
+        OrderType::MarketIfTouched,
+        OrderSide::Sell,
+        None,
+        Some("0.99990"),
+        "1.00000"
+    )]
+    #[case(OrderType::Limit, OrderSide::Buy, Some("1.00010"), None, "1.00006")]
+    #[case(OrderType::Limit, OrderSide::Sell, Some("0.99990"), None, "1.00000")]
+    fn test_triggered_order_released_as_market(
+        mut emulator: OrderEmulator,
+        #[case] order_type: OrderType,
+        #[case] side: OrderSide,
+        #[case] price: Option<&str>,
+        #[case] trigger_price: Option<&str>,
+        #[case] released_price: &str,
+    ) {
+        let order = emulated_order(
+            "O-1",
+            order_type,
+            side,
+            price,
+            trigger_price,
+            Some(TriggerType::BidAsk),
+        );
+        emulator.handle_submit_order(order).unwrap();
+        emulator.drain_events();
+
+        process_quote(&mut emulator, "1.00000", "1.00006");
+
+        let released = released_order(&mut emulator);
+        assert_eq!(released.order_type(), OrderType::Market);
+        assert_eq!(released.status(), OrderStatus::Released);
+        assert_eq!(released.client_order_id(), ClientOrderId::from("O-1"));
+        assert_eq!(released.emulation_trigger(), None);
+        assert_eq!(released.event_count(), 2);
+        match emulator.drain_events().as_slice() {
+            [OrderEvent::OrderReleased(event)] => {
+                assert_eq!(event.released_price, Price::from(released_price));
+            }
+            events => panic!("Unexpected events {events:?}"),
+        }
+        assert!(emulator.get_emulated_orders().is_empty());
+    }
+
+    #[rstest]
+    #[case(OrderType::StopLimit, OrderSide::Buy, "1.00010", "1.00005")]
+    #[case(OrderType::StopLimit, OrderSide::Sell, "0.99995", "1.00000")]
+    #[case(OrderType::LimitIfTouched, OrderSide::Buy, "1.00005", "1.00010")]
+    #[case(OrderType::LimitIfTouched, OrderSide::Sell, "1.00000", "0.99990")]
+    fn test_triggered_order_released_as_limit(
+        mut emulator: OrderEmulator,
+        #[case] order_type: OrderType,
+        #[case] side: OrderSide,
+        #[case] price: &str,
+        #[case] trigger_price: &str,
+    ) {
+        let order = emulated_order(
+            "O-1",
+            order_type,
+            side,
+            Some(price),
+            Some(trigger_price),
+            Some(TriggerType::Default),
+        );
+        emulator.handle_submit_order(order).unwrap();
+        emulator.drain_events();
+
+        process_quote(&mut emulator, "1.00000", "1.00006");
+
+        let released = released_order(&mut emulator);
+        assert_eq!(released.order_type(), OrderType::Limit);
+        assert_eq!(released.status(), OrderStatus::Released);
+        assert_eq!(released.price(), Some(Price::from(price)));
+        assert_eq!(released.emulation_trigger(), None);
+        match emulator.drain_events().as_slice() {
+            [OrderEvent::OrderReleased(event)] => {
+                assert_eq!(event.released_price, Price::from(price));
+            }
+            events => panic!("Unexpected events {events:?}"),
+        }
+    }
+
+    #[rstest]
+    fn test_trade_triggers_order_with_last_trade_trigger(mut emulator: OrderEmulator) {
+        let order = emulated_order(
+            "O-1",
+            OrderType::StopMarket,
+            OrderSide::Sell,
+            None,
+            Some("0.99990"),
+            Some(TriggerType::LastTrade),
+        );
+        emulator.handle_submit_order(order).unwrap();
+
+        process_trade(&mut emulator, "0.99995");
+        assert!(emulator.drain_commands().is_empty());
+
+        process_trade(&mut emulator, "0.99990");
+        let released = released_order(&mut emulator);
+        assert_eq!(released.order_type(), OrderType::Market);
+    }
+
+    #[rstest]
+    fn test_last_trade_trigger_ignores_quotes(mut emulator: OrderEmulator) {
+        let quoted = emulated_order(
+            "O-1",
+            OrderType::StopMarket,
+            OrderSide::Buy,
+            None,
+            Some("1.00020"),
+            Some(TriggerType::BidAsk),
+        );
+        let traded = emulated_order(
+            "O-2",
+            OrderType::StopMarket,
+            OrderSide::Sell,
+            None,
+            Some("0.99990"),
+            Some(TriggerType::LastTrade),
+        );
+        emulator.handle_submit_order(quoted).unwrap();
+        emulator.handle_submit_order(traded).unwrap();
+        emulator.drain_events();
+
+        process_quote(&mut emulator, "0.99980", "0.99982");
+        assert!(emulator.drain_commands().is_empty());
+
+        process_trade(&mut emulator, "0.99990");
+        let released = released_order(&mut emulator);
+        assert_eq!(released.client_order_id(), ClientOrderId::from("O-2"));
+        match emulator.drain_events().as_slice() {
+            [OrderEvent::OrderReleased(event)] => {
+                assert_eq!(event.released_price, Price::from("0.99990"));
+            }
+            events => panic!("Unexpected events {events:?}"),
+        }
+
+        // The trade does not move the bid and ask of the quote triggered order
+        process_trade(&mut emulator, "1.00030");
+        assert!(emulator.drain_commands().is_empty());
+        process_quote(&mut emulator, "1.00018", "1.00020");
+        let released = released_order(&mut emulator);
+        assert_eq!(released.client_order_id(), ClientOrderId::from("O-1"));
+    }
+
+    #[rstest]
+    fn test_modify_order_into_market_releases_order(mut emulator: OrderEmulator) {
+        let order = emulated_order(
+            "O-1",
+            OrderType::StopMarket,
+            OrderSide::Buy,
+            None,
+            Some("1.00010"),
+            Some(TriggerType::Default),
+        );
+        emulator.handle_submit_order(order).unwrap();
+        process_quote(&mut emulator, "1.00000", "1.00005");
+        emulator.drain_events();
+
+        let command = ModifyOrder::new(
+            TraderId::default(),
+            ClientId::from("SIM"),
+            StrategyId::default(),
+            InstrumentId::from("AUD/USD.SIM"),
+            ClientOrderId::from("O-1"),
+            None,
+            None,
+            None,
+            Some(Price::from("1.00005")),
+            UUID4::new(),
+            0,
+        )
+        .unwrap();
+        emulator.handle_modify_order(&command).unwrap();
+
+        let released = released_order(&mut emulator);
+        assert_eq!(released.order_type(), OrderType::Market);
+        let events = emulator.drain_events();
+        assert!(matches!(
+            events.as_slice(),
+            [OrderEvent::OrderUpdated(_), OrderEvent::OrderReleased(_)]
+        ));
+    }
+
+    #[rstest]
+    fn test_modify_order_with_invalid_price(mut emulator: OrderEmulator) {
+        let order = emulated_order(
+            "O-1",
+            OrderType::StopMarket,
+            OrderSide::Buy,
+            None,
+            Some("1.00010"),
+            Some(TriggerType::Default),
+        );
+        emulator.handle_submit_order(order).unwrap();
+
+        let command = ModifyOrder::new(
+            TraderId::default(),
+            ClientId::from("SIM"),
+            StrategyId::default(),
+            InstrumentId::from("AUD/USD.SIM"),
+            ClientOrderId::from("O-1"),
+            None,
+            None,
+            Some(Price::from("1.00020")),
+            None,
+            UUID4::new(),
+            0,
+        )
+        .unwrap();
+
+        assert!(emulator.handle_modify_order(&command).is_err());
+    }
+
+    #[rstest]
+    fn test_cancel_order(mut emulator: OrderEmulator) {
+        let order = emulated_order(
+            "O-1",
+            OrderType::StopMarket,
+            OrderSide::Buy,
+            None,
+            Some("1.00010"),
+            Some(TriggerType::Default),
+        );
+        emulator.handle_submit_order(order).unwrap();
+        emulator.drain_events();
+
+        let command = CancelOrder::new(
+            TraderId::default(),
+            ClientId::from("SIM"),
+            StrategyId::default(),
+            InstrumentId::from("AUD/USD.SIM"),
+            ClientOrderId::from("O-1"),
+            None,
+            UUID4::new(),
+            0,
+        )
+        .unwrap();
+        emulator.handle_cancel_order(&command).unwrap();
+
+        assert!(emulator.get_emulated_orders().is_empty());
+        let events = emulator.drain_events();
+        assert!(matches!(events.as_slice(), [OrderEvent::OrderCanceled(_)]));
+        assert!(emulator.handle_cancel_order(&command).is_err());
+    }
+
+    #[rstest]
+    fn test_cancel_all_orders_for_side(mut emulator: OrderEmulator) {
+        for (client_order_id, side, trigger_price) in [
+            ("O-1", OrderSide::Buy, "1.00010"),
+            ("O-2", OrderSide::Sell, "0.99990"),
+            ("O-3", OrderSide::Buy, "1.00020"),
+        ] {
+            let order = emulated_order(
+                client_order_id,
+                OrderType::StopMarket,
+                side,
+                None,
+                Some(trigger_price),
+                Some(TriggerType::Default),
+            );
+            emulator.handle_submit_order(order).unwrap();
+        }
+
+        let command = CancelAllOrders::new(
+            TraderId::default(),
+            ClientId::from("SIM"),
+            StrategyId::default(),
+            InstrumentId::from("AUD/USD.SIM"),
+            OrderSide::Buy,
+            UUID4::new(),
+            0,
+        )
+        .unwrap();
+        emulator.handle_cancel_all_orders(&command).unwrap();
+
+        let orders = emulator.get_emulated_orders();
+        assert_eq!(orders.len(), 1);
+        assert_eq!(orders[0].client_order_id(), ClientOrderId::from("O-2"));
+    }
+
+    #[rstest]
+    fn test_trailing_stop_market_trails_then_releases(mut emulator: OrderEmulator) {
+        let order = emulated_order(
+            "O-1",
+            OrderType::TrailingStopMarket,
+            OrderSide::Sell,
+            None,
+            Some("0.99900"),
+            Some(TriggerType::BidAsk),
+        );
+        emulator.handle_submit_order(order).unwrap();
+        emulator.drain_events();
+
+        process_quote(&mut emulator, "1.00000", "1.00002");
+        let order = emulator.get_order(&ClientOrderId::from("O-1")).unwrap();
+        assert_eq!(order.trigger_price(), Some(Price::from("0.99990")));
+        assert_eq!(order.status(), OrderStatus::Emulated);
+
+        // Trigger price only trails in the favorable direction
+        process_quote(&mut emulator, "0.99995", "0.99997");
+        let order = emulator.get_order(&ClientOrderId::from("O-1")).unwrap();
+        assert_eq!(order.trigger_price(), Some(Price::from("0.99990")));
+        assert!(emulator.drain_commands().is_empty());
+
+        process_quote(&mut emulator, "0.99990", "0.99992");
+        let released = released_order(&mut emulator);
+        assert_eq!(released.order_type(), OrderType::Market);
+        let events = emulator.drain_events();
+        assert!(matches!(
+            events.as_slice(),
+            [OrderEvent::OrderUpdated(_), OrderEvent::OrderReleased(_)]
+        ));
+    }
+
+    #[rstest]
+    fn test_trailing_stop_limit_released_as_limit(mut emulator: OrderEmulator) {
+        let order = emulated_order(
+            "O-1",
+            OrderType::TrailingStopLimit,
+            OrderSide::Buy,
+            Some("1.00200"),
+            Some("1.00100"),
+            Some(TriggerType::BidAsk),
+        );
+        emulator.handle_submit_order(order).unwrap();
+
+        process_quote(&mut emulator, "1.00000", "1.00002");
+        let order = emulator.get_order(&ClientOrderId::from("O-1")).unwrap();
+        assert_eq!(order.trigger_price(), Some(Price::from("1.00012")));
+        assert_eq!(order.price(), Some(Price::from("1.00007")));
+
+        process_quote(&mut emulator, "1.00010", "1.00012");
+        let released = released_order(&mut emulator);
+        assert_eq!(released.order_type(), OrderType::Limit);
+        assert_eq!(released.price(), Some(Price::from("1.00007")));
+    }
+
+    #[rstest]
+    fn test_trailing_stop_with_price_tier_offset(mut emulator: OrderEmulator) {
+        let order: OrderAny = OrderInitializedBuilder::default()
+            .instrument_id(InstrumentId::from("AUD/USD.SIM"))
+            .client_order_id(ClientOrderId::from("O-1"))
+            .order_type(OrderType::TrailingStopMarket)
+            .order_side(OrderSide::Sell)
+            .trigger_price(Some(Price::from("0.99900")))
+            .trigger_type(Some(TriggerType::BidAsk))
+            .trailing_offset(Some(Price::from("2")))
+            .trailing_offset_type(Some(TrailingOffsetType::PriceTier))
+            .emulation_trigger(Some(TriggerType::BidAsk))
+            .build()
+            .unwrap()
+            .into();
+        assert!(emulator.handle_submit_order(order.clone()).is_err());
+
+        emulator
+            .create_matching_core(InstrumentId::from("AUD/USD.SIM"), Price::from("0.00001"))
+            .set_price_tiers(vec![
+                PriceTier::new(
+                    Price::from("0.90000"),
+                    Price::from("1.00000"),
+                    Price::from("0.00001"),
+                ),
+                PriceTier::new(
+                    Price::from("1.00000"),
+                    Price::from("1.10000"),
+                    Price::from("0.00005"),
+                ),
+            ]);
+        emulator.handle_submit_order(order).unwrap();
+
+        process_quote(&mut emulator, "1.00005", "1.00007");
+        let order = emulator.get_order(&ClientOrderId::from("O-1")).unwrap();
+        assert_eq!(order.trigger_price(), Some(Price::from("0.99999")));
+    }
+}
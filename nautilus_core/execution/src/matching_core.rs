@@ -0,0 +1,219 @@
+// -------------------------------------------------------------------------------------------------
+//  Copyright (C) 2015-2024 Nautech Systems Pty Ltd. All rights reserved.
+//  https://nautechsystems.io
+//
+//  Licensed under the GNU Lesser General Public License Version 3.0 (the "License");
+//  You may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at https://www.gnu.org/licenses/lgpl-3.0.en.html
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+// -------------------------------------------------------------------------------------------------
+
+use nautilus_model::{
+    data::{bar::Bar, quote::QuoteTick, trade::TradeTick},
+    enums::OrderSide,
+    identifiers::instrument_id::InstrumentId,
+    types::price::Price,
+};
+
+use crate::trailing::PriceTier;
+
+/// Provides a generic order matching core for a single instrument.
+///
+/// Holds the current bid, ask and last prices and answers whether a resting
+/// limit, stop or touch order would be matched or triggered at those prices.
+pub struct OrderMatchingCore {
+    pub instrument_id: InstrumentId,
+    pub price_increment: Price,
+    pub price_tiers: Vec<PriceTier>,
+    pub bid: Option<Price>,
+    pub ask: Option<Price>,
+    pub last: Option<Price>,
+}
+
+impl OrderMatchingCore {
+    #[must_use]
+    pub fn new(instrument_id: InstrumentId, price_increment: Price) -> Self {
+        Self {
+            instrument_id,
+            price_increment,
+            price_tiers: Vec::new(),
+            bid: None,
+            ask: None,
+            last: None,
+        }
+    }
+
+    #[must_use]
+    pub fn price_precision(&self) -> u8 {
+        self.price_increment.precision
+    }
+
+    /// Sets the price tiers of the venue, used for `PRICE_TIER` trailing offsets.
+    pub fn set_price_tiers(&mut self, price_tiers: Vec<PriceTier>) {
+        self.price_tiers = price_tiers;
+    }
+
+    pub fn set_bid(&mut self, bid: Price) {
+        self.bid = Some(bid);
+    }
+
+    pub fn set_ask(&mut self, ask: Price) {
+        self.ask = Some(ask);
+    }
+
+    pub fn set_last(&mut self, last: Price) {
+        self.last = Some(last);
+    }
+
+    /// Updates the bid and ask prices from the given `quote`.
+    pub fn handle_quote_tick(&mut self, quote: &QuoteTick) {
+        self.set_bid(quote.bid_price);
+        self.set_ask(quote.ask_price);
+    }
+
+    /// Updates the last price from the given `trade`.
+    pub fn handle_trade_tick(&mut self, trade: &TradeTick) {
+        self.set_last(trade.price);
+    }
+
+    /// Updates the last price from the close of the given `bar`.
+    pub fn handle_bar(&mut self, bar: &Bar) {
+        self.set_last(bar.close);
+    }
+
+    pub fn reset(&mut self) {
+        self.bid = None;
+        self.ask = None;
+        self.last = None;
+    }
+
+    /// Returns whether a limit order at `price` would be matched by the opposite side.
+    #[must_use]
+    pub fn is_limit_matched(&self, side: OrderSide, price: Price) -> bool {
+        match side {
+            OrderSide::Buy => self.ask.map_or(false, |ask| ask <= price),
+            OrderSide::Sell => self.bid.map_or(false, |bid| bid >= price),
+            OrderSide::NoOrderSide => panic!("Invalid order side {side}"),
+        }
+    }
+
+    /// Returns whether a stop order with `trigger_price` would be triggered.
+    #[must_use]
+    pub fn is_stop_triggered(&self, side: OrderSide, trigger_price: Price) -> bool {
+        match side {
+            OrderSide::Buy => self.ask.map_or(false, |ask| ask >= trigger_price),
+            OrderSide::Sell => self.bid.map_or(false, |bid| bid <= trigger_price),
+            OrderSide::NoOrderSide => panic!("Invalid order side {side}"),
+        }
+    }
+
+    /// Returns whether an if-touched order with `trigger_price` would be triggered.
+    #[must_use]
+    pub fn is_touch_triggered(&self, side: OrderSide, trigger_price: Price) -> bool {
+        match side {
+            OrderSide::Buy => self.ask.map_or(false, |ask| ask <= trigger_price),
+            OrderSide::Sell => self.bid.map_or(false, |bid| bid >= trigger_price),
+            OrderSide::NoOrderSide => panic!("Invalid order side {side}"),
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Tests
+////////////////////////////////////////////////////////////////////////////////
+#[cfg(test)]
+mod tests {
+    use nautilus_model::data::{
+        bar::stubs::stub_bar, quote::stubs::quote_tick_ethusdt_binance,
+        trade::stubs::stub_trade_tick_ethusdt_buyer,
+    };
+    use rstest::rstest;
+
+    use super::*;
+
+    fn core_with_quote(bid: &str, ask: &str) -> OrderMatchingCore {
+        let mut core =
+            OrderMatchingCore::new(InstrumentId::from("AUD/USD.SIM"), Price::from("0.00001"));
+        core.set_bid(Price::from(bid));
+        core.set_ask(Price::from(ask));
+        core
+    }
+
+    #[rstest]
+    fn test_no_market_matches_nothing() {
+        let core =
+            OrderMatchingCore::new(InstrumentId::from("AUD/USD.SIM"), Price::from("0.00001"));
+        let price = Price::from("1.00000");
+
+        assert!(!core.is_limit_matched(OrderSide::Buy, price));
+        assert!(!core.is_limit_matched(OrderSide::Sell, price));
+        assert!(!core.is_stop_triggered(OrderSide::Buy, price));
+        assert!(!core.is_stop_triggered(OrderSide::Sell, price));
+        assert_eq!(core.price_precision(), 5);
+    }
+
+    #[rstest]
+    #[case(OrderSide::Buy, "1.00001", true)]
+    #[case(OrderSide::Buy, "1.00000", false)]
+    #[case(OrderSide::Sell, "1.00000", true)]
+    #[case(OrderSide::Sell, "1.00001", false)]
+    fn test_is_limit_matched(#[case] side: OrderSide, #[case] price: &str, #[case] expected: bool) {
+        let core = core_with_quote("1.00000", "1.00001");
+        assert_eq!(core.is_limit_matched(side, Price::from(price)), expected);
+    }
+
+    #[rstest]
+    #[case(OrderSide::Buy, "1.00001", true)]
+    #[case(OrderSide::Buy, "1.00002", false)]
+    #[case(OrderSide::Sell, "1.00000", true)]
+    #[case(OrderSide::Sell, "0.99999", false)]
+    fn test_is_stop_triggered(
+        #[case] side: OrderSide,
+        #[case] price: &str,
+        #[case] expected: bool,
+    ) {
+        let core = core_with_quote("1.00000", "1.00001");
+        assert_eq!(core.is_stop_triggered(side, Price::from(price)), expected);
+    }
+
+    #[rstest]
+    #[case(OrderSide::Buy, "1.00001", true)]
+    #[case(OrderSide::Buy, "1.00000", false)]
+    #[case(OrderSide::Sell, "1.00000", true)]
+    #[case(OrderSide::Sell, "1.00001", false)]
+    fn test_is_touch_triggered(
+        #[case] side: OrderSide,
+        #[case] price: &str,
+        #[case] expected: bool,
+    ) {
+        let core = core_with_quote("1.00000", "1.00001");
+        assert_eq!(core.is_touch_triggered(side, Price::from(price)), expected);
+    }
+
+    #[rstest]
+    fn test_handle_market_data(
+        quote_tick_ethusdt_binance: QuoteTick,
+        stub_trade_tick_ethusdt_buyer: TradeTick,
+        stub_bar: Bar,
+    ) {
+        let mut core = OrderMatchingCore::new(
+            InstrumentId::from("ETHUSDT-PERP.BINANCE"),
+            Price::from("0.0001"),
+        );
+
+        core.handle_quote_tick(&quote_tick_ethusdt_binance);
+        assert_eq!(core.bid, Some(quote_tick_ethusdt_binance.bid_price));
+        assert_eq!(core.ask, Some(quote_tick_ethusdt_binance.ask_price));
+
+        core.handle_trade_tick(&stub_trade_tick_ethusdt_buyer);
+        assert_eq!(core.last, Some(stub_trade_tick_ethusdt_buyer.price));
+
+        core.handle_bar(&stub_bar);
+        assert_eq!(core.last, Some(stub_bar.close));
+    }
+}
@@ -0,0 +1,43 @@
+// -------------------------------------------------------------------------------------------------
+//  Copyright (C) 2015-2024 Nautech Systems Pty Ltd. All rights reserved.
+//  https://nautechsystems.io
+//
+//  Licensed under the GNU Lesser General Public License Version 3.0 (the "License");
+//  You may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at https://www.gnu.org/licenses/lgpl-3.0.en.html
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+// -------------------------------------------------------------------------------------------------
+
+pub mod cancel;
+pub mod cancel_all;
+pub mod modify;
+
+use nautilus_model::{identifiers::instrument_id::InstrumentId, orders::base::Order};
+
+use self::{cancel::CancelOrder, cancel_all::CancelAllOrders, modify::ModifyOrder};
+
+/// Represents a trading command routed to an execution venue.
+pub enum TradingCommand {
+    SubmitOrder(Box<dyn Order>),
+    ModifyOrder(ModifyOrder),
+    CancelOrder(CancelOrder),
+    CancelAllOrders(CancelAllOrders),
+}
+
+impl TradingCommand {
+    /// Returns the instrument ID the command is for.
+    #[must_use]
+    pub fn instrument_id(&self) -> InstrumentId {
+        match self {
+            Self::SubmitOrder(order) => order.instrument_id(),
+            Self::ModifyOrder(command) => command.instrument_id,
+            Self::CancelOrder(command) => command.instrument_id,
+            Self::CancelAllOrders(command) => command.instrument_id,
+        }
+    }
+}
@@ -0,0 +1,718 @@
+// -------------------------------------------------------------------------------------------------
+//  Copyright (C) 2015-2024 Nautech Systems Pty Ltd. All rights reserved.
+//  https://nautechsystems.io
+//
+//  Licensed under the GNU Lesser General Public License Version 3.0 (the "License");
+//  You may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at https://www.gnu.org/licenses/lgpl-3.0.en.html
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+// -------------------------------------------------------------------------------------------------
+
+use anyhow::{anyhow, bail, Result};
+use nautilus_model::{
+    enums::{OrderSide, OrderType, TrailingOffsetType, TriggerType},
+    orders::base::Order,
+    types::{fixed::FIXED_SCALAR, price::Price},
+};
+
+/// Represents a price tier of a venue, within which prices move in steps of `increment`.
+///
+/// A tier covers prices from `start` (inclusive) up to `stop` (exclusive).
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct PriceTier {
+    pub start: Price,
+    pub stop: Price,
+    pub increment: Price,
+}
+
+impl PriceTier {
+    #[must_use]
+    pub fn new(start: Price, stop: Price, increment: Price) -> Self {
+        Self {
+            start,
+            stop,
+            increment,
+        }
+    }
+}
+
+/// Calculates the updated trigger price and (for trailing stop limit orders) limit price of
+/// the trailing stop `order`, given the latest market prices.
+///
+/// The market price used depends on the trigger type of the order:
+/// - `DEFAULT`, `LAST_TRADE`, `DOUBLE_LAST` and `MARK_PRICE` use the `last` price.
+/// - `BID_ASK` and `DOUBLE_BID_ASK` use the `ask` for buy orders and the `bid` for sell orders.
+/// - `LAST_OR_BID_ASK` uses whichever of the above gives the tighter price.
+/// - `MID_POINT` uses the mid-point of the `bid` and `ask`.
+///
+/// Prices only ever trail in the favorable direction, so a new price is only returned when
+/// it is lower than the current price for buy orders, or higher for sell orders. Returns the
+/// new trigger price and new limit price, each `None` if unchanged.
+///
+/// # Errors
+///
+/// If the order is not a trailing stop order, the market prices required by the trigger type
+/// are not available, or the trigger type or trailing offset type is not supported. Orders
+/// with a `PRICE_TIER` offset require [`trailing_stop_calculate_with_price_tiers`].
+pub fn trailing_stop_calculate(
+    price_increment: Price,
+    order: &dyn Order,
+    bid: Option<Price>,
+    ask: Option<Price>,
+    last: Option<Price>,
+) -> Result<(Option<Price>, Option<Price>)> {
+    trailing_stop_calculate_with_price_tiers(price_increment, &[], order, bid, ask, last)
+}
+
+/// Calculates the updated trigger price and (for trailing stop limit orders) limit price of
+/// the trailing stop `order`, as for [`trailing_stop_calculate`], with the `price_tiers` of
+/// the venue.
+///
+/// A `PRICE_TIER` offset is a number of ticks, where the size of each tick is the increment
+/// of the tier the price is stepping through.
+///
+/// # Errors
+///
+/// If the price cannot be calculated by [`trailing_stop_calculate`], or a `PRICE_TIER` offset
+/// steps outside of the `price_tiers`.
+pub fn trailing_stop_calculate_with_price_tiers(
+    price_increment: Price,
+    price_tiers: &[PriceTier],
+    order: &dyn Order,
+    bid: Option<Price>,
+    ask: Option<Price>,
+    last: Option<Price>,
+) -> Result<(Option<Price>, Option<Price>)> {
+    let order_type = order.order_type();
+    if !matches!(
+        order_type,
+        OrderType::TrailingStopMarket | OrderType::TrailingStopLimit
+    ) {
+        bail!("Invalid `OrderType` {order_type} for trailing stop calculation");
+    }
+
+    let side = order.side();
+    let trigger_type = order
+        .trigger_type()
+        .ok_or_else(|| anyhow!("Invalid order, `trigger_type` was `None`"))?;
+    let offset_type = order
+        .trailing_offset_type()
+        .ok_or_else(|| anyhow!("Invalid order, `trailing_offset_type` was `None`"))?;
+    let trailing_offset = order
+        .trailing_offset()
+        .ok_or_else(|| anyhow!("Invalid order, `trailing_offset` was `None`"))?;
+    let limit_offset = match order_type {
+        OrderType::TrailingStopLimit => order.limit_offset(),
+        _ => None,
+    };
+
+    let last = || last.ok_or_else(|| anyhow!("Cannot calculate trailing stop, no last price"));
+    let bid_ask = || match (bid, ask) {
+        (Some(bid), Some(ask)) => Ok((bid, ask)),
+        _ => Err(anyhow!(
+            "Cannot calculate trailing stop, no bid and ask prices"
+        )),
+    };
+
+    let mut references = Vec::with_capacity(2);
+    match trigger_type {
+        TriggerType::Default
+        | TriggerType::LastTrade
+        | TriggerType::DoubleLast
+        | TriggerType::MarkPrice => references.push(last()?),
+        TriggerType::BidAsk | TriggerType::DoubleBidAsk => {
+            let (bid, ask) = bid_ask()?;
+            references.push(bid_ask_reference(side, bid, ask)?);
+        }
+        TriggerType::LastOrBidAsk => {
+            let (bid, ask) = bid_ask()?;
+            references.push(last()?);
+            references.push(bid_ask_reference(side, bid, ask)?);
+        }
+        TriggerType::MidPoint => {
+            let (bid, ask) = bid_ask()?;
+            let mid = (bid.as_f64() + ask.as_f64()) / 2.0;
+            references.push(Price::new(mid, price_increment.precision + 1)?);
+        }
+        _ => bail!("Cannot calculate trailing stop, `TriggerType` {trigger_type} not supported"),
+    }
+
+    let mut trigger_price = order.trigger_price();
+    let mut price = order.price();
+    let mut new_trigger_price = None;
+    let mut new_price = None;
+
+    for reference in references {
+        if let Some(limit_offset) = limit_offset {
+            let candidate = trailing_stop_calculate_with_last(
+                price_increment,
+                price_tiers,
+                offset_type,
+                side,
+                limit_offset.as_f64(),
+                reference,
+            )?;
+            if is_improved(side, price, candidate) {
+                price = Some(candidate);
+                new_price = Some(candidate);
+            }
+        }
+
+        let candidate = trailing_stop_calculate_with_last(
+            price_increment,
+            price_tiers,
+            offset_type,
+            side,
+            trailing_offset.as_f64(),
+            reference,
+        )?;
+        if is_improved(side, trigger_price, candidate) {
+            trigger_price = Some(candidate);
+            new_trigger_price = Some(candidate);
+        }
+    }
+
+    Ok((new_trigger_price, new_price))
+}
+
+/// Calculates a trailing price at the given `offset` from the `last` price.
+///
+/// # Errors
+///
+/// If the `offset_type` is not supported, the `side` is invalid, or a `PRICE_TIER` offset
+/// steps outside of the `price_tiers`.
+pub fn trailing_stop_calculate_with_last(
+    price_increment: Price,
+    price_tiers: &[PriceTier],
+    offset_type: TrailingOffsetType,
+    side: OrderSide,
+    offset: f64,
+    last: Price,
+) -> Result<Price> {
+    let offset = match offset_type {
+        TrailingOffsetType::Price => offset,
+        TrailingOffsetType::BasisPoints => last.as_f64() * (offset / 100.0) / 100.0,
+        TrailingOffsetType::Ticks => offset * price_increment.as_f64(),
+        TrailingOffsetType::PriceTier => price_tier_offset(price_tiers, side, offset, last)?,
+        _ => bail!(
+            "Cannot calculate trailing stop, `TrailingOffsetType` {offset_type} not supported"
+        ),
+    };
+
+    match side {
+        OrderSide::Buy => Price::new(last.as_f64() + offset, price_increment.precision),
+        OrderSide::Sell => Price::new(last.as_f64() - offset, price_increment.precision),
+        OrderSide::NoOrderSide => bail!("Invalid `OrderSide` {side}"),
+    }
+}
+
+/// Calculates a trailing price at the given `offset` from the `ask` (for buy orders) or the
+/// `bid` (for sell orders).
+///
+/// # Errors
+///
+/// If the `offset_type` is not supported, the `side` is invalid, or a `PRICE_TIER` offset
+/// steps outside of the `price_tiers`.
+pub fn trailing_stop_calculate_with_bid_ask(
+    price_increment: Price,
+    price_tiers: &[PriceTier],
+    offset_type: TrailingOffsetType,
+    side: OrderSide,
+    offset: f64,
+    bid: Price,
+    ask: Price,
+) -> Result<Price> {
+    let reference = bid_ask_reference(side, bid, ask)?;
+    trailing_stop_calculate_with_last(
+        price_increment,
+        price_tiers,
+        offset_type,
+        side,
+        offset,
+        reference,
+    )
+}
+
+/// Returns the distance from `last` after stepping `ticks` tier increments away from it, up
+/// for buy orders and down for sell orders.
+fn price_tier_offset(
+    price_tiers: &[PriceTier],
+    side: OrderSide,
+    ticks: f64,
+    last: Price,
+) -> Result<f64> {
+    if ticks < 0.0 || ticks.fract() != 0.0 {
+        bail!("Invalid `PRICE_TIER` trailing offset {ticks}, must be a whole number of ticks");
+    }
+
+    // Step in raw units so that tier boundaries are hit exactly
+    let mut raw = last.raw;
+    for _ in 0..ticks as u64 {
+        let tier = price_tiers
+            .iter()
+            .find(|tier| match side {
+                OrderSide::Buy => tier.start.raw <= raw && raw < tier.stop.raw,
+                _ => tier.start.raw < raw && raw <= tier.stop.raw,
+            })
+            .ok_or_else(|| {
+                anyhow!("Cannot calculate trailing stop, {ticks} ticks from {last} leaves the price tiers")
+            })?;
+        match side {
+            OrderSide::Buy => raw += tier.increment.raw,
+            OrderSide::Sell => raw -= tier.increment.raw,
+            OrderSide::NoOrderSide => bail!("Invalid `OrderSide` {side}"),
+        }
+    }
+
+    Ok((raw - last.raw).abs() as f64 / FIXED_SCALAR)
+}
+
+fn bid_ask_reference(side: OrderSide, bid: Price, ask: Price) -> Result<Price> {
+    match side {
+        OrderSide::Buy => Ok(ask),
+        OrderSide::Sell => Ok(bid),
+        OrderSide::NoOrderSide => bail!("Invalid `OrderSide` {side}"),
+    }
+}
+
+fn is_improved(side: OrderSide, current: Option<Price>, candidate: Price) -> bool {
+    match current {
+        None => true,
+        Some(current) => match side {
+            OrderSide::Buy => candidate < current,
+            _ => candidate > current,
+        },
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Tests
+////////////////////////////////////////////////////////////////////////////////
+#[cfg(test)]
+mod tests {
+    use nautilus_model::{
+        events::order::initialized::OrderInitializedBuilder,
+        orders::{
+            limit::LimitOrder, trailing_stop_limit::TrailingStopLimitOrder,
+            trailing_stop_market::TrailingStopMarketOrder,
+        },
+    };
+    use rstest::rstest;
+
+    use super::*;
+
+    fn price_tiers() -> Vec<PriceTier> {
+        vec![
+            PriceTier::new(
+                Price::from("1.01"),
+                Price::from("2.00"),
+                Price::from("0.01"),
+            ),
+            PriceTier::new(
+                Price::from("2.00"),
+                Price::from("3.00"),
+                Price::from("0.02"),
+            ),
+            PriceTier::new(
+                Price::from("3.00"),
+                Price::from("4.00"),
+                Price::from("0.05"),
+            ),
+        ]
+    }
+
+    fn trailing_stop_market(
+        side: OrderSide,
+        trigger_price: &str,
+        trigger_type: TriggerType,
+        offset: &str,
+        offset_type: TrailingOffsetType,
+    ) -> TrailingStopMarketOrder {
+        OrderInitializedBuilder::default()
+            .order_type(OrderType::TrailingStopMarket)
+            .order_side(side)
+            .trigger_price(Some(Price::from(trigger_price)))
+            .trigger_type(Some(trigger_type))
+            .trailing_offset(Some(Price::from(offset)))
+            .trailing_offset_type(Some(offset_type))
+            .build()
+            .unwrap()
+            .into()
+    }
+
+    fn trailing_stop_limit(
+        side: OrderSide,
+        price: &str,
+        trigger_price: &str,
+        trigger_type: TriggerType,
+        limit_offset: &str,
+        offset: &str,
+    ) -> TrailingStopLimitOrder {
+        OrderInitializedBuilder::default()
+            .order_type(OrderType::TrailingStopLimit)
+            .order_side(side)
+            .price(Some(Price::from(price)))
+            .trigger_price(Some(Price::from(trigger_price)))
+            .trigger_type(Some(trigger_type))
+            .limit_offset(Some(Price::from(limit_offset)))
+            .trailing_offset(Some(Price::from(offset)))
+            .trailing_offset_type(Some(TrailingOffsetType::Price))
+            .build()
+            .unwrap()
+            .into()
+    }
+
+    #[rstest]
+    #[case(
+        OrderSide::Sell,
+        "95.00",
+        TrailingOffsetType::Price,
+        "1.00",
+        Some("99.00")
+    )]
+    #[case(OrderSide::Sell, "99.50", TrailingOffsetType::Price, "1.00", None)]
+    #[case(
+        OrderSide::Buy,
+        "105.00",
+        TrailingOffsetType::Price,
+        "1.00",
+        Some("101.00")
+    )]
+    #[case(OrderSide::Buy, "100.50", TrailingOffsetType::Price, "1.00", None)]
+    #[case(
+        OrderSide::Sell,
+        "95.00",
+        TrailingOffsetType::BasisPoints,
+        "50",
+        Some("99.50")
+    )]
+    #[case(
+        OrderSide::Buy,
+        "105.00",
+        TrailingOffsetType::BasisPoints,
+        "50",
+        Some("100.50")
+    )]
+    #[case(
+        OrderSide::Sell,
+        "95.00",
+        TrailingOffsetType::Ticks,
+        "20",
+        Some("99.80")
+    )]
+    #[case(
+        OrderSide::Buy,
+        "105.00",
+        TrailingOffsetType::Ticks,
+        "20",
+        Some("100.20")
+    )]
+    fn test_calculate_with_last_trade(
+        #[case] side: OrderSide,
+        #[case] trigger_price: &str,
+        #[case] offset_type: TrailingOffsetType,
+        #[case] offset: &str,
+        #[case] expected: Option<&str>,
+    ) {
+        let order = trailing_stop_market(
+            side,
+            trigger_price,
+            TriggerType::LastTrade,
+            offset,
+            offset_type,
+        );
+
+        let (new_trigger_price, new_price) = trailing_stop_calculate(
+            Price::from("0.01"),
+            &order,
+            None,
+            None,
+            Some(Price::from("100.00")),
+        )
+        .unwrap();
+
+        assert_eq!(new_trigger_price, expected.map(Price::from));
+        assert_eq!(new_price, None);
+    }
+
+    #[rstest]
+    #[case(OrderSide::Sell, TriggerType::BidAsk, "98.90")]
+    #[case(OrderSide::Buy, TriggerType::BidAsk, "101.10")]
+    #[case(OrderSide::Sell, TriggerType::DoubleBidAsk, "98.90")]
+    #[case(OrderSide::Sell, TriggerType::MidPoint, "99.00")]
+    #[case(OrderSide::Buy, TriggerType::MidPoint, "101.00")]
+    #[case(OrderSide::Sell, TriggerType::LastOrBidAsk, "99.40")]
+    #[case(OrderSide::Buy, TriggerType::LastOrBidAsk, "101.10")]
+    fn test_calculate_with_bid_ask(
+        #[case] side: OrderSide,
+        #[case] trigger_type: TriggerType,
+        #[case] expected: &str,
+    ) {
+        let trigger_price = match side {
+            OrderSide::Buy => "110.00",
+            _ => "90.00",
+        };
+        let order = trailing_stop_market(
+            side,
+            trigger_price,
+            trigger_type,
+            "1.00",
+            TrailingOffsetType::Price,
+        );
+
+        let (new_trigger_price, _) = trailing_stop_calculate(
+            Price::from("0.01"),
+            &order,
+            Some(Price::from("99.90")),
+            Some(Price::from("100.10")),
+            Some(Price::from("100.40")),
+        )
+        .unwrap();
+
+        assert_eq!(new_trigger_price, Some(Price::from(expected)));
+    }
+
+    #[rstest]
+    fn test_calculate_trailing_stop_limit() {
+        let order = trailing_stop_limit(
+            OrderSide::Sell,
+            "94.00",
+            "95.00",
+            TriggerType::LastTrade,
+            "1.50",
+            "1.00",
+        );
+
+        let (new_trigger_price, new_price) = trailing_stop_calculate(
+            Price::from("0.01"),
+            &order,
+            None,
+            None,
+            Some(Price::from("100.00")),
+        )
+        .unwrap();
+
+        assert_eq!(new_trigger_price, Some(Price::from("99.00")));
+        assert_eq!(new_price, Some(Price::from("98.50")));
+    }
+
+    #[rstest]
+    fn test_calculate_trailing_stop_limit_price_not_improved() {
+        let order = trailing_stop_limit(
+            OrderSide::Buy,
+            "101.00",
+            "105.00",
+            TriggerType::BidAsk,
+            "1.50",
+            "1.00",
+        );
+
+        let (new_trigger_price, new_price) = trailing_stop_calculate(
+            Price::from("0.01"),
+            &order,
+            Some(Price::from("99.90")),
+            Some(Price::from("100.10")),
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(new_trigger_price, Some(Price::from("101.10")));
+        assert_eq!(new_price, None);
+    }
+
+    #[rstest]
+    #[case(OrderSide::Sell, "2.00", "3", "1.97")]
+    #[case(OrderSide::Sell, "2.04", "4", "1.98")]
+    #[case(OrderSide::Sell, "2.00", "0", "2.00")]
+    #[case(OrderSide::Buy, "2.00", "3", "2.06")]
+    #[case(OrderSide::Buy, "2.96", "3", "3.05")]
+    fn test_calculate_with_price_tier_offset(
+        #[case] side: OrderSide,
+        #[case] last: &str,
+        #[case] offset: &str,
+        #[case] expected: &str,
+    ) {
+        let trigger_price = match side {
+            OrderSide::Buy => "5.00",
+            _ => "1.00",
+        };
+        let order = trailing_stop_market(
+            side,
+            trigger_price,
+            TriggerType::LastTrade,
+            offset,
+            TrailingOffsetType::PriceTier,
+        );
+
+        let (new_trigger_price, _) = trailing_stop_calculate_with_price_tiers(
+            Price::from("0.01"),
+            &price_tiers(),
+            &order,
+            None,
+            None,
+            Some(Price::from(last)),
+        )
+        .unwrap();
+
+        assert_eq!(new_trigger_price, Some(Price::from(expected)));
+    }
+
+    #[rstest]
+    fn test_calculate_trailing_stop_limit_with_price_tier_offset() {
+        let order: TrailingStopLimitOrder = OrderInitializedBuilder::default()
+            .order_type(OrderType::TrailingStopLimit)
+            .order_side(OrderSide::Buy)
+            .price(Some(Price::from("5.00")))
+            .trigger_price(Some(Price::from("5.00")))
+            .trigger_type(Some(TriggerType::LastTrade))
+            .limit_offset(Some(Price::from("4")))
+            .trailing_offset(Some(Price::from("2")))
+            .trailing_offset_type(Some(TrailingOffsetType::PriceTier))
+            .build()
+            .unwrap()
+            .into();
+
+        let (new_trigger_price, new_price) = trailing_stop_calculate_with_price_tiers(
+            Price::from("0.01"),
+            &price_tiers(),
+            &order,
+            None,
+            None,
+            Some(Price::from("2.96")),
+        )
+        .unwrap();
+
+        assert_eq!(new_trigger_price, Some(Price::from("3.00")));
+        assert_eq!(new_price, Some(Price::from("3.10")));
+    }
+
+    #[rstest]
+    #[case(OrderSide::Sell, "2", "1.02")]
+    #[case(OrderSide::Buy, "3", "3.90")]
+    #[case(OrderSide::Buy, "1", "4.00")]
+    fn test_calculate_with_price_tier_offset_outside_tiers_errors(
+        #[case] side: OrderSide,
+        #[case] offset: &str,
+        #[case] last: &str,
+    ) {
+        let result = trailing_stop_calculate_with_last(
+            Price::from("0.01"),
+            &price_tiers(),
+            TrailingOffsetType::PriceTier,
+            side,
+            offset.parse().unwrap(),
+            Price::from(last),
+        );
+
+        assert!(result.is_err());
+    }
+
+    #[rstest]
+    fn test_calculate_with_price_tier_offset_without_tiers_errors() {
+        let order = trailing_stop_market(
+            OrderSide::Sell,
+            "1.00",
+            TriggerType::LastTrade,
+            "1",
+            TrailingOffsetType::PriceTier,
+        );
+
+        let result = trailing_stop_calculate(
+            Price::from("0.01"),
+            &order,
+            None,
+            None,
+            Some(Price::from("2.00")),
+        );
+
+        assert!(result.is_err());
+    }
+
+    #[rstest]
+    fn test_calculate_with_fractional_price_tier_offset_errors() {
+        let result = trailing_stop_calculate_with_last(
+            Price::from("0.01"),
+            &price_tiers(),
+            TrailingOffsetType::PriceTier,
+            OrderSide::Sell,
+            1.5,
+            Price::from("2.00"),
+        );
+
+        assert!(result.is_err());
+    }
+
+    #[rstest]
+    #[case(TriggerType::LastTrade)]
+    #[case(TriggerType::BidAsk)]
+    #[case(TriggerType::MidPoint)]
+    fn test_calculate_without_market_prices_errors(#[case] trigger_type: TriggerType) {
+        let order = trailing_stop_market(
+            OrderSide::Sell,
+            "95.00",
+            trigger_type,
+            "1.00",
+            TrailingOffsetType::Price,
+        );
+
+        let result = trailing_stop_calculate(Price::from("0.01"), &order, None, None, None);
+
+        assert!(result.is_err());
+    }
+
+    #[rstest]
+    fn test_calculate_for_non_trailing_order_errors() {
+        let order: LimitOrder = OrderInitializedBuilder::default()
+            .order_type(OrderType::Limit)
+            .price(Some(Price::from("100.00")))
+            .build()
+            .unwrap()
+            .into();
+
+        let result = trailing_stop_calculate(
+            Price::from("0.01"),
+            &order,
+            None,
+            None,
+            Some(Price::from("100.00")),
+        );
+
+        assert!(result.is_err());
+    }
+
+    #[rstest]
+    fn test_calculate_with_bid_ask_uses_side_of_book() {
+        let bid = Price::from("99.90");
+        let ask = Price::from("100.10");
+        let increment = Price::from("0.01");
+
+        let buy = trailing_stop_calculate_with_bid_ask(
+            increment,
+            &[],
+            TrailingOffsetType::Ticks,
+            OrderSide::Buy,
+            5.0,
+            bid,
+            ask,
+        )
+        .unwrap();
+        let sell = trailing_stop_calculate_with_bid_ask(
+            increment,
+            &[],
+            TrailingOffsetType::Ticks,
+            OrderSide::Sell,
+            5.0,
+            bid,
+            ask,
+        )
+        .unwrap();
+
+        assert_eq!(buy, Price::from("100.15"));
+        assert_eq!(sell, Price::from("99.85"));
+    }
+}
@@ -23,7 +23,11 @@ use std::{
 use anyhow::{anyhow, bail, Result};
 use nautilus_common::redis::{get_buffer_interval, get_redis_url};
 use nautilus_core::uuid::UUID4;
-use nautilus_model::identifiers::trader_id::TraderId;
+use nautilus_model::{
+    events::order::event::OrderEvent,
+    identifiers::{client_order_id::ClientOrderId, trader_id::TraderId},
+    orders::any::OrderAny,
+};
 use pyo3::prelude::*;
 use redis::{Commands, Connection, Pipeline};
 use serde_json::json;
@@ -71,10 +75,35 @@ const INDEX_POSITIONS_CLOSED: &str = "index:positions_closed";
 pub struct RedisCacheDatabase {
     pub trader_id: TraderId,
     trader_key: String,
+    encoding: String,
     conn: Connection,
     tx: Sender<DatabaseCommand>,
 }
 
+impl RedisCacheDatabase {
+    /// Loads the order for the given `client_order_id` by replaying its stored event list.
+    ///
+    /// Each stored event is expected in the serialized form of the corresponding Rust event
+    /// type, using the configured encoding. Returns `None` if no events are stored for the order.
+    ///
+    /// Only event lists written from Rust can be loaded: the Python `to_dict` schemas differ
+    /// (e.g. `OrderInitialized` nests the order type specific fields under `options` and has no
+    /// `ts_event`), so orders written by a Python cache database fail to deserialize.
+    pub fn load_order(&mut self, client_order_id: &ClientOrderId) -> Result<Option<OrderAny>> {
+        let payloads = self.read(&format!("{ORDERS}{DELIMITER}{client_order_id}"))?;
+        if payloads.is_empty() {
+            return Ok(None);
+        }
+
+        let events = payloads
+            .iter()
+            .map(|payload| deserialize_order_event(&self.encoding, payload))
+            .collect::<Result<Vec<_>>>()?;
+        let order = OrderAny::from_event_history(events)?;
+        Ok(Some(order))
+    }
+}
+
 impl CacheDatabase for RedisCacheDatabase {
     type DatabaseType = RedisCacheDatabase;
 
@@ -90,6 +119,7 @@ impl CacheDatabase for RedisCacheDatabase {
         let (tx, rx) = channel::<DatabaseCommand>();
         let trader_key = get_trader_key(trader_id, instance_id, &config);
         let trader_key_clone = trader_key.clone();
+        let encoding = get_encoding(&config);
 
         thread::spawn(move || {
             Self::handle_messages(rx, trader_key_clone, config);
@@ -98,6 +128,7 @@ impl CacheDatabase for RedisCacheDatabase {
         Ok(RedisCacheDatabase {
             trader_id,
             trader_key,
+            encoding,
             conn,
             tx,
         })
@@ -553,8 +584,6 @@ fn get_index_key(key: &str) -> Result<&str> {
         .ok_or_else(|| anyhow!("Invalid `key`, missing a '{DELIMITER}' delimiter, was {key}"))
 }
 
-// This function can be used when we handle cache serialization in Rust
-#[allow(dead_code)]
 fn get_encoding(config: &HashMap<String, serde_json::Value>) -> String {
     config
         .get("encoding")
@@ -563,8 +592,6 @@ fn get_encoding(config: &HashMap<String, serde_json::Value>) -> String {
         .to_string()
 }
 
-// This function can be used when we handle cache serialization in Rust
-#[allow(dead_code)]
 fn deserialize_payload(
     encoding: &str,
     payload: &[u8],
@@ -578,10 +605,47 @@ fn deserialize_payload(
     }
 }
 
+fn deserialize_order_event(encoding: &str, payload: &[u8]) -> Result<OrderEvent> {
+    let value = serde_json::Value::Object(
+        deserialize_payload(encoding, payload)?
+            .into_iter()
+            .collect(),
+    );
+    let event_type = value
+        .get("type")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| anyhow!("Invalid order event `payload`, missing 'type'"))?
+        .to_string();
+
+    let event = match event_type.as_str() {
+        "OrderInitialized" => OrderEvent::OrderInitialized(serde_json::from_value(value)?),
+        "OrderDenied" => OrderEvent::OrderDenied(serde_json::from_value(value)?),
+        "OrderEmulated" => OrderEvent::OrderEmulated(serde_json::from_value(value)?),
+        "OrderReleased" => OrderEvent::OrderReleased(serde_json::from_value(value)?),
+        "OrderSubmitted" => OrderEvent::OrderSubmitted(serde_json::from_value(value)?),
+        "OrderAccepted" => OrderEvent::OrderAccepted(serde_json::from_value(value)?),
+        "OrderRejected" => OrderEvent::OrderRejected(serde_json::from_value(value)?),
+        "OrderCanceled" => OrderEvent::OrderCanceled(serde_json::from_value(value)?),
+        "OrderExpired" => OrderEvent::OrderExpired(serde_json::from_value(value)?),
+        "OrderTriggered" => OrderEvent::OrderTriggered(serde_json::from_value(value)?),
+        "OrderPendingUpdate" => OrderEvent::OrderPendingUpdate(serde_json::from_value(value)?),
+        "OrderPendingCancel" => OrderEvent::OrderPendingCancel(serde_json::from_value(value)?),
+        "OrderModifyRejected" => OrderEvent::OrderModifyRejected(serde_json::from_value(value)?),
+        "OrderCancelRejected" => OrderEvent::OrderCancelRejected(serde_json::from_value(value)?),
+        "OrderUpdated" => OrderEvent::OrderUpdated(serde_json::from_value(value)?),
+        "OrderFilled" => OrderEvent::OrderFilled(serde_json::from_value(value)?),
+        _ => bail!("Unrecognized order event type '{event_type}'"),
+    };
+    Ok(event)
+}
+
 #[cfg(test)]
 mod tests {
     use std::collections::HashMap;
 
+    use nautilus_model::events::order::{
+        initialized::OrderInitializedBuilder, submitted::OrderSubmittedBuilder,
+    };
     use rstest::rstest;
     use serde_json::json;
 
@@ -623,4 +687,31 @@ mod tests {
         let key = "no_delimiter";
         assert!(get_index_key(key).is_err());
     }
+
+    #[rstest]
+    fn test_deserialize_order_event_json() {
+        let init = OrderInitializedBuilder::default().build().unwrap();
+        let payload = serde_json::to_vec(&init).unwrap();
+
+        let event = deserialize_order_event("json", &payload).unwrap();
+
+        assert_eq!(event, OrderEvent::OrderInitialized(init));
+    }
+
+    #[rstest]
+    fn test_deserialize_order_event_msgpack() {
+        let submitted = OrderSubmittedBuilder::default().build().unwrap();
+        let payload = rmp_serde::to_vec_named(&submitted).unwrap();
+
+        let event = deserialize_order_event("msgpack", &payload).unwrap();
+
+        assert_eq!(event, OrderEvent::OrderSubmitted(submitted));
+    }
+
+    #[rstest]
+    fn test_deserialize_order_event_with_unknown_type() {
+        let payload = serde_json::to_vec(&json!({"type": "OrderExploded"})).unwrap();
+
+        assert!(deserialize_order_event("json", &payload).is_err());
+    }
 }
@@ -27,7 +27,7 @@ use serde::{Deserialize, Serialize};
 
 use super::order::{BookOrder, OrderId, NULL_ORDER};
 use crate::{
-    enums::{BookAction, FromU8, OrderSide},
+    enums::{BookAction, FromU8, OrderSide, RecordFlag},
     identifiers::instrument_id::InstrumentId,
     types::{price::Price, quantity::Quantity},
 };
@@ -92,7 +92,7 @@ impl OrderBookDelta {
             instrument_id,
             action: BookAction::Clear,
             order: NULL_ORDER,
-            flags: 32, // TODO: Flags constants
+            flags: RecordFlag::F_SNAPSHOT as u8,
             sequence,
             ts_event,
             ts_init,
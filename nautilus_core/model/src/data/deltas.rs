@@ -94,7 +94,7 @@ pub mod stubs {
     use super::*;
     use crate::{
         data::{delta::OrderBookDelta, order::BookOrder},
-        enums::{BookAction, OrderSide},
+        enums::{BookAction, OrderSide, RecordFlag},
         identifiers::instrument_id::InstrumentId,
         types::{price::Price, quantity::Quantity},
     };
@@ -102,7 +102,7 @@ pub mod stubs {
     #[fixture]
     pub fn stub_deltas() -> OrderBookDeltas {
         let instrument_id = InstrumentId::from("AAPL.XNAS");
-        let flags = 32; // Snapshot flag
+        let flags = RecordFlag::F_SNAPSHOT as u8;
         let sequence = 0;
         let ts_event = 1;
         let ts_init = 2;
@@ -209,14 +209,14 @@ mod tests {
     use super::{stubs::*, *};
     use crate::{
         data::order::BookOrder,
-        enums::{BookAction, OrderSide},
+        enums::{BookAction, OrderSide, RecordFlag},
         types::{price::Price, quantity::Quantity},
     };
 
     #[rstest]
     fn test_new() {
         let instrument_id = InstrumentId::from("AAPL.XNAS");
-        let flags = 32; // Snapshot flag
+        let flags = RecordFlag::F_SNAPSHOT as u8;
         let sequence = 0;
         let ts_event = 1;
         let ts_init = 2;
@@ -17,6 +17,7 @@
 
 use std::str::FromStr;
 
+use bitflags::bitflags;
 use pyo3::{exceptions::PyValueError, prelude::*, types::PyType, PyTypeInfo};
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use strum::{AsRefStr, Display, EnumIter, EnumString, FromRepr};
@@ -965,6 +966,74 @@ pub enum PriceType {
     Last = 4,
 }
 
+/// A record flag bit field, indicating event end and data information.
+#[repr(C)]
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    Display,
+    Hash,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    AsRefStr,
+    FromRepr,
+    EnumIter,
+    EnumString,
+)]
+#[strum(ascii_case_insensitive)]
+#[strum(serialize_all = "SCREAMING_SNAKE_CASE")]
+#[allow(non_camel_case_types)]
+#[cfg_attr(
+    feature = "python",
+    pyclass(module = "nautilus_trader.core.nautilus_pyo3.model.enums")
+)]
+pub enum RecordFlag {
+    /// Last message in the packet from the venue for a given `instrument_id`.
+    F_LAST = 1 << 7,
+    /// Top-of-book message, not an individual order.
+    F_TOB = 1 << 6,
+    /// Message sourced from a replay, such as a snapshot server.
+    F_SNAPSHOT = 1 << 5,
+    /// Aggregated price level message, not an individual order.
+    F_MBP = 1 << 4,
+    /// The `ts_recv` value is inaccurate due to clock issues or packet reordering.
+    F_BAD_TS_RECV = 1 << 3,
+}
+
+impl RecordFlag {
+    /// Returns whether this flag is set in the given `value`.
+    #[must_use]
+    pub fn matches(self, value: u8) -> bool {
+        (self as u8) & value != 0
+    }
+}
+
+bitflags! {
+    /// A combination of [`RecordFlag`] bits, as carried in the `flags` field of market data.
+    #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+    pub struct RecordFlags: u8 {
+        /// Last message in the packet from the venue for a given `instrument_id`.
+        const F_LAST = RecordFlag::F_LAST as u8;
+        /// Top-of-book message, not an individual order.
+        const F_TOB = RecordFlag::F_TOB as u8;
+        /// Message sourced from a replay, such as a snapshot server.
+        const F_SNAPSHOT = RecordFlag::F_SNAPSHOT as u8;
+        /// Aggregated price level message, not an individual order.
+        const F_MBP = RecordFlag::F_MBP as u8;
+        /// The `ts_recv` value is inaccurate due to clock issues or packet reordering.
+        const F_BAD_TS_RECV = RecordFlag::F_BAD_TS_RECV as u8;
+    }
+}
+
+impl From<RecordFlag> for RecordFlags {
+    fn from(flag: RecordFlag) -> Self {
+        Self::from_bits_retain(flag as u8)
+    }
+}
+
 /// The 'Time in Force' instruction for an order in the financial market.
 #[repr(C)]
 #[derive(
@@ -1162,6 +1231,7 @@ enum_strum_serde!(OrderStatus);
 enum_strum_serde!(OrderType);
 enum_strum_serde!(PositionSide);
 enum_strum_serde!(PriceType);
+enum_strum_serde!(RecordFlag);
 enum_strum_serde!(TimeInForce);
 enum_strum_serde!(TradingState);
 enum_strum_serde!(TrailingOffsetType);
@@ -1186,6 +1256,7 @@ enum_for_python!(OrderStatus);
 enum_for_python!(OrderType);
 enum_for_python!(PositionSide);
 enum_for_python!(PriceType);
+enum_for_python!(RecordFlag);
 enum_for_python!(TimeInForce);
 enum_for_python!(TradingState);
 enum_for_python!(TrailingOffsetType);
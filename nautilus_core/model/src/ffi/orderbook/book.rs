@@ -143,9 +143,11 @@ pub extern "C" fn orderbook_clear_asks(book: &mut OrderBook_API, ts_event: u64,
     book.clear_asks(ts_event, sequence)
 }
 
+/// Applies the `delta` to the book, returning 0 if a sequence gap was detected (the delta is
+/// then buffered until the next snapshot), otherwise 1.
 #[no_mangle]
-pub extern "C" fn orderbook_apply_delta(book: &mut OrderBook_API, delta: OrderBookDelta) {
-    book.apply_delta(delta)
+pub extern "C" fn orderbook_apply_delta(book: &mut OrderBook_API, delta: OrderBookDelta) -> u8 {
+    book.apply_delta(delta).is_ok() as u8
 }
 
 #[no_mangle]
@@ -0,0 +1,202 @@
+// -------------------------------------------------------------------------------------------------
+//  Copyright (C) 2015-2024 Nautech Systems Pty Ltd. All rights reserved.
+//  https://nautechsystems.io
+//
+//  Licensed under the GNU Lesser General Public License Version 3.0 (the "License");
+//  You may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at https://www.gnu.org/licenses/lgpl-3.0.en.html
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+// -------------------------------------------------------------------------------------------------
+
+use crate::{
+    data::order::BookOrder,
+    enums::OrderSide,
+    orderbook::ladder::Ladder,
+    types::{price::Price, quantity::Quantity},
+};
+
+/// Represents a price level of an order book aggregated to market-by-price.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AggregatedLevel {
+    pub side: OrderSide,
+    pub price: Price,
+    pub size: Quantity,
+    /// The number of orders aggregated into the level.
+    pub count: u32,
+}
+
+impl AggregatedLevel {
+    /// Returns the level as a market-by-price order (identified by its raw price).
+    #[must_use]
+    pub fn to_book_order(&self) -> BookOrder {
+        BookOrder::new(self.side, self.price, self.size, self.price.raw as u64)
+    }
+}
+
+/// Returns the top `depth` levels of the `ladder` aggregated by price, optionally grouping
+/// prices into coarser buckets of `group_size`.
+///
+/// When grouping, bid prices are rounded down and ask prices rounded up to the bucket boundary,
+/// so a grouped level never shows a better price than the orders it contains.
+///
+/// # Panics
+///
+/// If `group_size` is not positive.
+#[must_use]
+pub fn aggregate_levels(
+    ladder: &Ladder,
+    depth: usize,
+    group_size: Option<Price>,
+) -> Vec<AggregatedLevel> {
+    if let Some(group_size) = group_size {
+        assert!(group_size.raw > 0, "`group_size` was not positive");
+    }
+
+    let mut levels: Vec<AggregatedLevel> = Vec::with_capacity(depth);
+    for level in ladder.levels.values() {
+        let Some(first) = level.first() else {
+            continue;
+        };
+
+        let price = match group_size {
+            Some(group_size) => group_price(level.price.value, group_size, ladder.side),
+            None => level.price.value,
+        };
+        let size_raw = level.size_raw();
+        let count = level.len() as u32;
+
+        match levels.last_mut() {
+            Some(last) if last.price == price => {
+                last.size = Quantity::from_raw(last.size.raw + size_raw, last.size.precision)
+                    .expect("Invalid size precision");
+                last.count += count;
+            }
+            _ => {
+                if levels.len() == depth {
+                    break;
+                }
+                levels.push(AggregatedLevel {
+                    side: ladder.side,
+                    price,
+                    size: Quantity::from_raw(size_raw, first.size.precision)
+                        .expect("Invalid size precision"),
+                    count,
+                });
+            }
+        }
+    }
+
+    levels
+}
+
+fn group_price(price: Price, group_size: Price, side: OrderSide) -> Price {
+    let remainder = price.raw.rem_euclid(group_size.raw);
+    let raw = match side {
+        OrderSide::Buy => price.raw - remainder,
+        _ if remainder == 0 => price.raw,
+        _ => price.raw - remainder + group_size.raw,
+    };
+    Price::from_raw(raw, group_size.precision).expect("Invalid price precision")
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Tests
+////////////////////////////////////////////////////////////////////////////////
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    fn ladder(side: OrderSide, orders: &[(&str, &str)]) -> Ladder {
+        let mut ladder = Ladder::new(side);
+        for (i, (price, size)) in orders.iter().enumerate() {
+            ladder.add(BookOrder::new(
+                side,
+                Price::from(*price),
+                Quantity::from(*size),
+                i as u64 + 1,
+            ));
+        }
+        ladder
+    }
+
+    #[rstest]
+    fn test_aggregate_levels_by_price() {
+        let bids = ladder(
+            OrderSide::Buy,
+            &[("10.00", "1"), ("10.00", "2"), ("9.95", "5"), ("9.90", "1")],
+        );
+
+        let levels = aggregate_levels(&bids, 2, None);
+
+        assert_eq!(
+            levels,
+            vec![
+                AggregatedLevel {
+                    side: OrderSide::Buy,
+                    price: Price::from("10.00"),
+                    size: Quantity::from("3"),
+                    count: 2,
+                },
+                AggregatedLevel {
+                    side: OrderSide::Buy,
+                    price: Price::from("9.95"),
+                    size: Quantity::from("5"),
+                    count: 1,
+                },
+            ]
+        );
+    }
+
+    #[rstest]
+    fn test_aggregate_levels_when_empty() {
+        let asks = Ladder::new(OrderSide::Sell);
+        assert!(aggregate_levels(&asks, 10, None).is_empty());
+    }
+
+    #[rstest]
+    fn test_aggregate_bid_levels_grouped_rounds_down() {
+        let bids = ladder(
+            OrderSide::Buy,
+            &[("10.05", "1"), ("10.00", "2"), ("9.95", "5"), ("9.85", "1")],
+        );
+
+        let levels = aggregate_levels(&bids, 10, Some(Price::from("0.1")));
+
+        assert_eq!(levels.len(), 3);
+        assert_eq!(levels[0].price, Price::from("10.0"));
+        assert_eq!(levels[0].size, Quantity::from("3"));
+        assert_eq!(levels[0].count, 2);
+        assert_eq!(levels[1].price, Price::from("9.9"));
+        assert_eq!(levels[2].price, Price::from("9.8"));
+    }
+
+    #[rstest]
+    fn test_aggregate_ask_levels_grouped_rounds_up() {
+        let asks = ladder(
+            OrderSide::Sell,
+            &[
+                ("10.00", "1"),
+                ("10.05", "2"),
+                ("10.10", "5"),
+                ("10.15", "1"),
+            ],
+        );
+
+        let levels = aggregate_levels(&asks, 2, Some(Price::from("0.1")));
+
+        assert_eq!(levels.len(), 2);
+        assert_eq!(levels[0].price, Price::from("10.0"));
+        assert_eq!(levels[0].size, Quantity::from("1"));
+        assert_eq!(levels[0].count, 1);
+        assert_eq!(levels[1].price, Price::from("10.1"));
+        assert_eq!(levels[1].size, Quantity::from("7"));
+        assert_eq!(levels[1].count, 2);
+    }
+}
@@ -0,0 +1,216 @@
+// -------------------------------------------------------------------------------------------------
+//  Copyright (C) 2015-2024 Nautech Systems Pty Ltd. All rights reserved.
+//  https://nautechsystems.io
+//
+//  Licensed under the GNU Lesser General Public License Version 3.0 (the "License");
+//  You may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at https://www.gnu.org/licenses/lgpl-3.0.en.html
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+// -------------------------------------------------------------------------------------------------
+
+use crate::{
+    orderbook::ladder::Ladder,
+    types::{fixed::FIXED_SCALAR, price::Price, quantity::Quantity},
+};
+
+/// Returns the imbalance of resting size over the top `depth` levels of each side.
+///
+/// The value is in the range [-1.0, 1.0], where positive values indicate more bid size than ask
+/// size. Returns `None` if neither side has any size.
+#[must_use]
+pub fn book_imbalance(bids: &Ladder, asks: &Ladder, depth: usize) -> Option<f64> {
+    let bid_size_raw = size_raw_to_depth(bids, depth);
+    let ask_size_raw = size_raw_to_depth(asks, depth);
+    let total_raw = bid_size_raw + ask_size_raw;
+    if total_raw == 0 {
+        return None;
+    }
+
+    Some((bid_size_raw as f64 - ask_size_raw as f64) / total_raw as f64)
+}
+
+/// Returns the microprice of the top levels, being the mid weighted towards the side with less
+/// resting size (`None` if either side is empty).
+#[must_use]
+pub fn microprice(bids: &Ladder, asks: &Ladder) -> Option<f64> {
+    let bid = bids.top()?;
+    let ask = asks.top()?;
+    let bid_size_raw = i128::from(bid.size_raw());
+    let ask_size_raw = i128::from(ask.size_raw());
+    let total_raw = bid_size_raw + ask_size_raw;
+    if total_raw == 0 {
+        return None;
+    }
+
+    let weighted_raw = i128::from(bid.price.value.raw) * ask_size_raw
+        + i128::from(ask.price.value.raw) * bid_size_raw;
+    Some(weighted_raw as f64 / total_raw as f64 / FIXED_SCALAR)
+}
+
+/// Returns the cumulative size at each of the top `depth` levels of the `ladder`, starting from
+/// the top of book.
+#[must_use]
+pub fn depth_curve(ladder: &Ladder, depth: usize) -> Vec<(Price, Quantity)> {
+    let mut cumulative_raw = 0u64;
+    ladder
+        .levels
+        .values()
+        .take(depth)
+        .filter_map(|level| {
+            let precision = level.first()?.size.precision;
+            cumulative_raw += level.size_raw();
+            let size = Quantity::from_raw(cumulative_raw, precision).ok()?;
+            Some((level.price.value, size))
+        })
+        .collect()
+}
+
+/// Returns the volume-weighted average price over the top `depth` levels of the `ladder`
+/// (`None` if the levels have no size).
+#[must_use]
+pub fn vwap_to_depth(ladder: &Ladder, depth: usize) -> Option<f64> {
+    let mut size_raw = 0i128;
+    let mut value_raw = 0i128;
+    for level in ladder.levels.values().take(depth) {
+        let level_size_raw = i128::from(level.size_raw());
+        size_raw += level_size_raw;
+        value_raw += i128::from(level.price.value.raw) * level_size_raw;
+    }
+
+    if size_raw == 0 {
+        return None;
+    }
+
+    Some(value_raw as f64 / size_raw as f64 / FIXED_SCALAR)
+}
+
+/// Returns the worst price reached when taking `notional` (in quote currency) from the `ladder`,
+/// or `None` if the ladder does not hold enough notional.
+#[must_use]
+pub fn price_for_notional(ladder: &Ladder, notional: f64) -> Option<Price> {
+    let scalar = FIXED_SCALAR as i128;
+    let target_raw = (notional * FIXED_SCALAR) as i128;
+    let mut cumulative_raw = 0i128;
+    for level in ladder.levels.values() {
+        cumulative_raw += i128::from(level.price.value.raw) * i128::from(level.size_raw()) / scalar;
+        if cumulative_raw >= target_raw {
+            return Some(level.price.value);
+        }
+    }
+
+    None
+}
+
+fn size_raw_to_depth(ladder: &Ladder, depth: usize) -> u64 {
+    ladder
+        .levels
+        .values()
+        .take(depth)
+        .map(|level| level.size_raw())
+        .sum()
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Tests
+////////////////////////////////////////////////////////////////////////////////
+#[cfg(test)]
+mod tests {
+    use rstest::{fixture, rstest};
+
+    use super::*;
+    use crate::{data::order::BookOrder, enums::OrderSide};
+
+    #[fixture]
+    fn bids() -> Ladder {
+        let mut ladder = Ladder::new(OrderSide::Buy);
+        ladder.add_bulk(vec![
+            BookOrder::new(OrderSide::Buy, Price::from("10.00"), Quantity::from(30), 1),
+            BookOrder::new(OrderSide::Buy, Price::from("9.00"), Quantity::from(20), 2),
+            BookOrder::new(OrderSide::Buy, Price::from("9.00"), Quantity::from(30), 3),
+            BookOrder::new(OrderSide::Buy, Price::from("8.00"), Quantity::from(100), 4),
+        ]);
+        ladder
+    }
+
+    #[fixture]
+    fn asks() -> Ladder {
+        let mut ladder = Ladder::new(OrderSide::Sell);
+        ladder.add_bulk(vec![
+            BookOrder::new(OrderSide::Sell, Price::from("11.00"), Quantity::from(10), 5),
+            BookOrder::new(OrderSide::Sell, Price::from("12.00"), Quantity::from(30), 6),
+            BookOrder::new(OrderSide::Sell, Price::from("13.00"), Quantity::from(60), 7),
+        ]);
+        ladder
+    }
+
+    #[rstest]
+    fn test_book_imbalance_when_empty() {
+        let bids = Ladder::new(OrderSide::Buy);
+        let asks = Ladder::new(OrderSide::Sell);
+        assert_eq!(book_imbalance(&bids, &asks, 5), None);
+    }
+
+    #[rstest]
+    #[case(1, 0.5)] // 30 vs 10
+    #[case(2, 1.0 / 3.0)] // 80 vs 40
+    #[case(10, 2.0 / 7.0)] // 180 vs 100
+    fn test_book_imbalance(
+        bids: Ladder,
+        asks: Ladder,
+        #[case] depth: usize,
+        #[case] expected: f64,
+    ) {
+        let imbalance = book_imbalance(&bids, &asks, depth).unwrap();
+        assert!((imbalance - expected).abs() < 1e-12);
+    }
+
+    #[rstest]
+    fn test_microprice(bids: Ladder, asks: Ladder) {
+        // (10.00 * 10 + 11.00 * 30) / 40
+        assert_eq!(microprice(&bids, &asks), Some(10.75));
+    }
+
+    #[rstest]
+    fn test_microprice_with_empty_side(bids: Ladder) {
+        let asks = Ladder::new(OrderSide::Sell);
+        assert_eq!(microprice(&bids, &asks), None);
+    }
+
+    #[rstest]
+    fn test_depth_curve(bids: Ladder) {
+        let curve = depth_curve(&bids, 2);
+        assert_eq!(
+            curve,
+            vec![
+                (Price::from("10.00"), Quantity::from(30)),
+                (Price::from("9.00"), Quantity::from(80)),
+            ]
+        );
+    }
+
+    #[rstest]
+    fn test_vwap_to_depth(asks: Ladder) {
+        // (11.00 * 10 + 12.00 * 30) / 40
+        assert_eq!(vwap_to_depth(&asks, 2), Some(11.75));
+        assert_eq!(vwap_to_depth(&Ladder::new(OrderSide::Sell), 2), None);
+    }
+
+    #[rstest]
+    #[case(50.0, Some(Price::from("11.00")))]
+    #[case(110.0, Some(Price::from("11.00")))]
+    #[case(110.01, Some(Price::from("12.00")))]
+    #[case(1250.0, Some(Price::from("13.00")))]
+    #[case(1250.01, None)]
+    fn test_price_for_notional(
+        asks: Ladder,
+        #[case] notional: f64,
+        #[case] expected: Option<Price>,
+    ) {
+        assert_eq!(price_for_notional(&asks, notional), expected);
+    }
+}
@@ -17,13 +17,22 @@ use nautilus_core::time::UnixNanos;
 use tabled::{settings::Style, Table, Tabled};
 use thiserror::Error;
 
-use super::{ladder::BookPrice, level::Level};
+use super::{
+    aggregation::{self, AggregatedLevel},
+    analysis,
+    ladder::BookPrice,
+    level::Level,
+};
 use crate::{
     data::{
-        delta::OrderBookDelta, depth::OrderBookDepth10, order::BookOrder, quote::QuoteTick,
+        delta::OrderBookDelta,
+        deltas::OrderBookDeltas,
+        depth::{OrderBookDepth10, DEPTH10_LEN},
+        order::{BookOrder, NULL_ORDER},
+        quote::QuoteTick,
         trade::TradeTick,
     },
-    enums::{BookAction, BookType, OrderSide},
+    enums::{BookAction, BookType, OrderSide, RecordFlag, RecordFlags},
     identifiers::instrument_id::InstrumentId,
     orderbook::ladder::Ladder,
     types::{price::Price, quantity::Quantity},
@@ -49,6 +58,31 @@ pub enum BookIntegrityError {
     TooManyOrders(OrderSide, usize),
     #[error("Integrity error: number of {0} levels > 1 for L1_MBP book, was {1}")]
     TooManyLevels(OrderSide, usize),
+    #[error("Integrity error: sequence gap: expected={0}, received={1}")]
+    SequenceGap(u64, u64),
+}
+
+/// The policy for handling an order which would cross or lock the book when added or updated.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CrossedBookPolicy {
+    /// Apply the order and flag the book as crossed.
+    #[default]
+    Allow,
+    /// Ignore the order, leaving the book uncrossed.
+    Reject,
+    /// Remove the stale opposite levels the order crosses, then apply the order.
+    RemoveStale,
+}
+
+/// Provides counts of crossed or locked orders handled by the book.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CrossedBookStats {
+    /// The number of added or updated orders which crossed or locked the book.
+    pub crossed: u64,
+    /// The number of crossing orders rejected.
+    pub rejected: u64,
+    /// The number of stale opposite levels removed.
+    pub levels_removed: u64,
 }
 
 #[derive(Tabled)]
@@ -67,6 +101,18 @@ pub struct OrderBook {
     pub sequence: u64,
     pub ts_last: UnixNanos,
     pub count: u64,
+    /// If sequence numbers of applied deltas should be checked for gaps.
+    pub check_sequence: bool,
+    /// If the derived top-of-book only updates once a delta with `F_LAST` completes a batch.
+    pub wait_for_last: bool,
+    /// The policy for orders which would cross or lock the book.
+    pub crossed_policy: CrossedBookPolicy,
+    pub(super) crossed_stats: CrossedBookStats,
+    pub(super) awaiting_snapshot: bool,
+    pub(super) in_snapshot: bool,
+    pub(super) buffered: Vec<OrderBookDelta>,
+    pub(super) batch_pending: bool,
+    pub(super) top_of_book: Option<QuoteTick>,
 }
 
 impl OrderBook {
@@ -80,6 +126,15 @@ impl OrderBook {
             sequence: 0,
             ts_last: 0,
             count: 0,
+            check_sequence: false,
+            wait_for_last: false,
+            crossed_policy: CrossedBookPolicy::default(),
+            crossed_stats: CrossedBookStats::default(),
+            awaiting_snapshot: false,
+            in_snapshot: false,
+            buffered: Vec::new(),
+            batch_pending: false,
+            top_of_book: None,
         }
     }
 
@@ -89,6 +144,12 @@ impl OrderBook {
         self.sequence = 0;
         self.ts_last = 0;
         self.count = 0;
+        self.crossed_stats = CrossedBookStats::default();
+        self.awaiting_snapshot = false;
+        self.in_snapshot = false;
+        self.buffered.clear();
+        self.batch_pending = false;
+        self.top_of_book = None;
     }
 
     pub fn add(&mut self, order: BookOrder, ts_event: u64, sequence: u64) {
@@ -98,29 +159,38 @@ impl OrderBook {
             BookType::L1_MBP => panic!("{}", InvalidBookOperation::Add(self.book_type)),
         };
 
-        match order.side {
-            OrderSide::Buy => self.bids.add(order),
-            OrderSide::Sell => self.asks.add(order),
-            _ => panic!("{}", BookIntegrityError::NoOrderSide),
+        if self.handle_crossing(&order) {
+            match order.side {
+                OrderSide::Buy => self.bids.add(order),
+                OrderSide::Sell => self.asks.add(order),
+                _ => panic!("{}", BookIntegrityError::NoOrderSide),
+            }
         }
 
+        // A rejected order still advances the sequence, as the update was processed
         self.increment(ts_event, sequence);
     }
 
     pub fn update(&mut self, order: BookOrder, ts_event: u64, sequence: u64) {
+        // Checked first so an L1_MBP book does not clear its opposite side for a rejected order
+        let accepted = self.handle_crossing(&order);
         let order = match self.book_type {
             BookType::L3_MBO => order, // No order pre-processing
             BookType::L2_MBP => self.pre_process_order(order),
             BookType::L1_MBP => {
-                self.update_l1(order, ts_event, sequence);
+                if accepted {
+                    self.update_l1(order, ts_event, sequence);
+                }
                 self.pre_process_order(order)
             }
         };
 
-        match order.side {
-            OrderSide::Buy => self.bids.update(order),
-            OrderSide::Sell => self.asks.update(order),
-            _ => panic!("{}", BookIntegrityError::NoOrderSide),
+        if accepted {
+            match order.side {
+                OrderSide::Buy => self.bids.update(order),
+                OrderSide::Sell => self.asks.update(order),
+                _ => panic!("{}", BookIntegrityError::NoOrderSide),
+            }
         }
 
         self.increment(ts_event, sequence);
@@ -158,26 +228,148 @@ impl OrderBook {
         self.increment(ts_event, sequence);
     }
 
-    pub fn apply_delta(&mut self, delta: OrderBookDelta) {
-        match delta.action {
-            BookAction::Add => self.add(delta.order, delta.ts_event, delta.sequence),
-            BookAction::Update => self.update(delta.order, delta.ts_event, delta.sequence),
-            BookAction::Delete => self.delete(delta.order, delta.ts_event, delta.sequence),
-            BookAction::Clear => self.clear(delta.ts_event, delta.sequence),
+    /// Applies the given `delta` to the book.
+    ///
+    /// When `check_sequence` is enabled a delta whose sequence skips ahead of the last applied
+    /// sequence is buffered (along with all following deltas) until a snapshot arrives. A
+    /// snapshot starts with a `Clear` delta and ends with the delta carrying the `F_LAST` flag,
+    /// after which the buffered deltas newer than the snapshot are replayed. Outside of a
+    /// snapshot, a delta whose sequence was already applied is dropped, unless it shares the
+    /// sequence of a batch still awaiting its `F_LAST` delta. Deltas with a zero sequence are
+    /// never checked.
+    ///
+    /// Snapshot mode is only left on the next `F_LAST` delta, or at the end of the batch when
+    /// applied through [`OrderBook::apply_deltas`].
+    ///
+    /// # Errors
+    ///
+    /// If a sequence gap is detected, in which case the book is left awaiting a snapshot.
+    pub fn apply_delta(&mut self, delta: OrderBookDelta) -> Result<(), BookIntegrityError> {
+        if !self.check_sequence {
+            self.apply_delta_unchecked(delta);
+            return Ok(());
         }
+
+        if self.awaiting_snapshot && delta.action != BookAction::Clear {
+            self.buffered.push(delta);
+            return Ok(());
+        }
+
+        if delta.action == BookAction::Clear {
+            self.awaiting_snapshot = false;
+            self.in_snapshot = true;
+        } else if !self.in_snapshot {
+            if self.is_stale(delta.sequence) {
+                return Ok(());
+            }
+            if self.is_sequence_gap(delta.sequence) {
+                let expected = self.sequence + 1;
+                self.awaiting_snapshot = true;
+                self.buffered.push(delta);
+                return Err(BookIntegrityError::SequenceGap(expected, delta.sequence));
+            }
+        }
+
+        self.apply_delta_unchecked(delta);
+
+        if self.in_snapshot && RecordFlag::F_LAST.matches(delta.flags) {
+            return self.complete_snapshot();
+        }
+
+        Ok(())
+    }
+
+    /// Applies all deltas in the given batch to the book.
+    ///
+    /// A batch which starts with a `Clear` delta is treated as a complete snapshot, even if its
+    /// last delta does not carry the `F_LAST` flag.
+    ///
+    /// # Errors
+    ///
+    /// If a sequence gap is detected (see [`OrderBook::apply_delta`]), returning the first gap.
+    pub fn apply_deltas(&mut self, deltas: OrderBookDeltas) -> Result<(), BookIntegrityError> {
+        let mut result = Ok(());
+        for delta in deltas.deltas {
+            if let Err(e) = self.apply_delta(delta) {
+                if result.is_ok() {
+                    result = Err(e);
+                }
+            }
+        }
+
+        // Also ends a snapshot started by an earlier `Clear` which never saw its `F_LAST`
+        if self.in_snapshot {
+            if let Err(e) = self.complete_snapshot() {
+                if result.is_ok() {
+                    result = Err(e);
+                }
+            }
+        }
+
+        result
+    }
+
+    /// Returns whether the book detected a sequence gap and is waiting for a snapshot.
+    #[must_use]
+    pub fn is_awaiting_snapshot(&self) -> bool {
+        self.awaiting_snapshot
+    }
+
+    /// Returns the number of deltas buffered while awaiting a snapshot.
+    #[must_use]
+    pub fn buffered_count(&self) -> usize {
+        self.buffered.len()
     }
 
     pub fn apply_depth(&mut self, depth: OrderBookDepth10) {
         self.bids.clear();
         self.asks.clear();
 
-        for order in depth.bids {
+        // Unpopulated levels of the depth snapshot have no size
+        for order in depth.bids.into_iter().filter(|o| o.size.is_positive()) {
             self.add(order, depth.ts_event, depth.sequence);
         }
 
-        for order in depth.asks {
+        for order in depth.asks.into_iter().filter(|o| o.size.is_positive()) {
             self.add(order, depth.ts_event, depth.sequence);
         }
+
+        self.complete_batch(depth.ts_event);
+    }
+
+    /// Returns whether deltas have been applied since the last delta carrying `F_LAST`, in which
+    /// case the book may be in a transient state part way through a venue event batch.
+    #[must_use]
+    pub fn is_batch_pending(&self) -> bool {
+        self.batch_pending
+    }
+
+    /// Returns the top-of-book as a quote (if both sides have a level).
+    ///
+    /// When `wait_for_last` is set this is the top-of-book as at the end of the last complete
+    /// event batch, otherwise it reflects the current state of the book.
+    #[must_use]
+    pub fn top_of_book(&self) -> Option<QuoteTick> {
+        if self.wait_for_last {
+            self.top_of_book
+        } else {
+            self.derive_top_of_book(self.ts_last)
+        }
+    }
+
+    /// Returns whether the best bid is at or above the best ask.
+    #[must_use]
+    pub fn is_crossed(&self) -> bool {
+        match (self.best_bid_price(), self.best_ask_price()) {
+            (Some(bid), Some(ask)) => bid >= ask,
+            _ => false,
+        }
+    }
+
+    /// Returns the counts of crossed or locked orders handled since the book was created or reset.
+    #[must_use]
+    pub fn crossed_stats(&self) -> CrossedBookStats {
+        self.crossed_stats
     }
 
     pub fn bids(&self) -> Vec<&Level> {
@@ -188,6 +380,17 @@ impl OrderBook {
         self.asks.levels.values().collect()
     }
 
+    /// Returns the price level on the `side` of the book at the given `price` (if it exists).
+    #[must_use]
+    pub fn get_level(&self, side: OrderSide, price: Price) -> Option<&Level> {
+        let book_price = BookPrice::new(price, side);
+        match side {
+            OrderSide::Buy => self.bids.levels.get(&book_price),
+            OrderSide::Sell => self.asks.levels.get(&book_price),
+            _ => panic!("{}", BookIntegrityError::NoOrderSide),
+        }
+    }
+
     pub fn has_bid(&self) -> bool {
         match self.bids.top() {
             Some(top) => !top.orders.is_empty(),
@@ -293,17 +496,110 @@ impl OrderBook {
         matched_size
     }
 
+    /// Returns the imbalance of resting size over the top `depth` levels of each side, in the
+    /// range [-1.0, 1.0] where positive values indicate more bid size (if the book has size).
+    #[must_use]
+    pub fn imbalance(&self, depth: usize) -> Option<f64> {
+        analysis::book_imbalance(&self.bids, &self.asks, depth)
+    }
+
+    /// Returns the size-weighted microprice of the top of book (if both sides have a level).
+    #[must_use]
+    pub fn microprice(&self) -> Option<f64> {
+        analysis::microprice(&self.bids, &self.asks)
+    }
+
+    /// Returns the cumulative size at each of the top `depth` levels on the `side` of the book.
+    #[must_use]
+    pub fn depth_curve(&self, side: OrderSide, depth: usize) -> Vec<(Price, Quantity)> {
+        analysis::depth_curve(self.ladder(side), depth)
+    }
+
+    /// Returns the volume-weighted average price over the top `depth` levels on the `side` of
+    /// the book (if the levels have size).
+    #[must_use]
+    pub fn vwap_to_depth(&self, side: OrderSide, depth: usize) -> Option<f64> {
+        analysis::vwap_to_depth(self.ladder(side), depth)
+    }
+
+    /// Returns the worst price an order on `order_side` would reach when filling `notional` (in
+    /// quote currency), or `None` if the opposite side of the book does not hold enough notional.
+    #[must_use]
+    pub fn price_for_notional(&self, notional: f64, order_side: OrderSide) -> Option<Price> {
+        match order_side {
+            OrderSide::Buy => analysis::price_for_notional(&self.asks, notional),
+            OrderSide::Sell => analysis::price_for_notional(&self.bids, notional),
+            _ => panic!("Invalid `OrderSide` {}", order_side),
+        }
+    }
+
+    /// Returns the top `depth` levels on the `side` of the book aggregated by price, with the
+    /// number of orders at each level, optionally grouping prices into buckets of `group_size`.
+    #[must_use]
+    pub fn aggregated_levels(
+        &self,
+        side: OrderSide,
+        depth: usize,
+        group_size: Option<Price>,
+    ) -> Vec<AggregatedLevel> {
+        aggregation::aggregate_levels(self.ladder(side), depth, group_size)
+    }
+
+    /// Returns a market-by-price snapshot of the top ten aggregated levels on each side of the
+    /// book, optionally grouping prices into buckets of `group_size`.
+    ///
+    /// Levels beyond the depth of the book are left empty (with no side and zero size).
+    #[must_use]
+    pub fn to_depth10(&self, group_size: Option<Price>, ts_init: UnixNanos) -> OrderBookDepth10 {
+        let mut bids = [NULL_ORDER; DEPTH10_LEN];
+        let mut asks = [NULL_ORDER; DEPTH10_LEN];
+        let mut bid_counts = [0; DEPTH10_LEN];
+        let mut ask_counts = [0; DEPTH10_LEN];
+
+        let bid_levels = self.aggregated_levels(OrderSide::Buy, DEPTH10_LEN, group_size);
+        for (i, level) in bid_levels.iter().enumerate() {
+            bids[i] = level.to_book_order();
+            bid_counts[i] = level.count;
+        }
+
+        let ask_levels = self.aggregated_levels(OrderSide::Sell, DEPTH10_LEN, group_size);
+        for (i, level) in ask_levels.iter().enumerate() {
+            asks[i] = level.to_book_order();
+            ask_counts[i] = level.count;
+        }
+
+        OrderBookDepth10::new(
+            self.instrument_id,
+            bids,
+            asks,
+            bid_counts,
+            ask_counts,
+            (RecordFlags::F_MBP | RecordFlags::F_LAST).bits(),
+            self.sequence,
+            self.ts_last,
+            ts_init,
+        )
+    }
+
+    /// Replaces the top order on each side of the book with the bid and ask of the `tick`.
+    ///
+    /// Both top orders are removed before the crossed book policy is applied, so the bid is
+    /// checked against the asks left in the book and the ask against the book including the
+    /// new bid.
     pub fn update_quote_tick(&mut self, tick: &QuoteTick) {
-        self.update_bid(
-            BookOrder::from_quote_tick(tick, OrderSide::Buy),
-            tick.ts_event,
-            0,
-        );
-        self.update_ask(
-            BookOrder::from_quote_tick(tick, OrderSide::Sell),
-            tick.ts_event,
-            0,
-        );
+        self.remove_top(OrderSide::Buy, tick.ts_event, 0);
+        self.remove_top(OrderSide::Sell, tick.ts_event, 0);
+
+        let bid = BookOrder::from_quote_tick(tick, OrderSide::Buy);
+        if self.handle_crossing(&bid) {
+            self.bids.add(bid);
+        }
+        let ask = BookOrder::from_quote_tick(tick, OrderSide::Sell);
+        if self.handle_crossing(&ask) {
+            self.asks.add(ask);
+        }
+
+        self.complete_batch(tick.ts_event);
     }
 
     pub fn update_trade_tick(&mut self, tick: &TradeTick) {
@@ -317,6 +613,7 @@ impl OrderBook {
             tick.ts_event,
             0,
         );
+        self.complete_batch(tick.ts_event);
     }
 
     pub fn simulate_fills(&self, order: &BookOrder) -> Vec<(Price, Quantity)> {
@@ -442,6 +739,117 @@ impl OrderBook {
         Ok(())
     }
 
+    /// Applies the crossed book policy to the `order`, returning whether it should be applied.
+    ///
+    /// Note that the policy sees each order individually, so deltas within a venue event batch
+    /// which only transiently cross the book are also handled by the policy.
+    fn handle_crossing(&mut self, order: &BookOrder) -> bool {
+        let is_crossing = order.size.is_positive()
+            && match order.side {
+                OrderSide::Buy => self
+                    .best_ask_price()
+                    .map_or(false, |ask| order.price >= ask),
+                OrderSide::Sell => self
+                    .best_bid_price()
+                    .map_or(false, |bid| order.price <= bid),
+                _ => false,
+            };
+        if !is_crossing {
+            return true;
+        }
+
+        self.crossed_stats.crossed += 1;
+        match self.crossed_policy {
+            CrossedBookPolicy::Allow => true,
+            CrossedBookPolicy::Reject => {
+                self.crossed_stats.rejected += 1;
+                false
+            }
+            CrossedBookPolicy::RemoveStale => {
+                let removed = match order.side {
+                    OrderSide::Buy => self.asks.remove_crossed_levels(order.price),
+                    _ => self.bids.remove_crossed_levels(order.price),
+                };
+                self.crossed_stats.levels_removed += removed as u64;
+                true
+            }
+        }
+    }
+
+    fn ladder(&self, side: OrderSide) -> &Ladder {
+        match side {
+            OrderSide::Buy => &self.bids,
+            OrderSide::Sell => &self.asks,
+            _ => panic!("{}", BookIntegrityError::NoOrderSide),
+        }
+    }
+
+    fn apply_delta_unchecked(&mut self, delta: OrderBookDelta) {
+        match delta.action {
+            BookAction::Add => self.add(delta.order, delta.ts_event, delta.sequence),
+            BookAction::Update => self.update(delta.order, delta.ts_event, delta.sequence),
+            BookAction::Delete => self.delete(delta.order, delta.ts_event, delta.sequence),
+            BookAction::Clear => self.clear(delta.ts_event, delta.sequence),
+        }
+
+        if RecordFlag::F_LAST.matches(delta.flags) {
+            self.complete_batch(delta.ts_event);
+        } else {
+            self.batch_pending = true;
+        }
+    }
+
+    fn complete_batch(&mut self, ts_event: UnixNanos) {
+        self.batch_pending = false;
+        if self.wait_for_last {
+            self.top_of_book = self.derive_top_of_book(ts_event);
+        }
+    }
+
+    fn derive_top_of_book(&self, ts_event: UnixNanos) -> Option<QuoteTick> {
+        QuoteTick::new(
+            self.instrument_id,
+            self.best_bid_price()?,
+            self.best_ask_price()?,
+            self.best_bid_size()?,
+            self.best_ask_size()?,
+            ts_event,
+            ts_event,
+        )
+        .ok()
+    }
+
+    fn is_sequence_gap(&self, sequence: u64) -> bool {
+        // Deltas within the same venue event may share a sequence, so only skipping ahead
+        // is a gap (a zero sequence on either side means there is nothing to check against)
+        sequence != 0 && self.sequence != 0 && sequence > self.sequence + 1
+    }
+
+    fn is_stale(&self, sequence: u64) -> bool {
+        if sequence == 0 {
+            return false;
+        }
+        sequence < self.sequence || (sequence == self.sequence && !self.batch_pending)
+    }
+
+    fn complete_snapshot(&mut self) -> Result<(), BookIntegrityError> {
+        self.in_snapshot = false;
+
+        let mut buffered = std::mem::take(&mut self.buffered).into_iter();
+        for delta in buffered.by_ref() {
+            if delta.sequence != 0 && delta.sequence <= self.sequence {
+                continue; // Already reflected in the snapshot
+            }
+            if let Err(e) = self.apply_delta(delta) {
+                // Awaiting another snapshot, so keep the rest for the next replay
+                self.buffered.extend(buffered);
+                return Err(e);
+            }
+        }
+
+        Ok(())
+    }
+
     fn increment(&mut self, ts_event: u64, sequence: u64) {
         self.ts_last = ts_event;
         self.sequence = sequence;
@@ -473,6 +881,21 @@ impl OrderBook {
         }
     }
 
+    fn remove_top(&mut self, side: OrderSide, ts_event: u64, sequence: u64) {
+        let ladder = match side {
+            OrderSide::Buy => &mut self.bids,
+            OrderSide::Sell => &mut self.asks,
+            _ => panic!("{}", BookIntegrityError::NoOrderSide),
+        };
+        let top_order_id = ladder
+            .top()
+            .and_then(|level| level.first())
+            .map(|order| order.order_id);
+        if let Some(order_id) = top_order_id {
+            ladder.remove(order_id, ts_event, sequence);
+        }
+    }
+
     fn update_bid(&mut self, order: BookOrder, ts_event: u64, sequence: u64) {
         match self.bids.top() {
             Some(top_bids) => match top_bids.first() {
@@ -545,6 +968,29 @@ mod tests {
         OrderBook::new(instrument_id, book_type)
     }
 
+    fn create_delta(side: OrderSide, price: &str, order_id: u64, sequence: u64) -> OrderBookDelta {
+        let order = BookOrder::new(side, Price::from(price), Quantity::from("1.0"), order_id);
+        OrderBookDelta::new(
+            InstrumentId::from("ETHUSDT-PERP.BINANCE"),
+            BookAction::Add,
+            order,
+            0,
+            sequence,
+            sequence,
+            sequence,
+        )
+    }
+
+    fn create_sequenced_book() -> OrderBook {
+        let mut book = create_stub_book(BookType::L3_MBO);
+        book.check_sequence = true;
+        book.apply_delta(create_delta(OrderSide::Buy, "1.000", 1, 1))
+            .unwrap();
+        book.apply_delta(create_delta(OrderSide::Sell, "2.000", 2, 2))
+            .unwrap();
+        book
+    }
+
     #[rstest]
     fn test_orderbook_creation() {
         let instrument_id = InstrumentId::from("ETHUSDT-PERP.BINANCE");
@@ -920,4 +1366,526 @@ mod tests {
         println!("{}", pprint_output);
         assert_eq!(pprint_output, expected_output);
     }
+
+    #[rstest]
+    fn test_apply_delta_with_gap_when_sequence_not_checked() {
+        let mut book = create_stub_book(BookType::L3_MBO);
+        book.apply_delta(create_delta(OrderSide::Buy, "1.000", 1, 1))
+            .unwrap();
+        book.apply_delta(create_delta(OrderSide::Buy, "1.500", 2, 5))
+            .unwrap();
+
+        assert_eq!(book.sequence, 5);
+        assert_eq!(book.best_bid_price(), Some(Price::from("1.500")));
+        assert!(!book.is_awaiting_snapshot());
+    }
+
+    #[rstest]
+    fn test_apply_delta_with_shared_sequence_is_not_gap() {
+        let mut book = create_sequenced_book();
+        book.apply_delta(create_delta(OrderSide::Buy, "1.500", 3, 2))
+            .unwrap();
+        book.apply_delta(create_delta(OrderSide::Buy, "1.600", 4, 3))
+            .unwrap();
+
+        assert_eq!(book.sequence, 3);
+        assert_eq!(book.best_bid_price(), Some(Price::from("1.600")));
+    }
+
+    #[rstest]
+    fn test_apply_delta_drops_already_applied_sequence() {
+        let mut book = create_sequenced_book();
+        let mut last = create_delta(OrderSide::Buy, "1.200", 3, 3);
+        last.flags = RecordFlag::F_LAST as u8;
+        book.apply_delta(last).unwrap();
+
+        book.apply_delta(create_delta(OrderSide::Buy, "1.500", 4, 3))
+            .unwrap();
+        book.apply_delta(create_delta(OrderSide::Buy, "1.600", 5, 1))
+            .unwrap();
+
+        assert_eq!(book.sequence, 3);
+        assert_eq!(book.bids().len(), 2);
+        assert_eq!(book.best_bid_price(), Some(Price::from("1.200")));
+    }
+
+    #[rstest]
+    fn test_apply_delta_with_gap_buffers_until_snapshot() {
+        let mut book = create_sequenced_book();
+
+        let result = book.apply_delta(create_delta(OrderSide::Buy, "1.500", 3, 4));
+        book.apply_delta(create_delta(OrderSide::Buy, "1.600", 4, 5))
+            .unwrap();
+
+        assert!(matches!(result, Err(BookIntegrityError::SequenceGap(3, 4))));
+        assert!(book.is_awaiting_snapshot());
+        assert_eq!(book.buffered_count(), 2);
+        assert_eq!(book.sequence, 2);
+        assert_eq!(book.best_bid_price(), Some(Price::from("1.000")));
+    }
+
+    #[rstest]
+    fn test_apply_deltas_snapshot_replays_buffered_deltas() {
+        let mut book = create_sequenced_book();
+        let _ = book.apply_delta(create_delta(OrderSide::Buy, "1.500", 3, 4));
+        book.apply_delta(create_delta(OrderSide::Buy, "1.600", 4, 5))
+            .unwrap();
+
+        let instrument_id = book.instrument_id;
+        let snapshot = OrderBookDeltas::new(
+            instrument_id,
+            vec![
+                OrderBookDelta::clear(instrument_id, 4, 4, 4),
+                create_delta(OrderSide::Buy, "1.500", 3, 4),
+                create_delta(OrderSide::Sell, "2.500", 5, 4),
+            ],
+            0,
+            4,
+            4,
+            4,
+        );
+        book.apply_deltas(snapshot).unwrap();
+
+        assert!(!book.is_awaiting_snapshot());
+        assert_eq!(book.buffered_count(), 0);
+        assert_eq!(book.sequence, 5);
+        assert_eq!(book.bids().len(), 2);
+        assert_eq!(book.best_bid_price(), Some(Price::from("1.600")));
+        assert_eq!(book.best_ask_price(), Some(Price::from("2.500")));
+    }
+
+    #[rstest]
+    fn test_apply_delta_snapshot_completes_on_last_flag() {
+        let mut book = create_sequenced_book();
+        let _ = book.apply_delta(create_delta(OrderSide::Buy, "1.500", 3, 10));
+        book.apply_delta(create_delta(OrderSide::Buy, "1.600", 4, 11))
+            .unwrap();
+
+        book.apply_delta(OrderBookDelta::clear(book.instrument_id, 10, 10, 10))
+            .unwrap();
+        assert!(!book.is_awaiting_snapshot());
+        assert_eq!(book.buffered_count(), 2);
+
+        let mut last = create_delta(OrderSide::Sell, "2.500", 5, 10);
+        last.flags = RecordFlag::F_LAST as u8;
+        book.apply_delta(last).unwrap();
+
+        assert_eq!(book.buffered_count(), 0);
+        assert_eq!(book.sequence, 11);
+        assert_eq!(book.best_bid_price(), Some(Price::from("1.600")));
+        assert_eq!(book.best_ask_price(), Some(Price::from("2.500")));
+    }
+
+    #[rstest]
+    fn test_apply_deltas_snapshot_with_gap_in_buffer_awaits_again() {
+        let mut book = create_sequenced_book();
+        let _ = book.apply_delta(create_delta(OrderSide::Buy, "1.500", 3, 4));
+        book.apply_delta(create_delta(OrderSide::Buy, "1.600", 4, 8))
+            .unwrap();
+
+        let instrument_id = book.instrument_id;
+        let snapshot = OrderBookDeltas::new(
+            instrument_id,
+            vec![
+                OrderBookDelta::clear(instrument_id, 4, 4, 4),
+                create_delta(OrderSide::Buy, "1.500", 3, 4),
+            ],
+            0,
+            4,
+            4,
+            4,
+        );
+        let result = book.apply_deltas(snapshot);
+
+        assert!(matches!(result, Err(BookIntegrityError::SequenceGap(5, 8))));
+        assert!(book.is_awaiting_snapshot());
+        assert_eq!(book.buffered_count(), 1);
+        assert_eq!(book.sequence, 4);
+    }
+
+    #[rstest]
+    fn test_apply_deltas_ends_snapshot_started_without_last_flag() {
+        let mut book = create_sequenced_book();
+        let _ = book.apply_delta(create_delta(OrderSide::Buy, "1.500", 3, 4));
+        book.apply_delta(OrderBookDelta::clear(book.instrument_id, 6, 6, 6))
+            .unwrap();
+        assert!(book.in_snapshot);
+
+        let instrument_id = book.instrument_id;
+        let snapshot = OrderBookDeltas::new(
+            instrument_id,
+            vec![
+                OrderBookDelta::clear(instrument_id, 4, 4, 4),
+                create_delta(OrderSide::Buy, "1.500", 3, 4),
+            ],
+            0,
+            4,
+            4,
+            4,
+        );
+        book.apply_deltas(snapshot).unwrap();
+
+        assert!(!book.in_snapshot);
+        assert!(!book.is_awaiting_snapshot());
+        assert_eq!(book.buffered_count(), 0);
+        assert_eq!(book.sequence, 4);
+        assert_eq!(book.best_bid_price(), Some(Price::from("1.500")));
+    }
+
+    #[rstest]
+    fn test_reset_clears_snapshot_recovery_state() {
+        let mut book = create_sequenced_book();
+        let _ = book.apply_delta(create_delta(OrderSide::Buy, "1.500", 3, 4));
+
+        book.reset();
+
+        assert!(!book.is_awaiting_snapshot());
+        assert_eq!(book.buffered_count(), 0);
+    }
+
+    #[rstest]
+    fn test_top_of_book_when_not_waiting_for_last() {
+        let mut book = create_stub_book(BookType::L3_MBO);
+        book.apply_delta(create_delta(OrderSide::Buy, "1.000", 1, 1))
+            .unwrap();
+        book.apply_delta(create_delta(OrderSide::Sell, "2.000", 2, 2))
+            .unwrap();
+
+        let quote = book.top_of_book().unwrap();
+
+        assert!(book.is_batch_pending());
+        assert_eq!(quote.bid_price, Price::from("1.000"));
+        assert_eq!(quote.ask_price, Price::from("2.000"));
+        assert_eq!(quote.ts_event, 2);
+    }
+
+    #[rstest]
+    fn test_top_of_book_when_waiting_for_last_updates_on_batch_completion() {
+        let mut book = create_stub_book(BookType::L3_MBO);
+        book.wait_for_last = true;
+
+        let mut last = create_delta(OrderSide::Sell, "2.000", 2, 1);
+        last.flags = RecordFlag::F_LAST as u8;
+        book.apply_delta(create_delta(OrderSide::Buy, "1.000", 1, 1))
+            .unwrap();
+        book.apply_delta(last).unwrap();
+
+        assert!(!book.is_batch_pending());
+        assert_eq!(book.top_of_book().unwrap().bid_price, Price::from("1.000"));
+
+        let mut last = create_delta(OrderSide::Sell, "1.800", 4, 2);
+        last.flags = RecordFlag::F_LAST as u8;
+        book.apply_delta(create_delta(OrderSide::Buy, "1.500", 3, 2))
+            .unwrap();
+
+        assert!(book.is_batch_pending());
+        assert_eq!(book.top_of_book().unwrap().bid_price, Price::from("1.000"));
+
+        book.apply_delta(last).unwrap();
+        let quote = book.top_of_book().unwrap();
+
+        assert!(!book.is_batch_pending());
+        assert_eq!(quote.bid_price, Price::from("1.500"));
+        assert_eq!(quote.ask_price, Price::from("1.800"));
+        assert_eq!(quote.ts_event, 2);
+    }
+
+    #[rstest]
+    fn test_top_of_book_when_waiting_for_last_with_depth(stub_depth10: OrderBookDepth10) {
+        let mut book = create_stub_book(BookType::L2_MBP);
+        book.wait_for_last = true;
+
+        book.apply_depth(stub_depth10);
+        let quote = book.top_of_book().unwrap();
+
+        assert_eq!(quote.bid_price, Price::from("99.00"));
+        assert_eq!(quote.ask_price, Price::from("100.00"));
+    }
+
+    #[rstest]
+    fn test_analytics_when_book_empty() {
+        let book = create_stub_book(BookType::L2_MBP);
+
+        assert_eq!(book.imbalance(5), None);
+        assert_eq!(book.microprice(), None);
+        assert!(book.depth_curve(OrderSide::Buy, 5).is_empty());
+        assert_eq!(book.vwap_to_depth(OrderSide::Sell, 5), None);
+        assert_eq!(book.price_for_notional(100.0, OrderSide::Buy), None);
+    }
+
+    #[rstest]
+    fn test_analytics(stub_depth10: OrderBookDepth10) {
+        let mut book = create_stub_book(BookType::L2_MBP);
+        book.apply_depth(stub_depth10);
+
+        let curve = book.depth_curve(OrderSide::Sell, 2);
+
+        assert_eq!(book.imbalance(10), Some(0.0));
+        assert_eq!(book.microprice(), Some(99.5));
+        assert_eq!(curve[1], (Price::from("101.00"), Quantity::from("300")));
+        let vwap = book.vwap_to_depth(OrderSide::Buy, 2).unwrap();
+        assert!((vwap - 29_500.0 / 300.0).abs() < 1e-9);
+        assert_eq!(
+            book.price_for_notional(10_000.0, OrderSide::Buy),
+            Some(Price::from("100.00"))
+        );
+        assert_eq!(
+            book.price_for_notional(10_000.0, OrderSide::Sell),
+            Some(Price::from("98.00"))
+        );
+    }
+
+    #[rstest]
+    fn test_to_depth10_from_l3_book() {
+        let mut book = create_stub_book(BookType::L3_MBO);
+        book.apply_delta(create_delta(OrderSide::Buy, "1.000", 1, 1))
+            .unwrap();
+        book.apply_delta(create_delta(OrderSide::Buy, "1.000", 2, 2))
+            .unwrap();
+        book.apply_delta(create_delta(OrderSide::Buy, "0.900", 3, 3))
+            .unwrap();
+        book.apply_delta(create_delta(OrderSide::Sell, "1.100", 4, 4))
+            .unwrap();
+
+        let depth = book.to_depth10(None, 10);
+
+        assert_eq!(depth.instrument_id, book.instrument_id);
+        assert_eq!(depth.bids[0].price, Price::from("1.000"));
+        assert_eq!(depth.bids[0].size, Quantity::from("2.0"));
+        assert_eq!(depth.bids[1].price, Price::from("0.900"));
+        assert_eq!(depth.bids[2].side, OrderSide::NoOrderSide);
+        assert_eq!(depth.bid_counts[..3], [2, 1, 0]);
+        assert_eq!(depth.asks[0].price, Price::from("1.100"));
+        assert_eq!(depth.ask_counts[..2], [1, 0]);
+        assert_eq!(depth.sequence, 4);
+        assert_eq!(depth.ts_event, 4);
+        assert_eq!(depth.ts_init, 10);
+    }
+
+    #[rstest]
+    fn test_to_depth10_applies_to_l2_book() {
+        let mut book = create_stub_book(BookType::L3_MBO);
+        book.apply_delta(create_delta(OrderSide::Buy, "1.000", 1, 1))
+            .unwrap();
+        book.apply_delta(create_delta(OrderSide::Buy, "1.050", 2, 2))
+            .unwrap();
+        book.apply_delta(create_delta(OrderSide::Sell, "1.150", 3, 3))
+            .unwrap();
+
+        let mut l2_book = create_stub_book(BookType::L2_MBP);
+        l2_book.apply_depth(book.to_depth10(Some(Price::from("0.1")), 0));
+
+        assert_eq!(l2_book.bids().len(), 1);
+        assert_eq!(l2_book.asks().len(), 1);
+        assert_eq!(l2_book.best_bid_price(), Some(Price::from("1.0")));
+        assert_eq!(l2_book.best_bid_size(), Some(Quantity::from("2.0")));
+        assert_eq!(l2_book.best_ask_price(), Some(Price::from("1.2")));
+    }
+
+    fn create_asks_book(policy: CrossedBookPolicy) -> OrderBook {
+        let mut book = create_stub_book(BookType::L2_MBP);
+        book.crossed_policy = policy;
+        for (i, price) in ["1.500", "2.000", "2.500"].into_iter().enumerate() {
+            let order = BookOrder::new(
+                OrderSide::Sell,
+                Price::from(price),
+                Quantity::from("1.0"),
+                0,
+            );
+            book.add(order, i as u64 + 1, i as u64 + 1);
+        }
+        book
+    }
+
+    #[rstest]
+    fn test_crossing_order_when_allowed() {
+        let mut book = create_asks_book(CrossedBookPolicy::Allow);
+        let bid = BookOrder::new(
+            OrderSide::Buy,
+            Price::from("2.000"),
+            Quantity::from("1.0"),
+            0,
+        );
+
+        book.add(bid, 4, 4);
+
+        assert!(book.is_crossed());
+        assert_eq!(book.best_bid_price(), Some(Price::from("2.000")));
+        assert_eq!(book.asks().len(), 3);
+        assert_eq!(
+            book.crossed_stats(),
+            CrossedBookStats {
+                crossed: 1,
+                rejected: 0,
+                levels_removed: 0,
+            }
+        );
+    }
+
+    #[rstest]
+    fn test_crossing_order_when_rejected() {
+        let mut book = create_asks_book(CrossedBookPolicy::Reject);
+        let locked = BookOrder::new(
+            OrderSide::Buy,
+            Price::from("1.500"),
+            Quantity::from("1.0"),
+            0,
+        );
+        let bid = BookOrder::new(
+            OrderSide::Buy,
+            Price::from("1.000"),
+            Quantity::from("1.0"),
+            0,
+        );
+
+        book.add(locked, 4, 4);
+        book.update(bid, 5, 5);
+
+        assert!(!book.is_crossed());
+        assert_eq!(book.best_bid_price(), Some(Price::from("1.000")));
+        assert_eq!(book.sequence, 5);
+        assert_eq!(book.crossed_stats().crossed, 1);
+        assert_eq!(book.crossed_stats().rejected, 1);
+    }
+
+    #[rstest]
+    fn test_crossing_order_when_removing_stale_levels() {
+        let mut book = create_asks_book(CrossedBookPolicy::RemoveStale);
+        let bid = BookOrder::new(
+            OrderSide::Buy,
+            Price::from("2.000"),
+            Quantity::from("1.0"),
+            0,
+        );
+
+        book.update(bid, 4, 4);
+
+        assert!(!book.is_crossed());
+        assert_eq!(book.best_bid_price(), Some(Price::from("2.000")));
+        assert_eq!(book.best_ask_price(), Some(Price::from("2.500")));
+        assert_eq!(book.asks().len(), 1);
+        assert_eq!(book.crossed_stats().crossed, 1);
+        assert_eq!(book.crossed_stats().levels_removed, 2);
+        assert!(book.check_integrity().is_ok());
+    }
+
+    fn create_l1_quote_book(policy: CrossedBookPolicy) -> OrderBook {
+        let mut book = create_stub_book(BookType::L1_MBP);
+        book.crossed_policy = policy;
+        book.update_quote_tick(&create_quote("1.000", "1.010", 1));
+        book
+    }
+
+    fn create_quote(bid: &str, ask: &str, ts_event: u64) -> QuoteTick {
+        QuoteTick::new(
+            InstrumentId::from("ETHUSDT-PERP.BINANCE"),
+            Price::from(bid),
+            Price::from(ask),
+            Quantity::from("1.0"),
+            Quantity::from("1.0"),
+            ts_event,
+            ts_event,
+        )
+        .unwrap()
+    }
+
+    #[rstest]
+    #[case(CrossedBookPolicy::Allow)]
+    #[case(CrossedBookPolicy::Reject)]
+    #[case(CrossedBookPolicy::RemoveStale)]
+    fn test_quote_tick_l1_moving_through_previous_quote_is_not_crossing(
+        #[case] policy: CrossedBookPolicy,
+    ) {
+        let mut book = create_l1_quote_book(policy);
+
+        book.update_quote_tick(&create_quote("1.020", "1.030", 2));
+
+        assert_eq!(book.best_bid_price(), Some(Price::from("1.020")));
+        assert_eq!(book.best_ask_price(), Some(Price::from("1.030")));
+        assert_eq!(book.crossed_stats(), CrossedBookStats::default());
+    }
+
+    #[rstest]
+    fn test_crossed_quote_tick_l1_when_allowed() {
+        let mut book = create_l1_quote_book(CrossedBookPolicy::Allow);
+
+        book.update_quote_tick(&create_quote("1.050", "1.040", 2));
+
+        assert!(book.is_crossed());
+        assert_eq!(book.best_bid_price(), Some(Price::from("1.050")));
+        assert_eq!(book.best_ask_price(), Some(Price::from("1.040")));
+        assert_eq!(book.crossed_stats().crossed, 1);
+    }
+
+    #[rstest]
+    fn test_crossed_quote_tick_l1_when_rejected() {
+        let mut book = create_l1_quote_book(CrossedBookPolicy::Reject);
+
+        book.update_quote_tick(&create_quote("1.050", "1.040", 2));
+
+        assert!(!book.is_crossed());
+        assert_eq!(book.best_bid_price(), Some(Price::from("1.050")));
+        assert_eq!(book.best_ask_price(), None);
+        assert_eq!(
+            book.crossed_stats(),
+            CrossedBookStats {
+                crossed: 1,
+                rejected: 1,
+                levels_removed: 0,
+            }
+        );
+    }
+
+    #[rstest]
+    fn test_crossed_quote_tick_l1_when_removing_stale_levels() {
+        let mut book = create_l1_quote_book(CrossedBookPolicy::RemoveStale);
+
+        book.update_quote_tick(&create_quote("1.050", "1.040", 2));
+
+        assert!(!book.is_crossed());
+        assert_eq!(book.best_bid_price(), None);
+        assert_eq!(book.best_ask_price(), Some(Price::from("1.040")));
+        assert_eq!(
+            book.crossed_stats(),
+            CrossedBookStats {
+                crossed: 1,
+                rejected: 0,
+                levels_removed: 1,
+            }
+        );
+    }
+
+    #[rstest]
+    fn test_crossing_update_l1_when_rejected_keeps_opposite_side() {
+        let mut book = create_l1_quote_book(CrossedBookPolicy::Reject);
+        let bid = BookOrder::new(
+            OrderSide::Buy,
+            Price::from("1.020"),
+            Quantity::from("1.0"),
+            0,
+        );
+
+        book.update(bid, 2, 2);
+
+        assert_eq!(book.best_bid_price(), Some(Price::from("1.000")));
+        assert_eq!(book.best_ask_price(), Some(Price::from("1.010")));
+        assert_eq!(book.crossed_stats().rejected, 1);
+    }
+
+    #[rstest]
+    fn test_reset_clears_crossed_stats() {
+        let mut book = create_asks_book(CrossedBookPolicy::Reject);
+        let bid = BookOrder::new(
+            OrderSide::Buy,
+            Price::from("3.000"),
+            Quantity::from("1.0"),
+            0,
+        );
+        book.add(bid, 4, 4);
+
+        book.reset();
+
+        assert_eq!(book.crossed_stats(), CrossedBookStats::default());
+        assert_eq!(book.crossed_policy, CrossedBookPolicy::Reject);
+    }
 }
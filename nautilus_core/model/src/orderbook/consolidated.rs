@@ -0,0 +1,369 @@
+// -------------------------------------------------------------------------------------------------
+//  Copyright (C) 2015-2024 Nautech Systems Pty Ltd. All rights reserved.
+//  https://nautechsystems.io
+//
+//  Licensed under the GNU Lesser General Public License Version 3.0 (the "License");
+//  You may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at https://www.gnu.org/licenses/lgpl-3.0.en.html
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+// -------------------------------------------------------------------------------------------------
+
+use std::collections::BTreeMap;
+
+use anyhow::{bail, Result};
+use indexmap::IndexMap;
+
+use super::{book::OrderBook, ladder::BookPrice, level::Level};
+use crate::{
+    data::order::BookOrder,
+    enums::OrderSide,
+    identifiers::{instrument_id::InstrumentId, venue::Venue},
+    types::{fixed::fixed_u64_to_f64, price::Price, quantity::Quantity},
+};
+
+/// Represents a price level of a consolidated book, with the size available on each venue.
+#[derive(Clone, Debug)]
+pub struct ConsolidatedLevel {
+    pub price: BookPrice,
+    pub sizes: IndexMap<Venue, Quantity>,
+}
+
+impl ConsolidatedLevel {
+    #[must_use]
+    pub fn new(price: BookPrice) -> Self {
+        Self {
+            price,
+            sizes: IndexMap::new(),
+        }
+    }
+
+    #[must_use]
+    pub fn size(&self) -> f64 {
+        fixed_u64_to_f64(self.size_raw())
+    }
+
+    #[must_use]
+    pub fn size_raw(&self) -> u64 {
+        self.sizes.values().map(|size| size.raw).sum()
+    }
+
+    /// Returns the venues with size at this level.
+    #[must_use]
+    pub fn venues(&self) -> Vec<Venue> {
+        self.sizes.keys().copied().collect()
+    }
+}
+
+/// Provides a consolidated view of the order books for the same economic instrument across
+/// several venues, with each level retaining a per-venue size breakdown.
+#[derive(Clone, Debug, Default)]
+pub struct ConsolidatedBook {
+    venues: IndexMap<Venue, InstrumentId>,
+    bids: BTreeMap<BookPrice, ConsolidatedLevel>,
+    asks: BTreeMap<BookPrice, ConsolidatedLevel>,
+}
+
+impl ConsolidatedBook {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a consolidated book from the given `books`, one per venue.
+    ///
+    /// # Errors
+    ///
+    /// If more than one book is for the same venue.
+    pub fn from_books(books: &[&OrderBook]) -> Result<Self> {
+        let mut consolidated = Self::new();
+        for book in books {
+            let venue = book.instrument_id.venue;
+            if consolidated.venues.contains_key(&venue) {
+                bail!("Duplicate book for venue {venue}");
+            }
+            consolidated.update(book);
+        }
+        Ok(consolidated)
+    }
+
+    /// Returns the instrument ID of the book included for each venue, in the order added.
+    #[must_use]
+    pub fn instrument_ids(&self) -> Vec<InstrumentId> {
+        self.venues.values().copied().collect()
+    }
+
+    /// Replaces the levels for the venue of the given `book` with the current state of the book.
+    ///
+    /// Venues keep the priority they were first added with when filling across venues at the
+    /// same price.
+    pub fn update(&mut self, book: &OrderBook) {
+        let venue = book.instrument_id.venue;
+        self.remove_levels(&venue);
+        self.venues.insert(venue, book.instrument_id);
+
+        for level in book.bids() {
+            Self::add_level(&mut self.bids, venue, level);
+        }
+        for level in book.asks() {
+            Self::add_level(&mut self.asks, venue, level);
+        }
+    }
+
+    /// Removes the given `venue` from the consolidated book.
+    pub fn remove(&mut self, venue: &Venue) {
+        self.remove_levels(venue);
+        self.venues.shift_remove(venue);
+    }
+
+    pub fn bids(&self) -> Vec<&ConsolidatedLevel> {
+        self.bids.values().collect()
+    }
+
+    pub fn asks(&self) -> Vec<&ConsolidatedLevel> {
+        self.asks.values().collect()
+    }
+
+    /// Returns the best bid price across all venues.
+    #[must_use]
+    pub fn best_bid_price(&self) -> Option<Price> {
+        self.bids.keys().next().map(|book_price| book_price.value)
+    }
+
+    /// Returns the best ask price across all venues.
+    #[must_use]
+    pub fn best_ask_price(&self) -> Option<Price> {
+        self.asks.keys().next().map(|book_price| book_price.value)
+    }
+
+    /// Returns the best level on the `side` of the consolidated book, including which venues
+    /// are quoting at that price.
+    #[must_use]
+    pub fn best_level(&self, side: OrderSide) -> Option<&ConsolidatedLevel> {
+        match side {
+            OrderSide::Buy => self.bids.values().next(),
+            OrderSide::Sell => self.asks.values().next(),
+            _ => panic!("Invalid `OrderSide` {side}"),
+        }
+    }
+
+    /// Simulates filling the given `order` against the opposite side of the consolidated book,
+    /// returning the fills as (venue, price, quantity) in execution order.
+    ///
+    /// Levels are taken best price first up to the order limit price, and at each price the
+    /// venues are filled in the priority they were added to the book.
+    #[must_use]
+    pub fn simulate_fills(&self, order: &BookOrder) -> Vec<(Venue, Price, Quantity)> {
+        let (levels, is_reversed) = match order.side {
+            OrderSide::Buy => (&self.asks, false),
+            OrderSide::Sell => (&self.bids, true),
+            _ => panic!("Invalid `OrderSide` {}", order.side),
+        };
+
+        let mut fills = Vec::new();
+        let mut remaining_raw = order.size.raw;
+        for level in levels.values() {
+            if remaining_raw == 0
+                || (is_reversed && level.price.value < order.price)
+                || (!is_reversed && level.price.value > order.price)
+            {
+                break;
+            }
+
+            for venue in self.venues.keys() {
+                let Some(size) = level.sizes.get(venue) else {
+                    continue;
+                };
+                let fill_raw = size.raw.min(remaining_raw);
+                let fill_qty = Quantity::from_raw(fill_raw, order.size.precision)
+                    .expect("Invalid size precision");
+                fills.push((*venue, level.price.value, fill_qty));
+                remaining_raw -= fill_raw;
+                if remaining_raw == 0 {
+                    break;
+                }
+            }
+        }
+
+        fills
+    }
+
+    fn add_level(levels: &mut BTreeMap<BookPrice, ConsolidatedLevel>, venue: Venue, level: &Level) {
+        let Some(first) = level.first() else {
+            return;
+        };
+        let size = Quantity::from_raw(level.size_raw(), first.size.precision)
+            .expect("Invalid size precision");
+        levels
+            .entry(level.price)
+            .or_insert_with(|| ConsolidatedLevel::new(level.price))
+            .sizes
+            .insert(venue, size);
+    }
+
+    fn remove_levels(&mut self, venue: &Venue) {
+        for levels in [&mut self.bids, &mut self.asks] {
+            levels.retain(|_, level| {
+                level.sizes.shift_remove(venue);
+                !level.sizes.is_empty()
+            });
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Tests
+////////////////////////////////////////////////////////////////////////////////
+#[cfg(test)]
+mod tests {
+    use rstest::{fixture, rstest};
+
+    use super::*;
+    use crate::{enums::BookType, orderbook::stubs::stub_book};
+
+    #[fixture]
+    fn consolidated() -> ConsolidatedBook {
+        let book1 = stub_book(
+            "AAPL.XNAS",
+            BookType::L2_MBP,
+            &[
+                (OrderSide::Buy, "100.00", "10", 0),
+                (OrderSide::Buy, "99.00", "20", 0),
+                (OrderSide::Sell, "101.00", "10", 0),
+                (OrderSide::Sell, "102.00", "30", 0),
+            ],
+        );
+        let book2 = stub_book(
+            "AAPL.ARCX",
+            BookType::L2_MBP,
+            &[
+                (OrderSide::Buy, "100.00", "5", 0),
+                (OrderSide::Buy, "99.50", "15", 0),
+                (OrderSide::Sell, "101.00", "5", 0),
+                (OrderSide::Sell, "101.50", "20", 0),
+            ],
+        );
+        ConsolidatedBook::from_books(&[&book1, &book2]).unwrap()
+    }
+
+    #[rstest]
+    fn test_from_books_with_duplicate_venue() {
+        let book1 = stub_book("AAPL.XNAS", BookType::L2_MBP, &[]);
+        let book2 = stub_book("MSFT.XNAS", BookType::L2_MBP, &[]);
+
+        assert!(ConsolidatedBook::from_books(&[&book1, &book2]).is_err());
+    }
+
+    #[rstest]
+    fn test_consolidated_levels(consolidated: ConsolidatedBook) {
+        let bids = consolidated.bids();
+        let asks = consolidated.asks();
+
+        assert_eq!(consolidated.instrument_ids().len(), 2);
+        assert_eq!(bids.len(), 3);
+        assert_eq!(asks.len(), 3);
+        assert_eq!(bids[0].size(), 15.0);
+        assert_eq!(
+            bids[0].venues(),
+            vec![Venue::from("XNAS"), Venue::from("ARCX")]
+        );
+        assert_eq!(bids[1].price.value, Price::from("99.50"));
+        assert_eq!(
+            asks[1].sizes.get(&Venue::from("ARCX")),
+            Some(&Quantity::from("20"))
+        );
+    }
+
+    #[rstest]
+    fn test_best_prices(consolidated: ConsolidatedBook) {
+        let best_ask = consolidated.best_level(OrderSide::Sell).unwrap();
+
+        assert_eq!(consolidated.best_bid_price(), Some(Price::from("100.00")));
+        assert_eq!(consolidated.best_ask_price(), Some(Price::from("101.00")));
+        assert_eq!(best_ask.size(), 15.0);
+        assert_eq!(best_ask.venues().len(), 2);
+    }
+
+    #[rstest]
+    fn test_simulate_fills_across_venues(consolidated: ConsolidatedBook) {
+        let order = BookOrder::new(
+            OrderSide::Buy,
+            Price::from("102.00"),
+            Quantity::from("30"),
+            1,
+        );
+
+        let fills = consolidated.simulate_fills(&order);
+
+        assert_eq!(
+            fills,
+            vec![
+                (
+                    Venue::from("XNAS"),
+                    Price::from("101.00"),
+                    Quantity::from("10")
+                ),
+                (
+                    Venue::from("ARCX"),
+                    Price::from("101.00"),
+                    Quantity::from("5")
+                ),
+                (
+                    Venue::from("ARCX"),
+                    Price::from("101.50"),
+                    Quantity::from("15")
+                ),
+            ]
+        );
+    }
+
+    #[rstest]
+    fn test_simulate_fills_respects_limit_price(consolidated: ConsolidatedBook) {
+        let order = BookOrder::new(
+            OrderSide::Sell,
+            Price::from("99.50"),
+            Quantity::from("100"),
+            1,
+        );
+
+        let fills = consolidated.simulate_fills(&order);
+        let total: f64 = fills.iter().map(|(_, _, qty)| qty.as_f64()).sum();
+
+        assert_eq!(fills.len(), 3);
+        assert_eq!(total, 30.0);
+        assert!(fills.iter().all(|(_, px, _)| *px >= Price::from("99.50")));
+    }
+
+    #[rstest]
+    fn test_update_replaces_venue_levels(mut consolidated: ConsolidatedBook) {
+        let book2 = stub_book(
+            "AAPL.ARCX",
+            BookType::L2_MBP,
+            &[(OrderSide::Sell, "100.50", "1", 0)],
+        );
+
+        consolidated.update(&book2);
+
+        assert_eq!(consolidated.bids().len(), 2);
+        assert_eq!(consolidated.best_bid_price(), Some(Price::from("100.00")));
+        assert_eq!(consolidated.best_ask_price(), Some(Price::from("100.50")));
+        assert_eq!(consolidated.asks().len(), 3);
+    }
+
+    #[rstest]
+    fn test_remove_venue(mut consolidated: ConsolidatedBook) {
+        consolidated.remove(&Venue::from("XNAS"));
+
+        assert_eq!(
+            consolidated.instrument_ids(),
+            vec![InstrumentId::from("AAPL.ARCX")]
+        );
+        assert_eq!(consolidated.best_ask_price(), Some(Price::from("101.00")));
+        assert_eq!(consolidated.asks()[0].size(), 5.0);
+        assert_eq!(consolidated.bids().len(), 2);
+    }
+}
@@ -0,0 +1,293 @@
+// -------------------------------------------------------------------------------------------------
+//  Copyright (C) 2015-2024 Nautech Systems Pty Ltd. All rights reserved.
+//  https://nautechsystems.io
+//
+//  Licensed under the GNU Lesser General Public License Version 3.0 (the "License");
+//  You may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at https://www.gnu.org/licenses/lgpl-3.0.en.html
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+// -------------------------------------------------------------------------------------------------
+
+use indexmap::IndexMap;
+use nautilus_core::time::UnixNanos;
+
+use super::{book::OrderBook, level::Level};
+use crate::{
+    data::{
+        delta::OrderBookDelta,
+        deltas::OrderBookDeltas,
+        depth::OrderBookDepth10,
+        order::{BookOrder, OrderId},
+    },
+    enums::{BookAction, BookType, RecordFlags},
+};
+
+/// Returns the minimal deltas which transform the `from` book into the `to` book.
+///
+/// Orders are matched by order ID on each side: orders missing from `to` are deleted, orders
+/// with a changed price or size are updated, and new orders are added in queue order. The deletes
+/// for both sides come first, so replaying the deltas never crosses the book against an order
+/// which is about to be removed. The deltas carry the sequence and last event timestamp of the
+/// `to` book, with `F_LAST` set on the final delta.
+#[must_use]
+pub fn diff_books(from: &OrderBook, to: &OrderBook, ts_init: UnixNanos) -> OrderBookDeltas {
+    let sides = [
+        (collect_orders(&from.bids()), collect_orders(&to.bids())),
+        (collect_orders(&from.asks()), collect_orders(&to.asks())),
+    ];
+
+    let mut deltas = Vec::new();
+    for (from_orders, to_orders) in &sides {
+        push_deletes(from_orders, to_orders, to, ts_init, &mut deltas);
+    }
+    for (from_orders, to_orders) in &sides {
+        push_changes(from_orders, to_orders, to, ts_init, &mut deltas);
+    }
+
+    if let Some(last) = deltas.last_mut() {
+        last.flags |= RecordFlags::F_LAST.bits();
+    }
+
+    OrderBookDeltas::new(
+        to.instrument_id,
+        deltas,
+        RecordFlags::F_LAST.bits(),
+        to.sequence,
+        to.ts_last,
+        ts_init,
+    )
+}
+
+/// Returns the minimal deltas which transform the `book` into the state described by the
+/// `depth` snapshot (as if the snapshot had been applied with [`OrderBook::apply_depth`]).
+#[must_use]
+pub fn diff_depth(book: &OrderBook, depth: &OrderBookDepth10) -> OrderBookDeltas {
+    let mut target = OrderBook::new(book.instrument_id, book.book_type);
+    match book.book_type {
+        BookType::L1_MBP => {
+            let top_orders = [depth.bids[0], depth.asks[0]];
+            for order in top_orders.into_iter().filter(|o| o.size.is_positive()) {
+                target.update(order, depth.ts_event, depth.sequence);
+            }
+        }
+        BookType::L2_MBP | BookType::L3_MBO => target.apply_depth(*depth),
+    }
+    target.sequence = depth.sequence;
+    target.ts_last = depth.ts_event;
+
+    diff_books(book, &target, depth.ts_init)
+}
+
+fn push_deletes(
+    from_orders: &IndexMap<OrderId, BookOrder>,
+    to_orders: &IndexMap<OrderId, BookOrder>,
+    to: &OrderBook,
+    ts_init: UnixNanos,
+    deltas: &mut Vec<OrderBookDelta>,
+) {
+    for (order_id, order) in from_orders {
+        if !to_orders.contains_key(order_id) {
+            deltas.push(new_delta(to, BookAction::Delete, *order, ts_init));
+        }
+    }
+}
+
+fn push_changes(
+    from_orders: &IndexMap<OrderId, BookOrder>,
+    to_orders: &IndexMap<OrderId, BookOrder>,
+    to: &OrderBook,
+    ts_init: UnixNanos,
+    deltas: &mut Vec<OrderBookDelta>,
+) {
+    for (order_id, order) in to_orders {
+        let action = match from_orders.get(order_id) {
+            Some(existing) if existing.price == order.price && existing.size == order.size => {
+                continue
+            }
+            Some(_) => BookAction::Update,
+            // An L1_MBP book only accepts its top level by update
+            None if to.book_type == BookType::L1_MBP => BookAction::Update,
+            None => BookAction::Add,
+        };
+        deltas.push(new_delta(to, action, *order, ts_init));
+    }
+}
+
+fn new_delta(
+    to: &OrderBook,
+    action: BookAction,
+    order: BookOrder,
+    ts_init: UnixNanos,
+) -> OrderBookDelta {
+    OrderBookDelta::new(
+        to.instrument_id,
+        action,
+        order,
+        0,
+        to.sequence,
+        to.ts_last,
+        ts_init,
+    )
+}
+
+fn collect_orders(levels: &[&Level]) -> IndexMap<OrderId, BookOrder> {
+    levels
+        .iter()
+        .flat_map(|level| level.get_orders())
+        .map(|order| (order.order_id, order))
+        .collect()
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Tests
+////////////////////////////////////////////////////////////////////////////////
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+    use crate::{
+        data::depth::stubs::stub_depth10,
+        enums::OrderSide,
+        identifiers::instrument_id::InstrumentId,
+        orderbook::{
+            book::{CrossedBookPolicy, CrossedBookStats},
+            stubs::{assert_books_equal, stub_book},
+        },
+        types::{price::Price, quantity::Quantity},
+    };
+
+    #[rstest]
+    fn test_diff_books_when_identical() {
+        let orders = [
+            (OrderSide::Buy, "10.00", "1", 1),
+            (OrderSide::Sell, "11.00", "2", 2),
+        ];
+        let from = stub_book("AAPL.XNAS", BookType::L3_MBO, &orders);
+        let to = stub_book("AAPL.XNAS", BookType::L3_MBO, &orders);
+
+        let deltas = diff_books(&from, &to, 0);
+
+        assert!(deltas.deltas.is_empty());
+    }
+
+    #[rstest]
+    fn test_diff_books_l3() {
+        let mut from = stub_book(
+            "AAPL.XNAS",
+            BookType::L3_MBO,
+            &[
+                (OrderSide::Buy, "10.00", "1", 1),
+                (OrderSide::Buy, "10.00", "2", 2),
+                (OrderSide::Sell, "11.00", "3", 3),
+            ],
+        );
+        let to = stub_book(
+            "AAPL.XNAS",
+            BookType::L3_MBO,
+            &[
+                (OrderSide::Buy, "10.00", "1", 1),
+                (OrderSide::Buy, "9.00", "2", 2),
+                (OrderSide::Sell, "12.00", "4", 4),
+                (OrderSide::Sell, "12.00", "5", 5),
+            ],
+        );
+
+        let deltas = diff_books(&from, &to, 10);
+        let actions: Vec<BookAction> = deltas.deltas.iter().map(|d| d.action).collect();
+
+        assert_eq!(
+            actions,
+            vec![
+                BookAction::Delete,
+                BookAction::Update,
+                BookAction::Add,
+                BookAction::Add,
+            ]
+        );
+        assert_eq!(deltas.sequence, 4);
+        assert!(RecordFlags::from_bits_retain(deltas.deltas[3].flags).contains(RecordFlags::F_LAST));
+        assert!(
+            !RecordFlags::from_bits_retain(deltas.deltas[2].flags).contains(RecordFlags::F_LAST)
+        );
+        assert!(deltas.deltas.iter().all(|d| d.ts_init == 10));
+
+        from.apply_deltas(deltas).unwrap();
+        assert_books_equal(&to, &from);
+    }
+
+    #[rstest]
+    fn test_diff_books_replays_through_rejecting_book() {
+        let mut from = stub_book(
+            "AAPL.XNAS",
+            BookType::L3_MBO,
+            &[
+                (OrderSide::Buy, "10.00", "1", 1),
+                (OrderSide::Sell, "11.00", "1", 2),
+            ],
+        );
+        from.crossed_policy = CrossedBookPolicy::Reject;
+        let to = stub_book(
+            "AAPL.XNAS",
+            BookType::L3_MBO,
+            &[
+                (OrderSide::Buy, "11.50", "1", 3),
+                (OrderSide::Sell, "12.00", "1", 4),
+            ],
+        );
+
+        let deltas = diff_books(&from, &to, 0);
+        from.apply_deltas(deltas).unwrap();
+
+        assert_eq!(from.crossed_stats(), CrossedBookStats::default());
+        assert_books_equal(&to, &from);
+    }
+
+    #[rstest]
+    fn test_diff_depth_l2(stub_depth10: OrderBookDepth10) {
+        let mut book = stub_book(
+            "AAPL.XNAS",
+            BookType::L2_MBP,
+            &[
+                (OrderSide::Buy, "99.00", "100", 0),
+                (OrderSide::Buy, "98.00", "150", 0),
+                (OrderSide::Buy, "50.00", "1", 0),
+                (OrderSide::Sell, "100.00", "100", 0),
+            ],
+        );
+        let mut expected = OrderBook::new(book.instrument_id, BookType::L2_MBP);
+        expected.apply_depth(stub_depth10);
+
+        let deltas = diff_depth(&book, &stub_depth10);
+
+        // Bid at 50.00 deleted, bid at 98.00 updated, 8 bids and 9 asks added
+        assert_eq!(deltas.deltas.len(), 19);
+        assert_eq!(deltas.ts_event, stub_depth10.ts_event);
+        assert_eq!(deltas.ts_init, stub_depth10.ts_init);
+
+        book.apply_deltas(deltas).unwrap();
+        assert_books_equal(&expected, &book);
+    }
+
+    #[rstest]
+    fn test_diff_depth_l1(stub_depth10: OrderBookDepth10) {
+        let mut book = OrderBook::new(InstrumentId::from("AAPL.XNAS"), BookType::L1_MBP);
+        let bid = BookOrder::new(OrderSide::Buy, Price::from("98.00"), Quantity::from("5"), 0);
+        book.update(bid, 1, 1);
+
+        let deltas = diff_depth(&book, &stub_depth10);
+
+        assert_eq!(deltas.deltas.len(), 2);
+        assert!(deltas.deltas.iter().all(|d| d.action == BookAction::Update));
+
+        book.apply_deltas(deltas).unwrap();
+        assert_eq!(book.best_bid_price(), Some(Price::from("99.00")));
+        assert_eq!(book.best_ask_price(), Some(Price::from("100.00")));
+        assert_eq!(book.best_bid_size(), Some(Quantity::from("100")));
+    }
+}
@@ -162,6 +162,30 @@ impl Ladder {
         }
     }
 
+    /// Removes all levels which an opposite side order at `price` would cross or lock, returning
+    /// the number of levels removed.
+    pub fn remove_crossed_levels(&mut self, price: Price) -> usize {
+        let crossed: Vec<BookPrice> = self
+            .levels
+            .keys()
+            .take_while(|book_price| match self.side {
+                OrderSide::Buy => book_price.value >= price,
+                _ => book_price.value <= price,
+            })
+            .copied()
+            .collect();
+
+        for book_price in &crossed {
+            if let Some(level) = self.levels.remove(book_price) {
+                for order_id in level.orders.keys() {
+                    self.cache.remove(order_id);
+                }
+            }
+        }
+
+        crossed.len()
+    }
+
     #[must_use]
     pub fn sizes(&self) -> f64 {
         return self.levels.values().map(|l| l.size()).sum();
@@ -691,4 +715,20 @@ mod tests {
         assert_eq!(ladder_buy.top().unwrap().price.value, min_price);
         assert_eq!(ladder_sell.top().unwrap().price.value, max_price);
     }
+
+    #[rstest]
+    fn test_remove_crossed_levels() {
+        let mut ladder = Ladder::new(OrderSide::Sell);
+        let order1 = BookOrder::new(OrderSide::Sell, Price::from("11.00"), Quantity::from(20), 1);
+        let order2 = BookOrder::new(OrderSide::Sell, Price::from("12.00"), Quantity::from(30), 2);
+        let order3 = BookOrder::new(OrderSide::Sell, Price::from("13.00"), Quantity::from(50), 3);
+        ladder.add_bulk(vec![order1, order2, order3]);
+
+        let removed = ladder.remove_crossed_levels(Price::from("12.00"));
+
+        assert_eq!(removed, 2);
+        assert_eq!(ladder.len(), 1);
+        assert_eq!(ladder.cache.len(), 1);
+        assert_eq!(ladder.top().unwrap().price.value, Price::from("13.00"));
+    }
 }
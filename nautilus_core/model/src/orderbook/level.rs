@@ -66,6 +66,16 @@ impl Level {
             .and_then(|&id| self.orders.get(&id))
     }
 
+    /// Returns the orders at this level in time priority (FIFO) order.
+    #[must_use]
+    pub fn get_orders(&self) -> Vec<BookOrder> {
+        self.insertion_order
+            .iter()
+            .filter_map(|id| self.orders.get(id))
+            .cloned()
+            .collect()
+    }
+
     pub fn add_bulk(&mut self, orders: Vec<BookOrder>) {
         self.insertion_order
             .extend(orders.iter().map(|o| o.order_id));
@@ -398,4 +408,20 @@ mod tests {
         level.add(order2);
         assert_eq!(level.exposure_raw(), 60_000_000_000);
     }
+
+    #[rstest]
+    fn test_get_orders_in_time_priority() {
+        let mut level = Level::new(BookPrice::new(Price::from("1.00"), OrderSide::Buy));
+        let order1 = BookOrder::new(OrderSide::Buy, Price::from("1.00"), Quantity::from(10), 3);
+        let order2 = BookOrder::new(OrderSide::Buy, Price::from("1.00"), Quantity::from(20), 1);
+        let order3 = BookOrder::new(OrderSide::Buy, Price::from("1.00"), Quantity::from(30), 2);
+
+        level.add(order1);
+        level.add(order2);
+        level.add(order3);
+        level.delete(&order2);
+
+        let order_ids: Vec<u64> = level.get_orders().iter().map(|o| o.order_id).collect();
+        assert_eq!(order_ids, vec![3, 2]);
+    }
 }
@@ -13,6 +13,15 @@
 //  limitations under the License.
 // -------------------------------------------------------------------------------------------------
 
+pub mod aggregation;
+pub mod analysis;
 pub mod book;
+pub mod consolidated;
+pub mod diff;
 pub mod ladder;
 pub mod level;
+pub mod own;
+pub mod snapshot;
+
+#[cfg(feature = "stubs")]
+pub mod stubs;
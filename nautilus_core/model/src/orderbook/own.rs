@@ -0,0 +1,461 @@
+// -------------------------------------------------------------------------------------------------
+//  Copyright (C) 2015-2024 Nautech Systems Pty Ltd. All rights reserved.
+//  https://nautechsystems.io
+//
+//  Licensed under the GNU Lesser General Public License Version 3.0 (the "License");
+//  You may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at https://www.gnu.org/licenses/lgpl-3.0.en.html
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+// -------------------------------------------------------------------------------------------------
+
+use std::collections::{BTreeMap, HashMap};
+
+use indexmap::IndexMap;
+use nautilus_core::time::UnixNanos;
+
+use super::{
+    book::{BookIntegrityError, OrderBook},
+    ladder::BookPrice,
+};
+use crate::{
+    enums::OrderSide,
+    identifiers::{client_order_id::ClientOrderId, instrument_id::InstrumentId},
+    orders::base::Order,
+    types::{fixed::fixed_u64_to_f64, price::Price, quantity::Quantity},
+};
+
+/// Represents one of our own working orders resting in an order book.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct OwnBookOrder {
+    pub client_order_id: ClientOrderId,
+    pub side: OrderSide,
+    pub price: Price,
+    pub size: Quantity,
+    pub ts_init: UnixNanos,
+}
+
+impl OwnBookOrder {
+    #[must_use]
+    pub fn new(
+        client_order_id: ClientOrderId,
+        side: OrderSide,
+        price: Price,
+        size: Quantity,
+        ts_init: UnixNanos,
+    ) -> Self {
+        Self {
+            client_order_id,
+            side,
+            price,
+            size,
+            ts_init,
+        }
+    }
+
+    /// Returns the own book order for the leaves quantity of the given `order` (`None` if the
+    /// order has no limit price to rest at).
+    #[must_use]
+    pub fn from_order(order: &dyn Order) -> Option<Self> {
+        Some(Self::new(
+            order.client_order_id(),
+            order.side(),
+            order.price()?,
+            order.leaves_qty(),
+            order.ts_init(),
+        ))
+    }
+
+    #[must_use]
+    pub fn to_book_price(&self) -> BookPrice {
+        BookPrice::new(self.price, self.side)
+    }
+}
+
+/// Represents a price level of own orders, in time priority.
+#[derive(Clone, Debug)]
+pub struct OwnLevel {
+    pub price: BookPrice,
+    pub orders: IndexMap<ClientOrderId, OwnBookOrder>,
+}
+
+impl OwnLevel {
+    #[must_use]
+    pub fn new(price: BookPrice) -> Self {
+        Self {
+            price,
+            orders: IndexMap::new(),
+        }
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.orders.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.orders.is_empty()
+    }
+
+    #[must_use]
+    pub fn size(&self) -> f64 {
+        fixed_u64_to_f64(self.size_raw())
+    }
+
+    #[must_use]
+    pub fn size_raw(&self) -> u64 {
+        self.orders.values().map(|o| o.size.raw).sum()
+    }
+}
+
+/// Provides an overlay of our own working orders, to be read alongside the public `OrderBook`
+/// for the same instrument.
+pub struct OwnOrderBook {
+    pub instrument_id: InstrumentId,
+    bids: BTreeMap<BookPrice, OwnLevel>,
+    asks: BTreeMap<BookPrice, OwnLevel>,
+    cache: HashMap<ClientOrderId, BookPrice>,
+}
+
+impl OwnOrderBook {
+    #[must_use]
+    pub fn new(instrument_id: InstrumentId) -> Self {
+        Self {
+            instrument_id,
+            bids: BTreeMap::new(),
+            asks: BTreeMap::new(),
+            cache: HashMap::new(),
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.bids.clear();
+        self.asks.clear();
+        self.cache.clear();
+    }
+
+    /// Adds the `order` to the back of the queue at its price level (an order already in the
+    /// book is updated).
+    pub fn add(&mut self, order: OwnBookOrder) {
+        if self.cache.contains_key(&order.client_order_id) {
+            self.update(order);
+            return;
+        }
+
+        let book_price = order.to_book_price();
+        self.levels_mut(order.side)
+            .entry(book_price)
+            .or_insert_with(|| OwnLevel::new(book_price))
+            .orders
+            .insert(order.client_order_id, order);
+        self.cache.insert(order.client_order_id, book_price);
+    }
+
+    /// Updates the `order`, keeping its queue priority only if the price is unchanged and the
+    /// size has not increased (as with venue amend rules).
+    pub fn update(&mut self, order: OwnBookOrder) {
+        let Some(existing) = self.get(&order.client_order_id).copied() else {
+            self.add(order);
+            return;
+        };
+
+        if existing.price == order.price
+            && existing.side == order.side
+            && order.size <= existing.size
+        {
+            let book_price = order.to_book_price();
+            if let Some(level) = self.levels_mut(order.side).get_mut(&book_price) {
+                level.orders.insert(order.client_order_id, order);
+            }
+        } else {
+            self.delete(&order.client_order_id);
+            self.add(order);
+        }
+    }
+
+    /// Deletes the order with the given `client_order_id`, returning it (if found).
+    pub fn delete(&mut self, client_order_id: &ClientOrderId) -> Option<OwnBookOrder> {
+        let book_price = self.cache.remove(client_order_id)?;
+        let levels = self.levels_mut(book_price.side);
+        let level = levels.get_mut(&book_price)?;
+        let order = level.orders.shift_remove(client_order_id);
+        if level.is_empty() {
+            levels.remove(&book_price);
+        }
+        order
+    }
+
+    #[must_use]
+    pub fn get(&self, client_order_id: &ClientOrderId) -> Option<&OwnBookOrder> {
+        let book_price = self.cache.get(client_order_id)?;
+        self.levels(book_price.side)
+            .get(book_price)?
+            .orders
+            .get(client_order_id)
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.cache.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.cache.is_empty()
+    }
+
+    pub fn bids(&self) -> Vec<&OwnLevel> {
+        self.bids.values().collect()
+    }
+
+    pub fn asks(&self) -> Vec<&OwnLevel> {
+        self.asks.values().collect()
+    }
+
+    /// Returns the own order level on the `side` of the book at the given `price` (if it exists).
+    #[must_use]
+    pub fn get_level(&self, side: OrderSide, price: Price) -> Option<&OwnLevel> {
+        self.levels(side).get(&BookPrice::new(price, side))
+    }
+
+    /// Returns the size on the `side` of the public `book` at the given `price`, excluding the
+    /// size of our own orders at that price.
+    #[must_use]
+    pub fn public_size(&self, book: &OrderBook, side: OrderSide, price: Price) -> f64 {
+        fixed_u64_to_f64(self.public_size_raw(book, side, price))
+    }
+
+    /// Returns the estimated size queued ahead of the order with the given `client_order_id`
+    /// (`None` if the order is not in the book).
+    ///
+    /// The estimate is conservative, assuming all other public size at the price level was
+    /// queued before our orders, followed by our own orders in time priority.
+    #[must_use]
+    pub fn queue_position(&self, book: &OrderBook, client_order_id: &ClientOrderId) -> Option<f64> {
+        let book_price = self.cache.get(client_order_id)?;
+        let level = self.levels(book_price.side).get(book_price)?;
+        let own_ahead_raw: u64 = level
+            .orders
+            .values()
+            .take_while(|o| o.client_order_id != *client_order_id)
+            .map(|o| o.size.raw)
+            .sum();
+        let others_raw = self.public_size_raw(book, book_price.side, book_price.value);
+
+        Some(fixed_u64_to_f64(others_raw + own_ahead_raw))
+    }
+
+    /// Returns the estimated size queued ahead of each of our orders (see
+    /// [`OwnOrderBook::queue_position`]), bids then asks from the top of book.
+    #[must_use]
+    pub fn queue_positions(&self, book: &OrderBook) -> IndexMap<ClientOrderId, f64> {
+        self.bids
+            .values()
+            .chain(self.asks.values())
+            .flat_map(|level| level.orders.keys())
+            .filter_map(|id| Some((*id, self.queue_position(book, id)?)))
+            .collect()
+    }
+
+    fn public_size_raw(&self, book: &OrderBook, side: OrderSide, price: Price) -> u64 {
+        let public_raw = book.get_level(side, price).map_or(0, |l| l.size_raw());
+        let own_raw = self.get_level(side, price).map_or(0, OwnLevel::size_raw);
+        public_raw.saturating_sub(own_raw)
+    }
+
+    fn levels(&self, side: OrderSide) -> &BTreeMap<BookPrice, OwnLevel> {
+        match side {
+            OrderSide::Buy => &self.bids,
+            OrderSide::Sell => &self.asks,
+            _ => panic!("{}", BookIntegrityError::NoOrderSide),
+        }
+    }
+
+    fn levels_mut(&mut self, side: OrderSide) -> &mut BTreeMap<BookPrice, OwnLevel> {
+        match side {
+            OrderSide::Buy => &mut self.bids,
+            OrderSide::Sell => &mut self.asks,
+            _ => panic!("{}", BookIntegrityError::NoOrderSide),
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Tests
+////////////////////////////////////////////////////////////////////////////////
+#[cfg(test)]
+mod tests {
+    use rstest::{fixture, rstest};
+
+    use super::*;
+    use crate::{
+        data::order::BookOrder,
+        enums::{BookType, OrderSide},
+    };
+
+    fn own_order(id: &str, side: OrderSide, price: &str, size: &str) -> OwnBookOrder {
+        OwnBookOrder::new(
+            ClientOrderId::from(id),
+            side,
+            Price::from(price),
+            Quantity::from(size),
+            0,
+        )
+    }
+
+    #[fixture]
+    fn book() -> OrderBook {
+        let mut book = OrderBook::new(InstrumentId::from("ETHUSDT-PERP.BINANCE"), BookType::L2_MBP);
+        let bid = BookOrder::new(
+            OrderSide::Buy,
+            Price::from("100.0"),
+            Quantity::from("10.0"),
+            1,
+        );
+        let ask = BookOrder::new(
+            OrderSide::Sell,
+            Price::from("101.0"),
+            Quantity::from("4.0"),
+            2,
+        );
+        book.add(bid, 1, 1);
+        book.add(ask, 2, 2);
+        book
+    }
+
+    fn own_book() -> OwnOrderBook {
+        OwnOrderBook::new(InstrumentId::from("ETHUSDT-PERP.BINANCE"))
+    }
+
+    #[rstest]
+    fn test_add_orders() {
+        let mut own = own_book();
+        own.add(own_order("O-1", OrderSide::Buy, "100.0", "1.0"));
+        own.add(own_order("O-2", OrderSide::Buy, "99.0", "2.0"));
+        own.add(own_order("O-3", OrderSide::Buy, "100.0", "3.0"));
+        own.add(own_order("O-4", OrderSide::Sell, "101.0", "1.0"));
+
+        assert_eq!(own.len(), 4);
+        assert_eq!(own.bids().len(), 2);
+        assert_eq!(own.asks().len(), 1);
+        assert_eq!(own.bids()[0].price.value, Price::from("100.0"));
+        assert_eq!(
+            own.get_level(OrderSide::Buy, Price::from("100.0"))
+                .unwrap()
+                .size(),
+            4.0
+        );
+    }
+
+    #[rstest]
+    fn test_delete_removes_empty_level() {
+        let mut own = own_book();
+        let order = own_order("O-1", OrderSide::Buy, "100.0", "1.0");
+        own.add(order);
+
+        let deleted = own.delete(&order.client_order_id);
+
+        assert_eq!(deleted, Some(order));
+        assert!(own.is_empty());
+        assert!(own.bids().is_empty());
+        assert_eq!(own.delete(&order.client_order_id), None);
+    }
+
+    #[rstest]
+    fn test_update_with_reduced_size_keeps_priority() {
+        let mut own = own_book();
+        own.add(own_order("O-1", OrderSide::Buy, "100.0", "2.0"));
+        own.add(own_order("O-2", OrderSide::Buy, "100.0", "1.0"));
+
+        own.update(own_order("O-1", OrderSide::Buy, "100.0", "1.0"));
+
+        let level = own.get_level(OrderSide::Buy, Price::from("100.0")).unwrap();
+        assert_eq!(
+            level.orders.keys().next(),
+            Some(&ClientOrderId::from("O-1"))
+        );
+        assert_eq!(level.size(), 2.0);
+    }
+
+    #[rstest]
+    fn test_update_with_increased_size_loses_priority() {
+        let mut own = own_book();
+        own.add(own_order("O-1", OrderSide::Buy, "100.0", "1.0"));
+        own.add(own_order("O-2", OrderSide::Buy, "100.0", "1.0"));
+
+        own.update(own_order("O-1", OrderSide::Buy, "100.0", "3.0"));
+
+        let level = own.get_level(OrderSide::Buy, Price::from("100.0")).unwrap();
+        assert_eq!(
+            level.orders.keys().next(),
+            Some(&ClientOrderId::from("O-2"))
+        );
+        assert_eq!(level.size(), 4.0);
+    }
+
+    #[rstest]
+    fn test_update_with_new_price_moves_level() {
+        let mut own = own_book();
+        own.add(own_order("O-1", OrderSide::Sell, "101.0", "1.0"));
+
+        own.update(own_order("O-1", OrderSide::Sell, "102.0", "1.0"));
+
+        assert_eq!(own.len(), 1);
+        assert!(own
+            .get_level(OrderSide::Sell, Price::from("101.0"))
+            .is_none());
+        assert_eq!(
+            own.get(&ClientOrderId::from("O-1")).unwrap().price,
+            Price::from("102.0")
+        );
+    }
+
+    #[rstest]
+    fn test_public_size_excludes_own_orders(book: OrderBook) {
+        let mut own = own_book();
+        own.add(own_order("O-1", OrderSide::Buy, "100.0", "3.0"));
+        own.add(own_order("O-2", OrderSide::Sell, "101.0", "5.0"));
+
+        assert_eq!(
+            own.public_size(&book, OrderSide::Buy, Price::from("100.0")),
+            7.0
+        );
+        assert_eq!(
+            own.public_size(&book, OrderSide::Sell, Price::from("101.0")),
+            0.0
+        );
+        assert_eq!(
+            own.public_size(&book, OrderSide::Buy, Price::from("99.0")),
+            0.0
+        );
+    }
+
+    #[rstest]
+    fn test_queue_positions(book: OrderBook) {
+        let mut own = own_book();
+        own.add(own_order("O-1", OrderSide::Buy, "100.0", "2.0"));
+        own.add(own_order("O-2", OrderSide::Buy, "100.0", "3.0"));
+        own.add(own_order("O-3", OrderSide::Sell, "101.0", "1.0"));
+
+        let positions = own.queue_positions(&book);
+
+        assert_eq!(
+            own.queue_position(&book, &ClientOrderId::from("O-1")),
+            Some(5.0)
+        );
+        assert_eq!(own.queue_position(&book, &ClientOrderId::from("O-X")), None);
+        assert_eq!(
+            positions.into_iter().collect::<Vec<_>>(),
+            vec![
+                (ClientOrderId::from("O-1"), 5.0),
+                (ClientOrderId::from("O-2"), 7.0),
+                (ClientOrderId::from("O-3"), 3.0),
+            ]
+        );
+    }
+}
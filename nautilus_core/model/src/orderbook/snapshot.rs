@@ -0,0 +1,559 @@
+// -------------------------------------------------------------------------------------------------
+//  Copyright (C) 2015-2024 Nautech Systems Pty Ltd. All rights reserved.
+//  https://nautechsystems.io
+//
+//  Licensed under the GNU Lesser General Public License Version 3.0 (the "License");
+//  You may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at https://www.gnu.org/licenses/lgpl-3.0.en.html
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+// -------------------------------------------------------------------------------------------------
+
+use anyhow::{anyhow, bail, Result};
+use nautilus_core::time::UnixNanos;
+
+use super::book::{CrossedBookPolicy, CrossedBookStats, OrderBook};
+use crate::{
+    data::{delta::OrderBookDelta, deltas::OrderBookDeltas, order::BookOrder, quote::QuoteTick},
+    enums::{BookAction, BookType, FromU8, OrderSide, RecordFlags},
+    identifiers::instrument_id::InstrumentId,
+    types::{price::Price, quantity::Quantity},
+};
+
+const SNAPSHOT_MAGIC: &[u8; 4] = b"NTOB";
+const SNAPSHOT_VERSION: u8 = 2;
+
+impl OrderBook {
+    /// Returns all orders in the book, bids then asks from the top of book, in queue order.
+    #[must_use]
+    pub fn orders(&self) -> Vec<BookOrder> {
+        self.bids()
+            .into_iter()
+            .chain(self.asks())
+            .flat_map(|level| level.get_orders())
+            .collect()
+    }
+
+    /// Serializes the full state of the book to a compact binary snapshot.
+    ///
+    /// The snapshot holds every order in queue order, the `sequence`, `ts_last` and `count` of
+    /// the book, its configuration (`check_sequence`, `wait_for_last` and `crossed_policy`), the
+    /// crossed book stats, and the sequence recovery state (including any buffered deltas and
+    /// the cached top-of-book). It can be restored exactly with [`OrderBook::from_bytes`].
+    #[must_use]
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let orders = self.orders();
+        let instrument_id = self.instrument_id.to_string();
+
+        let mut buf = Vec::with_capacity(
+            128 + instrument_id.len() + orders.len() * 27 + self.buffered.len() * 52,
+        );
+        buf.extend_from_slice(SNAPSHOT_MAGIC);
+        buf.push(SNAPSHOT_VERSION);
+        buf.extend_from_slice(&(instrument_id.len() as u16).to_le_bytes());
+        buf.extend_from_slice(instrument_id.as_bytes());
+        buf.push(self.book_type as u8);
+        buf.extend_from_slice(&self.sequence.to_le_bytes());
+        buf.extend_from_slice(&self.ts_last.to_le_bytes());
+        buf.extend_from_slice(&self.count.to_le_bytes());
+
+        let state = [
+            self.check_sequence,
+            self.wait_for_last,
+            self.awaiting_snapshot,
+            self.in_snapshot,
+            self.batch_pending,
+        ];
+        let state_bits = state
+            .iter()
+            .enumerate()
+            .fold(0u8, |bits, (i, set)| bits | (u8::from(*set) << i));
+        buf.push(state_bits);
+        buf.push(self.crossed_policy as u8);
+        buf.extend_from_slice(&self.crossed_stats.crossed.to_le_bytes());
+        buf.extend_from_slice(&self.crossed_stats.rejected.to_le_bytes());
+        buf.extend_from_slice(&self.crossed_stats.levels_removed.to_le_bytes());
+
+        match &self.top_of_book {
+            Some(quote) => {
+                buf.push(1);
+                write_price(&mut buf, quote.bid_price);
+                write_price(&mut buf, quote.ask_price);
+                write_quantity(&mut buf, quote.bid_size);
+                write_quantity(&mut buf, quote.ask_size);
+                buf.extend_from_slice(&quote.ts_event.to_le_bytes());
+                buf.extend_from_slice(&quote.ts_init.to_le_bytes());
+            }
+            None => buf.push(0),
+        }
+
+        buf.extend_from_slice(&(orders.len() as u32).to_le_bytes());
+        for order in &orders {
+            write_order(&mut buf, order);
+        }
+
+        buf.extend_from_slice(&(self.buffered.len() as u32).to_le_bytes());
+        for delta in &self.buffered {
+            buf.push(delta.action as u8);
+            write_order(&mut buf, &delta.order);
+            buf.push(delta.flags);
+            buf.extend_from_slice(&delta.sequence.to_le_bytes());
+            buf.extend_from_slice(&delta.ts_event.to_le_bytes());
+            buf.extend_from_slice(&delta.ts_init.to_le_bytes());
+        }
+
+        buf
+    }
+
+    /// Restores a book from a binary snapshot created with [`OrderBook::to_bytes`].
+    ///
+    /// # Errors
+    ///
+    /// If the snapshot is truncated, has an unsupported version or contains invalid values.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let mut reader = SnapshotReader::new(bytes);
+        if reader.take(4)? != SNAPSHOT_MAGIC {
+            bail!("Invalid order book snapshot: bad magic bytes");
+        }
+        let version = reader.read_u8()?;
+        if version != SNAPSHOT_VERSION {
+            bail!("Unsupported order book snapshot version {version}");
+        }
+
+        let id_len = reader.read_u16()? as usize;
+        let instrument_id: InstrumentId = std::str::from_utf8(reader.take(id_len)?)?.parse()?;
+        let book_type_value = reader.read_u8()?;
+        let book_type = BookType::from_u8(book_type_value)
+            .ok_or_else(|| anyhow!("Invalid `BookType` value, was {book_type_value}"))?;
+        let sequence = reader.read_u64()?;
+        let ts_last = reader.read_u64()?;
+        let count = reader.read_u64()?;
+
+        let state_bits = reader.read_u8()?;
+        let state = |i: u8| state_bits & (1 << i) != 0;
+        let crossed_policy = match reader.read_u8()? {
+            0 => CrossedBookPolicy::Allow,
+            1 => CrossedBookPolicy::Reject,
+            2 => CrossedBookPolicy::RemoveStale,
+            value => bail!("Invalid `CrossedBookPolicy` value, was {value}"),
+        };
+        let crossed_stats = CrossedBookStats {
+            crossed: reader.read_u64()?,
+            rejected: reader.read_u64()?,
+            levels_removed: reader.read_u64()?,
+        };
+
+        let top_of_book = match reader.read_u8()? {
+            0 => None,
+            1 => Some(QuoteTick::new(
+                instrument_id,
+                reader.read_price()?,
+                reader.read_price()?,
+                reader.read_quantity()?,
+                reader.read_quantity()?,
+                reader.read_u64()?,
+                reader.read_u64()?,
+            )?),
+            value => bail!("Invalid order book snapshot: bad top-of-book marker {value}"),
+        };
+
+        // Restore the orders with the default policy, as the snapshot is already consistent
+        let mut book = Self::new(instrument_id, book_type);
+        let num_orders = reader.read_u32()?;
+        for _ in 0..num_orders {
+            let order = reader.read_order()?;
+            book.restore_order(order, ts_last, sequence);
+        }
+
+        let num_buffered = reader.read_u32()?;
+        for _ in 0..num_buffered {
+            let action_value = reader.read_u8()?;
+            let action = BookAction::from_u8(action_value)
+                .ok_or_else(|| anyhow!("Invalid `BookAction` value, was {action_value}"))?;
+            let order = reader.read_order()?;
+            let flags = reader.read_u8()?;
+            let delta_sequence = reader.read_u64()?;
+            let ts_event = reader.read_u64()?;
+            let ts_init = reader.read_u64()?;
+            book.buffered.push(OrderBookDelta::new(
+                instrument_id,
+                action,
+                order,
+                flags,
+                delta_sequence,
+                ts_event,
+                ts_init,
+            ));
+        }
+
+        if !reader.is_empty() {
+            bail!("Invalid order book snapshot: trailing bytes");
+        }
+
+        book.sequence = sequence;
+        book.ts_last = ts_last;
+        book.count = count;
+        book.check_sequence = state(0);
+        book.wait_for_last = state(1);
+        book.awaiting_snapshot = state(2);
+        book.in_snapshot = state(3);
+        book.batch_pending = state(4);
+        book.crossed_policy = crossed_policy;
+        book.crossed_stats = crossed_stats;
+        book.top_of_book = top_of_book;
+        Ok(book)
+    }
+
+    /// Returns the deltas which rebuild the full state of the book: a `Clear` followed by every
+    /// order in queue order, all flagged `F_SNAPSHOT` with `F_LAST` on the final delta.
+    ///
+    /// This lets a snapshot be stored with the same schema as incremental deltas. Only the
+    /// orders, `sequence` and `ts_last` are represented: the book `count`, configuration,
+    /// crossed book stats and sequence recovery state are not (use [`OrderBook::to_bytes`] to
+    /// capture those).
+    #[must_use]
+    pub fn to_snapshot_deltas(&self, ts_init: UnixNanos) -> OrderBookDeltas {
+        let snapshot_flags = RecordFlags::F_SNAPSHOT;
+        let action = match self.book_type {
+            BookType::L1_MBP => BookAction::Update, // L1_MBP books do not accept adds
+            BookType::L2_MBP | BookType::L3_MBO => BookAction::Add,
+        };
+
+        let mut deltas = vec![OrderBookDelta::clear(
+            self.instrument_id,
+            self.sequence,
+            self.ts_last,
+            ts_init,
+        )];
+        deltas.extend(self.orders().into_iter().map(|order| {
+            OrderBookDelta::new(
+                self.instrument_id,
+                action,
+                order,
+                snapshot_flags.bits(),
+                self.sequence,
+                self.ts_last,
+                ts_init,
+            )
+        }));
+        if let Some(last) = deltas.last_mut() {
+            last.flags |= RecordFlags::F_LAST.bits();
+        }
+
+        OrderBookDeltas::new(
+            self.instrument_id,
+            deltas,
+            (snapshot_flags | RecordFlags::F_LAST).bits(),
+            self.sequence,
+            self.ts_last,
+            ts_init,
+        )
+    }
+
+    /// Restores a book of the given `book_type` from snapshot deltas created with
+    /// [`OrderBook::to_snapshot_deltas`], setting the book `count`.
+    ///
+    /// The restored book has the default configuration and no crossed book stats or sequence
+    /// recovery state.
+    ///
+    /// # Errors
+    ///
+    /// If `deltas` is empty, or does not start with a `Clear` delta.
+    pub fn from_snapshot_deltas(
+        book_type: BookType,
+        deltas: &[OrderBookDelta],
+        count: u64,
+    ) -> Result<Self> {
+        let (first, orders) = deltas
+            .split_first()
+            .ok_or_else(|| anyhow!("No deltas for order book snapshot"))?;
+        if first.action != BookAction::Clear {
+            bail!("Order book snapshot must start with a `Clear` delta");
+        }
+
+        let mut book = Self::new(first.instrument_id, book_type);
+        for delta in orders {
+            book.restore_order(delta.order, delta.ts_event, delta.sequence);
+        }
+
+        book.sequence = first.sequence;
+        book.ts_last = first.ts_event;
+        book.count = count;
+        Ok(book)
+    }
+
+    fn restore_order(&mut self, order: BookOrder, ts_event: UnixNanos, sequence: u64) {
+        match self.book_type {
+            BookType::L1_MBP => self.update(order, ts_event, sequence),
+            BookType::L2_MBP | BookType::L3_MBO => self.add(order, ts_event, sequence),
+        }
+    }
+}
+
+fn write_price(buf: &mut Vec<u8>, price: Price) {
+    buf.extend_from_slice(&price.raw.to_le_bytes());
+    buf.push(price.precision);
+}
+
+fn write_quantity(buf: &mut Vec<u8>, quantity: Quantity) {
+    buf.extend_from_slice(&quantity.raw.to_le_bytes());
+    buf.push(quantity.precision);
+}
+
+fn write_order(buf: &mut Vec<u8>, order: &BookOrder) {
+    buf.push(order.side as u8);
+    write_price(buf, order.price);
+    write_quantity(buf, order.size);
+    buf.extend_from_slice(&order.order_id.to_le_bytes());
+}
+
+struct SnapshotReader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> SnapshotReader<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        if self.bytes.len() < len {
+            bail!("Invalid order book snapshot: truncated");
+        }
+        let (head, tail) = self.bytes.split_at(len);
+        self.bytes = tail;
+        Ok(head)
+    }
+
+    fn read_array<const N: usize>(&mut self) -> Result<[u8; N]> {
+        Ok(self.take(N)?.try_into()?)
+    }
+
+    fn read_u8(&mut self) -> Result<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn read_u16(&mut self) -> Result<u16> {
+        Ok(u16::from_le_bytes(self.read_array()?))
+    }
+
+    fn read_u32(&mut self) -> Result<u32> {
+        Ok(u32::from_le_bytes(self.read_array()?))
+    }
+
+    fn read_u64(&mut self) -> Result<u64> {
+        Ok(u64::from_le_bytes(self.read_array()?))
+    }
+
+    fn read_i64(&mut self) -> Result<i64> {
+        Ok(i64::from_le_bytes(self.read_array()?))
+    }
+
+    fn read_price(&mut self) -> Result<Price> {
+        let raw = self.read_i64()?;
+        Price::from_raw(raw, self.read_u8()?)
+    }
+
+    fn read_quantity(&mut self) -> Result<Quantity> {
+        let raw = self.read_u64()?;
+        Quantity::from_raw(raw, self.read_u8()?)
+    }
+
+    fn read_order(&mut self) -> Result<BookOrder> {
+        let side_value = self.read_u8()?;
+        let side = OrderSide::from_u8(side_value)
+            .filter(|side| *side != OrderSide::NoOrderSide)
+            .ok_or_else(|| anyhow!("Invalid `OrderSide` value, was {side_value}"))?;
+        let price = self.read_price()?;
+        let size = self.read_quantity()?;
+        let order_id = self.read_u64()?;
+        Ok(BookOrder::new(side, price, size, order_id))
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Tests
+////////////////////////////////////////////////////////////////////////////////
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+    use crate::{
+        data::depth::{stubs::stub_depth10, OrderBookDepth10},
+        orderbook::stubs::{assert_books_equal, stub_book},
+    };
+
+    fn create_l3_book() -> OrderBook {
+        stub_book(
+            "AAPL.XNAS",
+            BookType::L3_MBO,
+            &[
+                (OrderSide::Buy, "100.00", "3", 7),
+                (OrderSide::Buy, "100.00", "1", 2),
+                (OrderSide::Buy, "99.50", "5", 4),
+                (OrderSide::Sell, "100.50", "2", 9),
+                (OrderSide::Sell, "101.00", "8", 1),
+            ],
+        )
+    }
+
+    #[rstest]
+    fn test_bytes_round_trip_l3() {
+        let book = create_l3_book();
+
+        let restored = OrderBook::from_bytes(&book.to_bytes()).unwrap();
+
+        assert_books_equal(&book, &restored);
+        assert_eq!(restored.count, book.count);
+        // Queue order within a level is preserved
+        assert_eq!(restored.bids()[0].get_orders()[0].order_id, 7);
+    }
+
+    #[rstest]
+    fn test_bytes_round_trip_l2(stub_depth10: OrderBookDepth10) {
+        let mut book = OrderBook::new(stub_depth10.instrument_id, BookType::L2_MBP);
+        book.apply_depth(stub_depth10);
+
+        let restored = OrderBook::from_bytes(&book.to_bytes()).unwrap();
+
+        assert_books_equal(&book, &restored);
+        assert_eq!(restored.count, 20);
+    }
+
+    #[rstest]
+    fn test_bytes_round_trip_l1() {
+        let mut book = OrderBook::new(InstrumentId::from("AAPL.XNAS"), BookType::L1_MBP);
+        let bid = BookOrder::new(OrderSide::Buy, Price::from("1.0"), Quantity::from("5"), 0);
+        let ask = BookOrder::new(OrderSide::Sell, Price::from("1.1"), Quantity::from("6"), 0);
+        book.update(bid, 1, 1);
+        book.update(ask, 2, 2);
+
+        let restored = OrderBook::from_bytes(&book.to_bytes()).unwrap();
+
+        assert_books_equal(&book, &restored);
+    }
+
+    #[rstest]
+    fn test_bytes_round_trip_empty_book() {
+        let book = OrderBook::new(InstrumentId::from("AAPL.XNAS"), BookType::L3_MBO);
+
+        let restored = OrderBook::from_bytes(&book.to_bytes()).unwrap();
+
+        assert_books_equal(&book, &restored);
+        assert!(restored.orders().is_empty());
+    }
+
+    #[rstest]
+    fn test_bytes_round_trip_config_and_recovery_state() {
+        let mut book = create_l3_book();
+        book.check_sequence = true;
+        book.wait_for_last = true;
+        book.crossed_policy = CrossedBookPolicy::Reject;
+
+        let crossing = BookOrder::new(
+            OrderSide::Buy,
+            Price::from("102.00"),
+            Quantity::from("1"),
+            3,
+        );
+        let mut last = OrderBookDelta::new(
+            book.instrument_id,
+            BookAction::Add,
+            crossing,
+            RecordFlags::F_LAST.bits(),
+            6,
+            106,
+            106,
+        );
+        book.apply_delta(last).unwrap();
+        last.sequence = 9;
+        assert!(book.apply_delta(last).is_err());
+
+        let restored = OrderBook::from_bytes(&book.to_bytes()).unwrap();
+
+        assert_books_equal(&book, &restored);
+        assert!(restored.check_sequence);
+        assert!(restored.wait_for_last);
+        assert_eq!(restored.crossed_policy, CrossedBookPolicy::Reject);
+        assert_eq!(restored.crossed_stats().rejected, 1);
+        assert_eq!(restored.crossed_stats(), book.crossed_stats());
+        assert!(restored.is_awaiting_snapshot());
+        assert_eq!(restored.buffered, book.buffered);
+        assert_eq!(restored.top_of_book(), book.top_of_book());
+        assert_eq!(restored.is_batch_pending(), book.is_batch_pending());
+    }
+
+    #[rstest]
+    fn test_from_bytes_when_truncated() {
+        let bytes = create_l3_book().to_bytes();
+
+        let result = OrderBook::from_bytes(&bytes[..bytes.len() - 1]);
+
+        assert!(result.is_err());
+    }
+
+    #[rstest]
+    fn test_from_bytes_with_bad_magic() {
+        let mut bytes = create_l3_book().to_bytes();
+        bytes[0] = b'X';
+
+        assert!(OrderBook::from_bytes(&bytes).is_err());
+    }
+
+    #[rstest]
+    fn test_snapshot_deltas_round_trip() {
+        let book = create_l3_book();
+
+        let deltas = book.to_snapshot_deltas(200);
+        let restored =
+            OrderBook::from_snapshot_deltas(book.book_type, &deltas.deltas, book.count).unwrap();
+
+        assert_eq!(deltas.deltas.len(), 6);
+        assert_eq!(deltas.deltas[0].action, BookAction::Clear);
+        assert!(deltas
+            .deltas
+            .iter()
+            .all(|d| RecordFlags::from_bits_retain(d.flags).contains(RecordFlags::F_SNAPSHOT)));
+        assert!(RecordFlags::from_bits_retain(deltas.deltas[5].flags).contains(RecordFlags::F_LAST));
+        assert!(
+            !RecordFlags::from_bits_retain(deltas.deltas[4].flags).contains(RecordFlags::F_LAST)
+        );
+        assert_books_equal(&book, &restored);
+        assert_eq!(restored.count, book.count);
+    }
+
+    #[rstest]
+    fn test_snapshot_deltas_apply_to_book() {
+        let book = create_l3_book();
+        let mut other = OrderBook::new(book.instrument_id, BookType::L3_MBO);
+        other.add(
+            BookOrder::new(OrderSide::Buy, Price::from("1.00"), Quantity::from("1"), 99),
+            1,
+            1,
+        );
+
+        other.apply_deltas(book.to_snapshot_deltas(200)).unwrap();
+
+        assert_books_equal(&book, &other);
+    }
+
+    #[rstest]
+    fn test_from_snapshot_deltas_without_clear() {
+        let book = create_l3_book();
+        let deltas = book.to_snapshot_deltas(200);
+
+        let result = OrderBook::from_snapshot_deltas(BookType::L3_MBO, &deltas.deltas[1..], 0);
+
+        assert!(result.is_err());
+        assert!(OrderBook::from_snapshot_deltas(BookType::L3_MBO, &[], 0).is_err());
+    }
+}
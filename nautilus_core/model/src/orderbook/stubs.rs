@@ -0,0 +1,52 @@
+// -------------------------------------------------------------------------------------------------
+//  Copyright (C) 2015-2024 Nautech Systems Pty Ltd. All rights reserved.
+//  https://nautechsystems.io
+//
+//  Licensed under the GNU Lesser General Public License Version 3.0 (the "License");
+//  You may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at https://www.gnu.org/licenses/lgpl-3.0.en.html
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+// -------------------------------------------------------------------------------------------------
+
+use super::book::OrderBook;
+use crate::{
+    data::order::{BookOrder, OrderId},
+    enums::{BookType, OrderSide},
+    identifiers::instrument_id::InstrumentId,
+    types::{price::Price, quantity::Quantity},
+};
+
+/// Returns a book with the given `(side, price, size, order_id)` orders added in turn, each
+/// with an event timestamp and sequence one after the last.
+#[must_use]
+pub fn stub_book(
+    instrument_id: &str,
+    book_type: BookType,
+    orders: &[(OrderSide, &str, &str, OrderId)],
+) -> OrderBook {
+    let mut book = OrderBook::new(InstrumentId::from(instrument_id), book_type);
+    for (i, (side, price, size, order_id)) in orders.iter().enumerate() {
+        let order = BookOrder::new(*side, Price::from(*price), Quantity::from(*size), *order_id);
+        book.add(order, i as u64 + 1, i as u64 + 1);
+    }
+    book
+}
+
+/// Asserts the `actual` book holds the same orders, in queue order, with the same identity,
+/// `sequence` and `ts_last` as the `expected` book.
+pub fn assert_books_equal(expected: &OrderBook, actual: &OrderBook) {
+    let key = |o: &BookOrder| (o.side, o.price, o.size, o.order_id);
+    let expected_orders: Vec<_> = expected.orders().iter().map(key).collect();
+    let actual_orders: Vec<_> = actual.orders().iter().map(key).collect();
+
+    assert_eq!(actual.instrument_id, expected.instrument_id);
+    assert_eq!(actual.book_type, expected.book_type);
+    assert_eq!(actual_orders, expected_orders);
+    assert_eq!(actual.sequence, expected.sequence);
+    assert_eq!(actual.ts_last, expected.ts_last);
+}
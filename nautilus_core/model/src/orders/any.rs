@@ -0,0 +1,526 @@
+// -------------------------------------------------------------------------------------------------
+//  Copyright (C) 2015-2024 Nautech Systems Pty Ltd. All rights reserved.
+//  https://nautechsystems.io
+//
+//  Licensed under the GNU Lesser General Public License Version 3.0 (the "License");
+//  You may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at https://www.gnu.org/licenses/lgpl-3.0.en.html
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+// -------------------------------------------------------------------------------------------------
+
+use serde::{Deserialize, Serialize};
+
+use super::{
+    base::{Order, OrderCore, OrderError},
+    limit::LimitOrder,
+    limit_if_touched::LimitIfTouchedOrder,
+    market::MarketOrder,
+    market_if_touched::MarketIfTouchedOrder,
+    market_to_limit::MarketToLimitOrder,
+    stop_limit::StopLimitOrder,
+    stop_market::StopMarketOrder,
+    trailing_stop_limit::TrailingStopLimitOrder,
+    trailing_stop_market::TrailingStopMarketOrder,
+};
+use crate::{
+    enums::{OrderSide, OrderStatus, OrderType},
+    events::order::{event::OrderEvent, initialized::OrderInitialized},
+    identifiers::{
+        client_order_id::ClientOrderId, instrument_id::InstrumentId, strategy_id::StrategyId,
+    },
+    types::{price::Price, quantity::Quantity},
+};
+
+/// Represents any concrete order type, for storing and handling orders generically.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum OrderAny {
+    Limit(LimitOrder),
+    LimitIfTouched(LimitIfTouchedOrder),
+    Market(MarketOrder),
+    MarketIfTouched(MarketIfTouchedOrder),
+    MarketToLimit(MarketToLimitOrder),
+    StopLimit(StopLimitOrder),
+    StopMarket(StopMarketOrder),
+    TrailingStopLimit(TrailingStopLimitOrder),
+    TrailingStopMarket(TrailingStopMarketOrder),
+}
+
+macro_rules! impl_from_order {
+    ($order:ty, $variant:ident) => {
+        impl From<$order> for OrderAny {
+            fn from(order: $order) -> Self {
+                Self::$variant(order)
+            }
+        }
+    };
+}
+
+impl_from_order!(LimitOrder, Limit);
+impl_from_order!(LimitIfTouchedOrder, LimitIfTouched);
+impl_from_order!(MarketOrder, Market);
+impl_from_order!(MarketIfTouchedOrder, MarketIfTouched);
+impl_from_order!(MarketToLimitOrder, MarketToLimit);
+impl_from_order!(StopLimitOrder, StopLimit);
+impl_from_order!(StopMarketOrder, StopMarket);
+impl_from_order!(TrailingStopLimitOrder, TrailingStopLimit);
+impl_from_order!(TrailingStopMarketOrder, TrailingStopMarket);
+
+impl From<OrderInitialized> for OrderAny {
+    fn from(event: OrderInitialized) -> Self {
+        match event.order_type {
+            OrderType::Limit => Self::Limit(event.into()),
+            OrderType::LimitIfTouched => Self::LimitIfTouched(event.into()),
+            OrderType::Market => Self::Market(event.into()),
+            OrderType::MarketIfTouched => Self::MarketIfTouched(event.into()),
+            OrderType::MarketToLimit => Self::MarketToLimit(event.into()),
+            OrderType::StopLimit => Self::StopLimit(event.into()),
+            OrderType::StopMarket => Self::StopMarket(event.into()),
+            OrderType::TrailingStopLimit => Self::TrailingStopLimit(event.into()),
+            OrderType::TrailingStopMarket => Self::TrailingStopMarket(event.into()),
+        }
+    }
+}
+
+impl TryFrom<OrderInitialized> for OrderAny {
+    type Error = OrderError;
+
+    /// Creates an order from the `event`, checking the fields the order type requires are set
+    /// rather than panicking as the `From` conversion does.
+    fn try_from(event: OrderInitialized) -> Result<Self, Self::Error> {
+        let required: &[(&'static str, bool)] = match event.order_type {
+            OrderType::Limit => &[("price", event.price.is_some())],
+            OrderType::LimitIfTouched | OrderType::StopLimit => &[
+                ("price", event.price.is_some()),
+                ("trigger_price", event.trigger_price.is_some()),
+                ("trigger_type", event.trigger_type.is_some()),
+            ],
+            OrderType::Market => {
+                return MarketOrder::try_from(event)
+                    .map(Self::Market)
+                    .map_err(|e| OrderError::InvalidInitialization(e.to_string()));
+            }
+            OrderType::MarketIfTouched | OrderType::StopMarket => &[
+                ("trigger_price", event.trigger_price.is_some()),
+                ("trigger_type", event.trigger_type.is_some()),
+            ],
+            OrderType::MarketToLimit => &[],
+            OrderType::TrailingStopLimit => &[
+                ("price", event.price.is_some()),
+                ("trigger_price", event.trigger_price.is_some()),
+                ("trigger_type", event.trigger_type.is_some()),
+                ("limit_offset", event.limit_offset.is_some()),
+                ("trailing_offset", event.trailing_offset.is_some()),
+                ("trailing_offset_type", event.trailing_offset_type.is_some()),
+            ],
+            OrderType::TrailingStopMarket => &[
+                ("trigger_price", event.trigger_price.is_some()),
+                ("trigger_type", event.trigger_type.is_some()),
+                ("trailing_offset", event.trailing_offset.is_some()),
+                ("trailing_offset_type", event.trailing_offset_type.is_some()),
+            ],
+        };
+
+        if let Some((field, _)) = required.iter().find(|(_, is_set)| !is_set) {
+            return Err(OrderError::MissingField(field, event.order_type));
+        }
+        Ok(Self::from(event))
+    }
+}
+
+impl OrderAny {
+    /// Creates an order from the `init` event, then applies each of the subsequent `events`.
+    ///
+    /// # Errors
+    ///
+    /// If the `init` event is missing a field required by the order type, or any event is not a
+    /// valid transition from the current order status.
+    pub fn from_events(
+        init: OrderInitialized,
+        events: impl IntoIterator<Item = OrderEvent>,
+    ) -> Result<Self, OrderError> {
+        let mut order = Self::try_from(init)?;
+        for event in events {
+            order.apply(event)?;
+        }
+        Ok(order)
+    }
+
+    /// Rebuilds an order from its full event history, such as the event list stored for the
+    /// order in the cache database.
+    ///
+    /// The first event must be the `OrderInitialized` event the order was created from, with
+    /// every subsequent event replayed in order through [`OrderCore::apply`].
+    ///
+    /// # Errors
+    ///
+    /// If the first event is not `OrderInitialized` (or is missing a field required by the order
+    /// type), or any subsequent event is not a valid transition from the order status at that
+    /// point.
+    pub fn from_event_history(events: Vec<OrderEvent>) -> Result<Self, OrderError> {
+        let mut events = events.into_iter();
+        match events.next() {
+            Some(OrderEvent::OrderInitialized(init)) => Self::from_events(init, events),
+            _ => Err(OrderError::NotInitialized),
+        }
+    }
+
+    /// Returns a reference to the order as a trait object.
+    #[must_use]
+    pub fn as_order(&self) -> &dyn Order {
+        match self {
+            Self::Limit(order) => order,
+            Self::LimitIfTouched(order) => order,
+            Self::Market(order) => order,
+            Self::MarketIfTouched(order) => order,
+            Self::MarketToLimit(order) => order,
+            Self::StopLimit(order) => order,
+            Self::StopMarket(order) => order,
+            Self::TrailingStopLimit(order) => order,
+            Self::TrailingStopMarket(order) => order,
+        }
+    }
+
+    /// Returns a mutable reference to the order as a trait object.
+    pub fn as_order_mut(&mut self) -> &mut dyn Order {
+        match self {
+            Self::Limit(order) => order,
+            Self::LimitIfTouched(order) => order,
+            Self::Market(order) => order,
+            Self::MarketIfTouched(order) => order,
+            Self::MarketToLimit(order) => order,
+            Self::StopLimit(order) => order,
+            Self::StopMarket(order) => order,
+            Self::TrailingStopLimit(order) => order,
+            Self::TrailingStopMarket(order) => order,
+        }
+    }
+
+    /// Returns a reference to the core state shared by all order types.
+    #[must_use]
+    pub fn core(&self) -> &OrderCore {
+        match self {
+            Self::Limit(order) => order,
+            Self::LimitIfTouched(order) => order,
+            Self::Market(order) => order,
+            Self::MarketIfTouched(order) => order,
+            Self::MarketToLimit(order) => order,
+            Self::StopLimit(order) => order,
+            Self::StopMarket(order) => order,
+            Self::TrailingStopLimit(order) => order,
+            Self::TrailingStopMarket(order) => order,
+        }
+    }
+
+    /// Applies the given `event` to the order.
+    ///
+    /// # Errors
+    ///
+    /// If the event is not a valid transition from the current order status.
+    pub fn apply(&mut self, event: OrderEvent) -> Result<(), OrderError> {
+        self.as_order_mut().apply(event)
+    }
+
+    #[must_use]
+    pub fn client_order_id(&self) -> ClientOrderId {
+        self.core().client_order_id
+    }
+
+    #[must_use]
+    pub fn strategy_id(&self) -> StrategyId {
+        self.core().strategy_id
+    }
+
+    #[must_use]
+    pub fn instrument_id(&self) -> InstrumentId {
+        self.core().instrument_id
+    }
+
+    #[must_use]
+    pub fn order_type(&self) -> OrderType {
+        self.core().order_type
+    }
+
+    #[must_use]
+    pub fn side(&self) -> OrderSide {
+        self.core().side
+    }
+
+    #[must_use]
+    pub fn status(&self) -> OrderStatus {
+        self.core().status
+    }
+
+    #[must_use]
+    pub fn quantity(&self) -> Quantity {
+        self.core().quantity
+    }
+
+    #[must_use]
+    pub fn price(&self) -> Option<Price> {
+        self.as_order().price()
+    }
+
+    #[must_use]
+    pub fn trigger_price(&self) -> Option<Price> {
+        self.as_order().trigger_price()
+    }
+
+    #[must_use]
+    pub fn is_open(&self) -> bool {
+        self.as_order().is_open()
+    }
+
+    #[must_use]
+    pub fn is_closed(&self) -> bool {
+        self.as_order().is_closed()
+    }
+}
+
+impl PartialEq for OrderAny {
+    fn eq(&self, other: &Self) -> bool {
+        self.client_order_id() == other.client_order_id()
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Tests
+////////////////////////////////////////////////////////////////////////////////
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+    use crate::{
+        enums::{TimeInForce, TrailingOffsetType, TriggerType},
+        events::order::{
+            accepted::OrderAcceptedBuilder, cancel_rejected::OrderCancelRejectedBuilder,
+            filled::OrderFilledBuilder, initialized::OrderInitializedBuilder,
+            pending_cancel::OrderPendingCancelBuilder, pending_update::OrderPendingUpdateBuilder,
+            submitted::OrderSubmittedBuilder, updated::OrderUpdatedBuilder,
+        },
+    };
+
+    fn initialized(order_type: OrderType) -> OrderInitialized {
+        OrderInitializedBuilder::default()
+            .order_type(order_type)
+            .price(Some(Price::from("1.00010")))
+            .trigger_price(Some(Price::from("1.00000")))
+            .trigger_type(Some(TriggerType::Default))
+            .limit_offset(Some(Price::from("0.00010")))
+            .trailing_offset(Some(Price::from("0.00010")))
+            .trailing_offset_type(Some(TrailingOffsetType::Price))
+            .build()
+            .unwrap()
+    }
+
+    #[rstest]
+    #[case(OrderType::Limit)]
+    #[case(OrderType::LimitIfTouched)]
+    #[case(OrderType::Market)]
+    #[case(OrderType::MarketIfTouched)]
+    #[case(OrderType::MarketToLimit)]
+    #[case(OrderType::StopLimit)]
+    #[case(OrderType::StopMarket)]
+    #[case(OrderType::TrailingStopLimit)]
+    #[case(OrderType::TrailingStopMarket)]
+    fn test_from_order_initialized(#[case] order_type: OrderType) {
+        let init = initialized(order_type);
+
+        let order = OrderAny::from(init.clone());
+
+        assert_eq!(order.order_type(), order_type);
+        assert_eq!(order.client_order_id(), init.client_order_id);
+        assert_eq!(order.status(), OrderStatus::Initialized);
+        assert_eq!(order.as_order().order_type(), order_type);
+    }
+
+    #[rstest]
+    fn test_from_events() {
+        let init = initialized(OrderType::Limit);
+        let events = vec![
+            OrderEvent::OrderSubmitted(OrderSubmittedBuilder::default().build().unwrap()),
+            OrderEvent::OrderAccepted(OrderAcceptedBuilder::default().build().unwrap()),
+            OrderEvent::OrderFilled(OrderFilledBuilder::default().build().unwrap()),
+        ];
+
+        let order = OrderAny::from_events(init, events).unwrap();
+
+        assert!(matches!(order, OrderAny::Limit(_)));
+        assert_eq!(order.status(), OrderStatus::Filled);
+        assert_eq!(order.price(), Some(Price::from("1.00010")));
+        assert_eq!(order.as_order().event_count(), 3);
+        assert!(order.is_closed());
+    }
+
+    #[rstest]
+    fn test_from_events_with_invalid_transition() {
+        let init = initialized(OrderType::Market);
+        let events = vec![OrderEvent::OrderFilled(
+            OrderFilledBuilder::default().build().unwrap(),
+        )];
+
+        let result = OrderAny::from_events(init, events);
+
+        assert!(matches!(result, Err(OrderError::InvalidStateTransition)));
+    }
+
+    #[rstest]
+    fn test_serde_round_trip() {
+        let mut order = OrderAny::from(initialized(OrderType::StopLimit));
+        order
+            .apply(OrderEvent::OrderSubmitted(
+                OrderSubmittedBuilder::default().build().unwrap(),
+            ))
+            .unwrap();
+
+        let json = serde_json::to_string(&order).unwrap();
+        let deserialized: OrderAny = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(deserialized, order);
+        assert!(matches!(deserialized, OrderAny::StopLimit(_)));
+        assert_eq!(deserialized.status(), OrderStatus::Submitted);
+        assert_eq!(deserialized.trigger_price(), Some(Price::from("1.00000")));
+        assert_eq!(deserialized.as_order().event_count(), 1);
+    }
+
+    fn event_history() -> Vec<OrderEvent> {
+        vec![
+            OrderEvent::OrderInitialized(initialized(OrderType::Limit)),
+            OrderEvent::OrderSubmitted(OrderSubmittedBuilder::default().build().unwrap()),
+            OrderEvent::OrderAccepted(OrderAcceptedBuilder::default().build().unwrap()),
+            OrderEvent::OrderPendingUpdate(OrderPendingUpdateBuilder::default().build().unwrap()),
+            OrderEvent::OrderUpdated(
+                OrderUpdatedBuilder::default()
+                    .quantity(Quantity::from(100_000))
+                    .price(Some(Price::from("1.00020")))
+                    .build()
+                    .unwrap(),
+            ),
+            OrderEvent::OrderFilled(
+                OrderFilledBuilder::default()
+                    .last_qty(Quantity::from(40_000))
+                    .build()
+                    .unwrap(),
+            ),
+            OrderEvent::OrderPendingCancel(OrderPendingCancelBuilder::default().build().unwrap()),
+            OrderEvent::OrderCancelRejected(OrderCancelRejectedBuilder::default().build().unwrap()),
+            OrderEvent::OrderFilled(
+                OrderFilledBuilder::default()
+                    .last_qty(Quantity::from(60_000))
+                    .build()
+                    .unwrap(),
+            ),
+        ]
+    }
+
+    #[rstest]
+    #[case(1, OrderStatus::Initialized)]
+    #[case(4, OrderStatus::PendingUpdate)]
+    #[case(5, OrderStatus::Accepted)]
+    #[case(6, OrderStatus::PartiallyFilled)]
+    #[case(7, OrderStatus::PendingCancel)]
+    #[case(8, OrderStatus::PartiallyFilled)]
+    #[case(9, OrderStatus::Filled)]
+    fn test_from_event_history_status(#[case] count: usize, #[case] expected: OrderStatus) {
+        let events = event_history().into_iter().take(count).collect();
+
+        let order = OrderAny::from_event_history(events).unwrap();
+
+        assert_eq!(order.status(), expected);
+    }
+
+    #[rstest]
+    fn test_from_event_history() {
+        let events = event_history();
+        let OrderEvent::OrderInitialized(init) = &events[0] else {
+            panic!("Expected `OrderInitialized`")
+        };
+        let init_id = init.event_id;
+
+        let order = OrderAny::from_event_history(events.clone()).unwrap();
+
+        assert!(matches!(order, OrderAny::Limit(_)));
+        assert_eq!(order.core().init_id, init_id);
+        assert_eq!(order.price(), Some(Price::from("1.00020")));
+        assert_eq!(order.core().filled_qty, Quantity::from(100_000));
+        assert_eq!(order.core().leaves_qty, Quantity::from(0));
+        assert_eq!(order.core().trade_ids.len(), 2);
+        assert_eq!(order.as_order().event_count(), events.len() - 1);
+        assert_eq!(order.as_order().last_event(), events.last().unwrap());
+    }
+
+    #[rstest]
+    fn test_from_event_history_without_initialized_event() {
+        let mut events = event_history();
+        events.remove(0);
+
+        let result = OrderAny::from_event_history(events);
+
+        assert!(matches!(result, Err(OrderError::NotInitialized)));
+    }
+
+    #[rstest]
+    #[case(OrderType::Limit, "price")]
+    #[case(OrderType::StopMarket, "trigger_price")]
+    #[case(OrderType::TrailingStopMarket, "trigger_price")]
+    fn test_try_from_initialized_with_missing_field(
+        #[case] order_type: OrderType,
+        #[case] field: &str,
+    ) {
+        let init = OrderInitializedBuilder::default()
+            .order_type(order_type)
+            .build()
+            .unwrap();
+
+        let result = OrderAny::try_from(init);
+
+        assert!(
+            matches!(result, Err(OrderError::MissingField(f, t)) if f == field && t == order_type)
+        );
+    }
+
+    #[rstest]
+    fn test_try_from_initialized_market_with_gtd() {
+        let init = OrderInitializedBuilder::default()
+            .order_type(OrderType::Market)
+            .time_in_force(TimeInForce::Gtd)
+            .build()
+            .unwrap();
+
+        let result = OrderAny::try_from(init);
+
+        assert!(matches!(result, Err(OrderError::InvalidInitialization(_))));
+    }
+
+    #[rstest]
+    fn test_from_event_history_with_missing_field() {
+        let mut events = event_history();
+        let mut init = initialized(OrderType::Limit);
+        init.price = None;
+        events[0] = OrderEvent::OrderInitialized(init);
+
+        let result = OrderAny::from_event_history(events);
+
+        assert!(matches!(
+            result,
+            Err(OrderError::MissingField("price", OrderType::Limit))
+        ));
+    }
+
+    #[rstest]
+    fn test_from_event_history_with_invalid_transition() {
+        let mut events = event_history();
+        events.push(OrderEvent::OrderAccepted(
+            OrderAcceptedBuilder::default().build().unwrap(),
+        ));
+
+        let result = OrderAny::from_event_history(events);
+
+        assert!(matches!(result, Err(OrderError::InvalidStateTransition)));
+    }
+}
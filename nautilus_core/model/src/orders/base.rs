@@ -53,6 +53,12 @@ pub enum OrderError {
     UnrecognizedEvent,
     #[error("No previous state")]
     NoPreviousState,
+    #[error("First event was not `OrderInitialized`")]
+    NotInitialized,
+    #[error("Missing `{0}` for {1} order")]
+    MissingField(&'static str, OrderType),
+    #[error("Invalid order initialization: {0}")]
+    InvalidInitialization(String),
 }
 
 const VALID_STOP_ORDER_TYPES: &[OrderType] = &[
@@ -468,9 +474,11 @@ impl OrderCore {
         assert_eq!(self.client_order_id, event.client_order_id());
         assert_eq!(self.strategy_id, event.strategy_id());
 
-        let new_status = self.status.transition(&event)?;
-        self.previous_status = Some(self.status);
-        self.status = new_status;
+        let new_status = self.next_status(&event)?;
+        if new_status != self.status {
+            self.previous_status = Some(self.status);
+            self.status = new_status;
+        }
 
         match &event {
             OrderEvent::OrderDenied(event) => self.denied(event),
@@ -487,6 +495,7 @@ impl OrderCore {
             OrderEvent::OrderTriggered(event) => self.triggered(event),
             OrderEvent::OrderCanceled(event) => self.canceled(event),
             OrderEvent::OrderExpired(event) => self.expired(event),
+            OrderEvent::OrderPartiallyFilled(event) => self.filled(event),
             OrderEvent::OrderFilled(event) => self.filled(event),
             _ => return Err(OrderError::UnrecognizedEvent),
         }
@@ -496,6 +505,38 @@ impl OrderCore {
         Ok(())
     }
 
+    fn next_status(&self, event: &OrderEvent) -> Result<OrderStatus, OrderError> {
+        let mut status = self.status;
+        match event {
+            // Updates and rejected requests only restore the status from before a pending request
+            OrderEvent::OrderUpdated(_) | OrderEvent::OrderModifyRejected(_) => {
+                self.restore_status(OrderStatus::PendingUpdate)
+            }
+            OrderEvent::OrderCancelRejected(_) => self.restore_status(OrderStatus::PendingCancel),
+            // A fill for less than the leaves quantity is a partial fill
+            OrderEvent::OrderFilled(fill) if self.filled_qty + fill.last_qty < self.quantity => {
+                status.transition(&OrderEvent::OrderPartiallyFilled(*fill))
+            }
+            _ => status.transition(event),
+        }
+    }
+
+    fn restore_status(&self, pending_status: OrderStatus) -> Result<OrderStatus, OrderError> {
+        match self.status {
+            status if status == pending_status => {
+                self.previous_status.ok_or(OrderError::NoPreviousState)
+            }
+            // Otherwise only an order working at the venue (or emulated) can be modified
+            OrderStatus::PendingUpdate
+            | OrderStatus::PendingCancel
+            | OrderStatus::Accepted
+            | OrderStatus::Triggered
+            | OrderStatus::PartiallyFilled
+            | OrderStatus::Emulated => Ok(self.status),
+            _ => Err(OrderError::InvalidStateTransition),
+        }
+    }
+
     fn denied(&self, _event: &OrderDenied) {
         // Do nothing else
     }
@@ -528,16 +569,12 @@ impl OrderCore {
         // Do nothing else
     }
 
-    fn modify_rejected(&mut self, _event: &OrderModifyRejected) {
-        self.status = self
-            .previous_status
-            .unwrap_or_else(|| panic!("{}", OrderError::NoPreviousState));
+    fn modify_rejected(&self, _event: &OrderModifyRejected) {
+        // Do nothing else
     }
 
-    fn cancel_rejected(&mut self, _event: &OrderCancelRejected) {
-        self.status = self
-            .previous_status
-            .unwrap_or_else(|| panic!("{}", OrderError::NoPreviousState));
+    fn cancel_rejected(&self, _event: &OrderCancelRejected) {
+        // Do nothing else
     }
 
     fn triggered(&mut self, _event: &OrderTriggered) {}
@@ -656,8 +693,10 @@ mod tests {
     use crate::{
         enums::{OrderSide, OrderStatus, PositionSide},
         events::order::{
-            accepted::OrderAcceptedBuilder, denied::OrderDeniedBuilder, filled::OrderFilledBuilder,
-            initialized::OrderInitializedBuilder, submitted::OrderSubmittedBuilder,
+            accepted::OrderAcceptedBuilder, cancel_rejected::OrderCancelRejectedBuilder,
+            denied::OrderDeniedBuilder, filled::OrderFilledBuilder,
+            initialized::OrderInitializedBuilder, modify_rejected::OrderModifyRejectedBuilder,
+            submitted::OrderSubmittedBuilder, updated::OrderUpdatedBuilder,
         },
         orders::market::MarketOrder,
     };
@@ -749,6 +788,69 @@ mod tests {
         assert_eq!(order.last_event(), &event);
     }
 
+    #[rstest]
+    #[case(OrderEvent::OrderUpdated(OrderUpdatedBuilder::default().build().unwrap()))]
+    #[case(OrderEvent::OrderModifyRejected(OrderModifyRejectedBuilder::default().build().unwrap()))]
+    #[case(OrderEvent::OrderCancelRejected(OrderCancelRejectedBuilder::default().build().unwrap()))]
+    fn test_modify_events_rejected_when_not_working(#[case] event: OrderEvent) {
+        let init = OrderInitializedBuilder::default().build().unwrap();
+        let mut initialized: MarketOrder = init.clone().into();
+        let mut filled: MarketOrder = init.into();
+        filled
+            .apply(OrderEvent::OrderSubmitted(
+                OrderSubmittedBuilder::default().build().unwrap(),
+            ))
+            .unwrap();
+        filled
+            .apply(OrderEvent::OrderAccepted(
+                OrderAcceptedBuilder::default().build().unwrap(),
+            ))
+            .unwrap();
+        filled
+            .apply(OrderEvent::OrderFilled(
+                OrderFilledBuilder::default().build().unwrap(),
+            ))
+            .unwrap();
+
+        let initialized_result = initialized.apply(event.clone());
+        let filled_result = filled.apply(event);
+
+        assert!(matches!(
+            initialized_result,
+            Err(OrderError::InvalidStateTransition)
+        ));
+        assert!(matches!(
+            filled_result,
+            Err(OrderError::InvalidStateTransition)
+        ));
+        assert_eq!(initialized.status(), OrderStatus::Initialized);
+        assert_eq!(filled.status(), OrderStatus::Filled);
+        assert_eq!(filled.event_count(), 4);
+    }
+
+    #[rstest]
+    fn test_updated_when_accepted_keeps_status() {
+        let mut order: MarketOrder = OrderInitializedBuilder::default().build().unwrap().into();
+        order
+            .apply(OrderEvent::OrderSubmitted(
+                OrderSubmittedBuilder::default().build().unwrap(),
+            ))
+            .unwrap();
+        order
+            .apply(OrderEvent::OrderAccepted(
+                OrderAcceptedBuilder::default().build().unwrap(),
+            ))
+            .unwrap();
+
+        order
+            .apply(OrderEvent::OrderUpdated(
+                OrderUpdatedBuilder::default().build().unwrap(),
+            ))
+            .unwrap();
+
+        assert_eq!(order.status(), OrderStatus::Accepted);
+    }
+
     #[rstest]
     fn test_order_life_cycle_to_filled() {
         let init = OrderInitializedBuilder::default().build().unwrap();
@@ -21,6 +21,7 @@ use std::{
 use anyhow::{bail, Result};
 use nautilus_core::{time::UnixNanos, uuid::UUID4};
 use pyo3::prelude::*;
+use serde::{Deserialize, Serialize};
 use ustr::Ustr;
 
 use super::base::{Order, OrderCore};
@@ -43,6 +44,7 @@ use crate::{
     },
 };
 
+#[derive(Clone, Debug, Serialize, Deserialize)]
 #[cfg_attr(
     feature = "python",
     pyclass(module = "nautilus_trader.core.nautilus_pyo3.model")
@@ -311,11 +313,15 @@ impl Order for MarketOrder {
     }
 
     fn apply(&mut self, event: OrderEvent) -> Result<(), OrderError> {
-        if let OrderEvent::OrderUpdated(ref event) = event {
-            self.update(event);
+        let updated = match event {
+            OrderEvent::OrderUpdated(updated) => Some(updated),
+            _ => None,
         };
 
         self.core.apply(event)?;
+        if let Some(updated) = updated {
+            self.update(&updated);
+        }
 
         Ok(())
     }
@@ -333,8 +339,10 @@ impl Order for MarketOrder {
     }
 }
 
-impl From<OrderInitialized> for MarketOrder {
-    fn from(event: OrderInitialized) -> Self {
+impl TryFrom<OrderInitialized> for MarketOrder {
+    type Error = anyhow::Error;
+
+    fn try_from(event: OrderInitialized) -> Result<Self> {
         MarketOrder::new(
             event.trader_id,
             event.strategy_id,
@@ -356,7 +364,12 @@ impl From<OrderInitialized> for MarketOrder {
             event.exec_spawn_id,
             event.tags,
         )
-        .unwrap()
+    }
+}
+
+impl From<OrderInitialized> for MarketOrder {
+    fn from(event: OrderInitialized) -> Self {
+        Self::try_from(event).unwrap()
     }
 }
 
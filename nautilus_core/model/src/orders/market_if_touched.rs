@@ -20,6 +20,7 @@ use std::{
 
 use nautilus_core::{time::UnixNanos, uuid::UUID4};
 use pyo3::prelude::*;
+use serde::{Deserialize, Serialize};
 use ustr::Ustr;
 
 use super::base::{Order, OrderCore, OrderError};
@@ -38,6 +39,7 @@ use crate::{
     types::{price::Price, quantity::Quantity},
 };
 
+#[derive(Clone, Debug, Serialize, Deserialize)]
 #[cfg_attr(
     feature = "python",
     pyclass(module = "nautilus_trader.core.nautilus_pyo3.model")
@@ -322,12 +324,16 @@ impl Order for MarketIfTouchedOrder {
     }
 
     fn apply(&mut self, event: OrderEvent) -> Result<(), OrderError> {
-        if let OrderEvent::OrderUpdated(ref event) = event {
-            self.update(event);
+        let updated = match event {
+            OrderEvent::OrderUpdated(updated) => Some(updated),
+            _ => None,
         };
         let is_order_filled = matches!(event, OrderEvent::OrderFilled(_));
 
         self.core.apply(event)?;
+        if let Some(updated) = updated {
+            self.update(&updated);
+        }
 
         if is_order_filled {
             self.core.set_slippage(self.trigger_price)
@@ -15,6 +15,7 @@
 
 #![allow(dead_code)]
 
+pub mod any;
 pub mod base;
 pub mod default;
 pub mod limit;
@@ -87,7 +87,7 @@ pub struct Position {
 }
 
 impl Position {
-    pub fn new<T: Instrument>(instrument: T, fill: OrderFilled) -> Result<Self> {
+    pub fn new<T: Instrument + ?Sized>(instrument: &T, fill: OrderFilled) -> Result<Self> {
         assert_eq!(instrument.id(), fill.instrument_id);
         assert!(fill.position_id.is_some());
         assert_ne!(fill.order_side, OrderSide::NoOrderSide);
@@ -597,7 +597,7 @@ mod tests {
             None,
             None,
         );
-        let mut position = Position::new(audusd_sim, fill1).unwrap();
+        let mut position = Position::new(&audusd_sim, fill1).unwrap();
         position.apply(&fill2);
     }
 
@@ -622,7 +622,7 @@ mod tests {
             None,
         );
         let last_price = Price::from_str("1.0005").unwrap();
-        let position = Position::new(audusd_sim, fill).unwrap();
+        let position = Position::new(&audusd_sim, fill).unwrap();
         assert_eq!(position.symbol(), audusd_sim.id.symbol);
         assert_eq!(position.venue(), audusd_sim.id.venue);
         assert!(!position.is_opposite_side(fill.order_side));
@@ -688,7 +688,7 @@ mod tests {
             None,
         );
         let last_price = Price::from_str("1.00050").unwrap();
-        let position = Position::new(audusd_sim, fill).unwrap();
+        let position = Position::new(&audusd_sim, fill).unwrap();
         assert_eq!(position.symbol(), audusd_sim.id.symbol);
         assert_eq!(position.venue(), audusd_sim.id.venue);
         assert!(!position.is_opposite_side(fill.order_side));
@@ -752,7 +752,7 @@ mod tests {
             None,
         );
         let last_price = Price::from_str("1.00048").unwrap();
-        let position = Position::new(audusd_sim, fill).unwrap();
+        let position = Position::new(&audusd_sim, fill).unwrap();
         assert_eq!(position.quantity, Quantity::from(50_000));
         assert_eq!(position.peak_qty, Quantity::from(50_000));
         assert_eq!(position.side, PositionSide::Long);
@@ -819,7 +819,7 @@ mod tests {
             None,
         );
         let last_price = Price::from_str("1.0005").unwrap();
-        let mut position = Position::new(audusd_sim, fill1).unwrap();
+        let mut position = Position::new(&audusd_sim, fill1).unwrap();
         position.apply(&fill2);
 
         assert_eq!(position.quantity, Quantity::from(100_000));
@@ -872,7 +872,7 @@ mod tests {
             None,
             Some(1_000_000_000),
         );
-        let mut position = Position::new(audusd_sim, fill).unwrap();
+        let mut position = Position::new(&audusd_sim, fill).unwrap();
 
         let fill2 = OrderFilled::new(
             order.trader_id,
@@ -962,7 +962,7 @@ mod tests {
             None,
             None,
         );
-        let mut position = Position::new(audusd_sim, fill1).unwrap();
+        let mut position = Position::new(&audusd_sim, fill1).unwrap();
         // create closing from order from different venue but same strategy
         let fill2 = TestOrderEventStubs::order_filled(
             &order2,
@@ -1053,7 +1053,7 @@ mod tests {
             None,
             None,
         );
-        let mut position = Position::new(audusd_sim, fill1).unwrap();
+        let mut position = Position::new(&audusd_sim, fill1).unwrap();
         let fill2 = TestOrderEventStubs::order_filled(
             &order2,
             &audusd_sim,
@@ -1161,7 +1161,7 @@ mod tests {
             None,
             None,
         );
-        let mut position = Position::new(audusd_sim, fill1).unwrap();
+        let mut position = Position::new(&audusd_sim, fill1).unwrap();
         let last = Price::from("1.0005");
         position.apply(&fill2);
         position.apply(&fill3);
@@ -1227,7 +1227,7 @@ mod tests {
             Some(commission1),
             None,
         );
-        let mut position = Position::new(currency_pair_ethusdt, fill1).unwrap();
+        let mut position = Position::new(&currency_pair_ethusdt, fill1).unwrap();
         let quantity2 = Quantity::from(17);
         let order2 = TestOrderStubs::market_order(
             currency_pair_ethusdt.id,
@@ -1359,7 +1359,7 @@ mod tests {
             Some(commission1),
             Some(1_000_000_000),
         );
-        let mut position = Position::new(audusd_sim, fill1).unwrap();
+        let mut position = Position::new(&audusd_sim, fill1).unwrap();
 
         let fill2 = OrderFilled::new(
             order.trader_id,
@@ -1478,7 +1478,7 @@ mod tests {
             Some(commission1),
             None,
         );
-        let mut position = Position::new(currency_pair_btcusdt, fill1).unwrap();
+        let mut position = Position::new(&currency_pair_btcusdt, fill1).unwrap();
         let order2 = TestOrderStubs::market_order(
             currency_pair_btcusdt.id,
             OrderSide::Buy,
@@ -1635,7 +1635,7 @@ mod tests {
             None,
             None,
         );
-        let position = Position::new(currency_pair_btcusdt, fill).unwrap();
+        let position = Position::new(&currency_pair_btcusdt, fill).unwrap();
         let result = position.calculate_pnl(10500.0, 10500.0, Quantity::from("100000.0"));
         assert_eq!(result, Money::from("0 USDT"));
     }
@@ -1667,7 +1667,7 @@ mod tests {
             Some(commission),
             None,
         );
-        let position = Position::new(currency_pair_btcusdt, fill).unwrap();
+        let position = Position::new(&currency_pair_btcusdt, fill).unwrap();
         let pnl = position.calculate_pnl(10500.0, 10510.0, Quantity::from("12.0"));
         assert_eq!(pnl, Money::from("120 USDT"));
         assert_eq!(position.realized_pnl, Some(Money::from("-126 USDT")));
@@ -1709,7 +1709,7 @@ mod tests {
             Some(commission),
             None,
         );
-        let position = Position::new(currency_pair_btcusdt, fill).unwrap();
+        let position = Position::new(&currency_pair_btcusdt, fill).unwrap();
         let pnl = position.calculate_pnl(10500.0, 10480.5, Quantity::from("10.0"));
         assert_eq!(pnl, Money::from("-195 USDT"));
         assert_eq!(position.realized_pnl, Some(Money::from("-126 USDT")));
@@ -1751,7 +1751,7 @@ mod tests {
             Some(commission),
             None,
         );
-        let position = Position::new(currency_pair_btcusdt, fill).unwrap();
+        let position = Position::new(&currency_pair_btcusdt, fill).unwrap();
         let pnl = position.calculate_pnl(10500.0, 10390.0, Quantity::from("10.15"));
         assert_eq!(pnl, Money::from("1116.5 USDT"));
         assert_eq!(
@@ -1793,7 +1793,7 @@ mod tests {
             Some(commission),
             None,
         );
-        let position = Position::new(currency_pair_btcusdt, fill).unwrap();
+        let position = Position::new(&currency_pair_btcusdt, fill).unwrap();
         let pnl = position.calculate_pnl(10500.0, 10670.5, Quantity::from("10.0"));
         assert_eq!(pnl, Money::from("-1705 USDT"));
         assert_eq!(
@@ -1831,7 +1831,7 @@ mod tests {
             Some(commission),
             None,
         );
-        let position = Position::new(xbtusd_bitmex, fill).unwrap();
+        let position = Position::new(&xbtusd_bitmex, fill).unwrap();
         let pnl = position.calculate_pnl(10000.0, 11000.0, Quantity::from("100000.0"));
         assert_eq!(pnl, Money::from("-0.90909091 BTC"));
         assert_eq!(
@@ -1868,7 +1868,7 @@ mod tests {
             Some(commission),
             None,
         );
-        let position = Position::new(ethusdt_bitmex, fill).unwrap();
+        let position = Position::new(&ethusdt_bitmex, fill).unwrap();
 
         assert_eq!(
             position.unrealized_pnl(Price::from("370.00")),
@@ -1932,7 +1932,7 @@ mod tests {
             Some(commission2),
             None,
         );
-        let mut position = Position::new(currency_pair_btcusdt, fill1).unwrap();
+        let mut position = Position::new(&currency_pair_btcusdt, fill1).unwrap();
         position.apply(&fill2);
         let pnl = position.unrealized_pnl(Price::from("11505.60"));
         assert_eq!(pnl, Money::from("4022.40000000 USDT"));
@@ -1973,7 +1973,7 @@ mod tests {
             Some(commission),
             None,
         );
-        let position = Position::new(currency_pair_btcusdt, fill).unwrap();
+        let position = Position::new(&currency_pair_btcusdt, fill).unwrap();
         let pnl = position.unrealized_pnl(Price::from("10407.15"));
         assert_eq!(pnl, Money::from("582.03640000 USDT"));
         assert_eq!(
@@ -2010,7 +2010,7 @@ mod tests {
             None,
         );
 
-        let position = Position::new(xbtusd_bitmex, fill).unwrap();
+        let position = Position::new(&xbtusd_bitmex, fill).unwrap();
         let pnl = position.unrealized_pnl(Price::from("11505.60"));
         assert_eq!(pnl, Money::from("0.83238969 BTC"));
         assert_eq!(position.realized_pnl, Some(Money::from("-0.00714286 BTC")));
@@ -2040,7 +2040,7 @@ mod tests {
             Some(commission),
             None,
         );
-        let position = Position::new(xbtusd_bitmex, fill).unwrap();
+        let position = Position::new(&xbtusd_bitmex, fill).unwrap();
         let pnl = position.unrealized_pnl(Price::from("12506.65"));
 
         assert_eq!(pnl, Money::from("19.30166700 BTC"));
@@ -2078,7 +2078,7 @@ mod tests {
             Some(commission),
             None,
         );
-        let position = Position::new(audusd_sim, fill).unwrap();
+        let position = Position::new(&audusd_sim, fill).unwrap();
         assert_eq!(position.signed_qty, expected);
     }
 }
@@ -260,6 +260,7 @@ pub fn model(_: Python<'_>, m: &PyModule) -> PyResult<()> {
     m.add_class::<enums::OrderType>()?;
     m.add_class::<enums::PositionSide>()?;
     m.add_class::<enums::PriceType>()?;
+    m.add_class::<enums::RecordFlag>()?;
     m.add_class::<enums::TimeInForce>()?;
     m.add_class::<enums::TradingState>()?;
     m.add_class::<enums::TrailingOffsetType>()?;
@@ -54,22 +54,22 @@ impl Position {
             .extract::<String>(py)?;
         if instrument_type == "CryptoFuture" {
             let instrument_rust = instrument.extract::<CryptoFuture>(py)?;
-            Ok(Self::new(instrument_rust, fill).unwrap())
+            Ok(Self::new(&instrument_rust, fill).unwrap())
         } else if instrument_type == "CryptoPerpetual" {
             let instrument_rust = instrument.extract::<CryptoPerpetual>(py)?;
-            Ok(Self::new(instrument_rust, fill).unwrap())
+            Ok(Self::new(&instrument_rust, fill).unwrap())
         } else if instrument_type == "CurrencyPair" {
             let instrument_rust = instrument.extract::<CurrencyPair>(py)?;
-            Ok(Self::new(instrument_rust, fill).unwrap())
+            Ok(Self::new(&instrument_rust, fill).unwrap())
         } else if instrument_type == "Equity" {
             let instrument_rust = instrument.extract::<Equity>(py)?;
-            Ok(Self::new(instrument_rust, fill).unwrap())
+            Ok(Self::new(&instrument_rust, fill).unwrap())
         } else if instrument_type == "FuturesContract" {
             let instrument_rust = instrument.extract::<FuturesContract>(py)?;
-            Ok(Self::new(instrument_rust, fill).unwrap())
+            Ok(Self::new(&instrument_rust, fill).unwrap())
         } else if instrument_type == "OptionsContract" {
             let instrument_rust = instrument.extract::<OptionsContract>(py)?;
-            Ok(Self::new(instrument_rust, fill).unwrap())
+            Ok(Self::new(&instrument_rust, fill).unwrap())
         } else {
             Err(to_pyvalue_err("Unsupported instrument type"))
         }
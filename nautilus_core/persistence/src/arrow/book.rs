@@ -0,0 +1,272 @@
+// -------------------------------------------------------------------------------------------------
+//  Copyright (C) 2015-2024 Nautech Systems Pty Ltd. All rights reserved.
+//  https://nautechsystems.io
+//
+//  Licensed under the GNU Lesser General Public License Version 3.0 (the "License");
+//  You may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at https://www.gnu.org/licenses/lgpl-3.0.en.html
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+// -------------------------------------------------------------------------------------------------
+
+use std::{collections::HashMap, io::Write, sync::Arc};
+
+use datafusion::{
+    arrow::{
+        array::UInt8Array,
+        compute::concat_batches,
+        datatypes::{DataType, Field, FieldRef, Schema},
+        error::ArrowError,
+        record_batch::RecordBatch,
+    },
+    parquet::{
+        arrow::{arrow_reader::ParquetRecordBatchReaderBuilder, ArrowWriter},
+        file::reader::ChunkReader,
+    },
+};
+use nautilus_core::time::UnixNanos;
+use nautilus_model::{
+    data::delta::OrderBookDelta,
+    enums::{BookType, FromU8},
+    orderbook::book::OrderBook,
+    types::{price::Price, quantity::Quantity},
+};
+
+use super::{
+    extract_column, ArrowSchemaProvider, DecodeFromRecordBatch, EncodeToRecordBatch, EncodingError,
+};
+
+const KEY_BOOK_TYPE: &str = "book_type";
+const KEY_BOOK_COUNT: &str = "book_count";
+const COL_PRICE_PRECISION: &str = "price_precision";
+const COL_SIZE_PRECISION: &str = "size_precision";
+
+/// Encodes a snapshot of the full state of the `book` as order book delta records.
+///
+/// The records use the `OrderBookDelta` schema (a `Clear` followed by every order, see
+/// `OrderBook::to_snapshot_deltas`), with the book type and count added to the metadata. As the
+/// orders of a book may not share a precision, each row also carries its own price and size
+/// precision in trailing columns (the metadata holds the largest of each).
+///
+/// Only the orders, `sequence`, `ts_last` and `count` are encoded: the book configuration,
+/// crossed book stats and sequence recovery state are not restored on decoding.
+pub fn encode_book_snapshot(
+    book: &OrderBook,
+    ts_init: UnixNanos,
+) -> Result<RecordBatch, ArrowError> {
+    let deltas = book.to_snapshot_deltas(ts_init).deltas;
+    let price_precisions: Vec<u8> = deltas.iter().map(|d| d.order.price.precision).collect();
+    let size_precisions: Vec<u8> = deltas.iter().map(|d| d.order.size.precision).collect();
+
+    let mut metadata = OrderBookDelta::get_metadata(
+        &book.instrument_id,
+        price_precisions.iter().copied().max().unwrap_or(0),
+        size_precisions.iter().copied().max().unwrap_or(0),
+    );
+    metadata.insert(
+        KEY_BOOK_TYPE.to_string(),
+        (book.book_type as u8).to_string(),
+    );
+    metadata.insert(KEY_BOOK_COUNT.to_string(), book.count.to_string());
+
+    let record_batch = OrderBookDelta::encode_batch(&metadata, &deltas)?;
+    let mut fields: Vec<FieldRef> = record_batch.schema().fields().iter().cloned().collect();
+    fields.push(Arc::new(Field::new(
+        COL_PRICE_PRECISION,
+        DataType::UInt8,
+        false,
+    )));
+    fields.push(Arc::new(Field::new(
+        COL_SIZE_PRECISION,
+        DataType::UInt8,
+        false,
+    )));
+
+    let mut columns = record_batch.columns().to_vec();
+    columns.push(Arc::new(UInt8Array::from(price_precisions)));
+    columns.push(Arc::new(UInt8Array::from(size_precisions)));
+
+    RecordBatch::try_new(Schema::new_with_metadata(fields, metadata).into(), columns)
+}
+
+/// Decodes an order book from snapshot records created with [`encode_book_snapshot`].
+pub fn decode_book_snapshot(record_batch: RecordBatch) -> Result<OrderBook, EncodingError> {
+    let metadata = record_batch.schema().metadata().clone();
+    let (book_type, count) = parse_metadata(&metadata)?;
+
+    // The precision columns follow the `OrderBookDelta` columns
+    let cols = record_batch.columns();
+    let index = OrderBookDelta::get_schema(None).fields().len();
+    let price_precisions =
+        extract_column::<UInt8Array>(cols, COL_PRICE_PRECISION, index, DataType::UInt8)?.clone();
+    let size_precisions =
+        extract_column::<UInt8Array>(cols, COL_SIZE_PRECISION, index + 1, DataType::UInt8)?.clone();
+
+    let mut deltas = OrderBookDelta::decode_batch(&metadata, record_batch)?;
+    for (i, delta) in deltas.iter_mut().enumerate() {
+        let order = &mut delta.order;
+        order.price = Price::from_raw(order.price.raw, price_precisions.value(i))
+            .map_err(|e| EncodingError::ParseError(COL_PRICE_PRECISION, e.to_string()))?;
+        order.size = Quantity::from_raw(order.size.raw, size_precisions.value(i))
+            .map_err(|e| EncodingError::ParseError(COL_SIZE_PRECISION, e.to_string()))?;
+    }
+
+    OrderBook::from_snapshot_deltas(book_type, &deltas, count)
+        .map_err(|e| EncodingError::ParseError(stringify!(OrderBook), e.to_string()))
+}
+
+/// Writes a snapshot of the `book` in Parquet format to the given `writer`.
+///
+/// # Errors
+///
+/// If encoding or writing the snapshot fails.
+pub fn write_book_snapshot_parquet<W: Write + Send>(
+    writer: W,
+    book: &OrderBook,
+    ts_init: UnixNanos,
+) -> anyhow::Result<()> {
+    let record_batch = encode_book_snapshot(book, ts_init)?;
+    let mut writer = ArrowWriter::try_new(writer, record_batch.schema(), None)?;
+    writer.write(&record_batch)?;
+    writer.close()?;
+    Ok(())
+}
+
+/// Reads an order book from a Parquet snapshot written with [`write_book_snapshot_parquet`].
+///
+/// # Errors
+///
+/// If reading or decoding the snapshot fails.
+pub fn read_book_snapshot_parquet<R: ChunkReader + 'static>(
+    reader: R,
+) -> anyhow::Result<OrderBook> {
+    let builder = ParquetRecordBatchReaderBuilder::try_new(reader)?;
+    let schema = builder.schema().clone();
+    let record_batches = builder.build()?.collect::<Result<Vec<_>, _>>()?;
+    let record_batch = concat_batches(&schema, &record_batches)?;
+
+    Ok(decode_book_snapshot(record_batch)?)
+}
+
+fn parse_metadata(metadata: &HashMap<String, String>) -> Result<(BookType, u64), EncodingError> {
+    let book_type_value = metadata
+        .get(KEY_BOOK_TYPE)
+        .ok_or_else(|| EncodingError::MissingMetadata(KEY_BOOK_TYPE))?
+        .parse::<u8>()
+        .map_err(|e| EncodingError::ParseError(KEY_BOOK_TYPE, e.to_string()))?;
+    let book_type = BookType::from_u8(book_type_value).ok_or_else(|| {
+        EncodingError::ParseError(
+            KEY_BOOK_TYPE,
+            format!("Invalid enum value, was {book_type_value}"),
+        )
+    })?;
+
+    let count = metadata
+        .get(KEY_BOOK_COUNT)
+        .ok_or_else(|| EncodingError::MissingMetadata(KEY_BOOK_COUNT))?
+        .parse::<u64>()
+        .map_err(|e| EncodingError::ParseError(KEY_BOOK_COUNT, e.to_string()))?;
+
+    Ok((book_type, count))
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Tests
+////////////////////////////////////////////////////////////////////////////////
+#[cfg(test)]
+mod tests {
+    use std::fs::File;
+
+    use nautilus_model::{
+        data::depth::{stubs::stub_depth10, OrderBookDepth10},
+        enums::OrderSide,
+        identifiers::instrument_id::InstrumentId,
+        orderbook::stubs::{assert_books_equal, stub_book},
+    };
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    fn test_encode_decode_book_snapshot(stub_depth10: OrderBookDepth10) {
+        let mut book = OrderBook::new(stub_depth10.instrument_id, BookType::L2_MBP);
+        book.apply_depth(stub_depth10);
+
+        let record_batch = encode_book_snapshot(&book, 10).unwrap();
+        let metadata = record_batch.schema().metadata().clone();
+        let restored = decode_book_snapshot(record_batch).unwrap();
+
+        assert_eq!(metadata.get(KEY_BOOK_TYPE).unwrap(), "2");
+        assert_eq!(metadata.get(KEY_BOOK_COUNT).unwrap(), "20");
+        assert_eq!(metadata.get("price_precision").unwrap(), "2");
+        assert_books_equal(&book, &restored);
+        assert_eq!(restored.count, book.count);
+    }
+
+    #[rstest]
+    fn test_encode_decode_book_snapshot_with_mixed_precisions() {
+        let book = stub_book(
+            "AAPL.XNAS",
+            BookType::L3_MBO,
+            &[
+                (OrderSide::Buy, "100.00", "3", 1),
+                (OrderSide::Buy, "99.5", "1.25", 2),
+                (OrderSide::Sell, "100.125", "2.5", 3),
+            ],
+        );
+
+        let record_batch = encode_book_snapshot(&book, 10).unwrap();
+        let metadata = record_batch.schema().metadata().clone();
+        let restored = decode_book_snapshot(record_batch).unwrap();
+
+        assert_eq!(metadata.get("price_precision").unwrap(), "3");
+        assert_eq!(metadata.get("size_precision").unwrap(), "2");
+        assert_books_equal(&book, &restored);
+        let precisions: Vec<(u8, u8)> = restored
+            .orders()
+            .iter()
+            .map(|o| (o.price.precision, o.size.precision))
+            .collect();
+        assert_eq!(precisions, vec![(2, 0), (1, 2), (3, 1)]);
+    }
+
+    #[rstest]
+    fn test_decode_book_snapshot_missing_metadata() {
+        let book = OrderBook::new(InstrumentId::from("AAPL.XNAS"), BookType::L3_MBO);
+        let metadata = OrderBookDelta::get_metadata(&book.instrument_id, 0, 0);
+        let deltas = book.to_snapshot_deltas(0).deltas;
+        let record_batch = OrderBookDelta::encode_batch(&metadata, &deltas).unwrap();
+
+        let result = decode_book_snapshot(record_batch);
+
+        assert!(matches!(
+            result,
+            Err(EncodingError::MissingMetadata(KEY_BOOK_TYPE))
+        ));
+    }
+
+    #[rstest]
+    fn test_parquet_book_snapshot_round_trip() {
+        let book = stub_book(
+            "AAPL.XNAS",
+            BookType::L3_MBO,
+            &[
+                (OrderSide::Buy, "100.00", "3", 7),
+                (OrderSide::Buy, "100.00", "1", 2),
+                (OrderSide::Sell, "100.50", "2", 9),
+            ],
+        );
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("book.parquet");
+        write_book_snapshot_parquet(File::create(&path).unwrap(), &book, 200).unwrap();
+        let restored = read_book_snapshot_parquet(File::open(&path).unwrap()).unwrap();
+
+        assert_books_equal(&book, &restored);
+        assert_eq!(restored.count, book.count);
+    }
+}
@@ -14,9 +14,11 @@
 // -------------------------------------------------------------------------------------------------
 
 pub mod bar;
+pub mod book;
 pub mod delta;
 pub mod depth;
 pub mod quote;
+pub mod sampler;
 pub mod trade;
 
 use std::{
@@ -0,0 +1,468 @@
+// -------------------------------------------------------------------------------------------------
+//  Copyright (C) 2015-2024 Nautech Systems Pty Ltd. All rights reserved.
+//  https://nautechsystems.io
+//
+//  Licensed under the GNU Lesser General Public License Version 3.0 (the "License");
+//  You may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at https://www.gnu.org/licenses/lgpl-3.0.en.html
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+// -------------------------------------------------------------------------------------------------
+
+use std::{collections::HashMap, sync::Arc};
+
+use datafusion::arrow::{
+    array::{ArrayRef, Int64Array, UInt32Array, UInt64Array},
+    datatypes::{DataType, Field, Schema},
+    error::ArrowError,
+    record_batch::RecordBatch,
+};
+use nautilus_common::{clock::Clock, handlers::EventHandler, timer::TimeEvent};
+use nautilus_core::time::UnixNanos;
+use nautilus_model::{
+    data::depth::{OrderBookDepth10, DEPTH10_LEN},
+    enums::OrderSide,
+    identifiers::instrument_id::InstrumentId,
+    orderbook::{aggregation::AggregatedLevel, book::OrderBook},
+};
+
+use super::{ArrowSchemaProvider, EncodeToRecordBatch};
+
+const KEY_DEPTH: &str = "depth";
+
+/// Represents a snapshot of the top levels of an order book at a point in time.
+#[derive(Clone, Debug)]
+pub struct BookSample {
+    pub bids: Vec<AggregatedLevel>,
+    pub asks: Vec<AggregatedLevel>,
+    pub sequence: u64,
+    pub ts_event: UnixNanos,
+    pub ts_init: UnixNanos,
+}
+
+impl BookSample {
+    /// Returns the metadata for the type, for use with serialization formats.
+    #[must_use]
+    pub fn get_metadata(
+        instrument_id: &InstrumentId,
+        price_precision: u8,
+        size_precision: u8,
+        depth: usize,
+    ) -> HashMap<String, String> {
+        let mut metadata =
+            OrderBookDepth10::get_metadata(instrument_id, price_precision, size_precision);
+        metadata.insert(KEY_DEPTH.to_string(), depth.to_string());
+        metadata
+    }
+
+    #[must_use]
+    pub fn level(&self, side: OrderSide, index: usize) -> Option<&AggregatedLevel> {
+        match side {
+            OrderSide::Buy => self.bids.get(index),
+            OrderSide::Sell => self.asks.get(index),
+            _ => panic!("Invalid `OrderSide` {side}"),
+        }
+    }
+}
+
+/// Provides fixed-interval sampling of the top `depth` levels of an order book.
+///
+/// The sampler sets a timer on a [`Clock`], and a sample of the book is taken for each
+/// [`TimeEvent`] from that timer passed to [`BookSampler::on_time_event`]. Samples are
+/// accumulated until encoded as a record batch with one row per sample.
+pub struct BookSampler {
+    pub instrument_id: InstrumentId,
+    pub depth: usize,
+    pub interval_ns: u64,
+    timer_name: String,
+    price_precision: Option<u8>,
+    size_precision: Option<u8>,
+    samples: Vec<BookSample>,
+}
+
+impl BookSampler {
+    /// Initializes a new `BookSampler` instance.
+    ///
+    /// # Panics
+    ///
+    /// If `depth` or `interval_ns` is zero.
+    #[must_use]
+    pub fn new(instrument_id: InstrumentId, depth: usize, interval_ns: u64) -> Self {
+        assert!(depth > 0, "`depth` was zero");
+        assert!(interval_ns > 0, "`interval_ns` was zero");
+        Self {
+            instrument_id,
+            depth,
+            interval_ns,
+            timer_name: format!("BookSampler-{instrument_id}-{interval_ns}"),
+            price_precision: None,
+            size_precision: None,
+            samples: Vec::new(),
+        }
+    }
+
+    /// Returns the name of the timer used to drive the sampler.
+    #[must_use]
+    pub fn timer_name(&self) -> &str {
+        &self.timer_name
+    }
+
+    /// Sets the sampling timer on the given `clock`, with an optional `callback` for the timer
+    /// events (otherwise the default handler for the clock is used).
+    pub fn start(
+        &self,
+        clock: &mut dyn Clock,
+        start_time_ns: UnixNanos,
+        stop_time_ns: Option<UnixNanos>,
+        callback: Option<EventHandler>,
+    ) {
+        clock.set_timer_ns(
+            &self.timer_name,
+            self.interval_ns,
+            start_time_ns,
+            stop_time_ns,
+            callback,
+        );
+    }
+
+    /// Cancels the sampling timer on the given `clock`.
+    pub fn stop(&self, clock: &mut dyn Clock) {
+        clock.cancel_timer(&self.timer_name);
+    }
+
+    /// Handles the given time `event`, sampling the `book` if the event is from the sampling
+    /// timer.
+    ///
+    /// Returns whether a sample was taken.
+    pub fn on_time_event(&mut self, event: &TimeEvent, book: &OrderBook) -> bool {
+        if event.name.as_str() != self.timer_name {
+            return false;
+        }
+        self.sample(book, event.ts_event, event.ts_init);
+        true
+    }
+
+    /// Takes a sample of the top levels of the `book`.
+    ///
+    /// # Panics
+    ///
+    /// If the `book` is not for the sampler instrument.
+    pub fn sample(&mut self, book: &OrderBook, ts_event: UnixNanos, ts_init: UnixNanos) {
+        assert_eq!(
+            book.instrument_id, self.instrument_id,
+            "Book was for a different instrument"
+        );
+
+        let bids = book.aggregated_levels(OrderSide::Buy, self.depth, None);
+        let asks = book.aggregated_levels(OrderSide::Sell, self.depth, None);
+        if let Some(level) = bids.first().or_else(|| asks.first()) {
+            self.price_precision.get_or_insert(level.price.precision);
+            self.size_precision.get_or_insert(level.size.precision);
+        }
+
+        self.samples.push(BookSample {
+            bids,
+            asks,
+            sequence: book.sequence,
+            ts_event,
+            ts_init,
+        });
+    }
+
+    #[must_use]
+    pub fn samples(&self) -> &[BookSample] {
+        &self.samples
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    /// Returns the schema for the record batches of the sampler.
+    #[must_use]
+    pub fn get_schema(&self) -> Schema {
+        BookSample::get_schema(Some(self.get_metadata()))
+    }
+
+    /// Encodes the accumulated samples as a record batch with one row per sample.
+    pub fn to_record_batch(&self) -> Result<RecordBatch, ArrowError> {
+        BookSample::encode_batch(&self.get_metadata(), &self.samples)
+    }
+
+    /// Encodes the accumulated samples as a record batch, then clears the samples.
+    pub fn drain_record_batch(&mut self) -> Result<RecordBatch, ArrowError> {
+        let record_batch = self.to_record_batch()?;
+        self.samples.clear();
+        Ok(record_batch)
+    }
+
+    fn get_metadata(&self) -> HashMap<String, String> {
+        BookSample::get_metadata(
+            &self.instrument_id,
+            self.price_precision.unwrap_or(0),
+            self.size_precision.unwrap_or(0),
+            self.depth,
+        )
+    }
+}
+
+fn parse_depth(metadata: &HashMap<String, String>) -> Option<usize> {
+    metadata.get(KEY_DEPTH)?.parse().ok()
+}
+
+impl ArrowSchemaProvider for BookSample {
+    /// Returns the schema for the `depth` held in the `metadata`, or for a depth of ten levels
+    /// when there is no metadata.
+    fn get_schema(metadata: Option<HashMap<String, String>>) -> Schema {
+        let depth = metadata
+            .as_ref()
+            .and_then(parse_depth)
+            .unwrap_or(DEPTH10_LEN);
+        let mut fields = Vec::with_capacity(depth * 6 + 3);
+        for (prefix, data_type) in [
+            ("bid_price", DataType::Int64),
+            ("ask_price", DataType::Int64),
+            ("bid_size", DataType::UInt64),
+            ("ask_size", DataType::UInt64),
+            ("bid_count", DataType::UInt32),
+            ("ask_count", DataType::UInt32),
+        ] {
+            for i in 0..depth {
+                fields.push(Field::new(
+                    format!("{prefix}_{i}"),
+                    data_type.clone(),
+                    false,
+                ));
+            }
+        }
+        fields.push(Field::new("sequence", DataType::UInt64, false));
+        fields.push(Field::new("ts_event", DataType::UInt64, false));
+        fields.push(Field::new("ts_init", DataType::UInt64, false));
+
+        match metadata {
+            Some(metadata) => Schema::new_with_metadata(fields, metadata),
+            None => Schema::new(fields),
+        }
+    }
+}
+
+impl EncodeToRecordBatch for BookSample {
+    /// Encodes the `data` samples with one row per sample, to the `depth` held in the `metadata`.
+    ///
+    /// Levels beyond the depth of the book at the time of a sample are encoded with a zero
+    /// price, size and count.
+    fn encode_batch(
+        metadata: &HashMap<String, String>,
+        data: &[Self],
+    ) -> Result<RecordBatch, ArrowError> {
+        let depth = parse_depth(metadata).ok_or_else(|| {
+            ArrowError::InvalidArgumentError(format!("Missing or invalid `{KEY_DEPTH}` metadata"))
+        })?;
+        let mut columns: Vec<ArrayRef> = Vec::with_capacity(depth * 6 + 3);
+
+        for side in [OrderSide::Buy, OrderSide::Sell] {
+            for i in 0..depth {
+                let values: Vec<i64> = data
+                    .iter()
+                    .map(|s| s.level(side, i).map_or(0, |level| level.price.raw))
+                    .collect();
+                columns.push(Arc::new(Int64Array::from(values)));
+            }
+        }
+        for side in [OrderSide::Buy, OrderSide::Sell] {
+            for i in 0..depth {
+                let values: Vec<u64> = data
+                    .iter()
+                    .map(|s| s.level(side, i).map_or(0, |level| level.size.raw))
+                    .collect();
+                columns.push(Arc::new(UInt64Array::from(values)));
+            }
+        }
+        for side in [OrderSide::Buy, OrderSide::Sell] {
+            for i in 0..depth {
+                let values: Vec<u32> = data
+                    .iter()
+                    .map(|s| s.level(side, i).map_or(0, |level| level.count))
+                    .collect();
+                columns.push(Arc::new(UInt32Array::from(values)));
+            }
+        }
+
+        let sequence: Vec<u64> = data.iter().map(|s| s.sequence).collect();
+        let ts_event: Vec<u64> = data.iter().map(|s| s.ts_event).collect();
+        let ts_init: Vec<u64> = data.iter().map(|s| s.ts_init).collect();
+        columns.push(Arc::new(UInt64Array::from(sequence)));
+        columns.push(Arc::new(UInt64Array::from(ts_event)));
+        columns.push(Arc::new(UInt64Array::from(ts_init)));
+
+        RecordBatch::try_new(Self::get_schema(Some(metadata.clone())).into(), columns)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Tests
+////////////////////////////////////////////////////////////////////////////////
+#[cfg(test)]
+mod tests {
+    use datafusion::arrow::array::Array;
+    use nautilus_common::{clock::TestClock, handlers::SafeTimeEventCallback};
+    use nautilus_model::{
+        data::order::BookOrder,
+        enums::BookType,
+        orderbook::stubs::stub_book,
+        types::{price::Price, quantity::Quantity},
+    };
+    use rstest::rstest;
+
+    use super::*;
+
+    fn create_clock() -> TestClock {
+        let mut clock = TestClock::new();
+        clock.register_default_handler(EventHandler::new(
+            None,
+            Some(SafeTimeEventCallback {
+                callback: Arc::new(|_| {}),
+            }),
+        ));
+        clock
+    }
+
+    fn create_book() -> OrderBook {
+        stub_book(
+            "ETHUSDT.BINANCE",
+            BookType::L3_MBO,
+            &[
+                (OrderSide::Buy, "100.00", "1.0", 1),
+                (OrderSide::Buy, "100.00", "2.0", 2),
+                (OrderSide::Buy, "99.00", "3.0", 3),
+                (OrderSide::Sell, "101.00", "4.0", 4),
+            ],
+        )
+    }
+
+    fn column<T: Array + Clone + 'static>(record_batch: &RecordBatch, name: &str) -> T {
+        record_batch
+            .column_by_name(name)
+            .unwrap()
+            .as_any()
+            .downcast_ref::<T>()
+            .unwrap()
+            .clone()
+    }
+
+    #[rstest]
+    fn test_schema() {
+        let sampler = BookSampler::new(InstrumentId::from("ETHUSDT.BINANCE"), 3, 1_000);
+
+        let schema = sampler.get_schema();
+
+        assert_eq!(schema.fields().len(), 21);
+        assert_eq!(schema.field(0).name(), "bid_price_0");
+        assert_eq!(schema.field(3).name(), "ask_price_0");
+        assert_eq!(schema.field(17).name(), "ask_count_2");
+        assert_eq!(schema.metadata().get(KEY_DEPTH).unwrap(), "3");
+    }
+
+    #[rstest]
+    fn test_schema_without_metadata() {
+        let schema = BookSample::get_schema(None);
+
+        assert_eq!(schema.fields().len(), DEPTH10_LEN * 6 + 3);
+        assert!(schema.metadata().is_empty());
+    }
+
+    #[rstest]
+    fn test_encode_batch_without_depth_metadata() {
+        let metadata = OrderBookDepth10::get_metadata(&InstrumentId::from("ETHUSDT.BINANCE"), 2, 1);
+
+        let result = BookSample::encode_batch(&metadata, &[]);
+
+        assert!(matches!(result, Err(ArrowError::InvalidArgumentError(_))));
+    }
+
+    #[rstest]
+    fn test_sample_on_timer_events() {
+        let mut clock = create_clock();
+        let mut book = create_book();
+        let mut sampler = BookSampler::new(book.instrument_id, 2, 1_000);
+        sampler.start(&mut clock, 0, None, None);
+
+        let events = clock.advance_time(2_500, true);
+        for event in &events {
+            assert!(sampler.on_time_event(event, &book));
+        }
+        let order = BookOrder::new(
+            OrderSide::Sell,
+            Price::from("100.50"),
+            Quantity::from("5.0"),
+            5,
+        );
+        book.add(order, 5, 5);
+        for event in &clock.advance_time(3_000, true) {
+            sampler.on_time_event(event, &book);
+        }
+
+        assert_eq!(events.len(), 2);
+        assert_eq!(sampler.len(), 3);
+        assert_eq!(sampler.samples()[0].ts_event, 1_000);
+        assert_eq!(sampler.samples()[2].ts_event, 3_000);
+        assert_eq!(sampler.samples()[2].asks[0].price, Price::from("100.50"));
+    }
+
+    #[rstest]
+    fn test_on_time_event_ignores_other_timers() {
+        let book = create_book();
+        let mut sampler = BookSampler::new(book.instrument_id, 2, 1_000);
+        let event = TimeEvent::new("OTHER".into(), Default::default(), 1_000, 1_000).unwrap();
+
+        assert!(!sampler.on_time_event(&event, &book));
+        assert!(sampler.is_empty());
+    }
+
+    #[rstest]
+    fn test_stop_cancels_timer() {
+        let mut clock = create_clock();
+        let sampler = BookSampler::new(InstrumentId::from("ETHUSDT.BINANCE"), 2, 1_000);
+        sampler.start(&mut clock, 0, None, None);
+
+        sampler.stop(&mut clock);
+
+        assert_eq!(clock.timer_count(), 0);
+    }
+
+    #[rstest]
+    fn test_drain_record_batch() {
+        let book = create_book();
+        let mut sampler = BookSampler::new(book.instrument_id, 2, 1_000);
+        sampler.sample(&book, 1_000, 1_001);
+        sampler.sample(&book, 2_000, 2_001);
+
+        let record_batch = sampler.drain_record_batch().unwrap();
+        let bid_price_0 = column::<Int64Array>(&record_batch, "bid_price_0");
+        let bid_size_0 = column::<UInt64Array>(&record_batch, "bid_size_0");
+        let bid_count_0 = column::<UInt32Array>(&record_batch, "bid_count_0");
+        let ask_price_1 = column::<Int64Array>(&record_batch, "ask_price_1");
+        let ts_init = column::<UInt64Array>(&record_batch, "ts_init");
+        let metadata = record_batch.schema().metadata().clone();
+
+        assert!(sampler.is_empty());
+        assert_eq!(record_batch.num_rows(), 2);
+        assert_eq!(bid_price_0.value(0), Price::from("100.00").raw);
+        assert_eq!(bid_size_0.value(0), Quantity::from("3.0").raw);
+        assert_eq!(bid_count_0.value(0), 2);
+        assert_eq!(ask_price_1.value(1), 0);
+        assert_eq!(ts_init.value(1), 2_001);
+        assert_eq!(metadata.get("price_precision").unwrap(), "2");
+        assert_eq!(metadata.get("size_precision").unwrap(), "1");
+    }
+}
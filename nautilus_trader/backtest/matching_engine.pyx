@@ -344,7 +344,11 @@ cdef class OrderMatchingEngine:
         if logging_is_initialized():
             self._log.debug(f"Processing {repr(delta)}...")
 
-        self._book.apply_delta(delta)
+        try:
+            self._book.apply_delta(delta)
+        except RuntimeError as e:
+            # Matching continues against the last consistent state of the book
+            self._log.warning(str(e))
 
         # TODO(cs): WIP to introduce flags
         # if data.flags == TimeInForce.GTC:
@@ -373,7 +377,11 @@ cdef class OrderMatchingEngine:
         if logging_is_initialized():
             self._log.debug(f"Processing {repr(deltas)}...")
 
-        self._book.apply_deltas(deltas)
+        try:
+            self._book.apply_deltas(deltas)
+        except RuntimeError as e:
+            # Matching continues against the last consistent state of the book
+            self._log.warning(str(e))
 
         # TODO(cs): WIP to introduce flags
         # if data.flags == TimeInForce.GTC:
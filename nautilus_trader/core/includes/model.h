@@ -2076,7 +2076,11 @@ void orderbook_clear_bids(struct OrderBook_API *book, uint64_t ts_event, uint64_
 
 void orderbook_clear_asks(struct OrderBook_API *book, uint64_t ts_event, uint64_t sequence);
 
-void orderbook_apply_delta(struct OrderBook_API *book, struct OrderBookDelta_t delta);
+/**
+ * Applies the `delta` to the book, returning 0 if a sequence gap was detected (the delta is
+ * then buffered until the next snapshot), otherwise 1.
+ */
+uint8_t orderbook_apply_delta(struct OrderBook_API *book, struct OrderBookDelta_t delta);
 
 void orderbook_apply_depth(struct OrderBook_API *book, struct OrderBookDepth10_t depth);
 
@@ -374,7 +374,8 @@ class CashAccount:
         last_qty: Quantity,
         last_px: Price,
         liquidity_side: LiquiditySide,
-        use_quote_for_inverse: bool | None = None
+        use_quote_for_inverse: bool | None = None,
+        ts_event: int | None = None,
     ) -> Money: ...
     def calculate_pnls(
         self,
@@ -1423,7 +1423,9 @@ cdef extern from "../includes/model.h":
 
     void orderbook_clear_asks(OrderBook_API *book, uint64_t ts_event, uint64_t sequence);
 
-    void orderbook_apply_delta(OrderBook_API *book, OrderBookDelta_t delta);
+    # Applies the `delta` to the book, returning 0 if a sequence gap was detected (the delta is
+    # then buffered until the next snapshot), otherwise 1.
+    uint8_t orderbook_apply_delta(OrderBook_API *book, OrderBookDelta_t delta);
 
     void orderbook_apply_depth(OrderBook_API *book, OrderBookDepth10_t depth);
 
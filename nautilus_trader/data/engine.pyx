@@ -1548,7 +1548,10 @@ cdef class DataEngine(Component):
             )
             return
 
-        order_book.apply(data)
+        try:
+            order_book.apply(data)
+        except RuntimeError as e:
+            self._log.warning(f"Cannot update order book: {e}.")
 
     cpdef void _snapshot_order_book(self, TimeEvent snap_event):
         cdef tuple[str] parts = snap_event.name.partition('|')[2].rpartition('|')
@@ -310,11 +310,17 @@ cdef class OrderBook(Data):
         ------
         ValueError
             If `delta.book_type` is not equal to `self.type`.
+        RuntimeError
+            If a sequence gap is detected (the delta is then buffered until the next snapshot).
 
         """
         Condition.not_none(delta, "delta")
 
-        orderbook_apply_delta(&self._mem, delta._mem)
+        if not orderbook_apply_delta(&self._mem, delta._mem):
+            raise RuntimeError(
+                f"Sequence gap at sequence={delta.sequence} for {self.instrument_id}, "
+                "deltas are buffered until the next snapshot",
+            )
 
     cpdef void apply_deltas(self, OrderBookDeltas deltas):
         """
@@ -325,12 +331,25 @@ cdef class OrderBook(Data):
         deltas : OrderBookDeltas
             The deltas to apply.
 
+        Raises
+        ------
+        RuntimeError
+            If a sequence gap is detected, after applying all the deltas (for the first gap).
+
         """
         Condition.not_none(deltas, "deltas")
 
+        cdef OrderBookDelta gap = None
         cdef OrderBookDelta delta
         for delta in deltas.deltas:
-            self.apply_delta(delta)
+            if not orderbook_apply_delta(&self._mem, delta._mem) and gap is None:
+                gap = delta
+
+        if gap is not None:
+            raise RuntimeError(
+                f"Sequence gap at sequence={gap.sequence} for {self.instrument_id}, "
+                "deltas are buffered until the next snapshot",
+            )
 
     cpdef void apply_depth(self, OrderBookDepth10 depth):
         """